@@ -1,3 +1,5 @@
+use std::time::Duration;
+
 impl_handle!(AudioClipHandle);
 
 #[derive(Debug, Clone)]
@@ -6,3 +8,12 @@ pub struct AudioClip {
     pub channels: u8,
     pub sample_rate: u32,
 }
+
+impl AudioClip {
+    /// Gets the length of this clip.
+    pub fn duration(&self) -> Duration {
+        let samples = self.pcm.len() as f64 / f64::from(self.channels.max(1));
+        let micros = samples * 1_000_000.0 / f64::from(self.sample_rate);
+        Duration::from_micros(micros as u64)
+    }
+}
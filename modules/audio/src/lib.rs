@@ -22,10 +22,16 @@ mod system;
 
 pub mod prelude {
     pub use assets::prelude::AudioClipHandle;
-    pub use source::{AudioSource, AudioSourceAttenuation, AudioSourceHandle, AudioSourceWrap};
+    pub use source::{
+        AudioSource, AudioSourceAttenuation, AudioSourceAttenuationModel, AudioSourceHandle,
+        AudioSourceWrap,
+    };
 }
 
 pub use self::inside::{discard, setup};
+pub use self::mixer::OutputConfig;
+
+use std::time::Duration;
 
 use crayon::errors::Result;
 use crayon::math::prelude::Vector3;
@@ -36,6 +42,13 @@ use self::assets::prelude::AudioClipHandle;
 use self::inside::ctx;
 use self::source::{AudioSource, AudioSourceHandle};
 
+/// Gets the output format of audio device. Clips with different sample rate are
+/// resampled to it when mixing.
+#[inline]
+pub fn output_config() -> OutputConfig {
+    ctx().output_config()
+}
+
 /// Sets the position of listener.
 #[inline]
 pub fn set_listener<T>(position: T)
@@ -62,6 +75,12 @@ pub fn clip_state(handle: AudioClipHandle) -> ResourceState {
     ctx().clip_state(handle)
 }
 
+/// Gets the length of a loaded `AudioClip`.
+#[inline]
+pub fn duration(handle: AudioClipHandle) -> Option<Duration> {
+    ctx().duration(handle)
+}
+
 /// Deletes a `AudioClip` resource from `AudioSystem`.
 #[inline]
 pub fn delete_clip(handle: AudioClipHandle) {
@@ -83,6 +102,29 @@ pub fn stop(handle: AudioSourceHandle) {
     ctx().stop(handle)
 }
 
+/// Gets the current playback position of a playing audio source. Returns `None`
+/// if the source has been stopped or finished.
+///
+/// The position is updated by the mixer after every mixed buffer, so it might lag
+/// behind the sound a little.
+#[inline]
+pub fn position(handle: AudioSourceHandle) -> Option<Duration> {
+    ctx().position(handle)
+}
+
+/// Pauses a played audio source, the playback position is kept until it
+/// gets resumed.
+#[inline]
+pub fn pause(handle: AudioSourceHandle) {
+    ctx().pause(handle)
+}
+
+/// Resumes a paused audio source.
+#[inline]
+pub fn resume(handle: AudioSourceHandle) {
+    ctx().resume(handle)
+}
+
 /// Sets the emiiter position of playing sound.
 #[inline]
 pub fn set_position<T>(handle: AudioSourceHandle, position: T)
@@ -105,42 +147,37 @@ pub fn set_pitch(handle: AudioSourceHandle, pitch: f32) {
 }
 
 mod inside {
+    use crayon::utils::global::Global;
+
     use super::system::AudioSystem;
 
-    static mut CTX: *const AudioSystem = std::ptr::null();
+    static CTX: Global<AudioSystem> = Global::new();
 
     #[inline]
     pub fn ctx() -> &'static AudioSystem {
-        unsafe {
-            debug_assert!(
-                !CTX.is_null(),
-                "audio system has not been initialized properly."
-            );
-
-            &*CTX
-        }
+        CTX.get()
+            .expect("audio system has not been initialized properly.")
     }
 
-    /// Setup the world system.
+    /// Setup the audio system. It fails if the audio system has been set up already.
     pub fn setup() -> Result<(), failure::Error> {
-        unsafe {
-            debug_assert!(CTX.is_null(), "duplicated setup of audio system.");
+        if !CTX.is_null() {
+            bail!("duplicated setup of audio system.");
+        }
 
-            let ctx = AudioSystem::new()?;
-            CTX = Box::into_raw(Box::new(ctx));
-            Ok(())
+        let ctx = AudioSystem::new()?;
+        if CTX.set(ctx).is_err() {
+            bail!("duplicated setup of audio system.");
         }
+
+        crayon::application::register_module("audio", discard);
+        Ok(())
     }
 
     /// Discard the world system.
     pub fn discard() {
         unsafe {
-            if CTX.is_null() {
-                return;
-            }
-
-            drop(Box::from_raw(CTX as *mut AudioSystem));
-            CTX = std::ptr::null();
+            CTX.discard();
         }
     }
 }
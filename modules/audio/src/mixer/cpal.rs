@@ -1,13 +1,20 @@
 use std::sync::{Arc, RwLock};
 use std::thread::Builder;
+use std::time::Duration;
 
 use cpal::{self, EventLoop, StreamData, UnknownTypeOutputBuffer};
 use crayon::errors::Result;
+use crayon::utils::prelude::FastHashMap;
+
+use source::AudioSourceHandle;
 
 use super::sampler::Sampler;
-use super::Command;
+use super::{Command, OutputConfig};
 
-pub fn run(rx: Arc<RwLock<Vec<Command>>>) -> Result<()> {
+pub fn run(
+    rx: Arc<RwLock<Vec<Command>>>,
+    positions: Arc<RwLock<FastHashMap<AudioSourceHandle, Duration>>>,
+) -> Result<OutputConfig> {
     let device = cpal::default_output_device()
         .ok_or_else(|| format_err!("No avaiable audio output device"))?;
 
@@ -24,7 +31,12 @@ pub fn run(rx: Arc<RwLock<Vec<Command>>>) -> Result<()> {
         format
     );
 
-    let mut sampler = Sampler::new(format.channels as u8, format.sample_rate.0 as u32);
+    let config = OutputConfig {
+        sample_rate: format.sample_rate.0 as u32,
+        channels: format.channels as u8,
+    };
+
+    let mut sampler = Sampler::new(config.channels, config.sample_rate);
     Builder::new()
         .name("Audio".into())
         .spawn(move || {
@@ -61,8 +73,14 @@ pub fn run(rx: Arc<RwLock<Vec<Command>>>) -> Result<()> {
                         }
                     }
                 }
+
+                // Never blocks the audio thread, the positions are published in the
+                // next callback if they are being read.
+                if let Ok(mut positions) = positions.try_write() {
+                    sampler.positions(&mut positions);
+                }
             })
         }).expect("Failed to create thread for `AudioSystem`.");
 
-    Ok(())
+    Ok(config)
 }
@@ -1,25 +1,99 @@
 use std::sync::{Arc, RwLock};
 use std::thread::Builder;
+use std::time::{Duration, Instant};
 
-use super::Command;
 use crayon::errors::Result;
+use crayon::utils::prelude::FastHashMap;
 
-pub fn run(rx: Arc<RwLock<Vec<Command>>>) -> Result<()> {
+use source::AudioSourceHandle;
+
+use super::sampler::Sampler;
+use super::{Command, OutputConfig};
+
+pub fn run(
+    rx: Arc<RwLock<Vec<Command>>>,
+    positions: Arc<RwLock<FastHashMap<AudioSourceHandle, Duration>>>,
+) -> Result<OutputConfig> {
     info!("Create headless audio mixer.",);
 
+    let config = OutputConfig {
+        sample_rate: 44100,
+        channels: 2,
+    };
+
+    let mut sampler = Sampler::new(config.channels, config.sample_rate);
     Builder::new()
         .name("Audio".into())
         .spawn(move || {
-            //
+            let mut bufs = Vec::new();
+            let start = Instant::now();
+            let mut frames = 0;
+
             loop {
                 {
                     let mut rx = rx.write().unwrap();
-                    rx.clear();
+                    ::std::mem::swap(&mut bufs, &mut rx);
+                }
+
+                if !sampler.update(bufs.drain(..)) {
+                    return;
+                }
+
+                // Advances the sources in real time, like the samples are consumed by
+                // an output device.
+                let elapsed = start.elapsed().as_nanos() * u128::from(config.sample_rate);
+                let target = (elapsed / 1_000_000_000) as u64;
+                sampler.skip((target - frames) as usize);
+                frames = target;
+
+                if let Ok(mut positions) = positions.try_write() {
+                    sampler.positions(&mut positions);
                 }
 
-                std::thread::sleep(std::time::Duration::from_millis(50));
+                ::std::thread::sleep(Duration::from_millis(50));
             }
         }).expect("Failed to create thread for `AudioSystem`.");
 
-    Ok(())
+    Ok(config)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use assets::prelude::{AudioClip, AudioClipHandle};
+    use crayon::utils::handle::HandleLike;
+    use source::{AudioSource, AudioSourceWrap};
+
+    #[test]
+    fn position() {
+        let tx = Arc::new(RwLock::new(Vec::new()));
+        let positions = Arc::new(RwLock::new(FastHashMap::default()));
+        run(tx.clone(), positions.clone()).unwrap();
+
+        let clip = Arc::new(AudioClip {
+            pcm: vec![0; 44100],
+            channels: 1,
+            sample_rate: 44100,
+        });
+
+        let handle = AudioSourceHandle::new(0, 1);
+        let mut source = AudioSource::from(AudioClipHandle::default());
+        source.loops = AudioSourceWrap::Infinite;
+        tx.write()
+            .unwrap()
+            .push(Command::CreateSource(handle, source, clip));
+
+        // The sources keep playing even if there is no output device.
+        let mut position = None;
+        for _ in 0..40 {
+            ::std::thread::sleep(Duration::from_millis(50));
+            position = positions.read().unwrap().get(&handle).cloned();
+            if position.map(|v| v > Duration::from_millis(0)) == Some(true) {
+                break;
+            }
+        }
+
+        assert!(position.unwrap() > Duration::from_millis(0));
+        tx.write().unwrap().push(Command::Discard);
+    }
 }
@@ -7,34 +7,49 @@ mod headless;
 mod sampler;
 
 use std::sync::{Arc, RwLock};
+use std::time::Duration;
 
 use crayon::errors::Result;
 use crayon::math::prelude::Vector3;
 use crayon::res::utils::prelude::ResourcePool;
-use crayon::utils::prelude::HandlePool;
+use crayon::utils::prelude::{FastHashMap, HandlePool};
 
 use assets::prelude::{AudioClip, AudioClipHandle, AudioClipLoader};
 use source::{AudioSource, AudioSourceHandle};
 
+/// The output format of audio device.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct OutputConfig {
+    /// The number of samples per second of each channel.
+    pub sample_rate: u32,
+    /// The number of channels.
+    pub channels: u8,
+}
+
 pub struct Mixer {
+    config: OutputConfig,
     sources: RwLock<HandlePool<AudioSourceHandle>>,
     tx: Arc<RwLock<Vec<Command>>>,
+    positions: Arc<RwLock<FastHashMap<AudioSourceHandle, Duration>>>,
     clips: Arc<RwLock<ResourcePool<AudioClipHandle, AudioClipLoader>>>,
 }
 
 impl Mixer {
     pub fn new(clips: Arc<RwLock<ResourcePool<AudioClipHandle, AudioClipLoader>>>) -> Result<Self> {
         let tx = Arc::new(RwLock::new(Vec::new()));
+        let positions = Arc::new(RwLock::new(FastHashMap::default()));
 
         #[cfg(not(target_arch = "wasm32"))]
-        cpal::run(tx.clone())?;
+        let config = cpal::run(tx.clone(), positions.clone())?;
 
         #[cfg(target_arch = "wasm32")]
-        webaudio::run(tx.clone())?;
+        let config = webaudio::run(tx.clone(), positions.clone())?;
 
         Ok(Mixer {
+            config: config,
             sources: RwLock::new(HandlePool::new()),
             tx: tx,
+            positions: positions,
             clips: clips,
         })
     }
@@ -43,11 +58,14 @@ impl Mixer {
         clips: Arc<RwLock<ResourcePool<AudioClipHandle, AudioClipLoader>>>,
     ) -> Result<Self> {
         let tx = Arc::new(RwLock::new(Vec::new()));
-        headless::run(tx.clone())?;
+        let positions = Arc::new(RwLock::new(FastHashMap::default()));
+        let config = headless::run(tx.clone(), positions.clone())?;
 
         Ok(Mixer {
+            config: config,
             sources: RwLock::new(HandlePool::new()),
             tx: tx,
+            positions: positions,
             clips: clips,
         })
     }
@@ -61,6 +79,11 @@ impl Drop for Mixer {
 }
 
 impl Mixer {
+    #[inline]
+    pub fn output_config(&self) -> OutputConfig {
+        self.config
+    }
+
     #[inline]
     pub fn create_source(&self, params: AudioSource) -> Result<AudioSourceHandle> {
         if let Some(clip) = self.clips.read().unwrap().resource(params.clip).cloned() {
@@ -102,6 +125,23 @@ impl Mixer {
         let cmd = Command::SetPosition(handle, position);
         self.tx.write().unwrap().push(cmd);
     }
+
+    #[inline]
+    pub fn position(&self, handle: AudioSourceHandle) -> Option<Duration> {
+        self.positions.read().unwrap().get(&handle).cloned()
+    }
+
+    #[inline]
+    pub fn pause(&self, handle: AudioSourceHandle) {
+        let cmd = Command::Pause(handle);
+        self.tx.write().unwrap().push(cmd);
+    }
+
+    #[inline]
+    pub fn resume(&self, handle: AudioSourceHandle) {
+        let cmd = Command::Resume(handle);
+        self.tx.write().unwrap().push(cmd);
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -112,5 +152,7 @@ pub enum Command {
     SetVolume(AudioSourceHandle, f32),
     SetPitch(AudioSourceHandle, f32),
     SetPosition(AudioSourceHandle, Vector3<f32>),
+    Pause(AudioSourceHandle),
+    Resume(AudioSourceHandle),
     Discard,
 }
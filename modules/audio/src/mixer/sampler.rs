@@ -1,6 +1,8 @@
 use std::sync::Arc;
+use std::time::Duration;
 
 use crayon::math::prelude::Vector3;
+use crayon::utils::prelude::FastHashMap;
 
 use assets::prelude::AudioClip;
 use source::{AudioSource, AudioSourceAttenuation, AudioSourceHandle, AudioSourceWrap};
@@ -42,7 +44,7 @@ impl Sampler {
         let mut sum = 0.0;
         for v in &mut self.samplers {
             if let Some(ref source) = v {
-                sum += source.sample(self.channels_iter, self.listener);
+                sum += source.sample(self.channels, self.channels_iter, self.listener);
             }
         }
 
@@ -65,6 +67,22 @@ impl Sampler {
         sum
     }
 
+    /// Mixes and drops `frames` samples of every channel, which advances the sources as
+    /// if they were played.
+    pub fn skip(&mut self, frames: usize) {
+        for _ in 0..frames * self.channels as usize {
+            self.sample();
+        }
+    }
+
+    /// Collects the playback positions of all the playing sources.
+    pub fn positions(&self, positions: &mut FastHashMap<AudioSourceHandle, Duration>) {
+        positions.clear();
+        for v in self.samplers.iter().filter_map(|v| v.as_ref()) {
+            positions.insert(v.handle, v.position());
+        }
+    }
+
     pub fn update<T: Iterator<Item = Command>>(&mut self, bufs: T) -> bool {
         for cmd in bufs {
             match cmd {
@@ -74,6 +92,8 @@ impl Sampler {
                 Command::SetPitch(handle, pitch) => self.set_pitch(handle, pitch),
                 Command::SetVolume(handle, volume) => self.set_volume(handle, volume),
                 Command::SetPosition(handle, emitter) => self.set_position(handle, emitter),
+                Command::Pause(handle) => self.set_paused(handle, true),
+                Command::Resume(handle) => self.set_paused(handle, false),
                 Command::Discard => {
                     return false;
                 }
@@ -100,7 +120,7 @@ impl Sampler {
             self.samplers.resize(index + 1, None);
         }
 
-        self.samplers[index] = Some(AudioSourceSampler::new(clip, source));
+        self.samplers[index] = Some(AudioSourceSampler::new(handle, clip, source));
     }
 
     #[inline]
@@ -139,26 +159,38 @@ impl Sampler {
             v.set_position(position);
         }
     }
+
+    #[inline]
+    pub fn set_paused(&mut self, handle: AudioSourceHandle, paused: bool) {
+        let index = handle.index() as usize;
+        if let Some(v) = self.samplers.get_mut(index).and_then(|v| v.as_mut()) {
+            v.set_paused(paused);
+        }
+    }
 }
 
 #[derive(Clone)]
 pub struct AudioSourceSampler {
+    handle: AudioSourceHandle,
     clip: Arc<AudioClip>,
     volume: f32,
     pitch: f32,
     loops: AudioSourceWrap,
     attenuation: Option<AudioSourceAttenuation>,
+    paused: bool,
     iter: f32,
 }
 
 impl AudioSourceSampler {
-    pub fn new(clip: Arc<AudioClip>, source: AudioSource) -> Self {
+    pub fn new(handle: AudioSourceHandle, clip: Arc<AudioClip>, source: AudioSource) -> Self {
         AudioSourceSampler {
+            handle: handle,
             clip: clip,
             volume: source.volume,
             pitch: source.pitch,
             loops: source.loops,
             attenuation: source.attenuation,
+            paused: false,
             iter: 0.0,
         }
     }
@@ -180,7 +212,22 @@ impl AudioSourceSampler {
         }
     }
 
-    pub fn sample(&self, channels_iter: u8, listener: Vector3<f32>) -> f32 {
+    #[inline]
+    pub fn set_paused(&mut self, paused: bool) {
+        self.paused = paused;
+    }
+
+    /// Gets the playback position in the clip.
+    #[inline]
+    pub fn position(&self) -> Duration {
+        samples_to_duration(self.iter, self.clip.sample_rate)
+    }
+
+    pub fn sample(&self, channels: u8, channels_iter: u8, listener: Vector3<f32>) -> f32 {
+        if self.paused {
+            return 0.0;
+        }
+
         let mut idx = (self.iter as usize) * (self.clip.channels as usize);
         idx += (channels_iter % self.clip.channels) as usize;
 
@@ -189,6 +236,17 @@ impl AudioSourceSampler {
 
             if let Some(attenuation) = self.attenuation {
                 v *= attenuation.volume(listener);
+
+                // Pans between the left and right channels. Centered sounds are played
+                // at full volume on both sides.
+                if channels >= 2 && channels_iter < 2 {
+                    let pan = attenuation.panning(listener);
+                    v *= if channels_iter == 0 {
+                        (1.0 - pan).min(1.0)
+                    } else {
+                        (1.0 + pan).min(1.0)
+                    };
+                }
             }
 
             v
@@ -198,6 +256,10 @@ impl AudioSourceSampler {
     }
 
     pub fn advance(&mut self, sample_rate: u32) -> bool {
+        if self.paused {
+            return false;
+        }
+
         let pitch = self.pitch.min(100.0).max(0.01);
         self.iter += pitch * (self.clip.sample_rate as f32) / (sample_rate as f32);
 
@@ -222,6 +284,12 @@ impl AudioSourceSampler {
     }
 }
 
+#[inline]
+pub fn samples_to_duration(samples: f32, sample_rate: u32) -> Duration {
+    let micros = f64::from(samples) * 1_000_000.0 / f64::from(sample_rate);
+    Duration::from_micros(micros as u64)
+}
+
 #[inline]
 pub fn sample_i16_to_f32(sample: i16) -> f32 {
     if sample < 0 {
@@ -246,3 +314,132 @@ pub fn sample_f32_to_i16(sample: f32) -> i16 {
 pub fn sample_f32_to_u16(sample: f32) -> u16 {
     (((sample + 1.0) * 0.5) * ::std::u16::MAX as f32).round() as u16
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use assets::prelude::AudioClipHandle;
+    use crayon::utils::handle::HandleLike;
+
+    fn clip(sample_rate: u32) -> Arc<AudioClip> {
+        Arc::new(AudioClip {
+            pcm: vec![::std::i16::MAX / 2; 1024],
+            channels: 1,
+            sample_rate: sample_rate,
+        })
+    }
+
+    #[test]
+    fn panning() {
+        let mut sampler = Sampler::new(2, 44100);
+        sampler.set_listener(Vector3::new(0.0, 0.0, 0.0));
+
+        let mut source = AudioSource::from(AudioClipHandle::default());
+        let mut attenuation = AudioSourceAttenuation::new(1.0, 0.5);
+        attenuation.position = Vector3::new(-4.0, 0.0, 0.0);
+        source.attenuation = Some(attenuation);
+
+        sampler.create_source(AudioSourceHandle::new(0, 1), source, clip(44100));
+
+        let left = sampler.sample();
+        let right = sampler.sample();
+        assert!(left > right);
+        assert!(left > 0.0);
+        assert!(right.abs() < ::std::f32::EPSILON);
+    }
+
+    #[test]
+    fn pause() {
+        let mut sampler = Sampler::new(2, 44100);
+        let handle = AudioSourceHandle::new(0, 1);
+        let source = AudioSource::from(AudioClipHandle::default());
+        sampler.create_source(handle, source, clip(44100));
+
+        let cursor = |sampler: &Sampler| sampler.samplers[0].as_ref().unwrap().iter;
+
+        for _ in 0..64 {
+            sampler.sample();
+        }
+
+        sampler.update(vec![Command::Pause(handle), Command::Pause(handle)].into_iter());
+        let paused = cursor(&sampler);
+        assert!(paused > 0.0);
+
+        for _ in 0..4 {
+            for _ in 0..128 {
+                assert_eq!(sampler.sample(), 0.0);
+            }
+        }
+
+        assert_eq!(cursor(&sampler), paused);
+
+        sampler.update(vec![Command::Resume(handle)].into_iter());
+        for _ in 0..64 {
+            sampler.sample();
+        }
+
+        assert!(cursor(&sampler) > paused);
+
+        // Pausing a stopped source has no effects.
+        sampler.update(vec![Command::DeleteSource(handle), Command::Pause(handle)].into_iter());
+        assert!(sampler.samplers[0].is_none());
+    }
+
+    #[test]
+    fn position() {
+        let mut sampler = Sampler::new(2, 44100);
+        let handle = AudioSourceHandle::new(0, 1);
+        let mut source = AudioSource::from(AudioClipHandle::default());
+        source.loops = AudioSourceWrap::Infinite;
+        sampler.create_source(handle, source, clip(44100));
+
+        let mut positions = FastHashMap::default();
+        sampler.positions(&mut positions);
+        assert_eq!(positions.get(&handle), Some(&Duration::from_millis(0)));
+        assert_eq!(positions.get(&AudioSourceHandle::new(0, 3)), None);
+
+        // Mixes 10 milliseconds of stereo samples.
+        for _ in 0..(441 * 2) {
+            sampler.sample();
+        }
+
+        sampler.positions(&mut positions);
+        let position = positions[&handle];
+        assert!(position >= Duration::from_micros(9_900));
+        assert!(position <= Duration::from_micros(10_100));
+
+        // Skips 10 milliseconds without output devices.
+        sampler.skip(441);
+        sampler.positions(&mut positions);
+        let position = positions[&handle];
+        assert!(position >= Duration::from_micros(19_900));
+        assert!(position <= Duration::from_micros(20_100));
+
+        sampler.update(vec![Command::DeleteSource(handle)].into_iter());
+        sampler.positions(&mut positions);
+        assert_eq!(positions.get(&handle), None);
+    }
+
+    #[test]
+    fn resample() {
+        let mut sampler = Sampler::new(2, 48000);
+        let handle = AudioSourceHandle::new(0, 1);
+        let source = AudioSource::from(AudioClipHandle::default());
+        sampler.create_source(handle, source, clip(22050));
+
+        // Mixes 10 milliseconds of stereo samples at 48kHz.
+        for _ in 0..(480 * 2) {
+            sampler.sample();
+        }
+
+        // The clip should be played in real time without pitch changes.
+        let mut positions = FastHashMap::default();
+        sampler.positions(&mut positions);
+        let position = positions[&handle];
+        assert!(position >= Duration::from_micros(9_900));
+        assert!(position <= Duration::from_micros(10_100));
+
+        let iter = sampler.samplers[0].as_ref().unwrap().iter;
+        assert!((iter - 220.5).abs() < 0.01);
+    }
+}
@@ -1,25 +1,37 @@
 use std::cell::RefCell;
 use std::rc::Rc;
 use std::sync::{Arc, RwLock};
+use std::time::Duration;
 
 use crayon::errors::Result;
+use crayon::utils::prelude::FastHashMap;
 use wasm_bindgen::prelude::*;
 use wasm_bindgen::JsCast;
 use web_sys::{AudioContext, AudioProcessingEvent};
 
+use source::AudioSourceHandle;
+
 use super::sampler::Sampler;
-use super::Command;
+use super::{Command, OutputConfig};
 
 const CHANNELS: u8 = 2;
 
-pub fn run(rx: Arc<RwLock<Vec<Command>>>) -> Result<()> {
+pub fn run(
+    rx: Arc<RwLock<Vec<Command>>>,
+    positions: Arc<RwLock<FastHashMap<AudioSourceHandle, Duration>>>,
+) -> Result<OutputConfig> {
     info!("Create web audio mixer.",);
 
     let ctx = AudioContext::new().unwrap();
 
     let closure = Rc::new(RefCell::new(None));
     let clone = closure.clone();
-    let mut sampler = Sampler::new(CHANNELS, ctx.sample_rate() as u32);
+    let config = OutputConfig {
+        sample_rate: ctx.sample_rate() as u32,
+        channels: CHANNELS,
+    };
+
+    let mut sampler = Sampler::new(config.channels, config.sample_rate);
 
     let mut bufs = Vec::new();
     for _ in 0..CHANNELS {
@@ -51,6 +63,10 @@ pub fn run(rx: Arc<RwLock<Vec<Command>>>) -> Result<()> {
         for (i, mut buf) in bufs.iter_mut().enumerate() {
             buffer.copy_to_channel(&mut buf, i as i32).unwrap();
         }
+
+        if let Ok(mut positions) = positions.try_write() {
+            sampler.positions(&mut positions);
+        }
     }) as Box<FnMut(_)>));
 
     let source = ctx.create_buffer_source().unwrap();
@@ -67,5 +83,5 @@ pub fn run(rx: Arc<RwLock<Vec<Command>>>) -> Result<()> {
         .unwrap();
 
     source.start().unwrap();
-    Ok(())
+    Ok(config)
 }
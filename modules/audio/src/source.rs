@@ -37,6 +37,19 @@ pub enum AudioSourceWrap {
     Infinite,
 }
 
+/// The curve used to attenuate the volume of sound by distance.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum AudioSourceAttenuationModel {
+    /// The volume decreases by `attenuation` per unit of distance beyond the
+    /// minimum distance, until it reaches zero.
+    Linear,
+    /// The volume is inversely proportional to the distance.
+    Inverse,
+    /// The volume decreases exponentially with the ratio of distance to the
+    /// minimum distance, using `attenuation` as the exponent.
+    Exponential,
+}
+
 #[derive(Debug, Copy, Clone)]
 pub struct AudioSourceAttenuation {
     /// Set the emiiter position of playing sound.
@@ -49,6 +62,8 @@ pub struct AudioSourceAttenuation {
     ///
     /// To get a non-attenuated sound, you can use 0.
     pub attenuation: f32,
+    /// The attenuation curve, defaults to `AudioSourceAttenuationModel::Inverse`.
+    pub model: AudioSourceAttenuationModel,
 }
 
 impl AudioSourceAttenuation {
@@ -59,9 +74,11 @@ impl AudioSourceAttenuation {
             position: Vector3::new(0.0, 0.0, 0.0),
             minimum_distance: minimum_distance,
             attenuation: attenuation,
+            model: AudioSourceAttenuationModel::Inverse,
         }
     }
 
+    /// Gets the volume factor of sound heard by the listener.
     pub fn volume<T>(&self, listener: T) -> f32
     where
         T: Into<Vector3<f32>>,
@@ -72,7 +89,38 @@ impl AudioSourceAttenuation {
             .magnitude()
             .max(self.minimum_distance);
 
-        let attenuation = self.attenuation * (distance - self.minimum_distance);
-        self.minimum_distance / (self.minimum_distance + attenuation)
+        match self.model {
+            AudioSourceAttenuationModel::Linear => {
+                let attenuation = self.attenuation * (distance - self.minimum_distance);
+                (1.0 - attenuation).max(0.0)
+            }
+            AudioSourceAttenuationModel::Inverse => {
+                let attenuation = self.attenuation * (distance - self.minimum_distance);
+                self.minimum_distance / (self.minimum_distance + attenuation)
+            }
+            AudioSourceAttenuationModel::Exponential => {
+                (distance / self.minimum_distance).powf(-self.attenuation)
+            }
+        }
+    }
+
+    /// Gets the stereo panning of sound heard by the listener, ranges from -1.0 (hard
+    /// left) to 1.0 (hard right).
+    ///
+    /// The listener is assumed to be facing the positive z-axis, with the positive
+    /// x-axis on its right hand side.
+    pub fn panning<T>(&self, listener: T) -> f32
+    where
+        T: Into<Vector3<f32>>,
+    {
+        use crayon::math::prelude::InnerSpace;
+
+        let dir = self.position - listener.into();
+        let distance = dir.magnitude();
+        if distance <= ::std::f32::EPSILON {
+            0.0
+        } else {
+            (dir.x / distance).min(1.0).max(-1.0)
+        }
     }
 }
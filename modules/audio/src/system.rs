@@ -1,4 +1,5 @@
 use std::sync::{Arc, RwLock};
+use std::time::Duration;
 
 use crayon::application::prelude::{LifecycleListener, LifecycleListenerHandle};
 use crayon::errors::Result;
@@ -7,7 +8,7 @@ use crayon::res::utils::prelude::{ResourcePool, ResourceState};
 use crayon::uuid::Uuid;
 
 use super::assets::prelude::{AudioClipHandle, AudioClipLoader};
-use super::mixer::Mixer;
+use super::mixer::{Mixer, OutputConfig};
 use super::source::{AudioSource, AudioSourceHandle};
 
 /// The centralized management of audio sub-system.
@@ -54,6 +55,12 @@ impl AudioSystem {
         })
     }
 
+    /// Gets the output format of audio device.
+    #[inline]
+    pub fn output_config(&self) -> OutputConfig {
+        self.mixer.output_config()
+    }
+
     /// Sets the position of listener.
     #[inline]
     pub fn set_listener<T>(&self, position: T)
@@ -80,6 +87,16 @@ impl AudioSystem {
         self.clips.read().unwrap().state(handle)
     }
 
+    /// Gets the length of a loaded `AudioClip`.
+    #[inline]
+    pub fn duration(&self, handle: AudioClipHandle) -> Option<Duration> {
+        self.clips
+            .read()
+            .unwrap()
+            .resource(handle)
+            .map(|v| v.duration())
+    }
+
     /// Deletes a `AudioClip` resource from `AudioSystem`.
     #[inline]
     pub fn delete_clip(&self, handle: AudioClipHandle) {
@@ -101,6 +118,25 @@ impl AudioSystem {
         self.mixer.delete_source(handle);
     }
 
+    /// Gets the current playback position of a playing audio source.
+    #[inline]
+    pub fn position(&self, handle: AudioSourceHandle) -> Option<Duration> {
+        self.mixer.position(handle)
+    }
+
+    /// Pauses a played audio source, the playback position is kept until it
+    /// gets resumed.
+    #[inline]
+    pub fn pause(&self, handle: AudioSourceHandle) {
+        self.mixer.pause(handle);
+    }
+
+    /// Resumes a paused audio source.
+    #[inline]
+    pub fn resume(&self, handle: AudioSourceHandle) {
+        self.mixer.resume(handle);
+    }
+
     /// Sets the emiiter position of playing sound.
     #[inline]
     pub fn set_position<T>(&self, handle: AudioSourceHandle, position: T)
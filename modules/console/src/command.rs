@@ -0,0 +1,115 @@
+//! The registry of console commands.
+
+use std::collections::BTreeMap;
+
+use failure::Error;
+
+pub type CommandHandler = Box<dyn FnMut(&[&str]) -> Result<String, Error>>;
+
+struct Command {
+    help: String,
+    handler: CommandHandler,
+}
+
+/// A set of named command handlers.
+#[derive(Default)]
+pub struct Commands {
+    commands: BTreeMap<String, Command>,
+}
+
+impl Commands {
+    pub fn new() -> Self {
+        Commands {
+            commands: BTreeMap::new(),
+        }
+    }
+
+    /// Registers a command handler, it replaces the previous one with the same name.
+    pub fn register<T1, T2, T3>(&mut self, name: T1, help: T2, handler: T3)
+    where
+        T1: Into<String>,
+        T2: Into<String>,
+        T3: FnMut(&[&str]) -> Result<String, Error> + 'static,
+    {
+        let cmd = Command {
+            help: help.into(),
+            handler: Box::new(handler),
+        };
+
+        self.commands.insert(name.into(), cmd);
+    }
+
+    /// Removes a command handler.
+    pub fn unregister(&mut self, name: &str) -> bool {
+        self.commands.remove(name).is_some()
+    }
+
+    /// Returns true if there is a command with the name.
+    #[inline]
+    pub fn contains(&self, name: &str) -> bool {
+        self.commands.contains_key(name)
+    }
+
+    /// Gets the names and descriptions of registered commands in alphabetical order.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.commands
+            .iter()
+            .map(|(k, v)| (k.as_str(), v.help.as_str()))
+    }
+
+    /// Parses a command line and invokes the handler of its first word with the rest
+    /// arguments.
+    pub fn execute(&mut self, line: &str) -> Result<String, Error> {
+        let args = parse(line)?;
+        if args.is_empty() {
+            return Ok(String::new());
+        }
+
+        let cmd = self
+            .commands
+            .get_mut(&args[0])
+            .ok_or_else(|| format_err!("Unknown command {}.", args[0]))?;
+
+        let args: Vec<&str> = args[1..].iter().map(|v| v.as_str()).collect();
+        (cmd.handler)(&args)
+    }
+}
+
+/// Splits a command line into whitespace-separated words. Words could be quoted
+/// with `"` to include whitespaces.
+pub fn parse(line: &str) -> Result<Vec<String>, Error> {
+    let mut args = Vec::new();
+    let mut word = String::new();
+    let mut quoted = false;
+    let mut pending = false;
+
+    for c in line.chars() {
+        match c {
+            '"' => {
+                quoted = !quoted;
+                pending = true;
+            }
+            c if c.is_whitespace() && !quoted => {
+                if pending {
+                    args.push(word.clone());
+                    word.clear();
+                    pending = false;
+                }
+            }
+            c => {
+                word.push(c);
+                pending = true;
+            }
+        }
+    }
+
+    if quoted {
+        bail!("Unterminated quote in command {}.", line);
+    }
+
+    if pending {
+        args.push(word);
+    }
+
+    Ok(args)
+}
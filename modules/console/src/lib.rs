@@ -0,0 +1,139 @@
+//! A developer console that captures `log` output, reports frame statistics and
+//! dispatches typed commands to registered handlers.
+//!
+//! ```rust,ignore
+//! extern crate crayon;
+//! extern crate crayon_console;
+//!
+//! use crayon::application::logger::LevelFilter;
+//! use crayon_console::Console;
+//!
+//! let mut console = Console::new(128);
+//! console.logger(LevelFilter::Info).install().unwrap();
+//!
+//! console.register("echo", "Prints the arguments.", |args| Ok(args.join(" ")));
+//! console.execute("echo hello world").unwrap();
+//! ```
+//!
+//! The console does not render itself, the lines could be drawn with whatever UI
+//! the application uses.
+
+extern crate crayon;
+#[macro_use]
+extern crate failure;
+
+pub mod command;
+
+pub mod prelude {
+    pub use command::{CommandHandler, Commands};
+    pub use Console;
+}
+
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+
+use crayon::application;
+use crayon::application::logger::{LevelFilter, Logger};
+use failure::Error;
+
+use command::Commands;
+
+/// The developer console.
+pub struct Console {
+    commands: Commands,
+    lines: Arc<Mutex<VecDeque<String>>>,
+    capacity: usize,
+}
+
+impl Console {
+    /// Creates a new `Console` which keeps the latest `capacity` lines.
+    pub fn new(capacity: usize) -> Self {
+        Console {
+            commands: Commands::new(),
+            lines: Arc::new(Mutex::new(VecDeque::with_capacity(capacity))),
+            capacity: capacity,
+        }
+    }
+
+    /// Creates a `Logger` that forwards the accepted records into this console. It
+    /// could be configured further before installing.
+    pub fn logger(&self, level: LevelFilter) -> Logger {
+        let lines = self.lines.clone();
+        let capacity = self.capacity;
+
+        Logger::new(level).with_callback(move |record| {
+            let line = format!("[{}] {}", record.level, record.message);
+            push(&mut lines.lock().unwrap(), capacity, line);
+        })
+    }
+
+    /// Registers a command handler with the name and the description.
+    pub fn register<T1, T2, T3>(&mut self, name: T1, help: T2, handler: T3)
+    where
+        T1: Into<String>,
+        T2: Into<String>,
+        T3: FnMut(&[&str]) -> Result<String, Error> + 'static,
+    {
+        self.commands.register(name, help, handler);
+    }
+
+    /// Gets the registered commands.
+    #[inline]
+    pub fn commands(&self) -> &Commands {
+        &self.commands
+    }
+
+    /// Parses and executes a command line. The input and the output of command are
+    /// both appended to the console.
+    pub fn execute(&mut self, line: &str) -> Result<String, Error> {
+        self.push(format!("> {}", line));
+
+        match self.commands.execute(line) {
+            Ok(output) => {
+                if !output.is_empty() {
+                    self.push(output.clone());
+                }
+
+                Ok(output)
+            }
+            Err(err) => {
+                self.push(format!("{}", err));
+                Err(err)
+            }
+        }
+    }
+
+    /// Appends a line to the console.
+    pub fn push<T: Into<String>>(&self, line: T) {
+        push(&mut self.lines.lock().unwrap(), self.capacity, line.into());
+    }
+
+    /// Gets a snapshot of the lines in the console.
+    pub fn lines(&self) -> Vec<String> {
+        self.lines.lock().unwrap().iter().cloned().collect()
+    }
+
+    /// Removes all the lines.
+    pub fn clear(&self) {
+        self.lines.lock().unwrap().clear();
+    }
+
+    /// Formats the statistics of last frame, e.g. `FPS: 60 (16.67ms)`.
+    pub fn stats(&self) -> String {
+        let duration = application::real_frame_duration();
+        let ms = duration.as_secs() as f32 * 1000.0 + duration.subsec_nanos() as f32 / 1_000_000.0;
+        format!("FPS: {} ({:.2}ms)", application::fps(), ms)
+    }
+}
+
+fn push(lines: &mut VecDeque<String>, capacity: usize, line: String) {
+    if capacity == 0 {
+        return;
+    }
+
+    while lines.len() >= capacity {
+        lines.pop_front();
+    }
+
+    lines.push_back(line);
+}
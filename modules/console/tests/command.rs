@@ -0,0 +1,48 @@
+extern crate crayon_console;
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use crayon_console::command;
+use crayon_console::Console;
+
+#[test]
+fn parse() {
+    assert_eq!(command::parse("").unwrap(), Vec::<String>::new());
+    assert_eq!(command::parse("  a  b ").unwrap(), vec!["a", "b"]);
+    assert_eq!(
+        command::parse("a \"b c\" \"\"").unwrap(),
+        vec!["a", "b c", ""]
+    );
+    assert!(command::parse("a \"b").is_err());
+}
+
+#[test]
+fn execute() {
+    let invoked = Rc::new(RefCell::new(Vec::new()));
+    let clone = invoked.clone();
+
+    let mut console = Console::new(4);
+    console.register("spawn", "Spawns a prefab.", move |args| {
+        let args: Vec<String> = args.iter().map(|v| v.to_string()).collect();
+        clone.borrow_mut().push(args);
+        Ok("spawned".to_owned())
+    });
+
+    assert!(console.commands().contains("spawn"));
+    assert_eq!(
+        console.commands().iter().collect::<Vec<_>>(),
+        vec![("spawn", "Spawns a prefab.")]
+    );
+
+    assert_eq!(console.execute("spawn cube \"a b\" 3").unwrap(), "spawned");
+    assert_eq!(*invoked.borrow(), vec![vec!["cube", "a b", "3"]]);
+    assert_eq!(console.lines(), vec!["> spawn cube \"a b\" 3", "spawned"]);
+
+    assert!(console.execute("despawn cube").is_err());
+    assert_eq!(invoked.borrow().len(), 1);
+
+    // Only the latest 4 lines are kept.
+    assert_eq!(console.lines().len(), 4);
+    assert_eq!(console.lines()[3], "Unknown command despawn.");
+}
@@ -6,5 +6,5 @@ pub mod texture_builder;
 
 pub mod prelude {
     pub use super::prefab::{Prefab, PrefabHandle};
-    pub use super::prefab_loader::PrefabLoader;
+    pub use super::prefab_loader::{PrefabLoader, PrefabMigration};
 }
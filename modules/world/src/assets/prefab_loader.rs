@@ -1,22 +1,67 @@
 use std::io::Cursor;
-use std::sync::Arc;
+use std::sync::{Arc, RwLock};
 
 use crayon::errors::Result;
 use crayon::res::utils::prelude::ResourceLoader;
+use crayon::utils::prelude::FastHashMap;
 use crayon::{bincode, video};
 
 use super::prefab::*;
 
+/// The version of serialized prefab layout, which is stored in the last three bytes
+/// of `MAGIC`.
+pub const VERSION: u32 = 1;
+
 pub const MAGIC: [u8; 8] = [
     'P' as u8, 'R' as u8, 'E' as u8, 'B' as u8, ' ' as u8, 0, 0, 1,
 ];
 
+/// The migration hook that decodes a prefab serialized with an outdated layout.
+pub type PrefabMigration = fn(&[u8]) -> Result<Prefab>;
+
 #[derive(Clone)]
-pub struct PrefabLoader {}
+pub struct PrefabLoader {
+    migrations: Arc<RwLock<FastHashMap<u32, PrefabMigration>>>,
+}
 
 impl PrefabLoader {
     pub fn new() -> Self {
-        PrefabLoader {}
+        PrefabLoader {
+            migrations: Arc::new(RwLock::new(FastHashMap::default())),
+        }
+    }
+
+    /// Registers a migration hook for prefabs serialized with `version`.
+    pub fn add_migration(&self, version: u32, migration: PrefabMigration) {
+        assert!(
+            version != VERSION,
+            "The current version needs no migration."
+        );
+        self.migrations.write().unwrap().insert(version, migration);
+    }
+
+    fn decode(&self, bytes: &[u8]) -> Result<Prefab> {
+        if bytes.len() < MAGIC.len() || bytes[0..5] != MAGIC[0..5] {
+            bail!("[PrefabLoader] MAGIC number not match.");
+        }
+
+        let version =
+            (u32::from(bytes[5]) << 16) | (u32::from(bytes[6]) << 8) | u32::from(bytes[7]);
+
+        if version == VERSION {
+            let mut file = Cursor::new(&bytes[8..]);
+            return Ok(bincode::deserialize_from(&mut file)?);
+        }
+
+        if let Some(migration) = self.migrations.read().unwrap().get(&version) {
+            return migration(&bytes[8..]);
+        }
+
+        bail!(
+            "[PrefabLoader] prefab version {} is not supported (expects {}), and there is no migration registered for it.",
+            version,
+            VERSION
+        );
     }
 }
 
@@ -26,12 +71,7 @@ impl ResourceLoader for PrefabLoader {
     type Resource = Arc<Prefab>;
 
     fn load(&self, handle: Self::Handle, bytes: &[u8]) -> Result<Self::Intermediate> {
-        if &bytes[0..8] != &MAGIC[..] {
-            bail!("[PrefabLoader] MAGIC number not match.");
-        }
-
-        let mut file = Cursor::new(&bytes[8..]);
-        let mut prefab: Prefab = bincode::deserialize_from(&mut file)?;
+        let mut prefab = self.decode(bytes)?;
 
         for &v in &prefab.universe_meshes {
             let mesh = video::create_mesh_from_uuid(v)?;
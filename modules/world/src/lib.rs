@@ -17,7 +17,7 @@ pub mod utils;
 pub mod prelude {
     pub use super::assets::prelude::*;
     pub use super::renderable::prelude::*;
-    pub use super::scene::Scene;
+    pub use super::scene::{EntityBuilder, Scene, SceneComponent};
     pub use super::spatial::prelude::*;
     pub use super::Entity;
 }
@@ -30,7 +30,7 @@ pub use self::system::WorldDefaultResources;
 use crayon::res::utils::prelude::ResourceState;
 use std::sync::Arc;
 
-use self::assets::prelude::{Prefab, PrefabHandle};
+use self::assets::prelude::{Prefab, PrefabHandle, PrefabMigration};
 use self::inside::ctx;
 
 pub type Result<T> = ::std::result::Result<T, failure::Error>;
@@ -57,6 +57,15 @@ pub fn create_prefab_from<T: AsRef<str>>(url: T) -> Result<PrefabHandle> {
     ctx().create_prefab_from(url)
 }
 
+/// Registers a migration hook for prefabs serialized with an outdated `version`.
+///
+/// Prefabs are tagged with the version of their layout. The loader rejects prefabs
+/// with mismatched versions unless there is a migration registered for it.
+#[inline]
+pub fn add_prefab_migration(version: u32, migration: PrefabMigration) {
+    ctx().add_prefab_migration(version, migration);
+}
+
 /// Return the prefab obejct if exists.
 #[inline]
 pub fn prefab(handle: PrefabHandle) -> Option<Arc<Prefab>> {
@@ -82,42 +91,37 @@ pub fn default() -> WorldDefaultResources {
 }
 
 mod inside {
+    use crayon::utils::global::Global;
+
     use super::system::WorldSystem;
 
-    static mut CTX: *const WorldSystem = std::ptr::null();
+    static CTX: Global<WorldSystem> = Global::new();
 
     #[inline]
     pub fn ctx() -> &'static WorldSystem {
-        unsafe {
-            debug_assert!(
-                !CTX.is_null(),
-                "world system has not been initialized properly."
-            );
-
-            &*CTX
-        }
+        CTX.get()
+            .expect("world system has not been initialized properly.")
     }
 
-    /// Setup the world system.
+    /// Setup the world system. It fails if the world system has been set up already.
     pub fn setup() -> Result<(), failure::Error> {
-        unsafe {
-            debug_assert!(CTX.is_null(), "duplicated setup of world system.");
+        if !CTX.is_null() {
+            bail!("duplicated setup of world system.");
+        }
 
-            let ctx = WorldSystem::new()?;
-            CTX = Box::into_raw(Box::new(ctx));
-            Ok(())
+        let ctx = WorldSystem::new()?;
+        if CTX.set(ctx).is_err() {
+            bail!("duplicated setup of world system.");
         }
+
+        crayon::application::register_module("world", discard);
+        Ok(())
     }
 
     /// Discard the world system.
     pub fn discard() {
         unsafe {
-            if CTX.is_null() {
-                return;
-            }
-
-            drop(Box::from_raw(CTX as *mut WorldSystem));
-            CTX = std::ptr::null();
+            CTX.discard();
         }
     }
 }
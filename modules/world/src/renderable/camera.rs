@@ -1,7 +1,7 @@
 //! A device through which the player views the world.
 
 use crayon::math::prelude::*;
-use crayon::video::assets::surface::SurfaceHandle;
+use crayon::video::assets::surface::{SurfaceClear, SurfaceHandle};
 
 use spatial::prelude::Transform;
 
@@ -10,6 +10,8 @@ use spatial::prelude::Transform;
 pub struct Camera {
     frustum: Frustum<f32>,
     surface: Option<SurfaceHandle>,
+    clear: Option<SurfaceClear>,
+    culling_mask: u32,
 
     #[doc(hidden)]
     pub(crate) transform: Transform,
@@ -34,6 +36,8 @@ impl Camera {
         Camera {
             frustum: Frustum::new(projection),
             surface: None,
+            clear: None,
+            culling_mask: !0,
             transform: Transform::default(),
         }
     }
@@ -79,6 +83,50 @@ impl Camera {
         self.surface
     }
 
+    /// Sets the clear flags, which overrides the ones of surface before this camera
+    /// draws. Notes that a surface is cleared only once per frame, so cameras that
+    /// share the same surface could not clear it with different flags.
+    pub fn set_clear<C, D, S>(&mut self, color: C, depth: D, stencil: S)
+    where
+        C: Into<Option<Color<f32>>>,
+        D: Into<Option<f32>>,
+        S: Into<Option<i32>>,
+    {
+        self.clear = Some((color.into(), depth.into(), stencil.into()));
+    }
+
+    /// Removes the clear flags. The surface is restored to the flags it had before this
+    /// camera overrode them, unless they have been changed elsewhere since.
+    #[inline]
+    pub fn remove_clear(&mut self) {
+        self.clear = None;
+    }
+
+    /// Gets the clear flags of color, depth and stencil buffers.
+    #[inline]
+    pub fn clear(&self) -> Option<SurfaceClear> {
+        self.clear
+    }
+
+    /// Sets the culling mask. The camera only draws the meshes whose `layer` intersects
+    /// with this mask. Initially all the layers are drawn.
+    #[inline]
+    pub fn set_culling_mask(&mut self, mask: u32) {
+        self.culling_mask = mask;
+    }
+
+    /// Gets the culling mask.
+    #[inline]
+    pub fn culling_mask(&self) -> u32 {
+        self.culling_mask
+    }
+
+    /// Checks if the meshes in `layer` are drawn by this camera.
+    #[inline]
+    pub fn is_layer_visible(&self, layer: u32) -> bool {
+        self.culling_mask & layer != 0
+    }
+
     /// Sets the near/far clipping plane distances.
     #[inline]
     pub fn set_clip_plane(&mut self, near: f32, far: f32) {
@@ -0,0 +1,405 @@
+//! Line overlays for debugging spatial hierarchies. Only available with the `debug`
+//! feature, so it costs nothing in release builds.
+
+use crayon::math::prelude::{Aabb3, Color, EuclideanSpace, Vector3};
+use crayon::video;
+use crayon::video::assets::prelude::*;
+use crayon::video::prelude::{Draw, DrawCommandBuffer};
+use failure::Error;
+
+use inside::ctx;
+use renderable::prelude::{Camera, Renderer};
+use scene::Scene;
+use spatial::prelude::{SceneGraph, Transform};
+use Entity;
+
+impl_vertex!{
+    DebugVertex {
+        position => [Position; Float; 3; false],
+    }
+}
+
+const VS: &str = "
+    #version 100
+    precision lowp float;
+
+    attribute vec3 Position;
+    uniform mat4 u_MVPMatrix;
+
+    void main() {
+        gl_Position = u_MVPMatrix * vec4(Position, 1.0);
+    }
+";
+
+const FS: &str = "
+    #version 100
+    precision lowp float;
+
+    uniform vec4 u_Color;
+
+    void main() {
+        gl_FragColor = u_Color;
+    }
+";
+
+/// Draws the parent-child links and the bounding boxes of visible meshes of `scene`,
+/// from the view of its first camera. It draws with a `DebugRenderer` that is shared
+/// by the world system, and fails if the scene does not have any camera.
+pub fn draw_scene_graph<R: Renderer>(
+    scene: &Scene<R>,
+    surface: SurfaceHandle,
+) -> Result<(), Error> {
+    let camera = *scene
+        .entities_with_camera()
+        .first()
+        .ok_or_else(|| format_err!("The scene does not have any camera."))?;
+
+    ctx().debug_renderer(|v| v.draw_scene_graph(scene, camera, surface))
+}
+
+/// The edges of a box, as pairs of indices into `Aabb3::to_corners`.
+const AABB_EDGES: [(usize, usize); 12] = [
+    (0, 1),
+    (2, 3),
+    (4, 5),
+    (6, 7),
+    (0, 2),
+    (1, 3),
+    (4, 6),
+    (5, 7),
+    (0, 4),
+    (1, 5),
+    (2, 6),
+    (3, 7),
+];
+
+/// A list of line segments in world space.
+#[derive(Debug, Clone, Default)]
+pub struct DebugLines {
+    segments: Vec<(Vector3<f32>, Vector3<f32>)>,
+}
+
+impl DebugLines {
+    pub fn new() -> Self {
+        DebugLines {
+            segments: Vec::new(),
+        }
+    }
+
+    /// Removes all the line segments.
+    #[inline]
+    pub fn clear(&mut self) {
+        self.segments.clear();
+    }
+
+    /// Returns the number of line segments.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.segments.len()
+    }
+
+    /// Returns true if there is no line segments.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.segments.is_empty()
+    }
+
+    /// Gets the line segments.
+    #[inline]
+    pub fn segments(&self) -> &[(Vector3<f32>, Vector3<f32>)] {
+        &self.segments
+    }
+
+    /// Adds a line segment.
+    #[inline]
+    pub fn add_line<T1, T2>(&mut self, from: T1, to: T2)
+    where
+        T1: Into<Vector3<f32>>,
+        T2: Into<Vector3<f32>>,
+    {
+        self.segments.push((from.into(), to.into()));
+    }
+
+    /// Adds the 12 edges of a bounding box, which is defined in the local space
+    /// of `transform`.
+    pub fn add_aabb(&mut self, aabb: Aabb3<f32>, transform: &Transform) {
+        let corners = aabb.to_corners();
+        for &(a, b) in &AABB_EDGES {
+            let from = transform.transform_point(corners[a].to_vec());
+            let to = transform.transform_point(corners[b].to_vec());
+            self.segments.push((from, to));
+        }
+    }
+
+    /// Adds a grid on the XZ plane that covers `[-size, size]` on both axes, with a
+    /// line every `spacing` units. The two lines across the origin are added first, so
+    /// they could be emphasized.
+    pub fn add_grid(&mut self, size: f32, spacing: f32) {
+        if size <= 0.0 || spacing <= 0.0 {
+            return;
+        }
+
+        let n = (size / spacing).floor() as i32;
+        let mut offsets = vec![0.0];
+        for i in 1..=n {
+            offsets.push(i as f32 * spacing);
+            offsets.push(-(i as f32) * spacing);
+        }
+
+        for &v in &offsets {
+            self.segments
+                .push((Vector3::new(-size, 0.0, v), Vector3::new(size, 0.0, v)));
+            self.segments
+                .push((Vector3::new(v, 0.0, -size), Vector3::new(v, 0.0, size)));
+        }
+    }
+
+    /// Adds a link from each node to its parent in scene graph.
+    pub fn add_scene_graph(&mut self, sg: &SceneGraph) {
+        for &root in &sg.roots {
+            for ent in sg.descendants(root) {
+                let parent = sg.parent(ent).unwrap();
+                let from = sg.position(parent).unwrap();
+                let to = sg.position(ent).unwrap();
+                self.segments.push((from, to));
+            }
+        }
+    }
+}
+
+/// Draws `DebugLines` with a simple unlit line shader.
+pub struct DebugRenderer {
+    shader: ShaderHandle,
+    mesh: Option<(MeshHandle, usize)>,
+    grid: Option<(MeshHandle, f32, f32, usize)>,
+    drawcalls: DrawCommandBuffer<u32>,
+
+    pub color: Color<f32>,
+}
+
+impl Drop for DebugRenderer {
+    fn drop(&mut self) {
+        video::delete_shader(self.shader);
+
+        if let Some((mesh, _)) = self.mesh {
+            video::delete_mesh(mesh);
+        }
+
+        if let Some((mesh, _, _, _)) = self.grid {
+            video::delete_mesh(mesh);
+        }
+    }
+}
+
+impl DebugRenderer {
+    /// Creates a new `DebugRenderer`.
+    pub fn new() -> Result<Self, Error> {
+        let attributes = AttributeLayout::build()
+            .with(Attribute::Position, 3)
+            .finish();
+
+        let uniforms = UniformVariableLayout::build()
+            .with("u_MVPMatrix", UniformVariableType::Matrix4f)
+            .with("u_Color", UniformVariableType::Vector4f)
+            .finish();
+
+        let mut params = ShaderParams::default();
+        params.attributes = attributes;
+        params.uniforms = uniforms;
+
+        let shader = video::create_shader(params, VS.to_owned(), FS.to_owned())?;
+
+        Ok(DebugRenderer {
+            shader: shader,
+            mesh: None,
+            grid: None,
+            drawcalls: DrawCommandBuffer::new(),
+            color: Color::green(),
+        })
+    }
+
+    /// Draws the line segments from the view of `camera`.
+    pub fn draw(
+        &mut self,
+        lines: &DebugLines,
+        camera: &Camera,
+        surface: SurfaceHandle,
+    ) -> Result<(), Error> {
+        if lines.is_empty() {
+            return Ok(());
+        }
+
+        let mesh = self.prepare(lines.len() * 2)?;
+
+        let mut verts = Vec::with_capacity(lines.len() * 2);
+        for &(from, to) in lines.segments() {
+            verts.push(DebugVertex::new(from.into()));
+            verts.push(DebugVertex::new(to.into()));
+        }
+
+        video::update_vertex_buffer(mesh, 0, DebugVertex::encode(&verts))?;
+
+        let view_matrix = camera.transform.view_matrix();
+        let projection_matrix = camera.frustum().to_matrix();
+
+        let mut dc = Draw::new(self.shader, mesh);
+        dc.set_uniform_variable("u_MVPMatrix", projection_matrix * view_matrix);
+        dc.set_uniform_variable("u_Color", self.color.rgba());
+        dc.mesh_index = MeshIndex::Ptr(0, verts.len());
+
+        self.drawcalls.draw(0, dc);
+        self.drawcalls.submit(surface)?;
+        Ok(())
+    }
+
+    /// Draws a reference grid on the XZ plane with `DebugLines::add_grid`, from the view
+    /// of `camera`. The X and Z axes across the origin are emphasized with red and blue.
+    ///
+    /// The grid mesh is cached, and only rebuilt when `size` or `spacing` changes.
+    pub fn draw_grid(
+        &mut self,
+        camera: &Camera,
+        surface: SurfaceHandle,
+        size: f32,
+        spacing: f32,
+        color: Color<f32>,
+    ) -> Result<(), Error> {
+        let (mesh, num_verts) = match self.grid {
+            Some((mesh, s, p, num_verts)) if s == size && p == spacing => (mesh, num_verts),
+            _ => {
+                if let Some((mesh, _, _, _)) = self.grid.take() {
+                    video::delete_mesh(mesh);
+                }
+
+                let mut lines = DebugLines::new();
+                lines.add_grid(size, spacing);
+                if lines.is_empty() {
+                    return Ok(());
+                }
+
+                let mesh = Self::create_grid(&lines)?;
+                self.grid = Some((mesh, size, spacing, lines.len() * 2));
+                (mesh, lines.len() * 2)
+            }
+        };
+
+        let view_matrix = camera.transform.view_matrix();
+        let projection_matrix = camera.frustum().to_matrix();
+        let mvp = projection_matrix * view_matrix;
+
+        // The axes of origin come first, followed by the rest of lines.
+        let ranges = [
+            (0, 2, Color::red()),
+            (2, 2, Color::blue()),
+            (4, num_verts - 4, color),
+        ];
+
+        for &(from, len, color) in &ranges {
+            if len == 0 {
+                continue;
+            }
+
+            let mut dc = Draw::new(self.shader, mesh);
+            dc.set_uniform_variable("u_MVPMatrix", mvp);
+            dc.set_uniform_variable("u_Color", color.rgba());
+            dc.mesh_index = MeshIndex::Ptr(from, len);
+            self.drawcalls.draw(0, dc);
+        }
+
+        self.drawcalls.submit(surface)?;
+        Ok(())
+    }
+
+    /// Draws the parent-child links and the bounding boxes of visible meshes of
+    /// `scene`, from the view of the `camera` entity.
+    pub fn draw_scene_graph<R: Renderer>(
+        &mut self,
+        scene: &Scene<R>,
+        camera: Entity,
+        surface: SurfaceHandle,
+    ) -> Result<(), Error> {
+        let transform = scene.nodes.transform(camera);
+        let mut camera = *scene
+            .renderables
+            .camera(camera)
+            .ok_or_else(|| format_err!("{:?} does not have a camera component.", camera))?;
+
+        camera.transform = transform.unwrap();
+
+        let mut lines = DebugLines::new();
+        lines.add_scene_graph(&scene.nodes);
+
+        for &root in &scene.nodes.roots {
+            for ent in ::std::iter::once(root).chain(scene.nodes.descendants(root)) {
+                if let Some(mesh) = scene.renderables.mesh(ent) {
+                    if !mesh.visible {
+                        continue;
+                    }
+
+                    if let Some(aabb) = video::mesh_aabb(mesh.mesh) {
+                        lines.add_aabb(aabb, &scene.nodes.transform(ent).unwrap());
+                    }
+                }
+            }
+        }
+
+        self.draw(&lines, &camera, surface)
+    }
+
+    fn prepare(&mut self, num_verts: usize) -> Result<MeshHandle, Error> {
+        if let Some((mesh, capacity)) = self.mesh {
+            if capacity >= num_verts {
+                return Ok(mesh);
+            }
+
+            video::delete_mesh(mesh);
+            self.mesh = None;
+        }
+
+        let capacity = num_verts.next_power_of_two();
+        let idxes: Vec<u32> = (0..capacity as u32).collect();
+
+        let mut params = MeshParams::default();
+        params.hint = MeshHint::Stream;
+        params.layout = DebugVertex::layout();
+        params.primitive = MeshPrimitive::Lines;
+        params.set_num_verts(capacity);
+        params.num_idxes = capacity;
+
+        let data = MeshData {
+            vptr: vec![0; params.vertex_buffer_len()].into(),
+            iptr: params.index_format.pack(&idxes)?.into(),
+        };
+
+        let mesh = video::create_mesh(params, data)?;
+        self.mesh = Some((mesh, capacity));
+        Ok(mesh)
+    }
+
+    fn create_grid(lines: &DebugLines) -> Result<MeshHandle, Error> {
+        let mut verts = Vec::with_capacity(lines.len() * 2);
+        for &(from, to) in lines.segments() {
+            verts.push(DebugVertex::new(from.into()));
+            verts.push(DebugVertex::new(to.into()));
+        }
+
+        let idxes: Vec<u32> = (0..verts.len() as u32).collect();
+
+        let mut params = MeshParams {
+            hint: MeshHint::Immutable,
+            layout: DebugVertex::layout(),
+            primitive: MeshPrimitive::Lines,
+            ..Default::default()
+        };
+
+        params.set_num_verts(verts.len());
+        params.num_idxes = idxes.len();
+
+        let data = MeshData {
+            vptr: DebugVertex::encode(&verts[..]).into(),
+            iptr: params.index_format.pack(&idxes)?.into(),
+        };
+
+        video::create_mesh(params, data)
+    }
+}
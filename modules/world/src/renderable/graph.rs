@@ -0,0 +1,111 @@
+use crayon::utils::hash::FastHashMap;
+use crayon::video::assets::prelude::RenderTextureHandle;
+use failure::Error;
+
+/// A render pass in `RenderGraph`, which declares the render textures it reads
+/// from and writes to.
+#[derive(Debug, Clone)]
+pub struct RenderPass {
+    pub name: String,
+    pub reads: Vec<RenderTextureHandle>,
+    pub writes: Vec<RenderTextureHandle>,
+}
+
+/// A lightweight graph of render passes. Instead of ordering passes by hand, the
+/// execution order is resolved from the attachments they read and write, e.g. a pass
+/// sampling the shadow map always runs after the pass rendering into it.
+#[derive(Debug, Clone, Default)]
+pub struct RenderGraph {
+    passes: Vec<RenderPass>,
+}
+
+impl RenderGraph {
+    pub fn new() -> Self {
+        RenderGraph { passes: Vec::new() }
+    }
+
+    /// Adds a render pass, returning its index in this graph.
+    pub fn add_pass<T: Into<String>>(
+        &mut self,
+        name: T,
+        reads: &[RenderTextureHandle],
+        writes: &[RenderTextureHandle],
+    ) -> usize {
+        self.passes.push(RenderPass {
+            name: name.into(),
+            reads: reads.to_vec(),
+            writes: writes.to_vec(),
+        });
+
+        self.passes.len() - 1
+    }
+
+    /// Gets the render pass at `index`.
+    #[inline]
+    pub fn pass(&self, index: usize) -> Option<&RenderPass> {
+        self.passes.get(index)
+    }
+
+    /// Returns the number of render passes.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.passes.len()
+    }
+
+    /// Returns true if there is no render passes.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.passes.is_empty()
+    }
+
+    /// Resolves the execution order of passes. Every pass reading a render texture runs
+    /// after all the passes that write to it, independent passes are kept in the order
+    /// they were added.
+    ///
+    /// Returns error if there are cyclic dependencies between passes.
+    pub fn resolve(&self) -> Result<Vec<usize>, Error> {
+        let mut writers = FastHashMap::default();
+        for (i, pass) in self.passes.iter().enumerate() {
+            for &v in &pass.writes {
+                writers.entry(v).or_insert_with(Vec::new).push(i);
+            }
+        }
+
+        let mut edges = vec![Vec::new(); self.passes.len()];
+        let mut degrees = vec![0; self.passes.len()];
+        for (i, pass) in self.passes.iter().enumerate() {
+            for v in &pass.reads {
+                for &w in writers.get(v).into_iter().flatten() {
+                    if w != i && !edges[w].contains(&i) {
+                        edges[w].push(i);
+                        degrees[i] += 1;
+                    }
+                }
+            }
+        }
+
+        let mut order = Vec::with_capacity(self.passes.len());
+        let mut scheduled = vec![false; self.passes.len()];
+        while order.len() < self.passes.len() {
+            let next = (0..self.passes.len()).find(|&i| !scheduled[i] && degrees[i] == 0);
+
+            if let Some(i) = next {
+                scheduled[i] = true;
+                order.push(i);
+
+                for &v in &edges[i] {
+                    degrees[v] -= 1;
+                }
+            } else {
+                let names: Vec<_> = (0..self.passes.len())
+                    .filter(|&i| !scheduled[i])
+                    .map(|i| self.passes[i].name.as_str())
+                    .collect();
+
+                bail!("Cyclic dependencies between render passes {:?}.", names);
+            }
+        }
+
+        Ok(order)
+    }
+}
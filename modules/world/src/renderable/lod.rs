@@ -0,0 +1,65 @@
+use crayon::math::prelude::*;
+use crayon::video::prelude::*;
+
+use spatial::prelude::Transform;
+
+/// `Lod` swaps the mesh of `MeshRenderer` to lower-detail versions as it moves away
+/// from camera.
+///
+/// The distance is measured from camera to the center of bounding box of the mesh in
+/// `MeshRenderer`, which is supposed to be the most detailed one.
+#[derive(Debug, Clone, Default)]
+pub struct Lod {
+    /// Culls the mesh entirely when it's farther than the largest `max_distance`.
+    /// Otherwise the last level is used.
+    pub cull: bool,
+
+    levels: Vec<(MeshHandle, f32)>,
+}
+
+impl Lod {
+    pub fn new() -> Self {
+        Lod::default()
+    }
+
+    /// Adds a level that is used when the distance is less than or equal to
+    /// `max_distance`. Levels are kept sorted by their distances.
+    pub fn add(&mut self, mesh: MeshHandle, max_distance: f32) {
+        let index = self
+            .levels
+            .iter()
+            .position(|v| v.1 > max_distance)
+            .unwrap_or(self.levels.len());
+
+        self.levels.insert(index, (mesh, max_distance));
+    }
+
+    /// Adds a level, and returns self for chaining.
+    pub fn with(mut self, mesh: MeshHandle, max_distance: f32) -> Self {
+        self.add(mesh, max_distance);
+        self
+    }
+
+    /// Gets the levels sorted by their distances.
+    #[inline]
+    pub fn levels(&self) -> &[(MeshHandle, f32)] {
+        &self.levels
+    }
+
+    /// Selects the level for a distance. Returns `None` if there is no levels, or if
+    /// the mesh should be culled.
+    pub fn select(&self, distance: f32) -> Option<usize> {
+        match self.levels.iter().position(|v| distance <= v.1) {
+            Some(index) => Some(index),
+            None if self.cull || self.levels.is_empty() => None,
+            None => Some(self.levels.len() - 1),
+        }
+    }
+
+    /// Gets the distance from `camera` to the center of `aabb`, which is defined in the
+    /// local space of `transform`.
+    pub fn distance(camera: &Transform, transform: &Transform, aabb: Aabb3<f32>) -> f32 {
+        let center = transform.transform_point(aabb.center().to_vec());
+        (center - camera.position).magnitude()
+    }
+}
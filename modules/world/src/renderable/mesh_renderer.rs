@@ -13,6 +13,11 @@ pub struct MeshRenderer {
     pub shadow_receiver: bool,
     /// Is this renderer visible.
     pub visible: bool,
+    /// Could this renderer be picked by rays.
+    pub pickable: bool,
+    /// The layer bits of this renderer, it's only drawn by cameras whose culling mask
+    /// intersects with it.
+    pub layer: u32,
 
     #[doc(hidden)]
     pub(crate) transform: Transform,
@@ -36,6 +41,8 @@ impl Default for MeshRenderer {
             shadow_caster: false,
             shadow_receiver: false,
             visible: true,
+            pickable: true,
+            layer: 1,
             transform: Transform::default(),
             ent: Entity::default(),
         }
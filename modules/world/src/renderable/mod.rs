@@ -1,24 +1,38 @@
 mod camera;
 mod lit;
+mod lod;
 mod mesh_renderer;
 mod simple;
 
+#[cfg(feature = "debug")]
+pub mod debug;
+pub mod graph;
 pub mod headless;
 
 pub mod prelude {
     pub use super::camera::Camera;
+    #[cfg(feature = "debug")]
+    pub use super::debug::{DebugLines, DebugRenderer};
+    pub use super::graph::{RenderGraph, RenderPass};
     pub use super::lit::{Lit, LitSource};
+    pub use super::lod::Lod;
     pub use super::mesh_renderer::MeshRenderer;
     pub use super::simple::{SimpleMaterial, SimpleRenderer};
     pub use super::{Renderable, Renderer};
 }
 
+use crayon::math::prelude::Ray;
+use crayon::utils::hash::FastHashMap;
+use crayon::video;
+use crayon::video::assets::prelude::{MeshHandle, SurfaceClear, SurfaceHandle};
+
 use spatial::prelude::SceneGraph;
 use utils::prelude::Component;
 use Entity;
 
 use self::camera::Camera;
 use self::lit::{Lit, LitSource};
+use self::lod::Lod;
 use self::mesh_renderer::MeshRenderer;
 
 pub trait Renderer {
@@ -36,6 +50,10 @@ pub struct Renderable {
     cameras: Component<Camera>,
     lits: Component<Lit>,
     meshes: Component<MeshRenderer>,
+    lods: Component<Lod>,
+    visibles: Vec<MeshRenderer>,
+    // The original and the overridden clear flags of surfaces that cameras draw into.
+    clears: FastHashMap<SurfaceHandle, (SurfaceClear, SurfaceClear)>,
 }
 
 impl Renderable {
@@ -44,6 +62,9 @@ impl Renderable {
             cameras: Component::new(),
             lits: Component::new(),
             meshes: Component::new(),
+            lods: Component::new(),
+            visibles: Vec::new(),
+            clears: FastHashMap::default(),
         }
     }
 
@@ -106,31 +127,186 @@ impl Renderable {
     pub fn remove_mesh(&mut self, ent: Entity) {
         self.meshes.remove(ent);
     }
+
+    #[inline]
+    pub fn add_lod(&mut self, ent: Entity, lod: Lod) {
+        self.lods.add(ent, lod);
+    }
+
+    #[inline]
+    pub fn lod(&self, ent: Entity) -> Option<&Lod> {
+        self.lods.get(ent)
+    }
+
+    #[inline]
+    pub fn lod_mut(&mut self, ent: Entity) -> Option<&mut Lod> {
+        self.lods.get_mut(ent)
+    }
+
+    #[inline]
+    pub fn remove_lod(&mut self, ent: Entity) {
+        self.lods.remove(ent);
+    }
 }
 
 impl Renderable {
+    /// Gets the entities that have camera component, in no particular order.
+    #[inline]
+    pub fn entities_with_camera(&self) -> &[Entity] {
+        self.cameras.keys()
+    }
+
+    /// Gets the entities that have light component, in no particular order.
+    #[inline]
+    pub fn entities_with_lit(&self) -> &[Entity] {
+        self.lits.keys()
+    }
+
+    /// Gets the entities that have mesh component, in no particular order.
+    #[inline]
+    pub fn entities_with_mesh(&self) -> &[Entity] {
+        self.meshes.keys()
+    }
+
+    /// Gets the entities that have level-of-detail component, in no particular order.
+    #[inline]
+    pub fn entities_with_lod(&self) -> &[Entity] {
+        self.lods.keys()
+    }
+}
+
+impl Renderable {
+    // Overrides the clear flags of surfaces with cameras, and restores the surfaces that
+    // are not overridden anymore. The clear flags of the default surface are applied by
+    // renderer.
+    fn update_clears(&mut self) {
+        for v in self.cameras.values() {
+            if let (Some(surface), Some(clear)) = (v.surface(), v.clear()) {
+                let current = match video::surface(surface) {
+                    Some(params) => params.clear(),
+                    None => continue,
+                };
+
+                self.clears.entry(surface).or_insert((current, clear)).1 = clear;
+                if current != clear {
+                    video::update_surface_clear(surface, clear.0, clear.1, clear.2).unwrap();
+                }
+            }
+        }
+
+        let cameras = self.cameras.values();
+        self.clears.retain(|&surface, &mut (original, clear)| {
+            if cameras
+                .iter()
+                .any(|v| v.surface() == Some(surface) && v.clear().is_some())
+            {
+                return true;
+            }
+
+            // Leaves the surface alone if its flags have been changed by others.
+            if let Some(params) = video::surface(surface) {
+                if params.clear() == clear {
+                    let (color, depth, stencil) = original;
+                    video::update_surface_clear(surface, color, depth, stencil).unwrap();
+                }
+            }
+
+            false
+        });
+    }
+
     pub fn draw<R: Renderer>(&mut self, renderer: &mut R, sg: &SceneGraph) {
-        for (i, v) in self.cameras.data.iter_mut().enumerate() {
-            if let Some(transform) = sg.transform(self.cameras.entities[i]) {
+        for (ent, v) in self.cameras.iter_mut() {
+            if let Some(transform) = sg.transform(ent) {
                 v.transform = transform;
             }
         }
 
-        for (i, v) in self.lits.data.iter_mut().enumerate() {
-            if let Some(transform) = sg.transform(self.lits.entities[i]) {
+        for (ent, v) in self.lits.iter_mut() {
+            if let Some(transform) = sg.transform(ent) {
                 v.transform = transform;
             }
         }
 
-        for (i, v) in self.meshes.data.iter_mut().enumerate() {
-            if let Some(transform) = sg.transform(self.meshes.entities[i]) {
+        for (ent, v) in self.meshes.iter_mut() {
+            if let Some(transform) = sg.transform(ent) {
                 v.transform = transform;
-                v.ent = self.meshes.entities[i];
+                v.ent = ent;
             }
         }
 
-        for v in &self.cameras.data {
-            renderer.submit(&v, &self.lits.data, &self.meshes.data);
+        self.update_clears();
+
+        let visibles = &mut self.visibles;
+        for v in self.cameras.values() {
+            let culled = self
+                .meshes
+                .values()
+                .iter()
+                .any(|m| !v.is_layer_visible(m.layer));
+            if self.lods.is_empty() && !culled {
+                renderer.submit(&v, self.lits.values(), self.meshes.values());
+                continue;
+            }
+
+            visibles.clear();
+            for mesh in self.meshes.values() {
+                if !v.is_layer_visible(mesh.layer) {
+                    continue;
+                }
+
+                if let Some(handle) = Self::select_mesh(&self.lods, v, mesh) {
+                    let mut mesh = *mesh;
+                    mesh.mesh = handle;
+                    visibles.push(mesh);
+                }
+            }
+
+            renderer.submit(v, self.lits.values(), visibles);
         }
     }
+
+    /// Gets the mesh that should be drawn from the view of `camera`, with level-of-detail
+    /// applied. Returns `None` if the mesh is culled.
+    fn select_mesh(
+        lods: &Component<Lod>,
+        camera: &Camera,
+        mesh: &MeshRenderer,
+    ) -> Option<MeshHandle> {
+        match lods.get(mesh.ent) {
+            Some(lod) => {
+                let aabb = video::mesh_aabb(mesh.mesh)?;
+                let distance = Lod::distance(&camera.transform, &mesh.transform, aabb);
+                lod.select(distance).map(|i| lod.levels()[i].0)
+            }
+            None => Some(mesh.mesh),
+        }
+    }
+}
+
+impl Renderable {
+    /// Casts a ray against the world-space bounding boxes of visible and pickable meshes,
+    /// and returns the nearest one with its distance along the ray.
+    pub fn pick(&self, sg: &SceneGraph, ray: Ray<f32>) -> Option<(Entity, f32)> {
+        let mut nearest: Option<(Entity, f32)> = None;
+
+        for (ent, v) in self.meshes.iter() {
+            if !v.visible || !v.pickable {
+                continue;
+            }
+
+            let aabb = match (sg.transform(ent), video::mesh_aabb(v.mesh)) {
+                (Some(transform), Some(aabb)) => aabb.transform(&transform.matrix()),
+                _ => continue,
+            };
+
+            if let Some(distance) = ray.intersect_aabb(&aabb) {
+                if nearest.map(|(_, d)| distance < d).unwrap_or(true) {
+                    nearest = Some((ent, distance));
+                }
+            }
+        }
+
+        nearest
+    }
 }
@@ -8,6 +8,9 @@ pub struct SimpleMaterial {
     pub diffuse_texture: Option<TextureHandle>,
     pub specular: Color<f32>,
     pub specular_texture: Option<TextureHandle>,
+    /// The tangent-space normal map. It only takes effect on meshes with tangents,
+    /// others are shaded with their geometric normals.
+    pub normal_texture: Option<TextureHandle>,
     pub shininess: f32,
 }
 
@@ -19,6 +22,7 @@ impl Default for SimpleMaterial {
             diffuse_texture: None,
             specular: Color::black(),
             specular_texture: None,
+            normal_texture: None,
             shininess: 0.0,
         }
     }
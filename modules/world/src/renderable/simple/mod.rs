@@ -13,32 +13,69 @@ pub const MAX_DIR_LITS: usize = 1;
 pub const MAX_POINT_LITS: usize = 4;
 
 /// A simple renderer that draws some color into mesh objects.
+///
+/// With depth prepass enabled, meshes are drawn into the depth buffer first with color
+/// writes disabled, and then shaded with `Comparison::Equal` depth test. It avoids the
+/// expensive shading of overdrawn fragments, at the cost of drawing every mesh twice.
 pub struct SimpleRenderer {
     materials: Component<SimpleMaterial>,
 
     surface: SurfaceHandle,
     shader: ShaderHandle,
     drawcalls: DrawCommandBuffer<DrawOrder>,
+    prepass: Option<DepthPrepass>,
 
     global_ambient: Color<f32>,
     dir_lits: Vec<(String, String)>,
     point_lits: Vec<(String, String, String)>,
 }
 
+struct DepthPrepass {
+    depth: ShaderHandle,
+    color: ShaderHandle,
+    drawcalls: DrawCommandBuffer<DrawOrder>,
+}
+
 impl Drop for SimpleRenderer {
     fn drop(&mut self) {
         video::delete_surface(self.surface);
         video::delete_shader(self.shader);
+        self.set_depth_prepass(false).unwrap();
     }
 }
 
 impl SimpleRenderer {
     /// Creates a new `SimpleRenderer`.
     pub fn new() -> Result<Self, Error> {
-        // Create shader state.
+        let dir_lits = (0..MAX_DIR_LITS).map(Self::dir_lit).collect();
+        let point_lits = (0..MAX_POINT_LITS).map(Self::point_lit).collect();
+        let params = Self::shader_params();
+
+        let vs = Self::shader_source(include_str!("shaders/simple.vs"));
+        let fs = Self::shader_source(include_str!("shaders/simple.fs"));
+        let shader = video::create_shader(params, vs, fs)?;
+
+        let params = SurfaceParams::default();
+        let surface = video::create_surface(params)?;
+
+        Ok(SimpleRenderer {
+            materials: Component::new(),
+            surface: surface,
+            shader: shader,
+            drawcalls: DrawCommandBuffer::new(),
+            prepass: None,
+            dir_lits: dir_lits,
+            point_lits: point_lits,
+            global_ambient: Color::gray(),
+        })
+    }
+
+    /// Gets the parameters of the shader that `SimpleRenderer` draws with.
+    pub fn shader_params() -> ShaderParams {
         let attributes = AttributeLayout::build()
             .with(Attribute::Position, 3)
             .with(Attribute::Normal, 3)
+            .with_optional(Attribute::Tangent, 3)
             .with_optional(Attribute::Texcoord0, 2)
             .finish();
 
@@ -51,37 +88,23 @@ impl SimpleRenderer {
             .with("u_DiffuseTexture", UniformVariableType::Texture)
             .with("u_Specular", UniformVariableType::Vector3f)
             .with("u_SpecularTexture", UniformVariableType::Texture)
+            .with("u_NormalTexture", UniformVariableType::Texture)
+            .with("u_NormalMapping", UniformVariableType::F32)
             .with("u_Shininess", UniformVariableType::F32);
 
-        let mut dir_lits = Vec::new();
-        let mut point_lits = Vec::new();
-
         for i in 0..MAX_DIR_LITS {
-            let name = (
-                format!("u_DirLitViewDir[{0}]", i),
-                format!("u_DirLitColor[{0}]", i),
-            );
-
+            let name = Self::dir_lit(i);
             uniforms = uniforms
                 .with(name.0.as_str(), UniformVariableType::Vector3f)
                 .with(name.1.as_str(), UniformVariableType::Vector3f);
-
-            dir_lits.push(name);
         }
 
         for i in 0..MAX_POINT_LITS {
-            let name = (
-                format!("u_PointLitViewPos[{0}]", i),
-                format!("u_PointLitColor[{0}]", i),
-                format!("u_PointLitAttenuation[{0}]", i),
-            );
-
+            let name = Self::point_lit(i);
             uniforms = uniforms
                 .with(name.0.as_str(), UniformVariableType::Vector3f)
                 .with(name.1.as_str(), UniformVariableType::Vector3f)
                 .with(name.2.as_str(), UniformVariableType::Vector3f);
-
-            point_lits.push(name);
         }
 
         let mut params = ShaderParams::default();
@@ -89,22 +112,74 @@ impl SimpleRenderer {
         params.state.depth_test = Comparison::Less;
         params.attributes = attributes;
         params.uniforms = uniforms.finish();
+        params
+    }
 
-        let vs = format!(
-            "
-            #version 100
-            precision lowp float;
+    /// Gets the parameters of the shaders that draw depth only, and then colors when
+    /// depth prepass is enabled.
+    pub fn depth_prepass_params() -> (ShaderParams, ShaderParams) {
+        let mut depth = ShaderParams::default();
+        depth.state.depth_write = true;
+        depth.state.depth_test = Comparison::Less;
+        depth.state.color_write = (false, false, false, false);
+        depth.attributes = AttributeLayout::build()
+            .with(Attribute::Position, 3)
+            .finish();
+        depth.uniforms = UniformVariableLayout::build()
+            .with("u_MVPMatrix", UniformVariableType::Matrix4f)
+            .finish();
 
-            #define MAX_DIR_LITS {0}
-            #define MAX_POINT_LITS {1}
-            {2}
-            ",
-            MAX_DIR_LITS,
-            MAX_POINT_LITS,
-            include_str!("shaders/simple.vs")
-        );
+        let mut color = Self::shader_params();
+        color.state.depth_write = false;
+        color.state.depth_test = Comparison::Equal;
 
-        let fs = format!(
+        (depth, color)
+    }
+
+    /// Enables or disables depth prepass.
+    pub fn set_depth_prepass(&mut self, enabled: bool) -> Result<(), Error> {
+        if enabled && self.prepass.is_none() {
+            let (depth, color) = Self::depth_prepass_params();
+
+            let vs = Self::shader_source(include_str!("shaders/depth.vs"));
+            let fs = Self::shader_source(include_str!("shaders/depth.fs"));
+            let depth = video::create_shader(depth, vs, fs)?;
+
+            let vs = Self::shader_source(include_str!("shaders/simple.vs"));
+            let fs = Self::shader_source(include_str!("shaders/simple.fs"));
+            let color = match video::create_shader(color, vs, fs) {
+                Ok(color) => color,
+                Err(err) => {
+                    video::delete_shader(depth);
+                    return Err(err.into());
+                }
+            };
+
+            self.prepass = Some(DepthPrepass {
+                depth: depth,
+                color: color,
+                drawcalls: DrawCommandBuffer::new(),
+            });
+        }
+
+        if !enabled {
+            if let Some(prepass) = self.prepass.take() {
+                video::delete_shader(prepass.depth);
+                video::delete_shader(prepass.color);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Checks if depth prepass is enabled.
+    #[inline]
+    pub fn depth_prepass(&self) -> bool {
+        self.prepass.is_some()
+    }
+
+    fn shader_source(src: &str) -> String {
+        format!(
             "
             #version 100
             precision lowp float;
@@ -113,25 +188,23 @@ impl SimpleRenderer {
             #define MAX_POINT_LITS {1}
             {2}
             ",
-            MAX_DIR_LITS,
-            MAX_POINT_LITS,
-            include_str!("shaders/simple.fs")
-        );
-
-        let shader = video::create_shader(params, vs, fs)?;
+            MAX_DIR_LITS, MAX_POINT_LITS, src
+        )
+    }
 
-        let params = SurfaceParams::default();
-        let surface = video::create_surface(params)?;
+    fn dir_lit(i: usize) -> (String, String) {
+        (
+            format!("u_DirLitViewDir[{0}]", i),
+            format!("u_DirLitColor[{0}]", i),
+        )
+    }
 
-        Ok(SimpleRenderer {
-            materials: Component::new(),
-            surface: surface,
-            shader: shader,
-            drawcalls: DrawCommandBuffer::new(),
-            dir_lits: dir_lits,
-            point_lits: point_lits,
-            global_ambient: Color::gray(),
-        })
+    fn point_lit(i: usize) -> (String, String, String) {
+        (
+            format!("u_PointLitViewPos[{0}]", i),
+            format!("u_PointLitColor[{0}]", i),
+            format!("u_PointLitAttenuation[{0}]", i),
+        )
     }
 
     #[inline]
@@ -141,7 +214,7 @@ impl SimpleRenderer {
 
     #[inline]
     pub fn has(&self, ent: Entity) -> bool {
-        self.materials.has(ent)
+        self.materials.contains(ent)
     }
 
     #[inline]
@@ -156,7 +229,7 @@ impl SimpleRenderer {
 
     #[inline]
     pub fn remove(&mut self, ent: Entity) {
-        self.materials.remove(ent)
+        self.materials.remove(ent);
     }
 
     #[inline]
@@ -197,7 +270,20 @@ impl super::Renderer for SimpleRenderer {
             let mvp = projection_matrix * mv;
             let vn = mv.invert().and_then(|v| Some(v.transpose())).unwrap_or(mv);
 
-            let mut dc = Draw::new(self.shader, mesh.mesh);
+            let zorder = mesh.transform.position.distance2(camera.transform.position) as u32;
+
+            let shader = if let Some(ref mut prepass) = self.prepass {
+                let mut dc = Draw::new(prepass.depth, mesh.mesh);
+                dc.set_uniform_variable("u_MVPMatrix", mvp);
+                prepass
+                    .drawcalls
+                    .draw(DrawOrder::new(prepass.depth, false, zorder), dc);
+                prepass.color
+            } else {
+                self.shader
+            };
+
+            let mut dc = Draw::new(shader, mesh.mesh);
             dc.set_uniform_variable("u_ModelViewMatrix", mv);
             dc.set_uniform_variable("u_MVPMatrix", mvp);
             dc.set_uniform_variable("u_ViewNormalMatrix", vn);
@@ -205,6 +291,8 @@ impl super::Renderer for SimpleRenderer {
             let mat = self.material(mesh.ent).cloned().unwrap_or_default();
             let diffuse = mat.diffuse_texture.unwrap_or(crate::default().white);
             let specular = mat.specular_texture.unwrap_or(crate::default().white);
+            let normal = mat.normal_texture.unwrap_or(crate::default().white);
+            let mapping = mat.normal_texture.map(|_| 1.0).unwrap_or(0.0);
 
             let mut ambient = mat.ambient.rgb();
             ambient[0] *= self.global_ambient.r;
@@ -216,6 +304,8 @@ impl super::Renderer for SimpleRenderer {
             dc.set_uniform_variable("u_DiffuseTexture", diffuse);
             dc.set_uniform_variable("u_Specular", mat.specular.rgb());
             dc.set_uniform_variable("u_SpecularTexture", specular);
+            dc.set_uniform_variable("u_NormalTexture", normal);
+            dc.set_uniform_variable("u_NormalMapping", mapping);
             dc.set_uniform_variable("u_Shininess", mat.shininess);
 
             lits.sort_by_key(|v| mesh.transform.position.distance2(v.transform.position) as u32);
@@ -239,8 +329,9 @@ impl super::Renderer for SimpleRenderer {
                     LitSource::Point { radius, smoothness } => {
                         if point_index < self.point_lits.len() {
                             let names = &self.point_lits[point_index];
-                            let mut pos = view_matrix * lit.transform.position.extend(1.0);
-                            pos /= pos.w;
+                            // The lighting is calculated in view space, which the affine
+                            // view matrix maps points into with w = 1.
+                            let pos = (view_matrix * lit.transform.position.extend(1.0)).truncate();
                             let attenuation = Vector3::new(
                                 1.0,
                                 -1.0 / (radius + smoothness * radius * radius),
@@ -250,7 +341,7 @@ impl super::Renderer for SimpleRenderer {
                             color[0] *= lit.intensity;
                             color[1] *= lit.intensity;
                             color[2] *= lit.intensity;
-                            dc.set_uniform_variable(&names.0, pos.truncate());
+                            dc.set_uniform_variable(&names.0, pos);
                             dc.set_uniform_variable(&names.1, color);
                             dc.set_uniform_variable(&names.2, attenuation);
                             point_index += 1;
@@ -259,16 +350,25 @@ impl super::Renderer for SimpleRenderer {
                 }
             }
 
-            let order = DrawOrder::new(
-                self.shader,
-                false,
-                mesh.transform.position.distance2(camera.transform.position) as u32,
-            );
+            self.drawcalls
+                .draw(DrawOrder::new(shader, false, zorder), dc);
+        }
+
+        let surface = match camera.surface() {
+            Some(surface) => surface,
+            None => {
+                if let Some((color, depth, stencil)) = camera.clear() {
+                    video::update_surface_clear(self.surface, color, depth, stencil).unwrap();
+                }
+
+                self.surface
+            }
+        };
 
-            self.drawcalls.draw(order, dc);
+        if let Some(ref mut prepass) = self.prepass {
+            prepass.drawcalls.submit(surface).unwrap();
         }
 
-        let surface = camera.surface().unwrap_or(self.surface);
         self.drawcalls.submit(surface).unwrap();
     }
 }
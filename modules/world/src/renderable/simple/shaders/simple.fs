@@ -1,5 +1,6 @@
 varying vec3 v_EyeFragPos;
 varying vec3 v_EyeNormal;
+varying vec3 v_EyeTangent;
 varying vec2 v_Texcoord;
 
 uniform vec3 u_DirLitViewDir[MAX_DIR_LITS];
@@ -17,6 +18,9 @@ uniform sampler2D u_DiffuseTexture;
 uniform vec3 u_Specular;
 uniform sampler2D u_SpecularTexture;
 
+uniform sampler2D u_NormalTexture;
+uniform float u_NormalMapping;
+
 uniform float u_Shininess;
 
 vec3 Calculate(vec3 normal, vec3 viewDir, vec3 lightDir, vec3 reflectDir, vec3 d, vec3 s)
@@ -26,9 +30,25 @@ vec3 Calculate(vec3 normal, vec3 viewDir, vec3 lightDir, vec3 reflectDir, vec3 d
     return diffuse + specular;
 }
 
-void main()
+vec3 CalculateNormal()
 {
     vec3 normal = normalize(v_EyeNormal);
+
+    // falls back to the geometric normal if there is no normal map or tangents.
+    if (u_NormalMapping < 0.5 || dot(v_EyeTangent, v_EyeTangent) < 0.0001)
+    {
+        return normal;
+    }
+
+    vec3 tangent = normalize(v_EyeTangent - dot(v_EyeTangent, normal) * normal);
+    vec3 bitangent = cross(normal, tangent);
+    vec3 texel = texture2D(u_NormalTexture, v_Texcoord).rgb * 2.0 - 1.0;
+    return normalize(mat3(tangent, bitangent, normal) * texel);
+}
+
+void main()
+{
+    vec3 normal = CalculateNormal();
     vec3 viewDir = normalize(v_EyeFragPos);
 
     vec3 diffuse = texture2D(u_DiffuseTexture, v_Texcoord).rgb;
@@ -1,5 +1,8 @@
+invariant gl_Position;
+
 attribute vec3 Position;
 attribute vec3 Normal;
+attribute vec3 Tangent;
 attribute vec2 Texcoord0;
 
 uniform mat4 u_ModelViewMatrix;
@@ -8,6 +11,7 @@ uniform mat4 u_ViewNormalMatrix;
 
 varying vec3 v_EyeFragPos;
 varying vec3 v_EyeNormal;
+varying vec3 v_EyeTangent;
 varying vec2 v_Texcoord;
 
 void main() {
@@ -16,5 +20,6 @@ void main() {
     vec4 eyePos = u_ModelViewMatrix * vec4(Position, 1.0);
     v_EyeFragPos = eyePos.xyz / eyePos.w;
     v_EyeNormal = vec3(u_ViewNormalMatrix * vec4(Normal, 0.0));
+    v_EyeTangent = vec3(u_ModelViewMatrix * vec4(Tangent, 0.0));
     v_Texcoord = Texcoord0;
 }
\ No newline at end of file
@@ -1,11 +1,11 @@
 //! Scenes contain the environments and menus of your game.
 
 use crayon::errors::Result;
-use crayon::math::prelude::{Quaternion, Vector3};
+use crayon::math::prelude::{Quaternion, Ray, Vector3};
 use crayon::utils::prelude::HandlePool;
 
 use assets::prelude::PrefabHandle;
-use renderable::prelude::{Camera, Lit, MeshRenderer, Renderable, Renderer};
+use renderable::prelude::{Camera, Lit, Lod, MeshRenderer, Renderable, Renderer};
 use spatial::prelude::{SceneGraph, Transform};
 use tags::Tags;
 use Entity;
@@ -55,6 +55,39 @@ impl<R: Renderer> Scene<R> {
         e
     }
 
+    /// Creates a `EntityBuilder`, which adds components to the new Entity fluently.
+    ///
+    /// ```
+    /// # extern crate crayon_world;
+    /// use crayon_world::prelude::*;
+    /// use crayon_world::renderable::headless::HeadlessRenderer;
+    ///
+    /// # fn main() {
+    /// let mut scene = Scene::new(HeadlessRenderer::new());
+    /// let ent = scene
+    ///     .build("camera")
+    ///     .with(Camera::default())
+    ///     .with(Lit::default())
+    ///     .finish();
+    ///
+    /// assert!(scene.camera(ent).is_some());
+    /// assert!(scene.lit(ent).is_some());
+    /// # }
+    /// ```
+    #[inline]
+    pub fn build<T: AsRef<str>>(&mut self, name: T) -> EntityBuilder<R> {
+        EntityBuilder {
+            name: name.as_ref().to_owned(),
+            transform: None,
+            camera: None,
+            lit: None,
+            mesh: None,
+            lod: None,
+            mtl: None,
+            scene: self,
+        }
+    }
+
     /// Get the name of this Entity.
     #[inline]
     pub fn name(&self, ent: Entity) -> Option<&str> {
@@ -74,8 +107,10 @@ impl<R: Renderer> Scene<R> {
                 self.entities.free(v);
                 self.tags.remove(v);
                 self.renderables.remove_mesh(v);
+                self.renderables.remove_lod(v);
                 self.renderables.remove_lit(v);
                 self.renderables.remove_camera(v);
+                self.renderer.remove_mtl(v);
             }
 
             Some(deletions)
@@ -84,6 +119,22 @@ impl<R: Renderer> Scene<R> {
         }
     }
 
+    /// Removes all the entities from this scene, and rewinds the allocation of entities,
+    /// so the entities created afterwards start from index 0 and version 1 exactly like a
+    /// new scene.
+    ///
+    /// This is intended for testing, where the assertions on specific `Entity` values
+    /// should not depend on the allocation history. Notes that the `Entity`s created before
+    /// resetting might alias the new ones.
+    pub fn reset(&mut self) {
+        let roots = self.nodes.roots().to_vec();
+        for v in roots {
+            self.delete(v);
+        }
+
+        self.entities.clear();
+    }
+
     /// Finds a Entity by name and returns it.
     ///
     /// If no Entity with name can be found, None is returned. If name contains a '/' character,
@@ -220,6 +271,14 @@ impl<R: Renderer> Scene<R> {
     pub fn draw(&mut self) {
         self.renderables.draw(&mut self.renderer, &self.nodes);
     }
+
+    /// Picks the nearest visible and pickable mesh that intersects with `ray`, returns
+    /// the entity and its distance along the ray. Meshes are tested with their bounding
+    /// boxes in world space.
+    #[inline]
+    pub fn pick(&self, ray: Ray<f32>) -> Option<(Entity, f32)> {
+        self.renderables.pick(&self.nodes, ray)
+    }
 }
 
 impl<R: Renderer> Scene<R> {
@@ -289,6 +348,52 @@ impl<R: Renderer> Scene<R> {
         self.renderables.remove_mesh(ent);
     }
 
+    /// Add level-of-detail component to this Entity.
+    #[inline]
+    pub fn add_lod(&mut self, ent: Entity, lod: Lod) {
+        self.renderables.add_lod(ent, lod);
+    }
+
+    #[inline]
+    pub fn lod(&self, ent: Entity) -> Option<&Lod> {
+        self.renderables.lod(ent)
+    }
+
+    #[inline]
+    pub fn lod_mut(&mut self, ent: Entity) -> Option<&mut Lod> {
+        self.renderables.lod_mut(ent)
+    }
+
+    /// Remove level-of-detail component from this Entity.
+    #[inline]
+    pub fn remove_lod(&mut self, ent: Entity) {
+        self.renderables.remove_lod(ent);
+    }
+
+    /// Gets the entities that have camera component, in no particular order.
+    #[inline]
+    pub fn entities_with_camera(&self) -> &[Entity] {
+        self.renderables.entities_with_camera()
+    }
+
+    /// Gets the entities that have light component, in no particular order.
+    #[inline]
+    pub fn entities_with_lit(&self) -> &[Entity] {
+        self.renderables.entities_with_lit()
+    }
+
+    /// Gets the entities that have mesh component, in no particular order.
+    #[inline]
+    pub fn entities_with_mesh(&self) -> &[Entity] {
+        self.renderables.entities_with_mesh()
+    }
+
+    /// Gets the entities that have level-of-detail component, in no particular order.
+    #[inline]
+    pub fn entities_with_lod(&self) -> &[Entity] {
+        self.renderables.entities_with_lod()
+    }
+
     /// Add material component to this Entity.
     #[inline]
     pub fn add_mtl(&mut self, ent: Entity, mtl: R::Mtl) {
@@ -339,6 +444,21 @@ impl<R: Renderer> Scene<R> {
         self.nodes.set_parent(child, parent, keep_world_pose)
     }
 
+    /// Attachs a batch of children to parent transform in one pass, before existing
+    /// children. The relative order of `children` is preserved.
+    pub fn set_parent_many<T>(
+        &mut self,
+        children: &[Entity],
+        parent: T,
+        keep_world_pose: bool,
+    ) -> Result<()>
+    where
+        T: Into<Option<Entity>>,
+    {
+        self.nodes
+            .set_parent_many(children, parent, keep_world_pose)
+    }
+
     /// Detach a transform from its parent and siblings. Children are not affected.
     pub fn remove_from_parent(&mut self, child: Entity, keep_world_pose: bool) -> Result<()> {
         self.nodes.remove_from_parent(child, keep_world_pose)
@@ -495,3 +615,99 @@ impl<R: Renderer> Scene<R> {
         self.nodes.set_local_scale(ent, scale);
     }
 }
+
+/// The components that could be added with `EntityBuilder`.
+pub trait SceneComponent<R: Renderer> {
+    fn attach(self, builder: &mut EntityBuilder<R>);
+}
+
+impl<R: Renderer> SceneComponent<R> for Transform {
+    fn attach(self, builder: &mut EntityBuilder<R>) {
+        builder.transform = Some(self);
+    }
+}
+
+impl<R: Renderer> SceneComponent<R> for Camera {
+    fn attach(self, builder: &mut EntityBuilder<R>) {
+        builder.camera = Some(self);
+    }
+}
+
+impl<R: Renderer> SceneComponent<R> for Lit {
+    fn attach(self, builder: &mut EntityBuilder<R>) {
+        builder.lit = Some(self);
+    }
+}
+
+impl<R: Renderer> SceneComponent<R> for MeshRenderer {
+    fn attach(self, builder: &mut EntityBuilder<R>) {
+        builder.mesh = Some(self);
+    }
+}
+
+impl<R: Renderer> SceneComponent<R> for Lod {
+    fn attach(self, builder: &mut EntityBuilder<R>) {
+        builder.lod = Some(self);
+    }
+}
+
+/// The builder of Entity. Components are kept in the builder until `finish`, so the
+/// Entity is never observed with only a part of its components.
+pub struct EntityBuilder<'a, R: Renderer + 'a> {
+    scene: &'a mut Scene<R>,
+    name: String,
+    transform: Option<Transform>,
+    camera: Option<Camera>,
+    lit: Option<Lit>,
+    mesh: Option<MeshRenderer>,
+    lod: Option<Lod>,
+    mtl: Option<R::Mtl>,
+}
+
+impl<'a, R: Renderer + 'a> EntityBuilder<'a, R> {
+    /// Adds a component. Adding the same type of component twice overrides the
+    /// previous one.
+    #[inline]
+    pub fn with<T: SceneComponent<R>>(mut self, component: T) -> Self {
+        component.attach(&mut self);
+        self
+    }
+
+    /// Adds the material of `Renderer`.
+    #[inline]
+    pub fn with_mtl(mut self, mtl: R::Mtl) -> Self {
+        self.mtl = Some(mtl);
+        self
+    }
+
+    /// Creates the Entity with all the components.
+    pub fn finish(self) -> Entity {
+        let ent = self.scene.create(&self.name);
+
+        if let Some(v) = self.transform {
+            self.scene.set_local_transform(ent, v);
+        }
+
+        if let Some(v) = self.camera {
+            self.scene.add_camera(ent, v);
+        }
+
+        if let Some(v) = self.lit {
+            self.scene.add_lit(ent, v);
+        }
+
+        if let Some(v) = self.mesh {
+            self.scene.add_mesh(ent, v);
+        }
+
+        if let Some(v) = self.lod {
+            self.scene.add_lod(ent, v);
+        }
+
+        if let Some(v) = self.mtl {
+            self.scene.add_mtl(ent, v);
+        }
+
+        ent
+    }
+}
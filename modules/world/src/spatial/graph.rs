@@ -1,4 +1,5 @@
 use std::iter;
+use std::slice;
 
 use crayon::prelude::*;
 use crayon::utils::hash::{FastHashMap, FastHashSet};
@@ -18,7 +19,62 @@ pub struct SceneGraph {
     nodes: Vec<Node>,
     local_transforms: Vec<Transform>,
 
-    pub(crate) roots: FastHashSet<Entity>,
+    pub(crate) roots: Roots,
+}
+
+/// The root nodes of `SceneGraph`. It iterates in the order of insertion, so the
+/// traversal of scene graph is deterministic.
+#[derive(Default)]
+pub(crate) struct Roots {
+    remap: FastHashMap<Entity, usize>,
+    entities: Vec<Entity>,
+}
+
+impl Roots {
+    fn insert(&mut self, ent: Entity) {
+        if !self.remap.contains_key(&ent) {
+            self.remap.insert(ent, self.entities.len());
+            self.entities.push(ent);
+        }
+    }
+
+    // Removes a root while keeping the order of the rest. It takes O(n) time since the
+    // following roots are shifted, use `remove_many` to remove a batch of roots.
+    fn remove(&mut self, ent: &Entity) {
+        if let Some(index) = self.remap.remove(ent) {
+            self.entities.remove(index);
+            for v in &self.entities[index..] {
+                *self.remap.get_mut(v).unwrap() -= 1;
+            }
+        }
+    }
+
+    // Removes a batch of roots in one O(n) pass, while keeping the order of the rest.
+    fn remove_many(&mut self, ents: &[Entity]) {
+        let len = self.remap.len();
+        for v in ents {
+            self.remap.remove(v);
+        }
+
+        if self.remap.len() == len {
+            return;
+        }
+
+        let remap = &mut self.remap;
+        self.entities.retain(|v| remap.contains_key(v));
+        for (i, v) in self.entities.iter().enumerate() {
+            *remap.get_mut(v).unwrap() = i;
+        }
+    }
+}
+
+impl<'a> IntoIterator for &'a Roots {
+    type Item = &'a Entity;
+    type IntoIter = slice::Iter<'a, Entity>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.entities.iter()
+    }
 }
 
 impl SceneGraph {
@@ -28,10 +84,16 @@ impl SceneGraph {
             entities: Vec::new(),
             nodes: Vec::new(),
             local_transforms: Vec::new(),
-            roots: FastHashSet::default(),
+            roots: Roots::default(),
         }
     }
 
+    /// Gets the nodes that have no parent, in the order they became roots.
+    #[inline]
+    pub fn roots(&self) -> &[Entity] {
+        &self.roots.entities
+    }
+
     /// Adds a node.
     pub(crate) fn add(&mut self, ent: Entity) {
         assert!(
@@ -137,6 +199,11 @@ impl SceneGraph {
                         ::std::mem::replace(&mut node.first_child, Some(child))
                     };
 
+                    if let Some(next_sib) = next_sib {
+                        let nsi = self.index_unchecked(next_sib);
+                        self.nodes[nsi].prev_sib = Some(child);
+                    }
+
                     let child = self.nodes.get_unchecked_mut(child_index);
                     child.parent = Some(parent);
                     child.next_sib = next_sib;
@@ -155,6 +222,96 @@ impl SceneGraph {
         }
     }
 
+    /// Attachs a batch of children to parent transform in one pass, before existing
+    /// children. The relative order of `children` is preserved.
+    pub fn set_parent_many<T>(
+        &mut self,
+        children: &[Entity],
+        parent: T,
+        keep_world_pose: bool,
+    ) -> Result<(), Error>
+    where
+        T: Into<Option<Entity>>,
+    {
+        let parent = parent.into();
+
+        // Validates everything up front, so a failure leaves the graph untouched.
+        if let Some(parent) = parent {
+            self.index(parent)?;
+        }
+
+        let mut visited = FastHashSet::default();
+        for &child in children {
+            self.index(child)?;
+
+            if Some(child) == parent {
+                bail!("Node can not set self as parent.");
+            }
+
+            if !visited.insert(child) {
+                bail!("{:?} is duplicated in children.", child);
+            }
+        }
+
+        if let Some(parent) = parent {
+            if self.ancestors(parent).any(|v| visited.contains(&v)) {
+                bail!("Node can not set its descendant as parent.");
+            }
+        }
+
+        let positions: Vec<_> = if keep_world_pose {
+            children
+                .iter()
+                .map(|&v| self.position(v).unwrap())
+                .collect()
+        } else {
+            Vec::new()
+        };
+
+        for &child in children {
+            self.remove_from_parent(child, false)?;
+        }
+
+        if let Some(parent) = parent {
+            let parent_index = self.index(parent)?;
+
+            unsafe {
+                let mut next_sib = self.nodes[parent_index].first_child;
+                for &child in children.iter().rev() {
+                    if let Some(next_sib) = next_sib {
+                        let nsi = self.index_unchecked(next_sib);
+                        self.nodes[nsi].prev_sib = Some(child);
+                    }
+
+                    let child_index = self.index_unchecked(child);
+                    let node = &mut self.nodes[child_index];
+                    node.parent = Some(parent);
+                    node.next_sib = next_sib;
+                    next_sib = Some(child);
+                }
+
+                self.nodes[parent_index].first_child = next_sib;
+            }
+
+            self.roots.remove_many(children);
+
+            if keep_world_pose {
+                if let Some(inverse) = self.transform(parent).unwrap().inverse() {
+                    for (&child, &position) in children.iter().zip(positions.iter()) {
+                        let index = unsafe { self.index_unchecked(child) };
+                        self.local_transforms[index].position = inverse.transform_point(position);
+                    }
+                }
+            }
+        } else if keep_world_pose {
+            for (&child, &position) in children.iter().zip(positions.iter()) {
+                self.set_position(child, position);
+            }
+        }
+
+        Ok(())
+    }
+
     /// Detach a transform from its parent and siblings. Children are not affected.
     pub fn remove_from_parent(
         &mut self,
@@ -441,14 +598,7 @@ impl SceneGraph {
         T2: Into<Vector3<f32>>,
     {
         if let Some(eye) = self.position(ent) {
-            let center = center.into();
-            let up = up.into();
-
-            let dir = (center - eye).normalize();
-            let side = up.cross(dir).normalize();
-            let up = dir.cross(side).normalize();
-            let rotation: Quaternion<f32> = Matrix3::from_cols(side, up, dir).into();
-
+            let rotation = Transform::look_rotation(center.into() - eye, up.into());
             self.set_rotation(ent, rotation);
         }
     }
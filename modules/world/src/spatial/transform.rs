@@ -52,6 +52,52 @@ impl Transform {
         }
     }
 
+    /// Decomposes a matrix into translation, rotation and uniform scale, which is the
+    /// inverse of `matrix`. Returns `None` if the matrix is projective, sheared, reflected
+    /// or scaled non-uniformly, which could not be represented by a `Transform`.
+    pub fn from_matrix(m: &Matrix4<f32>) -> Option<Self> {
+        use crayon::math::prelude::{InnerSpace, SquareMatrix};
+
+        const EPSILON: f32 = 1e-4;
+
+        if m.x.w.abs() > EPSILON
+            || m.y.w.abs() > EPSILON
+            || m.z.w.abs() > EPSILON
+            || (m.w.w - 1.0).abs() > EPSILON
+        {
+            return None;
+        }
+
+        let cols = [m.x.truncate(), m.y.truncate(), m.z.truncate()];
+        let scale = cols[0].magnitude();
+        if scale <= EPSILON {
+            return None;
+        }
+
+        if cols[1..]
+            .iter()
+            .any(|v| (v.magnitude() - scale).abs() > EPSILON * scale)
+        {
+            return None;
+        }
+
+        let (x, y, z) = (cols[0] / scale, cols[1] / scale, cols[2] / scale);
+        if x.dot(y).abs() > EPSILON || y.dot(z).abs() > EPSILON || z.dot(x).abs() > EPSILON {
+            return None;
+        }
+
+        let rotation = Matrix3::from_cols(x, y, z);
+        if rotation.determinant() <= 0.0 {
+            return None;
+        }
+
+        Some(Transform {
+            scale,
+            position: m.w.truncate(),
+            rotation: rotation.into(),
+        })
+    }
+
     /// Transforms direction from local space to transform's space.
     ///
     /// This operation is not affected by scale or position of the transform. The returned
@@ -85,6 +131,17 @@ impl Transform {
         self.rotation * (v.into() * self.scale) + self.position
     }
 
+    /// Creates a rotation that looks along `forward` with `up` as the upward direction,
+    /// which is the same rotation that `SceneGraph::look_at` sets.
+    #[inline]
+    pub fn look_rotation<T1, T2>(forward: T1, up: T2) -> Quaternion<f32>
+    where
+        T1: Into<Vector3<f32>>,
+        T2: Into<Vector3<f32>>,
+    {
+        look_rotation(forward.into(), up.into())
+    }
+
     /// Returns the up direction in transform's space, which is looking down the positive y-axis.
     #[inline]
     pub fn up(&self) -> Vector3<f32> {
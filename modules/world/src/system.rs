@@ -1,3 +1,5 @@
+#[cfg(feature = "debug")]
+use std::sync::Mutex;
 use std::sync::{Arc, RwLock};
 
 use crayon::application::prelude::*;
@@ -7,6 +9,8 @@ use failure::Error;
 
 use assets::prelude::*;
 use assets::{mesh_builder, texture_builder};
+#[cfg(feature = "debug")]
+use renderable::debug::DebugRenderer;
 
 #[derive(Debug, Clone, Copy)]
 pub struct WorldDefaultResources {
@@ -18,7 +22,10 @@ pub struct WorldDefaultResources {
 
 pub struct WorldSystem {
     prefabs: Arc<RwLock<ResourcePool<PrefabHandle, PrefabLoader>>>,
+    prefab_loader: PrefabLoader,
     lis: LifecycleListenerHandle,
+    #[cfg(feature = "debug")]
+    debug: Mutex<Option<DebugRenderer>>,
 
     pub default: WorldDefaultResources,
 }
@@ -49,11 +56,15 @@ impl WorldSystem {
             quad: mesh_builder::quad()?,
         };
 
-        let prefabs = Arc::new(RwLock::new(ResourcePool::new(PrefabLoader::new())));
+        let prefab_loader = PrefabLoader::new();
+        let prefabs = Arc::new(RwLock::new(ResourcePool::new(prefab_loader.clone())));
 
         let shared = WorldSystem {
             prefabs: prefabs.clone(),
+            prefab_loader: prefab_loader,
             lis: crayon::application::attach(WorldState { prefabs }),
+            #[cfg(feature = "debug")]
+            debug: Mutex::new(None),
             default: default,
         };
 
@@ -69,6 +80,12 @@ impl WorldSystem {
         Ok(handle)
     }
 
+    /// Registers a migration hook for prefabs serialized with an outdated `version`.
+    #[inline]
+    pub fn add_prefab_migration(&self, version: u32, migration: PrefabMigration) {
+        self.prefab_loader.add_migration(version, migration);
+    }
+
     /// Creates a prefab object.
     #[inline]
     pub fn create_prefab(&self, prefab: Prefab) -> Result<PrefabHandle, Error> {
@@ -93,4 +110,18 @@ impl WorldSystem {
     pub fn delete_prefab(&self, handle: PrefabHandle) {
         self.prefabs.write().unwrap().delete(handle);
     }
+
+    /// Runs `func` with the shared `DebugRenderer`, which is created on the first use.
+    #[cfg(feature = "debug")]
+    pub fn debug_renderer<F, T>(&self, func: F) -> Result<T, Error>
+    where
+        F: FnOnce(&mut DebugRenderer) -> Result<T, Error>,
+    {
+        let mut debug = self.debug.lock().unwrap();
+        if debug.is_none() {
+            *debug = Some(DebugRenderer::new()?);
+        }
+
+        func(debug.as_mut().unwrap())
+    }
 }
@@ -1,60 +1,126 @@
-use crayon::utils::hash::FastHashMap;
+use crayon::utils::handle::HandleLike;
+use crayon::utils::handle_map::HandleMap;
 use Entity;
 
-pub struct Component<T> {
-    pub remap: FastHashMap<Entity, usize>,
-    pub entities: Vec<Entity>,
-    pub data: Vec<T>,
+/// A component storage that maps entities to their data with a hash map. Data are packed
+/// densely, so they could be iterated as slices.
+pub type Component<T> = HandleMap<Entity, T>;
+
+/// A component storage that maps entities to their data with a sparse array indexed
+/// by `Entity::index`, instead of a hash map. Data are packed densely like `Component`.
+///
+/// Lookups only cost an array access, at the price of memory proportional to the
+/// largest entity index. It suits the components that are added to a considerable
+/// portion of entities.
+pub struct SparseSet<T> {
+    sparse: Vec<Option<usize>>,
+    entities: Vec<Entity>,
+    data: Vec<T>,
 }
 
-impl<T> Component<T> {
+impl<T> SparseSet<T> {
     pub fn new() -> Self {
-        Component {
-            remap: FastHashMap::default(),
+        SparseSet {
+            sparse: Vec::new(),
             entities: Vec::new(),
             data: Vec::new(),
         }
     }
 
     pub fn add(&mut self, ent: Entity, mut v: T) -> Option<T> {
-        if let Some(&index) = self.remap.get(&ent) {
-            unsafe {
-                ::std::ptr::swap(&mut self.data[index], &mut v);
-                Some(v)
-            }
-        } else {
-            self.remap.insert(ent, self.data.len());
-            self.entities.push(ent);
-            self.data.push(v);
-            None
+        if let Some(index) = self.index(ent) {
+            ::std::mem::swap(&mut self.data[index], &mut v);
+            return Some(v);
+        }
+
+        let slot = ent.index() as usize;
+        if self.sparse.len() <= slot {
+            self.sparse.resize(slot + 1, None);
+        }
+
+        // Overrides the data of dead entity that shares the same index.
+        if let Some(index) = self.sparse[slot] {
+            self.entities[index] = ent;
+            self.data[index] = v;
+            return None;
         }
+
+        self.sparse[slot] = Some(self.data.len());
+        self.entities.push(ent);
+        self.data.push(v);
+        None
     }
 
     #[inline]
     pub fn has(&self, ent: Entity) -> bool {
-        self.remap.contains_key(&ent)
+        self.index(ent).is_some()
     }
 
-    pub fn remove(&mut self, ent: Entity) {
-        if let Some(index) = self.remap.remove(&ent) {
-            self.entities.swap_remove(index);
-            self.data.swap_remove(index);
+    pub fn remove(&mut self, ent: Entity) -> Option<T> {
+        let index = self.index(ent)?;
+        self.sparse[ent.index() as usize] = None;
+        self.entities.swap_remove(index);
 
-            if self.remap.len() != index {
-                *self.remap.get_mut(&self.entities[index]).unwrap() = index;
-            }
+        if index != self.entities.len() {
+            let moved = self.entities[index].index() as usize;
+            self.sparse[moved] = Some(index);
         }
+
+        Some(self.data.swap_remove(index))
     }
 
     #[inline]
     pub fn get(&self, ent: Entity) -> Option<&T> {
-        let data = &self.data;
-        self.remap.get(&ent).map(|&index| &data[index])
+        self.index(ent).map(|index| &self.data[index])
     }
 
     #[inline]
     pub fn get_mut(&mut self, ent: Entity) -> Option<&mut T> {
-        let data = &mut self.data;
-        self.remap.get(&ent).map(move |&index| &mut data[index])
+        match self.index(ent) {
+            Some(index) => Some(&mut self.data[index]),
+            None => None,
+        }
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    /// Gets the entities in the dense order, which is the same as `values`.
+    #[inline]
+    pub fn keys(&self) -> &[Entity] {
+        &self.entities
+    }
+
+    /// Gets the data in the dense order.
+    #[inline]
+    pub fn values(&self) -> &[T] {
+        &self.data
+    }
+
+    /// Gets the mutable data in the dense order.
+    #[inline]
+    pub fn values_mut(&mut self) -> &mut [T] {
+        &mut self.data
+    }
+
+    /// Iterates the entities and their data in the dense order.
+    #[inline]
+    pub fn iter<'a>(&'a self) -> impl Iterator<Item = (Entity, &'a T)> + 'a {
+        self.entities.iter().cloned().zip(self.data.iter())
+    }
+
+    #[inline]
+    fn index(&self, ent: Entity) -> Option<usize> {
+        match self.sparse.get(ent.index() as usize) {
+            Some(&Some(index)) if self.entities[index] == ent => Some(index),
+            _ => None,
+        }
     }
 }
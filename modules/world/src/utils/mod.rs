@@ -1,5 +1,5 @@
 pub mod component;
 
 pub mod prelude {
-    pub use super::component::Component;
+    pub use super::component::{Component, SparseSet};
 }
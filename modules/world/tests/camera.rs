@@ -0,0 +1,129 @@
+extern crate crayon;
+extern crate crayon_world;
+
+use std::sync::{Arc, Mutex};
+
+use crayon::prelude::*;
+use crayon::utils::handle::HandleLike;
+use crayon_world::prelude::*;
+use crayon_world::renderable::headless::HeadlessRenderer;
+
+// The culling mask of camera and the meshes it draws.
+type Records = Arc<Mutex<Vec<(u32, Vec<MeshHandle>)>>>;
+
+#[derive(Default)]
+struct RecordRenderer(Records);
+
+impl Renderer for RecordRenderer {
+    type Mtl = ();
+
+    fn add_mtl(&mut self, _: Entity, _: Self::Mtl) {}
+
+    fn mtl(&self, _: Entity) -> Option<&Self::Mtl> {
+        None
+    }
+
+    fn mtl_mut(&mut self, _: Entity) -> Option<&mut Self::Mtl> {
+        None
+    }
+
+    fn remove_mtl(&mut self, _: Entity) {}
+
+    fn submit(&mut self, camera: &Camera, _: &[Lit], meshes: &[MeshRenderer]) {
+        let meshes = meshes.iter().map(|v| v.mesh).collect();
+        self.0.lock().unwrap().push((camera.culling_mask(), meshes));
+    }
+}
+
+#[test]
+fn clear() {
+    crayon::application::oneshot().unwrap();
+
+    let mut scene = Scene::new(HeadlessRenderer::new());
+    let lhs = video::create_surface(SurfaceParams::default()).unwrap();
+    let rhs = video::create_surface(SurfaceParams::default()).unwrap();
+
+    let mut camera = Camera::default();
+    camera.set_surface(lhs);
+    camera.set_clear(Color::red(), 1.0, None);
+    let e1 = scene.create("e1");
+    scene.add_camera(e1, camera);
+
+    let mut camera = Camera::default();
+    camera.set_surface(rhs);
+    camera.set_clear(Color::blue(), None, 0);
+    let e2 = scene.create("e2");
+    scene.add_camera(e2, camera);
+
+    scene.draw();
+
+    let params = video::surface(lhs).unwrap();
+    assert_eq!(params.clear(), (Some(Color::red()), Some(1.0), None));
+
+    let params = video::surface(rhs).unwrap();
+    assert_eq!(params.clear(), (Some(Color::blue()), None, Some(0)));
+
+    scene.camera_mut(e2).unwrap().remove_clear();
+    video::update_surface_clear(rhs, Color::white(), 1.0, None).unwrap();
+    scene.draw();
+
+    let params = video::surface(rhs).unwrap();
+    assert_eq!(params.clear(), (Some(Color::white()), Some(1.0), None));
+
+    // The original flags are restored once the camera does not override them.
+    let original = SurfaceParams::default().clear();
+    scene.draw();
+    scene.camera_mut(e1).unwrap().remove_clear();
+    scene.draw();
+
+    let params = video::surface(lhs).unwrap();
+    assert_eq!(params.clear(), original);
+
+    scene
+        .camera_mut(e1)
+        .unwrap()
+        .set_clear(Color::green(), None, None);
+    scene.draw();
+    scene.delete(e1);
+    scene.draw();
+
+    let params = video::surface(lhs).unwrap();
+    assert_eq!(params.clear(), original);
+}
+
+#[test]
+fn culling_mask() {
+    let renderer = RecordRenderer::default();
+    let records = renderer.0.clone();
+    let mut scene = Scene::new(renderer);
+
+    let (world, ui) = (MeshHandle::new(1, 1), MeshHandle::new(2, 1));
+
+    let e1 = scene.create("world");
+    let mut mesh = MeshRenderer::from(world);
+    mesh.layer = 0b01;
+    scene.add_mesh(e1, mesh);
+
+    let e2 = scene.create("ui");
+    let mut mesh = MeshRenderer::from(ui);
+    mesh.layer = 0b10;
+    scene.add_mesh(e2, mesh);
+
+    for &mask in &[0b01, 0b10] {
+        let mut camera = Camera::default();
+        camera.set_culling_mask(mask);
+        let e = scene.create("camera");
+        scene.add_camera(e, camera);
+    }
+
+    scene.draw();
+
+    let mut records = records.lock().unwrap().clone();
+    records.sort_by_key(|v| v.0);
+    assert_eq!(records, [(0b01, vec![world]), (0b10, vec![ui])]);
+
+    // Cameras draw every layer by default.
+    let camera = Camera::default();
+    assert!(camera.is_layer_visible(MeshRenderer::default().layer));
+    assert!(camera.is_layer_visible(1 << 31));
+}
@@ -0,0 +1,63 @@
+extern crate crayon;
+extern crate crayon_world;
+
+use crayon::utils::prelude::HandleLike;
+use crayon_world::prelude::*;
+use crayon_world::utils::prelude::SparseSet;
+
+#[test]
+fn sparse_set() {
+    let mut set = SparseSet::new();
+    let e1 = Entity::new(1, 1);
+    let e2 = Entity::new(5, 1);
+    let e3 = Entity::new(3, 1);
+
+    assert_eq!(set.add(e1, 1), None);
+    assert_eq!(set.add(e2, 2), None);
+    assert_eq!(set.add(e3, 3), None);
+    assert_eq!(set.add(e1, 4), Some(1));
+    assert_eq!(set.len(), 3);
+
+    assert_eq!(set.get(e1), Some(&4));
+    assert_eq!(set.get(e2), Some(&2));
+    *set.get_mut(e3).unwrap() = 6;
+    assert_eq!(set.get(e3), Some(&6));
+
+    // Dead entities that share the same index are not accessible.
+    let e4 = Entity::new(1, 2);
+    assert!(!set.has(e4));
+    assert_eq!(set.get(e4), None);
+    assert_eq!(set.get(Entity::new(100, 1)), None);
+
+    assert_eq!(set.remove(e1), Some(4));
+    assert_eq!(set.remove(e1), None);
+    assert!(!set.has(e1));
+    assert_eq!(set.get(e2), Some(&2));
+    assert_eq!(set.get(e3), Some(&6));
+
+    let mut items: Vec<_> = set.iter().map(|(e, &v)| (e, v)).collect();
+    items.sort_by_key(|v| v.1);
+    assert_eq!(items, [(e2, 2), (e3, 6)]);
+    assert_eq!(set.keys().len(), set.values().len());
+
+    for v in set.values_mut() {
+        *v += 1;
+    }
+
+    let items: Vec<_> = set
+        .keys()
+        .iter()
+        .cloned()
+        .zip(set.values().iter())
+        .collect();
+    assert_eq!(items, set.iter().collect::<Vec<_>>());
+    assert_eq!(set.get(e2), Some(&3));
+    assert_eq!(set.get(e3), Some(&7));
+
+    assert_eq!(set.add(e4, 7), None);
+    assert_eq!(set.get(e4), Some(&7));
+    assert_eq!(set.remove(e3), Some(7));
+    assert_eq!(set.remove(e2), Some(3));
+    assert_eq!(set.iter().collect::<Vec<_>>(), [(e4, &7)]);
+    assert!(!set.is_empty());
+}
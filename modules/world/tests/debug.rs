@@ -0,0 +1,117 @@
+#![cfg(feature = "debug")]
+
+extern crate crayon;
+extern crate crayon_world;
+
+use crayon::math::prelude::{Aabb3, EuclideanSpace, Vector3};
+use crayon::video;
+use crayon::video::prelude::SurfaceParams;
+use crayon_world::prelude::*;
+use crayon_world::renderable::debug;
+use crayon_world::renderable::headless::HeadlessRenderer;
+
+#[test]
+fn scene_graph() {
+    let mut scene = Scene::new(HeadlessRenderer::new());
+
+    let e1 = scene.create("e1");
+    let e2 = scene.create("e2");
+    let e3 = scene.create("e3");
+    let e4 = scene.create("e4");
+    scene.create("e5");
+
+    scene.set_parent(e2, e1, false).unwrap();
+    scene.set_parent(e3, e1, false).unwrap();
+    scene.set_parent(e4, e3, false).unwrap();
+    scene.set_position(e4, [1.0, 2.0, 3.0]);
+
+    let mut lines = DebugLines::new();
+    lines.add_scene_graph(&scene.nodes);
+    assert_eq!(lines.len(), 3);
+
+    let link = lines
+        .segments()
+        .iter()
+        .find(|v| v.1 == Vector3::new(1.0, 2.0, 3.0))
+        .unwrap();
+    assert_eq!(link.0, Vector3::new(0.0, 0.0, 0.0));
+
+    let aabb = Aabb3::new(
+        EuclideanSpace::from_vec(Vector3::new(-1.0, -1.0, -1.0)),
+        EuclideanSpace::from_vec(Vector3::new(1.0, 1.0, 1.0)),
+    );
+    lines.add_aabb(aabb, &scene.nodes.transform(e2).unwrap());
+    lines.add_aabb(aabb, &scene.nodes.transform(e4).unwrap());
+    assert_eq!(lines.len(), 3 + 2 * 12);
+
+    for v in &lines.segments()[15..] {
+        let delta = v.1 - v.0;
+        assert_eq!(delta.x.abs() + delta.y.abs() + delta.z.abs(), 2.0);
+    }
+
+    lines.clear();
+    assert!(lines.is_empty());
+}
+
+#[test]
+fn grid() {
+    let mut lines = DebugLines::new();
+
+    // Lines at -2, -1, 0, 1, 2 on both axes.
+    lines.add_grid(2.0, 1.0);
+    assert_eq!(lines.len(), 2 * 5);
+
+    // The axes of origin come first.
+    assert_eq!(
+        lines.segments()[0],
+        (Vector3::new(-2.0, 0.0, 0.0), Vector3::new(2.0, 0.0, 0.0))
+    );
+    assert_eq!(
+        lines.segments()[1],
+        (Vector3::new(0.0, 0.0, -2.0), Vector3::new(0.0, 0.0, 2.0))
+    );
+
+    for v in lines.segments() {
+        assert_eq!((v.0.y, v.1.y), (0.0, 0.0));
+    }
+
+    // The spacing that does not divide the size evenly.
+    lines.clear();
+    lines.add_grid(10.0, 3.0);
+    assert_eq!(lines.len(), 2 * 7);
+
+    lines.clear();
+    lines.add_grid(10.0, 0.0);
+    assert!(lines.is_empty());
+}
+
+#[test]
+fn draw_scene_graph() {
+    crayon::application::oneshot().unwrap();
+    crayon_world::setup().unwrap();
+
+    let mut scene = Scene::new(HeadlessRenderer::new());
+    let surface = video::create_surface(SurfaceParams::default()).unwrap();
+
+    let e1 = scene.create("e1");
+    let e2 = scene.create("e2");
+    let e3 = scene.create("e3");
+    scene.set_parent(e2, e1, false).unwrap();
+    scene.set_parent(e3, e2, false).unwrap();
+    scene.add_mesh(e3, crayon_world::default().cube);
+    assert!(debug::draw_scene_graph(&scene, surface).is_err());
+
+    let camera = scene.create("camera");
+    scene.add_camera(camera, Camera::default());
+
+    // The links of e2 and e3, and the 12 edges of cube make 28 vertices. They are drawn
+    // with a mesh of 32 vertices and 32 u16 indices.
+    let stats = video::memory_stats();
+    debug::draw_scene_graph(&scene, surface).unwrap();
+    let bytes = video::memory_stats().mesh_bytes - stats.mesh_bytes;
+    assert_eq!(bytes, 32 * 12 + 32 * 2);
+
+    // The renderer is shared by the following draws.
+    debug::draw_scene_graph(&scene, surface).unwrap();
+    assert_eq!(video::memory_stats().mesh_bytes - stats.mesh_bytes, bytes);
+}
@@ -60,6 +60,47 @@ pub fn hierachy() {
     assert_ulps_eq!(scene.position(e2).unwrap(), [1.0, 0.0, 1.0].into());
 }
 
+#[test]
+fn roots() {
+    let build = || {
+        let mut scene = Scene::new(HeadlessRenderer::new());
+        let ents: Vec<_> = (0..32).map(|i| scene.create(format!("e{}", i))).collect();
+
+        for i in (0..32).filter(|v| v % 3 == 0) {
+            scene.set_parent(ents[i], ents[31 - i], false).unwrap();
+        }
+
+        scene.remove_from_parent(ents[9], false).unwrap();
+        scene.delete(ents[7]);
+        (scene, ents)
+    };
+
+    let (s1, ents) = build();
+    let roots = s1.nodes.roots().to_vec();
+    assert_eq!(roots, s1.nodes.roots());
+    assert!(!roots.contains(&ents[0]));
+    assert!(!roots.contains(&ents[7]));
+    assert_eq!(roots.last(), Some(&ents[9]));
+
+    let mut sorted = roots.clone();
+    sorted.pop();
+    sorted.sort();
+    assert_eq!(&sorted[..], &roots[..roots.len() - 1]);
+
+    let (mut s2, _) = build();
+    assert_eq!(roots, s2.nodes.roots());
+
+    // Batch removals keep the order of the rest roots.
+    let children = [roots[1], roots[3], roots[4]];
+    s2.set_parent_many(&children, roots[0], false).unwrap();
+    let expected: Vec<_> = roots
+        .iter()
+        .filter(|v| !children.contains(v))
+        .cloned()
+        .collect();
+    assert_eq!(s2.nodes.roots(), &expected[..]);
+}
+
 #[test]
 fn remove() {
     let mut scene = Scene::new(HeadlessRenderer::new());
@@ -161,6 +202,75 @@ fn look_at() {
     assert_ulps_eq!(scene.rotation(e1).unwrap(), euler.into());
 }
 
+#[test]
+fn look_at_rotation() {
+    use std::f32::consts::FRAC_1_SQRT_2;
+
+    let mut scene = Scene::new(HeadlessRenderer::new());
+    let e1 = scene.create("e1");
+
+    // (position, up, expected side, expected up, expected forward)
+    let cases = [
+        (
+            [0.0, 0.0, -5.0],
+            [0.0, 1.0, 0.0],
+            [1.0, 0.0, 0.0],
+            [0.0, 1.0, 0.0],
+            [0.0, 0.0, 1.0],
+        ),
+        (
+            [5.0, 0.0, 0.0],
+            [0.0, 1.0, 0.0],
+            [0.0, 0.0, 1.0],
+            [0.0, 1.0, 0.0],
+            [-1.0, 0.0, 0.0],
+        ),
+        (
+            [0.0, -5.0, 0.0],
+            [0.0, 0.0, 1.0],
+            [-1.0, 0.0, 0.0],
+            [0.0, 0.0, 1.0],
+            [0.0, 1.0, 0.0],
+        ),
+        (
+            [1.0, 0.0, 1.0],
+            [0.0, 1.0, 0.0],
+            [-FRAC_1_SQRT_2, 0.0, FRAC_1_SQRT_2],
+            [0.0, 1.0, 0.0],
+            [-FRAC_1_SQRT_2, 0.0, -FRAC_1_SQRT_2],
+        ),
+        (
+            [0.0, 0.0, -5.0],
+            [0.0, 1.0, 1.0],
+            [1.0, 0.0, 0.0],
+            [0.0, 1.0, 0.0],
+            [0.0, 0.0, 1.0],
+        ),
+    ];
+
+    for &(position, up, side, expected_up, forward) in &cases {
+        scene.set_position(e1, position);
+        scene.look_at(e1, [0.0, 0.0, 0.0], up);
+
+        let rotation = scene.rotation(e1).unwrap();
+        assert_ulps_eq!(rotation * Vector3::unit_x(), side.into());
+        assert_ulps_eq!(rotation * Vector3::unit_y(), expected_up.into());
+        assert_ulps_eq!(rotation * Vector3::unit_z(), forward.into());
+
+        let wrapped = Transform::look_rotation(-Vector3::from(position), up);
+        assert_ulps_eq!(wrapped * Vector3::unit_z(), forward.into());
+    }
+
+    scene.set_position(e1, [0.0, 0.0, -5.0]);
+    scene.look_at(e1, [0.0, 0.0, 0.0], [0.0, 1.0, 0.0]);
+    assert_ulps_eq!(scene.rotation(e1).unwrap(), Quaternion::one());
+
+    scene.set_position(e1, [5.0, 0.0, 0.0]);
+    scene.look_at(e1, [0.0, 0.0, 0.0], [0.0, 1.0, 0.0]);
+    let expected = Euler::new(Deg(0.0), Deg(-90.0), Deg(0.0)).into();
+    assert_ulps_eq!(scene.rotation(e1).unwrap(), expected);
+}
+
 #[test]
 fn iteration() {
     let mut scene = Scene::new(HeadlessRenderer::new());
@@ -229,3 +339,65 @@ fn random_iteration() {
     let len = scene.descendants(constructed[0]).count();
     assert_eq!(len, 254);
 }
+
+#[test]
+fn set_parent_many() {
+    let mut scene = Scene::new(HeadlessRenderer::new());
+    let parent = scene.create("parent");
+    let old_parent = scene.create("old_parent");
+    let sib = scene.create("sib");
+
+    scene.set_position(parent, [1.0, 2.0, 3.0]);
+    scene.set_position(old_parent, [-1.0, 0.0, 0.0]);
+    scene.set_parent(sib, parent, false).unwrap();
+
+    let mut children = Vec::new();
+    for i in 0..100 {
+        let e = scene.create(format!("child{}", i));
+        if i % 2 == 0 {
+            scene.set_parent(e, old_parent, false).unwrap();
+        }
+
+        scene.set_local_position(e, [i as f32, 0.0, 0.0]);
+        children.push(e);
+    }
+
+    let positions: Vec<_> = children
+        .iter()
+        .map(|&v| scene.position(v).unwrap())
+        .collect();
+
+    scene.set_parent_many(&children, parent, true).unwrap();
+
+    let mut expected = children.clone();
+    expected.push(sib);
+    assert_eq!(scene.children(parent).collect::<Vec<_>>(), expected);
+    assert_eq!(scene.children(old_parent).count(), 0);
+
+    for (&child, &position) in children.iter().zip(positions.iter()) {
+        assert_eq!(scene.parent(child), Some(parent));
+        assert!(!scene.is_root(child));
+        assert_ulps_eq!(scene.position(child).unwrap(), position);
+    }
+
+    // Detachs a child in the middle of siblings.
+    scene.remove_from_parent(children[50], false).unwrap();
+    assert_eq!(scene.children(parent).count(), 100);
+    assert!(scene.is_root(children[50]));
+
+    assert!(scene.set_parent_many(&[sib, sib], parent, false).is_err());
+    assert!(scene.set_parent_many(&[parent], parent, false).is_err());
+
+    // Failures must leave the hierarchy untouched.
+    assert!(scene
+        .set_parent_many(&[children[50], parent], children[0], false)
+        .is_err());
+    assert!(scene.is_root(children[50]));
+    assert_eq!(scene.parent(children[0]), Some(parent));
+    assert_eq!(scene.children(parent).count(), 100);
+
+    let dead = scene.create("dead");
+    scene.delete(dead);
+    assert!(scene.set_parent_many(&[children[50]], dead, false).is_err());
+    assert!(scene.is_root(children[50]));
+}
@@ -0,0 +1,65 @@
+#[macro_use]
+extern crate crayon;
+extern crate crayon_world;
+
+use crayon::math::prelude::*;
+use crayon::prelude::*;
+use crayon::utils::handle::HandleLike;
+use crayon_world::prelude::*;
+use crayon_world::spatial::prelude::Transform;
+
+#[test]
+fn select() {
+    let lod = Lod::new()
+        .with(MeshHandle::new(3, 1), 100.0)
+        .with(MeshHandle::new(1, 1), 10.0)
+        .with(MeshHandle::new(2, 1), 50.0);
+
+    let levels: Vec<_> = lod.levels().iter().map(|v| v.1).collect();
+    assert_eq!(levels, [10.0, 50.0, 100.0]);
+    assert_eq!(lod.levels()[0].0, MeshHandle::new(1, 1));
+
+    assert_eq!(lod.select(0.0), Some(0));
+    assert_eq!(lod.select(10.0), Some(0));
+    assert_eq!(lod.select(10.5), Some(1));
+    assert_eq!(lod.select(75.0), Some(2));
+    assert_eq!(lod.select(1000.0), Some(2));
+
+    let mut lod = lod;
+    lod.cull = true;
+    assert_eq!(lod.select(75.0), Some(2));
+    assert_eq!(lod.select(1000.0), None);
+
+    assert_eq!(Lod::new().select(0.0), None);
+}
+
+#[test]
+fn distance() {
+    let aabb = Aabb3::new(
+        EuclideanSpace::from_vec(Vector3::new(1.0, -1.0, -1.0)),
+        EuclideanSpace::from_vec(Vector3::new(3.0, 1.0, 1.0)),
+    );
+
+    let lod = Lod::new()
+        .with(MeshHandle::new(1, 1), 5.0)
+        .with(MeshHandle::new(2, 1), 20.0);
+
+    let mut transform = Transform::default();
+    transform.position = Vector3::new(0.0, 0.0, 10.0);
+
+    let mut camera = Transform::default();
+    for &(z, index) in &[
+        (10.0, Some(0)),
+        (2.0, Some(1)),
+        (-10.0, Some(1)),
+        (-20.0, None),
+    ] {
+        camera.position = Vector3::new(2.0, 0.0, z);
+        let distance = Lod::distance(&camera, &transform, aabb);
+        assert_ulps_eq!(distance, 10.0 - z);
+
+        let mut lod = lod.clone();
+        lod.cull = true;
+        assert_eq!(lod.select(distance), index);
+    }
+}
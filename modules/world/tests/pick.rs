@@ -0,0 +1,54 @@
+#[macro_use]
+extern crate crayon;
+extern crate crayon_world;
+
+use crayon::math::prelude::*;
+use crayon::prelude::*;
+use crayon_world::prelude::*;
+use crayon_world::renderable::headless::HeadlessRenderer;
+
+fn cube() -> MeshHandle {
+    let mut params = MeshParams::default();
+    params.aabb = Aabb3::new(
+        EuclideanSpace::from_vec(Vector3::new(-0.5, -0.5, -0.5)),
+        EuclideanSpace::from_vec(Vector3::new(0.5, 0.5, 0.5)),
+    );
+
+    video::create_mesh(params, None).unwrap()
+}
+
+#[test]
+fn pick() {
+    crayon::application::oneshot().unwrap();
+
+    let mesh = cube();
+    let mut scene = Scene::new(HeadlessRenderer::new());
+
+    let near = scene.create("near");
+    scene.add_mesh(near, mesh);
+    scene.set_position(near, [0.0, 0.0, 5.0]);
+
+    let far = scene.create("far");
+    scene.add_mesh(far, mesh);
+    scene.set_position(far, [0.0, 0.0, 10.0]);
+
+    let ray = Ray::new(Vector3::new(0.0, 0.0, 0.0), Vector3::new(0.0, 0.0, 2.0));
+    let (ent, distance) = scene.pick(ray).unwrap();
+    assert_eq!(ent, near);
+    assert_ulps_eq!(distance, 4.5);
+
+    scene.mesh_mut(near).unwrap().pickable = false;
+    let (ent, distance) = scene.pick(ray).unwrap();
+    assert_eq!(ent, far);
+    assert_ulps_eq!(distance, 9.5);
+
+    scene.mesh_mut(far).unwrap().visible = false;
+    assert!(scene.pick(ray).is_none());
+
+    scene.mesh_mut(near).unwrap().pickable = true;
+    let ray = Ray::new(Vector3::new(0.0, 1.0, 0.0), Vector3::new(0.0, 0.0, 1.0));
+    assert!(scene.pick(ray).is_none());
+
+    let ray = Ray::new(Vector3::new(0.0, 0.0, 5.0), Vector3::new(1.0, 0.0, 0.0));
+    assert_eq!(scene.pick(ray), Some((near, 0.0)));
+}
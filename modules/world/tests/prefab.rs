@@ -0,0 +1,67 @@
+extern crate crayon;
+extern crate crayon_world;
+
+use crayon::bincode;
+use crayon::res::utils::prelude::ResourceLoader;
+use crayon_world::assets::prefab::PrefabNode;
+use crayon_world::assets::prefab_loader::{MAGIC, VERSION};
+use crayon_world::prelude::*;
+
+fn serialize(version: u32, prefab: &Prefab) -> Vec<u8> {
+    let mut bytes = MAGIC[0..5].to_vec();
+    bytes.extend_from_slice(&[(version >> 16) as u8, (version >> 8) as u8, version as u8]);
+    bytes.extend_from_slice(&bincode::serialize(prefab).unwrap());
+    bytes
+}
+
+fn prefab() -> Prefab {
+    Prefab {
+        nodes: vec![PrefabNode {
+            name: "room".into(),
+            local_transform: Transform::default(),
+            first_child: None,
+            next_sib: None,
+            mesh_renderer: None,
+        }],
+        universe_meshes: Vec::new(),
+        meshes: Vec::new(),
+    }
+}
+
+#[test]
+fn load() {
+    let loader = PrefabLoader::new();
+    let bytes = serialize(VERSION, &prefab());
+    let prefab = loader.load(PrefabHandle::default(), &bytes).unwrap();
+    assert_eq!(prefab.nodes.len(), 1);
+    assert_eq!(prefab.nodes[0].name, "room");
+
+    assert!(loader.load(PrefabHandle::default(), &bytes[0..4]).is_err());
+}
+
+#[test]
+fn unknown_version() {
+    let loader = PrefabLoader::new();
+    let bytes = serialize(VERSION + 1, &prefab());
+    let err = loader.load(PrefabHandle::default(), &bytes).unwrap_err();
+
+    let msg = format!("{}", err);
+    assert!(msg.contains(&format!("version {}", VERSION + 1)));
+    assert!(msg.contains("migration"));
+}
+
+#[test]
+fn migration() {
+    fn migrate(_: &[u8]) -> crayon::errors::Result<Prefab> {
+        let mut prefab = prefab();
+        prefab.nodes[0].name = "migrated".into();
+        Ok(prefab)
+    }
+
+    let loader = PrefabLoader::new();
+    loader.add_migration(0, migrate);
+
+    let bytes = serialize(0, &prefab());
+    let prefab = loader.load(PrefabHandle::default(), &bytes).unwrap();
+    assert_eq!(prefab.nodes[0].name, "migrated");
+}
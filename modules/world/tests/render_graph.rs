@@ -0,0 +1,46 @@
+extern crate crayon;
+extern crate crayon_world;
+
+use crayon::utils::prelude::HandleLike;
+use crayon::video::assets::prelude::RenderTextureHandle;
+use crayon_world::prelude::*;
+
+#[test]
+fn resolve() {
+    let shadow = RenderTextureHandle::new(1, 1);
+    let color = RenderTextureHandle::new(2, 1);
+
+    let mut graph = RenderGraph::new();
+    let post = graph.add_pass("post", &[color], &[]);
+    let main = graph.add_pass("main", &[shadow], &[color]);
+    let shadow = graph.add_pass("shadow", &[], &[shadow]);
+
+    assert_eq!(graph.len(), 3);
+    assert_eq!(graph.pass(main).unwrap().name, "main");
+    assert_eq!(graph.resolve().unwrap(), vec![shadow, main, post]);
+}
+
+#[test]
+fn independent() {
+    let color = RenderTextureHandle::new(1, 1);
+
+    let mut graph = RenderGraph::new();
+    let a = graph.add_pass("a", &[], &[]);
+    let b = graph.add_pass("b", &[color], &[color]);
+    let c = graph.add_pass("c", &[], &[]);
+    assert_eq!(graph.resolve().unwrap(), vec![a, b, c]);
+}
+
+#[test]
+fn cycle() {
+    let t1 = RenderTextureHandle::new(1, 1);
+    let t2 = RenderTextureHandle::new(2, 1);
+
+    let mut graph = RenderGraph::new();
+    graph.add_pass("first", &[t2], &[t1]);
+    graph.add_pass("second", &[t1], &[t2]);
+    graph.add_pass("third", &[], &[]);
+
+    let err = graph.resolve().unwrap_err();
+    assert!(format!("{}", err).contains("first"));
+}
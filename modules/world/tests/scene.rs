@@ -31,6 +31,45 @@ fn find() {
     assert_eq!(scene.find("room.obj/blahblah"), None);
 }
 
+#[test]
+fn query() {
+    use crayon::video::assets::prelude::MeshHandle;
+
+    let mut scene = Scene::new(HeadlessRenderer::new());
+    let mesh = MeshHandle::default();
+
+    let e1 = scene.create("mesh");
+    scene.add_mesh(e1, mesh);
+
+    let e2 = scene.create("lit");
+    scene.add_lit(e2, Lit::default());
+
+    let e3 = scene.create("lit_mesh");
+    scene.add_mesh(e3, mesh);
+    scene.add_lit(e3, Lit::default());
+
+    let e4 = scene.create("empty");
+    scene.set_parent(e3, e4, false).unwrap();
+
+    let mut meshes = scene.entities_with_mesh().to_vec();
+    meshes.sort();
+    assert_eq!(meshes, [e1, e3]);
+
+    let mut lits = scene.entities_with_lit().to_vec();
+    lits.sort();
+    assert_eq!(lits, [e2, e3]);
+
+    assert!(scene.entities_with_camera().is_empty());
+    assert!(scene.entities_with_lod().is_empty());
+
+    scene.remove_mesh(e1);
+    assert_eq!(scene.entities_with_mesh(), [e3]);
+
+    scene.delete(e4);
+    assert!(scene.entities_with_mesh().is_empty());
+    assert_eq!(scene.entities_with_lit(), [e2]);
+}
+
 #[test]
 fn instantiate() {
     use crayon_world::assets::prefab::PrefabNode;
@@ -88,3 +127,42 @@ fn instantiate() {
         scene.find("room.obj/floor/tallBox")
     );
 }
+
+#[test]
+fn reset() {
+    use crayon::utils::prelude::HandleLike;
+
+    let mut scene = Scene::new(HeadlessRenderer::new());
+    let ids = |v: &[Entity]| -> Vec<_> { v.iter().map(|e| (e.index(), e.version())).collect() };
+
+    let e1 = scene.create("e1");
+    let e2 = scene.create("e2");
+    let e3 = scene.create("e3");
+    scene.set_parent(e3, e1, false).unwrap();
+    assert_eq!(ids(&[e1, e2, e3]), [(0, 1), (1, 1), (2, 1)]);
+
+    // The freed indices are reused from the lowest one, with a new version.
+    scene.delete(e2);
+    scene.delete(e1);
+    let e4 = scene.create("e4");
+    let e5 = scene.create("e5");
+    assert_eq!(ids(&[e4, e5]), [(0, 3), (1, 3)]);
+
+    scene.add_mtl(e4, ());
+    scene.add_mtl(e5, ());
+    scene.delete(e5);
+    assert!(scene.mtl(e5).is_none());
+
+    scene.reset();
+    assert_eq!(scene.len(), 0);
+    assert!(scene.find("e4").is_none());
+
+    let e1 = scene.create("e1");
+    let e2 = scene.create("e2");
+    scene.delete(e1);
+    let e3 = scene.create("e3");
+    assert_eq!(ids(&[e1, e2, e3]), [(0, 1), (1, 1), (0, 3)]);
+
+    // The materials of entities before resetting are removed, so they are not aliased.
+    assert!(scene.mtl(e3).is_none());
+}
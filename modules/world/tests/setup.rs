@@ -0,0 +1,12 @@
+extern crate crayon;
+extern crate crayon_world;
+
+#[test]
+fn duplicated_setup() {
+    crayon::application::oneshot().unwrap();
+    crayon_world::setup().unwrap();
+
+    let white = crayon_world::default().white;
+    assert!(crayon_world::setup().is_err());
+    assert_eq!(crayon_world::default().white, white);
+}
@@ -0,0 +1,141 @@
+#[cfg_attr(feature = "software", macro_use)]
+extern crate crayon;
+extern crate crayon_world;
+#[cfg(feature = "software")]
+extern crate image;
+
+use crayon::prelude::*;
+use crayon_world::prelude::*;
+
+#[test]
+fn normal_mapping() {
+    let params = SimpleRenderer::shader_params();
+
+    assert_eq!(
+        params.uniforms.variable_type("u_NormalTexture"),
+        Some(UniformVariableType::Texture)
+    );
+
+    assert_eq!(
+        params.uniforms.variable_type("u_NormalMapping"),
+        Some(UniformVariableType::F32)
+    );
+
+    let tangent = params
+        .attributes
+        .iter()
+        .find(|v| v.0 == Attribute::Tangent)
+        .unwrap();
+    assert_eq!(tangent, (Attribute::Tangent, 3, false));
+
+    assert!(SimpleMaterial::default().normal_texture.is_none());
+}
+
+// The software rasterizer does not execute shaders. It treats the positions of vertices
+// as clip space directly, and fills triangles with white.
+#[cfg(feature = "software")]
+impl_vertex!{
+    Vertex {
+        position => [Position; Float; 3; false],
+        normal => [Normal; Float; 3; false],
+    }
+}
+
+#[cfg(feature = "software")]
+struct Window {
+    _scene: Scene<SimpleRenderer>,
+}
+
+#[cfg(feature = "software")]
+impl LifecycleListener for Window {}
+
+#[cfg(feature = "software")]
+fn triangle(points: [[f32; 3]; 3]) -> crayon::errors::Result<MeshHandle> {
+    let verts: Vec<_> = points
+        .iter()
+        .map(|&v| Vertex::new(v, [0.0, 0.0, 1.0]))
+        .collect();
+
+    let params = MeshParams {
+        layout: Vertex::layout(),
+        num_verts: 3,
+        num_idxes: 3,
+        ..Default::default()
+    };
+
+    let data = MeshData {
+        vptr: Vertex::encode(&verts[..]).into(),
+        iptr: IndexFormat::encode(&[0u16, 1, 2]).into(),
+    };
+
+    video::create_mesh(params, data)
+}
+
+#[test]
+#[cfg(feature = "software")]
+fn depth_prepass() {
+    let path = std::env::temp_dir().join("crayon-world-depth-prepass.png");
+    let output = path.clone();
+
+    Engine::new_with(&Params::default())
+        .with_headless(true)
+        .with_software(true)
+        .run(move || -> crayon::errors::Result<Window> {
+            crayon_world::setup()?;
+
+            let params = RenderTextureParams {
+                format: RenderTextureFormat::RGBA8,
+                dimensions: (4, 4).into(),
+                ..Default::default()
+            };
+
+            let rt = video::create_render_texture(params)?;
+
+            let mut params = SurfaceParams::default();
+            params.set_attachments(&[rt], None)?;
+            let surface = video::create_surface(params)?;
+
+            let mut renderer = SimpleRenderer::new()?;
+            renderer.set_depth_prepass(true)?;
+            let mut scene = Scene::new(renderer);
+
+            let mut camera = Camera::default();
+            camera.set_surface(surface);
+            camera.set_clear(Color::black(), 1.0, None);
+            let e = scene.create("camera");
+            scene.add_camera(e, camera);
+
+            // A tilted triangle that covers the lower left half, and a nearer one in the
+            // lower right corner that overlaps with it.
+            let far = triangle([[-1.0, -1.0, -0.2], [1.0, -1.0, 0.6], [-1.0, 1.0, 0.2]])?;
+            let near = triangle([[0.0, -1.0, -0.8], [1.0, -1.0, -0.8], [1.0, 0.0, -0.4]])?;
+
+            for &mesh in &[far, near] {
+                let e = scene.create("mesh");
+                scene.add_mesh(e, mesh);
+            }
+
+            scene.draw();
+            video::save_surface_screenshot(surface, output)?;
+            Ok(Window { _scene: scene })
+        })
+        .unwrap();
+
+    let image = image::open(&path).unwrap().to_rgba();
+    std::fs::remove_file(&path).unwrap();
+
+    // The prepass does not write colors, so the pixels are white only if the color pass
+    // passed the `Equal` depth test. The rows of image are top to bottom.
+    for y in 0..4 {
+        for x in 0..4 {
+            let covered = x <= y || (x, y) == (3, 2);
+            let expected = if covered {
+                [255, 255, 255, 255]
+            } else {
+                [0, 0, 0, 255]
+            };
+
+            assert_eq!(image.get_pixel(x, y).data, expected, "({}, {})", x, y);
+        }
+    }
+}
@@ -1,7 +1,10 @@
 #[macro_use]
 extern crate crayon;
 extern crate crayon_world;
+extern crate rand;
+extern crate serde_json;
 
+use crayon::bincode;
 use crayon::prelude::*;
 use crayon_world::prelude::*;
 
@@ -44,3 +47,72 @@ fn inverse() {
     assert_ulps_eq!(v.scale, 1.0);
     assert_ulps_eq!(v.rotation, Quaternion::one());
 }
+
+#[test]
+fn serde() {
+    let e1 = Transform {
+        scale: 2.0,
+        position: [1.0, 2.0, 3.0].into(),
+        rotation: Euler::new(Deg(0.0), Deg(90.0), Deg(0.0)).into(),
+    };
+
+    let bytes = bincode::serialize(&e1).unwrap();
+    let e2: Transform = bincode::deserialize(&bytes).unwrap();
+    assert_eq!(e2.scale, e1.scale);
+    assert_eq!(e2.position, e1.position);
+    assert_eq!(e2.rotation, e1.rotation);
+
+    let json = serde_json::to_string(&e1).unwrap();
+    let e2: Transform = serde_json::from_str(&json).unwrap();
+    assert_eq!(e2.scale, e1.scale);
+    assert_eq!(e2.position, e1.position);
+    assert_eq!(e2.rotation, e1.rotation);
+}
+
+#[test]
+fn from_matrix() {
+    for _ in 0..100 {
+        let angle = || Deg(rand::random::<f32>() * 360.0 - 180.0);
+        let e1 = Transform {
+            scale: rand::random::<f32>() * 10.0 + 0.1,
+            position: Vector3::new(
+                rand::random::<f32>() * 200.0 - 100.0,
+                rand::random::<f32>() * 200.0 - 100.0,
+                rand::random::<f32>() * 200.0 - 100.0,
+            ),
+            rotation: Euler::new(angle(), angle(), angle()).into(),
+        };
+
+        let e2 = Transform::from_matrix(&e1.matrix()).unwrap();
+        assert_relative_eq!(e2.scale, e1.scale, max_relative = 1e-4);
+        assert_relative_eq!(e2.position, e1.position, epsilon = 1e-4);
+        // The quaternions q and -q represent the same rotation.
+        assert_relative_eq!(e2.rotation.dot(e1.rotation).abs(), 1.0, epsilon = 1e-4);
+    }
+}
+
+#[test]
+fn from_matrix_rejections() {
+    let mut m = Transform::default().matrix();
+    m.y.x = 0.5;
+    assert!(Transform::from_matrix(&m).is_none());
+
+    let m = Matrix4::from_nonuniform_scale(1.0, 2.0, 1.0);
+    assert!(Transform::from_matrix(&m).is_none());
+
+    let m = Matrix4::from_scale(-1.0);
+    assert!(Transform::from_matrix(&m).is_none());
+
+    let m = Matrix4::from_scale(0.0);
+    assert!(Transform::from_matrix(&m).is_none());
+
+    let mut m = Matrix4::from_scale(2.0);
+    m.x.w = 1.0;
+    assert!(Transform::from_matrix(&m).is_none());
+
+    let m = Matrix4::from_translation(Vector3::new(1.0, 2.0, 3.0)) * Matrix4::from_scale(2.0);
+    let v = Transform::from_matrix(&m).unwrap();
+    assert_ulps_eq!(v.position, [1.0, 2.0, 3.0].into());
+    assert_ulps_eq!(v.scale, 2.0);
+    assert_ulps_eq!(v.rotation, Quaternion::one());
+}
@@ -72,14 +72,14 @@ impl EngineSystem {
         Ok(sys)
     }
 
-    pub unsafe fn new_headless(params: Params) -> Result<Self> {
+    pub unsafe fn new_headless(params: Params, software: bool) -> Result<Self> {
         #[cfg(not(target_arch = "wasm32"))]
         crate::sched::inside::setup(4, None, None);
         #[cfg(target_arch = "wasm32")]
         crate::sched::inside::setup(0, None, None);
 
         crate::window::inside::headless();
-        crate::video::inside::headless();
+        crate::video::inside::headless(software);
         crate::input::inside::setup(params.input);
         crate::res::inside::setup(params.res)?;
 
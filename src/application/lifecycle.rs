@@ -29,6 +29,7 @@ pub trait LifecycleListener {
 pub struct LifecycleSystem {
     last_frame_lifecycles: Mutex<Vec<Arc<Mutex<LifecycleListener>>>>,
     lifecycles: Mutex<ObjectPool<LifecycleListenerHandle, Arc<Mutex<LifecycleListener>>>>,
+    modules: Mutex<Vec<(&'static str, fn())>>,
 }
 
 impl LifecycleSystem {
@@ -36,6 +37,31 @@ impl LifecycleSystem {
         LifecycleSystem {
             last_frame_lifecycles: Mutex::new(Vec::new()),
             lifecycles: Mutex::new(ObjectPool::new()),
+            modules: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Registers the discard function of module system. A module that is registered
+    /// again is moved to the end, as if it's set up for the first time.
+    pub fn register_module(&self, name: &'static str, discard: fn()) {
+        let mut modules = self.modules.lock().unwrap();
+        modules.retain(|v| v.0 != name);
+        modules.push((name, discard));
+    }
+
+    /// Discards all the registered module systems in the reverse order of registration.
+    pub fn discard_modules(&self) {
+        loop {
+            // Makes sure that the lock has been freed before discarding, since the module
+            // might be registered or discarded recursively.
+            let module = self.modules.lock().unwrap().pop();
+            match module {
+                Some((name, discard)) => {
+                    info!("[LifecycleSystem] Discards module {}.", name);
+                    discard();
+                }
+                None => return,
+            }
         }
     }
 
@@ -99,3 +125,43 @@ impl LifecycleSystem {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    static COUNTER: AtomicUsize = AtomicUsize::new(0);
+    static AUDIO: AtomicUsize = AtomicUsize::new(0);
+    static WORLD: AtomicUsize = AtomicUsize::new(0);
+    static CONSOLE: AtomicUsize = AtomicUsize::new(0);
+
+    fn discard_audio() {
+        AUDIO.store(COUNTER.fetch_add(1, Ordering::SeqCst) + 1, Ordering::SeqCst);
+    }
+
+    fn discard_world() {
+        WORLD.store(COUNTER.fetch_add(1, Ordering::SeqCst) + 1, Ordering::SeqCst);
+    }
+
+    fn discard_console() {
+        CONSOLE.store(COUNTER.fetch_add(1, Ordering::SeqCst) + 1, Ordering::SeqCst);
+    }
+
+    #[test]
+    fn discard_modules() {
+        let lifecycle = LifecycleSystem::new();
+        lifecycle.register_module("audio", discard_audio);
+        lifecycle.register_module("console", discard_console);
+        lifecycle.register_module("world", discard_world);
+        lifecycle.register_module("console", discard_console);
+        lifecycle.discard_modules();
+
+        assert_eq!(CONSOLE.load(Ordering::SeqCst), 1);
+        assert_eq!(WORLD.load(Ordering::SeqCst), 2);
+        assert_eq!(AUDIO.load(Ordering::SeqCst), 3);
+
+        lifecycle.discard_modules();
+        assert_eq!(COUNTER.load(Ordering::SeqCst), 3);
+    }
+}
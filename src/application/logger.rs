@@ -0,0 +1,149 @@
+//! An opt-in sink for the `log` facade.
+//!
+//! Crayon reports its diagnostics through the `log` crate, but it does not install
+//! a logger on native platforms. Applications that do not use `env_logger` or similar
+//! crates could install this one instead:
+//!
+//! ```rust,ignore
+//! use crayon::application::logger::{Logger, LevelFilter};
+//!
+//! Logger::new(LevelFilter::Info)
+//!     .with_module("crayon::res", LevelFilter::Warn)
+//!     .with_callback(|record| println!("{}", record.message))
+//!     .install()
+//!     .unwrap();
+//! ```
+
+use std::io::Write;
+
+use crate::errors::*;
+
+pub use log::{Level, LevelFilter};
+
+/// A log record with all of its formatted fields.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LogRecord {
+    pub level: Level,
+    /// The target of record, which is the module path by default.
+    pub target: String,
+    pub message: String,
+    pub file: Option<String>,
+    pub line: Option<u32>,
+}
+
+pub type LogCallback = Box<dyn Fn(&LogRecord) + Send + Sync>;
+
+/// A logger with per-module level filtering and custom forwarding.
+pub struct Logger {
+    level: LevelFilter,
+    modules: Vec<(String, LevelFilter)>,
+    callbacks: Vec<LogCallback>,
+    stderr: bool,
+}
+
+impl Logger {
+    /// Creates a new `Logger` that accepts records up to `level`, and prints them into
+    /// the standard error.
+    pub fn new(level: LevelFilter) -> Self {
+        Logger {
+            level,
+            modules: Vec::new(),
+            callbacks: Vec::new(),
+            stderr: true,
+        }
+    }
+
+    /// Overrides the level of records emitted by the module and its sub-modules. The
+    /// most specific module takes priority.
+    pub fn with_module<T: Into<String>>(mut self, module: T, level: LevelFilter) -> Self {
+        self.modules.push((module.into(), level));
+        self
+    }
+
+    /// Adds a callback that receives every accepted record.
+    pub fn with_callback<T>(mut self, callback: T) -> Self
+    where
+        T: Fn(&LogRecord) + Send + Sync + 'static,
+    {
+        self.callbacks.push(Box::new(callback));
+        self
+    }
+
+    /// Sets whether accepted records should be printed into the standard error.
+    pub fn with_stderr(mut self, stderr: bool) -> Self {
+        self.stderr = stderr;
+        self
+    }
+
+    /// Gets the level filter of specified target.
+    pub fn level(&self, target: &str) -> LevelFilter {
+        let mut matched: Option<&(String, LevelFilter)> = None;
+
+        for v in &self.modules {
+            let prefixed = target == v.0
+                || (target.starts_with(v.0.as_str()) && target[v.0.len()..].starts_with("::"));
+
+            if prefixed {
+                match matched {
+                    Some(m) if m.0.len() >= v.0.len() => {}
+                    _ => matched = Some(v),
+                }
+            }
+        }
+
+        matched.map_or(self.level, |v| v.1)
+    }
+
+    /// Installs this logger as the global logger of `log` facade. It fails if there is
+    /// already a logger installed, which is always the case on web browsers.
+    pub fn install(self) -> Result<()> {
+        let max = self
+            .modules
+            .iter()
+            .fold(self.level, |acc, v| ::std::cmp::max(acc, v.1));
+
+        log::set_boxed_logger(Box::new(self))
+            .map_err(|_| format_err!("A global logger has been installed already."))?;
+
+        log::set_max_level(max);
+        Ok(())
+    }
+}
+
+impl log::Log for Logger {
+    fn enabled(&self, metadata: &log::Metadata) -> bool {
+        metadata.level() <= self.level(metadata.target())
+    }
+
+    fn log(&self, record: &log::Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+
+        let record = LogRecord {
+            level: record.level(),
+            target: record.target().to_owned(),
+            message: format!("{}", record.args()),
+            file: record.file().map(|v| v.to_owned()),
+            line: record.line(),
+        };
+
+        if self.stderr {
+            let _ = writeln!(
+                ::std::io::stderr(),
+                "[{}] {}: {}",
+                record.level,
+                record.target,
+                record.message
+            );
+        }
+
+        for v in &self.callbacks {
+            v(&record);
+        }
+    }
+
+    fn flush(&self) {
+        let _ = ::std::io::stderr().flush();
+    }
+}
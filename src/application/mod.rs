@@ -21,11 +21,18 @@
 //!
 //! # Engine
 //!
-//! `Engine` mentioned above is the most fundamental module in crayon. It binds various
-//! essential systems in a central place, and responsible for running the main loop.
+//! `Engine` is the most fundamental module in crayon. It binds various essential systems
+//! in a central place, and responsible for running the main loop. The setup above is a
+//! shortcut of building an `Engine` and running it with the application:
+//!
+//! ```rust,ignore
+//! let params = Params::default();
+//! Engine::new_with(&params).run(|| Ok(Window {}))?;
+//! ```
 //!
 
 pub mod ins;
+pub mod logger;
 pub mod sys;
 
 mod engine;
@@ -36,7 +43,8 @@ mod time;
 pub mod prelude {
     pub use super::launcher::Launcher;
     pub use super::lifecycle::{LifecycleListener, LifecycleListenerHandle};
-    pub use super::Params;
+    pub use super::time::FrameInfo;
+    pub use super::{Engine, Params};
 }
 
 use crate::errors::*;
@@ -44,9 +52,9 @@ use crate::errors::*;
 use self::lifecycle::{LifecycleListener, LifecycleListenerHandle};
 
 use self::engine::EngineSystem;
-use self::inside::{ctx, lifecycle_ctx, time_ctx, CTX, LIFECYCLE_CTX, TIME_CTX};
-use self::lifecycle::LifecycleSystem;
-use self::time::TimeSystem;
+use self::inside::{ctx, lifecycle_ctx, setup_engine, setup_lifecycle, setup_time, time_ctx};
+use self::inside::{CTX, LIFECYCLE_CTX, TIME_CTX};
+use self::time::{FrameInfo, TimeSystem};
 
 use crate::input::InputParams;
 use crate::res::ResourceParams;
@@ -69,6 +77,8 @@ pub struct Params {
     pub max_inactive_fps: u32,
     /// Set how many frames to average for timestep smoothing.
     pub time_smooth_step: u32,
+    /// Set how many frames of `FrameInfo` to keep for `frame_history`.
+    pub frame_history_size: u32,
     /// The setup parameters for window sub-system.
     pub window: WindowParams,
     /// The setup parameters for input sub-system.
@@ -84,6 +94,7 @@ impl Default for Params {
             max_fps: 30,
             max_inactive_fps: 0,
             time_smooth_step: 0,
+            frame_history_size: 120,
             window: WindowParams::default(),
             input: InputParams::default(),
             res: ResourceParams::default(),
@@ -104,44 +115,99 @@ impl Params {
     }
 }
 
-/// Setup the core system.
-pub fn setup<T, T2>(mut params: Params, closure: T) -> Result<()>
+/// Setup the core system. It fails if the core system has been set up already.
+///
+/// It's a shortcut of `Engine::new_with(&params).run(closure)`.
+pub fn setup<T, T2>(params: Params, closure: T) -> Result<()>
 where
     T: FnOnce() -> Result<T2> + 'static,
     T2: LifecycleListener + Send + 'static,
 {
-    unsafe {
-        debug_assert!(LIFECYCLE_CTX.is_null(), "duplicated setup of crayon.");
+    Engine::new_with(&params).run(closure)
+}
 
-        sys::init();
-        params.validate();
+/// The builder of the core system, which runs the main loop with the application.
+pub struct Engine {
+    params: Params,
+    headless: bool,
+    software: bool,
+}
+
+impl Engine {
+    /// Creates an `Engine` with `params`. It runs headless if the program is launched with
+    /// the `headless` argument.
+    pub fn new_with(params: &Params) -> Self {
+        Engine {
+            params: params.clone(),
+            headless: std::env::args().any(|v| v == "headless"),
+            software: false,
+        }
+    }
+
+    /// Sets whether to run without window and video device. A headless engine runs one
+    /// frame after the resources are ready, and then shuts down.
+    pub fn with_headless(mut self, headless: bool) -> Self {
+        self.headless = headless;
+        self
+    }
+
+    /// Sets whether to render with the software rasterizer when running headless, so the
+    /// rendering results could be checked without GPU.
+    #[cfg(feature = "software")]
+    pub fn with_software(mut self, software: bool) -> Self {
+        self.software = software;
+        self
+    }
 
-        let dirs = params.res.dirs.clone();
-        LIFECYCLE_CTX = Box::into_raw(Box::new(LifecycleSystem::new()));
-        TIME_CTX = Box::into_raw(Box::new(TimeSystem::new(&params)));
+    /// Sets up the core system, and runs the application that `closure` creates once
+    /// the manifests of resources are loaded. It fails if the core system has been set
+    /// up already.
+    pub fn run<T, T2>(self, closure: T) -> Result<()>
+    where
+        T: FnOnce() -> Result<T2> + 'static,
+        T2: LifecycleListener + Send + 'static,
+    {
+        unsafe {
+            if !LIFECYCLE_CTX.is_null() {
+                bail!("duplicated setup of crayon.");
+            }
+
+            let mut params = self.params;
+
+            sys::init();
+            params.validate();
+
+            let dirs = params.res.dirs.clone();
+            setup_lifecycle();
+            setup_time(TimeSystem::new(&params));
+
+            let engine = if self.headless {
+                EngineSystem::new_headless(params, self.software)?
+            } else {
+                EngineSystem::new(params)?
+            };
 
-        if std::env::args().any(|v| v == "headless") {
-            CTX = Box::into_raw(Box::new(EngineSystem::new_headless(params)?));
-        } else {
-            CTX = Box::into_raw(Box::new(EngineSystem::new(params)?));
-        };
+            setup_engine(engine);
 
-        let latch = crate::res::inside::load_manifests(dirs)?;
-        ctx().run(latch, closure)
+            let latch = crate::res::inside::load_manifests(dirs)?;
+            ctx().run(latch, closure)
+        }
     }
 }
 
 #[doc(hidden)]
 pub fn oneshot() -> Result<()> {
     unsafe {
-        debug_assert!(LIFECYCLE_CTX.is_null(), "duplicated setup of crayon.");
+        if !LIFECYCLE_CTX.is_null() {
+            bail!("duplicated setup of crayon.");
+        }
 
         let params = Params::default();
 
         sys::init();
-        LIFECYCLE_CTX = Box::into_raw(Box::new(LifecycleSystem::new()));
-        TIME_CTX = Box::into_raw(Box::new(TimeSystem::new(&params)));
-        CTX = Box::into_raw(Box::new(EngineSystem::new_headless(params)?));
+        setup_lifecycle();
+        setup_time(TimeSystem::new(&params));
+        setup_engine(EngineSystem::new_headless(params, false)?);
 
         ctx().run_oneshot()
     }
@@ -153,21 +219,30 @@ pub fn discard() {
     ctx().shutdown()
 }
 
-pub(crate) unsafe fn late_discard() {
-    drop(Box::from_raw(CTX as *mut EngineSystem));
-    CTX = std::ptr::null();
+/// Registers the discard function of module system, which is called automatically
+/// when the engine shuts down.
+///
+/// Module systems are discarded in the reverse order of their registration, and before
+/// the core systems. So a module should register itself at the end of its `setup`, and
+/// then the modules that depend on others would always be discarded first. It's fine to
+/// discard a module manually, as long as its discard function could be called twice.
+#[inline]
+pub fn register_module(name: &'static str, discard: fn()) {
+    lifecycle_ctx().register_module(name, discard);
+}
 
-    drop(Box::from_raw(TIME_CTX as *mut TimeSystem));
-    TIME_CTX = std::ptr::null();
+pub(crate) unsafe fn late_discard() {
+    lifecycle_ctx().discard_modules();
 
-    drop(Box::from_raw(LIFECYCLE_CTX as *mut LifecycleSystem));
-    LIFECYCLE_CTX = std::ptr::null();
+    CTX.discard();
+    TIME_CTX.discard();
+    LIFECYCLE_CTX.discard();
 }
 
 /// Checks if the engine is enabled.
 #[inline]
 pub fn valid() -> bool {
-    unsafe { !LIFECYCLE_CTX.is_null() }
+    !LIFECYCLE_CTX.is_null()
 }
 
 /// Checks if the engine is running in headless mode.
@@ -224,18 +299,51 @@ pub fn set_time_smoothing_step(step: u32) {
     time_ctx().set_time_smoothing_step(step);
 }
 
+/// Sets the scale of `frame_duration`, e.g. 0.5 for slow-motion. Gameplay is paused
+/// if it's 0, while things driven by `real_frame_duration` (like UI) keep animating.
+/// Scales are clamped into `[0, 100]`, and NaN is treated as 0.
+#[inline]
+pub fn set_time_scale(scale: f32) {
+    time_ctx().set_time_scale(scale);
+}
+
+/// Gets the scale of `frame_duration`.
+#[inline]
+pub fn time_scale() -> f32 {
+    time_ctx().time_scale()
+}
+
 /// Gets current fps.
 #[inline]
 pub fn fps() -> u32 {
     time_ctx().fps()
 }
 
-/// Gets the duration duraing last frame.
+/// Gets the duration duraing last frame, scaled by the time scale.
 #[inline]
 pub fn frame_duration() -> ::std::time::Duration {
     time_ctx().frame_duration()
 }
 
+/// Gets the duration duraing last frame, regardless of the time scale.
+#[inline]
+pub fn real_frame_duration() -> ::std::time::Duration {
+    time_ctx().real_frame_duration()
+}
+
+/// Set how many frames of `FrameInfo` to keep for `frame_history`.
+#[inline]
+pub fn set_frame_history_size(size: u32) {
+    time_ctx().set_frame_history_size(size);
+}
+
+/// Gets the `FrameInfo` of up to the last `n` frames, from the oldest to the latest.
+/// The history is bounded by `frame_history_size` of `Params`.
+#[inline]
+pub fn frame_history(n: usize) -> Vec<FrameInfo> {
+    time_ctx().frame_history(n)
+}
+
 #[inline]
 fn foreach<T>(func: T) -> Result<()>
 where
@@ -253,44 +361,50 @@ where
 }
 
 mod inside {
+    use crate::utils::global::{Global, LocalGlobal};
+
     use super::engine::EngineSystem;
     use super::lifecycle::LifecycleSystem;
     use super::time::TimeSystem;
 
-    pub static mut LIFECYCLE_CTX: *const LifecycleSystem = std::ptr::null();
-    pub static mut TIME_CTX: *const TimeSystem = std::ptr::null();
-    pub static mut CTX: *const EngineSystem = std::ptr::null();
+    /// The lifecycle system holds listeners that are not `Send`, it could only be used on
+    /// the main thread.
+    pub static LIFECYCLE_CTX: LocalGlobal<LifecycleSystem> = LocalGlobal::new();
+    pub static TIME_CTX: Global<TimeSystem> = Global::new();
+    pub static CTX: Global<EngineSystem> = Global::new();
 
     pub fn ctx() -> &'static EngineSystem {
-        unsafe {
-            debug_assert!(
-                !TIME_CTX.is_null(),
-                "engine has not been initialized properly."
-            );
-
-            &*CTX
-        }
+        CTX.get()
+            .expect("engine has not been initialized properly.")
     }
 
     pub fn lifecycle_ctx() -> &'static LifecycleSystem {
-        unsafe {
-            debug_assert!(
-                !LIFECYCLE_CTX.is_null(),
-                "lifecycle system has not been initialized properly."
-            );
+        LIFECYCLE_CTX
+            .get()
+            .expect("lifecycle system has not been initialized properly.")
+    }
 
-            &*LIFECYCLE_CTX
+    pub fn time_ctx() -> &'static TimeSystem {
+        TIME_CTX
+            .get()
+            .expect("time system has not been initialized properly.")
+    }
+
+    pub fn setup_lifecycle() {
+        if LIFECYCLE_CTX.set(LifecycleSystem::new()).is_err() {
+            panic!("duplicated setup of lifecycle system.");
         }
     }
 
-    pub fn time_ctx() -> &'static TimeSystem {
-        unsafe {
-            debug_assert!(
-                !TIME_CTX.is_null(),
-                "time system has not been initialized properly."
-            );
+    pub fn setup_time(time: TimeSystem) {
+        if TIME_CTX.set(time).is_err() {
+            panic!("duplicated setup of time system.");
+        }
+    }
 
-            &*TIME_CTX
+    pub fn setup_engine(ctx: EngineSystem) {
+        if CTX.set(ctx).is_err() {
+            panic!("duplicated setup of engine.");
         }
     }
 }
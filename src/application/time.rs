@@ -10,6 +10,22 @@ use crate::utils::time::Timestamp;
 
 use super::Params;
 
+// The maximum time scale, which keeps the scaled timestep representable.
+const MAX_TIME_SCALE: f32 = 100.0;
+
+/// The timing information of a frame.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct FrameInfo {
+    /// The index of this frame, starts from 0.
+    pub frame: u64,
+    /// The fps of this frame.
+    pub fps: u32,
+    /// The duration of this frame, regardless of the time scale.
+    pub duration: Duration,
+    /// The duration of this frame, scaled by the time scale.
+    pub scaled_duration: Duration,
+}
+
 pub struct TimeSystem {
     lis: LifecycleListenerHandle,
     shared: Arc<TimeStateShared>,
@@ -20,7 +36,11 @@ struct TimeStateShared {
     max_fps: RwLock<u32>,
     max_inactive_fps: RwLock<u32>,
     smoothing_step: RwLock<usize>,
+    time_scale: RwLock<f32>,
     timestep: RwLock<Duration>,
+    scaled_timestep: RwLock<Duration>,
+    history_size: RwLock<usize>,
+    history: RwLock<VecDeque<FrameInfo>>,
 }
 
 struct TimeState {
@@ -28,9 +48,11 @@ struct TimeState {
     max_fps: u32,
     max_inactive_fps: u32,
     smoothing_step: usize,
+    time_scale: f32,
     timestep: Duration,
     previous_timesteps: VecDeque<Duration>,
     last_frame_timepoint: Timestamp,
+    frame: u64,
     shared: Arc<TimeStateShared>,
 }
 
@@ -41,6 +63,7 @@ impl LifecycleListener for TimeState {
         self.max_fps = *self.shared.max_fps.read().unwrap();
         self.max_inactive_fps = *self.shared.max_inactive_fps.read().unwrap();
         self.smoothing_step = *self.shared.smoothing_step.read().unwrap();
+        self.time_scale = *self.shared.time_scale.read().unwrap();
 
         // Perform waiting loop if maximum fps set, cooperatively gives up
         // a timeslice to the OS scheduler.
@@ -55,9 +78,15 @@ impl LifecycleListener for TimeState {
             }
         }
 
-        let mut elapsed = self.last_frame_timepoint.elapsed();
+        let elapsed = self.last_frame_timepoint.elapsed();
         self.last_frame_timepoint = Timestamp::now();
+        self.advance(elapsed);
+        Ok(())
+    }
+}
 
+impl TimeState {
+    fn advance(&mut self, mut elapsed: Duration) {
         // If fps lower than minimum, simply clamp it.
         if self.min_fps > 0 {
             elapsed = std::cmp::min(
@@ -84,8 +113,36 @@ impl LifecycleListener for TimeState {
             self.timestep = elapsed;
         }
 
+        // The time scale applies after clamping, so the scaled timestep never exceeds
+        // `scale / min_fps`.
+        let scaled = self.timestep.mul_f64(f64::from(self.time_scale));
+
         *self.shared.timestep.write().unwrap() = self.timestep;
-        Ok(())
+        *self.shared.scaled_timestep.write().unwrap() = scaled;
+
+        let info = FrameInfo {
+            frame: self.frame,
+            fps: fps(self.timestep),
+            duration: self.timestep,
+            scaled_duration: scaled,
+        };
+
+        self.frame += 1;
+
+        let size = *self.shared.history_size.read().unwrap();
+        let mut history = self.shared.history.write().unwrap();
+        history.push_back(info);
+        while history.len() > size {
+            history.pop_front();
+        }
+    }
+}
+
+fn fps(timestep: Duration) -> u32 {
+    if timestep.subsec_nanos() == 0 {
+        0
+    } else {
+        (1_000_000_000.0 / f64::from(timestep.subsec_nanos())) as u32
     }
 }
 
@@ -102,7 +159,11 @@ impl TimeSystem {
             max_fps: RwLock::new(setup.max_fps),
             max_inactive_fps: RwLock::new(setup.max_inactive_fps),
             smoothing_step: RwLock::new(setup.time_smooth_step as usize),
+            time_scale: RwLock::new(1.0),
             timestep: RwLock::new(Duration::new(0, 0)),
+            scaled_timestep: RwLock::new(Duration::new(0, 0)),
+            history_size: RwLock::new(setup.frame_history_size as usize),
+            history: RwLock::new(VecDeque::new()),
         });
 
         let state = TimeState {
@@ -110,9 +171,11 @@ impl TimeSystem {
             max_fps: setup.max_fps,
             max_inactive_fps: setup.max_inactive_fps,
             smoothing_step: setup.time_smooth_step as usize,
+            time_scale: 1.0,
             previous_timesteps: VecDeque::new(),
             timestep: Duration::new(0, 0),
             last_frame_timepoint: Timestamp::now(),
+            frame: 0,
             shared: shared.clone(),
         };
 
@@ -150,20 +213,147 @@ impl TimeSystem {
         *self.shared.smoothing_step.write().unwrap() = step as usize;
     }
 
+    /// Set how many frames of `FrameInfo` to keep in history.
+    #[inline]
+    pub fn set_frame_history_size(&self, size: u32) {
+        let size = size as usize;
+        *self.shared.history_size.write().unwrap() = size;
+
+        let mut history = self.shared.history.write().unwrap();
+        while history.len() > size {
+            history.pop_front();
+        }
+    }
+
+    /// Sets the scale of `frame_duration`. Slows down gameplay if it's less than 1.0, and
+    /// pauses it if 0. Scales are clamped into `[0, MAX_TIME_SCALE]`, and NaN pauses
+    /// gameplay too.
+    #[inline]
+    pub fn set_time_scale(&self, scale: f32) {
+        *self.shared.time_scale.write().unwrap() = clamp_time_scale(scale);
+    }
+
+    /// Gets the scale of `frame_duration`.
+    #[inline]
+    pub fn time_scale(&self) -> f32 {
+        *self.shared.time_scale.read().unwrap()
+    }
+
     /// Gets current fps.
     #[inline]
     pub fn fps(&self) -> u32 {
-        let ts = self.shared.timestep.read().unwrap();
-        if ts.subsec_nanos() == 0 {
-            0
-        } else {
-            (1_000_000_000.0 / f64::from(ts.subsec_nanos())) as u32
-        }
+        fps(*self.shared.timestep.read().unwrap())
     }
 
-    /// Gets the duration duraing last frame.
+    /// Gets the duration duraing last frame, scaled by the time scale.
     #[inline]
     pub fn frame_duration(&self) -> Duration {
+        *self.shared.scaled_timestep.read().unwrap()
+    }
+
+    /// Gets the duration duraing last frame, regardless of the time scale.
+    #[inline]
+    pub fn real_frame_duration(&self) -> Duration {
         *self.shared.timestep.read().unwrap()
     }
+
+    /// Gets the `FrameInfo` of up to the last `n` frames, from the oldest to the latest.
+    pub fn frame_history(&self, n: usize) -> Vec<FrameInfo> {
+        let history = self.shared.history.read().unwrap();
+        let skip = history.len().saturating_sub(n);
+        history.iter().skip(skip).cloned().collect()
+    }
+}
+
+fn clamp_time_scale(scale: f32) -> f32 {
+    if scale.is_nan() {
+        0.0
+    } else {
+        scale.clamp(0.0, MAX_TIME_SCALE)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn state(min_fps: u32, time_scale: f32) -> TimeState {
+        state_with_history(min_fps, time_scale, 0)
+    }
+
+    fn state_with_history(min_fps: u32, time_scale: f32, history_size: usize) -> TimeState {
+        let shared = Arc::new(TimeStateShared {
+            min_fps: RwLock::new(min_fps),
+            max_fps: RwLock::new(0),
+            max_inactive_fps: RwLock::new(0),
+            smoothing_step: RwLock::new(0),
+            time_scale: RwLock::new(time_scale),
+            timestep: RwLock::new(Duration::new(0, 0)),
+            scaled_timestep: RwLock::new(Duration::new(0, 0)),
+            history_size: RwLock::new(history_size),
+            history: RwLock::new(VecDeque::new()),
+        });
+
+        TimeState {
+            min_fps,
+            max_fps: 0,
+            max_inactive_fps: 0,
+            smoothing_step: 0,
+            time_scale,
+            timestep: Duration::new(0, 0),
+            previous_timesteps: VecDeque::new(),
+            last_frame_timepoint: Timestamp::now(),
+            frame: 0,
+            shared,
+        }
+    }
+
+    fn advance(min_fps: u32, time_scale: f32, ms: u64) -> (Duration, Duration) {
+        let mut ts = state(min_fps, time_scale);
+        ts.advance(Duration::from_millis(ms));
+
+        let real = *ts.shared.timestep.read().unwrap();
+        let scaled = *ts.shared.scaled_timestep.read().unwrap();
+        (real, scaled)
+    }
+
+    #[test]
+    fn time_scale() {
+        let (real, scaled) = advance(0, 0.5, 20);
+        assert_eq!(real, Duration::from_millis(20));
+        assert_eq!(scaled, Duration::from_millis(10));
+
+        // Pauses gameplay, while the real timestep keeps going.
+        let (real, scaled) = advance(0, 0.0, 20);
+        assert_eq!(real, Duration::from_millis(20));
+        assert_eq!(scaled, Duration::new(0, 0));
+
+        // Clamps with the minimum fps first, then scales.
+        let (real, scaled) = advance(10, 0.5, 500);
+        assert_eq!(real, Duration::from_millis(100));
+        assert_eq!(scaled, Duration::from_millis(50));
+
+        // Invalid scales must not overflow the scaled timestep.
+        for &v in &[std::f32::INFINITY, std::f32::NAN, std::f32::MAX, -1.0] {
+            let (_, scaled) = advance(0, clamp_time_scale(v), 20);
+            assert!(scaled <= Duration::from_millis(2000));
+        }
+    }
+
+    #[test]
+    fn frame_history() {
+        let mut ts = state_with_history(0, 0.5, 3);
+        for i in 1..6 {
+            ts.advance(Duration::from_millis(i * 10));
+        }
+
+        let history = ts.shared.history.read().unwrap();
+        let frames: Vec<_> = history.iter().map(|v| v.frame).collect();
+        assert_eq!(frames, [2, 3, 4]);
+
+        let latest = history.back().unwrap();
+        assert_eq!(latest.duration, Duration::from_millis(50));
+        assert_eq!(latest.scaled_duration, Duration::from_millis(25));
+        assert_eq!(latest.fps, 20);
+    }
 }
@@ -5,7 +5,7 @@ use super::mouse::MouseButton;
 use super::touchpad::TouchState;
 
 /// Input device event, supports mouse and keyboard only.
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone)]
 pub enum InputEvent {
     /// The cursor has moved on the window.
     /// The parameter are the (x, y) coords in pixels relative to the bottom-left
@@ -25,6 +25,14 @@ pub enum InputEvent {
     /// Received a unicode character.
     ReceivedCharacter { character: char },
 
+    /// An IME composition has started.
+    CompositionStart,
+    /// The preedit string of the in-progress IME composition has changed. The `cursor`
+    /// is the position of caret, measured in characters.
+    CompositionUpdate { text: String, cursor: usize },
+    /// The IME composition has finished, and the `text` is committed.
+    CompositionCommit { text: String },
+
     /// Represent touch event.
     ///
     /// Every time user touches screen new Start event with some finger id is generated. When
@@ -34,6 +42,8 @@ pub enum InputEvent {
     /// There may be 0 or more Move events.
     ///
     /// Depending on platform implementation id may or may not be reused by system after End event.
+    ///
+    /// The position is in pixels relative to the bottom-left corner of the window.
     Touch {
         id: u8,
         state: TouchState,
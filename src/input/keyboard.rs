@@ -184,6 +184,26 @@ pub enum Key {
     Wake,
 }
 
+/// A snapshot of the modifier keys that are held down. Left and right variants of
+/// the same key are reported as a single flag.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Modifiers {
+    pub shift: bool,
+    pub ctrl: bool,
+    pub alt: bool,
+    /// The Windows key on PC, or the Command key on Mac.
+    pub logo: bool,
+}
+
+/// The in-progress IME composition.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Composition {
+    /// The preedit string.
+    pub text: String,
+    /// The position of caret in preedit string, measured in characters.
+    pub cursor: usize,
+}
+
 enum KeyDownState {
     Start(Timestamp),
     Press(Timestamp),
@@ -194,6 +214,7 @@ pub struct Keyboard {
     presses: FastHashSet<Key>,
     releases: FastHashSet<Key>,
     chars: Vec<char>,
+    composition: Option<Composition>,
     setup: KeyboardParams,
     now: Timestamp,
 }
@@ -206,6 +227,7 @@ impl Keyboard {
             presses: FastHashSet::default(),
             releases: FastHashSet::default(),
             chars: Vec::with_capacity(setup.max_chars),
+            composition: None,
             now: Timestamp::now(),
         }
     }
@@ -216,6 +238,7 @@ impl Keyboard {
         self.presses.clear();
         self.releases.clear();
         self.chars.clear();
+        self.composition = None;
     }
 
     #[inline]
@@ -266,6 +289,27 @@ impl Keyboard {
         }
     }
 
+    #[inline]
+    pub fn on_composition_start(&mut self) {
+        self.composition = Some(Composition::default());
+    }
+
+    #[inline]
+    pub fn on_composition_update(&mut self, text: &str, cursor: usize) {
+        self.composition = Some(Composition {
+            text: text.to_owned(),
+            cursor,
+        });
+    }
+
+    pub fn on_composition_commit(&mut self, text: &str) {
+        self.composition = None;
+
+        for c in text.chars() {
+            self.on_char(c);
+        }
+    }
+
     #[inline]
     pub fn is_key_down(&self, key: Key) -> bool {
         self.downs.contains_key(&key)
@@ -292,8 +336,69 @@ impl Keyboard {
         }
     }
 
+    #[inline]
+    pub fn modifiers(&self) -> Modifiers {
+        Modifiers {
+            shift: self.is_key_down(Key::LShift) || self.is_key_down(Key::RShift),
+            ctrl: self.is_key_down(Key::LControl) || self.is_key_down(Key::RControl),
+            alt: self.is_key_down(Key::LAlt) || self.is_key_down(Key::RAlt),
+            logo: self.is_key_down(Key::LWin) || self.is_key_down(Key::RWin),
+        }
+    }
+
     #[inline]
     pub fn captured_chars(&self) -> &[char] {
         &self.chars
     }
+
+    #[inline]
+    pub fn composition(&self) -> Option<&Composition> {
+        self.composition.as_ref()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn modifiers() {
+        let mut keyboard = Keyboard::new(KeyboardParams::default());
+        assert_eq!(keyboard.modifiers(), Modifiers::default());
+
+        keyboard.on_key_pressed(Key::LControl);
+        keyboard.on_key_pressed(Key::RShift);
+        let modifiers = keyboard.modifiers();
+        assert!(modifiers.ctrl);
+        assert!(modifiers.shift);
+        assert!(!modifiers.alt);
+        assert!(!modifiers.logo);
+
+        keyboard.on_key_pressed(Key::RControl);
+        keyboard.on_key_released(Key::LControl);
+        assert!(keyboard.modifiers().ctrl);
+
+        keyboard.on_key_released(Key::RControl);
+        assert!(!keyboard.modifiers().ctrl);
+    }
+
+    #[test]
+    fn composition() {
+        let mut keyboard = Keyboard::new(KeyboardParams::default());
+        assert!(keyboard.composition().is_none());
+
+        keyboard.on_composition_start();
+        assert_eq!(keyboard.composition(), Some(&Composition::default()));
+
+        keyboard.on_composition_update("ni", 2);
+        keyboard.on_composition_update("你", 1);
+        let composition = keyboard.composition().unwrap();
+        assert_eq!(composition.text, "你");
+        assert_eq!(composition.cursor, 1);
+        assert!(keyboard.captured_chars().is_empty());
+
+        keyboard.on_composition_commit("你好");
+        assert!(keyboard.composition().is_none());
+        assert_eq!(keyboard.captured_chars(), &['你', '好']);
+    }
 }
@@ -27,6 +27,9 @@
 //!
 //! // Checks if a key has been released during the last frame.
 //! input::is_key_repeat(Key::A);
+//!
+//! // Checks if any of the control keys is held down.
+//! input::modifiers().ctrl;
 //! ```
 //!
 //! A list of all key codes can be found in the `Key` enumeration. Notes
@@ -72,7 +75,7 @@
 //! use crayon::prelude::*;
 //! application::oneshot().unwrap();
 //!
-//! // Gets the mouse position relative to the top-left hand corner of the window.
+//! // Gets the mouse position relative to the lower-left hand corner of the window.
 //! input::mouse_position();
 //!
 //! // Gets mouse movement in pixels since last frame.
@@ -89,6 +92,10 @@
 //! input::mouse_scroll();
 //! ```
 //!
+//! The positions are relative to the lower-left hand corner of the window by default,
+//! set `InputParams::origin` to `Origin::TopLeft` to have them relative to the top-left
+//! hand corner instead. Touch positions and gestures follow the same convention.
+//!
 //! Mouse positions and movement are reported in pixel coordinates which makes it
 //! difficult to derive useful movement information out of it. It might changes in
 //! the future versions (dividing by the framebuffer resolution is a simple but very
@@ -174,10 +181,10 @@ pub mod touchpad;
 
 pub mod prelude {
     pub use super::events::InputEvent;
-    pub use super::keyboard::{Key, KeyboardParams};
+    pub use super::keyboard::{Composition, Key, KeyboardParams, Modifiers};
     pub use super::mouse::{MouseButton, MouseParams};
     pub use super::touchpad::{GesturePan, GestureTap, TouchPadParams};
-    pub use super::InputParams;
+    pub use super::{InputParams, Origin};
 }
 
 mod system;
@@ -188,14 +195,27 @@ pub const MAX_TOUCHES: usize = 4;
 use crate::math::prelude::Vector2;
 
 use self::inside::{ctx, CTX};
-use self::keyboard::{Key, KeyboardParams};
+use self::keyboard::{Composition, Key, KeyboardParams, Modifiers};
 use self::mouse::{MouseButton, MouseParams};
 use self::touchpad::{GesturePan, GestureTap, TouchPadParams};
 
+/// The corner of window that positions of mouse and touches are relative to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Origin {
+    /// The y axis points up, which matches the NDC and viewport of video system.
+    #[default]
+    BottomLeft,
+    /// The y axis points down, which is common in 2D and UI systems.
+    TopLeft,
+}
+
 /// The setup parameters of all supported input devices.
 #[derive(Debug, Clone, Copy, Default)]
 pub struct InputParams {
     pub touch_emulation: bool,
+    /// The origin of positions reported by mouse and touches, `Origin::BottomLeft` by
+    /// default.
+    pub origin: Origin,
     pub keyboard: KeyboardParams,
     pub mouse: MouseParams,
     pub touchpad: TouchPadParams,
@@ -204,7 +224,7 @@ pub struct InputParams {
 /// Checks if the resource system is enabled.
 #[inline]
 pub fn valid() -> bool {
-    unsafe { !CTX.is_null() }
+    !CTX.is_null()
 }
 
 /// Reset input to initial states.
@@ -243,12 +263,25 @@ pub fn is_key_repeat(key: Key) -> bool {
     ctx().is_key_repeat(key)
 }
 
+/// Gets the modifier keys that are currently held down.
+#[inline]
+pub fn modifiers() -> Modifiers {
+    ctx().modifiers()
+}
+
 /// Gets captured text during the last frame.
 #[inline]
 pub fn text() -> String {
     ctx().text()
 }
 
+/// Gets the in-progress IME composition, text fields could use it to render the
+/// preedit string. The committed text is reported by `text` as usual.
+#[inline]
+pub fn composition() -> Option<Composition> {
+    ctx().composition()
+}
+
 /// Returns true if a mouse is attached
 #[inline]
 pub fn has_mouse_attached() -> bool {
@@ -285,7 +318,8 @@ pub fn is_mouse_double_click(button: MouseButton) -> bool {
     ctx().is_mouse_double_click(button)
 }
 
-/// Gets the mouse position relative to the lower-left hand corner of the window.
+/// Gets the mouse position relative to the corner of window specified by
+/// `InputParams::origin`, which is the lower-left hand corner by default.
 #[inline]
 pub fn mouse_position() -> Vector2<f32> {
     ctx().mouse_position()
@@ -340,38 +374,29 @@ pub fn finger_pan() -> GesturePan {
 }
 
 pub(crate) mod inside {
+    use crate::utils::global::Global;
+
     use super::system::InputSystem;
     use super::InputParams;
 
-    pub static mut CTX: *const InputSystem = std::ptr::null();
+    pub static CTX: Global<InputSystem> = Global::new();
 
     #[inline]
     pub fn ctx() -> &'static InputSystem {
-        unsafe {
-            debug_assert!(
-                !CTX.is_null(),
-                "input system has not been initialized properly."
-            );
-
-            &*CTX
-        }
+        CTX.get()
+            .expect("input system has not been initialized properly.")
     }
 
     /// Setup the resource system.
     pub unsafe fn setup(params: InputParams) {
-        debug_assert!(CTX.is_null(), "duplicated setup of resource system.");
-
         let ctx = InputSystem::new(params);
-        CTX = Box::into_raw(Box::new(ctx));
+        if CTX.set(ctx).is_err() {
+            panic!("duplicated setup of input system.");
+        }
     }
 
     /// Discard the resource system.
     pub unsafe fn discard() {
-        if CTX.is_null() {
-            return;
-        }
-
-        drop(Box::from_raw(CTX as *mut InputSystem));
-        CTX = std::ptr::null();
+        CTX.discard();
     }
 }
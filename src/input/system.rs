@@ -4,10 +4,10 @@ use crate::application::prelude::{LifecycleListener, LifecycleListenerHandle};
 use crate::window::prelude::{Event, EventListener, EventListenerHandle};
 
 use super::events::InputEvent;
-use super::keyboard::{Key, Keyboard};
+use super::keyboard::{Composition, Key, Keyboard, Modifiers};
 use super::mouse::{Mouse, MouseButton};
 use super::touchpad::{GesturePan, GestureTap, TouchPad, TouchState};
-use super::InputParams;
+use super::{InputParams, Origin};
 
 use crate::math::prelude::Vector2;
 
@@ -20,6 +20,7 @@ pub struct InputSystem {
 }
 
 struct InputState {
+    origin: Origin,
     touch_emulation: bool,
     touch_emulation_button: RwLock<Option<MouseButton>>,
     mouse: RwLock<Mouse>,
@@ -27,11 +28,41 @@ struct InputState {
     touchpad: RwLock<TouchPad>,
 }
 
+impl InputState {
+    fn new(setup: InputParams) -> Self {
+        InputState {
+            origin: setup.origin,
+            touch_emulation: setup.touch_emulation,
+            touch_emulation_button: RwLock::new(None),
+            mouse: RwLock::new(Mouse::new(setup.mouse)),
+            keyboard: RwLock::new(Keyboard::new(setup.keyboard)),
+            touchpad: RwLock::new(TouchPad::new(setup.touchpad)),
+        }
+    }
+
+    /// Converts the position reported by device, which is relative to the lower-left
+    /// corner of window, into the origin of input system.
+    fn convert(&self, position: Vector2<f32>) -> Vector2<f32> {
+        match self.origin {
+            Origin::BottomLeft => position,
+            // Queries the window instead of tracking resize events, since the dimensions
+            // are reported in the same units as positions of devices on all platforms.
+            Origin::TopLeft => flip(position, crate::window::dimensions()),
+        }
+    }
+}
+
+fn flip(position: Vector2<f32>, dimensions: Vector2<u32>) -> Vector2<f32> {
+    Vector2::new(position.x, dimensions.y as f32 - position.y)
+}
+
 impl EventListener for Arc<InputState> {
     fn on(&mut self, v: &Event) -> Result<(), failure::Error> {
-        if let Event::InputDevice(v) = *v {
-            match v {
+        if let Event::InputDevice(ref v) = *v {
+            match *v {
                 InputEvent::MouseMoved { position } => {
+                    let position = self.convert(position.into());
+
                     if self.touch_emulation_button.read().unwrap().is_some() {
                         self.touchpad.write().unwrap().on_touch(
                             255,
@@ -40,7 +71,7 @@ impl EventListener for Arc<InputState> {
                         );
                     }
 
-                    self.mouse.write().unwrap().on_move(position)
+                    self.mouse.write().unwrap().on_move(position.into())
                 }
 
                 InputEvent::MousePressed { button } => {
@@ -86,11 +117,26 @@ impl EventListener for Arc<InputState> {
                     self.keyboard.write().unwrap().on_char(character)
                 }
 
+                InputEvent::CompositionStart => {
+                    self.keyboard.write().unwrap().on_composition_start()
+                }
+
+                InputEvent::CompositionUpdate { ref text, cursor } => self
+                    .keyboard
+                    .write()
+                    .unwrap()
+                    .on_composition_update(text, cursor),
+
+                InputEvent::CompositionCommit { ref text } => {
+                    self.keyboard.write().unwrap().on_composition_commit(text)
+                }
+
                 InputEvent::Touch {
                     id,
                     state,
                     position,
                 } => {
+                    let position = self.convert(position);
                     self.touchpad.write().unwrap().on_touch(id, state, position);
                 }
             }
@@ -120,13 +166,7 @@ impl InputSystem {
     pub fn new(setup: InputParams) -> Self {
         debug_assert!(crate::application::valid(), "");
 
-        let state = Arc::new(InputState {
-            touch_emulation: setup.touch_emulation,
-            touch_emulation_button: RwLock::new(None),
-            mouse: RwLock::new(Mouse::new(setup.mouse)),
-            keyboard: RwLock::new(Keyboard::new(setup.keyboard)),
-            touchpad: RwLock::new(TouchPad::new(setup.touchpad)),
-        });
+        let state = Arc::new(InputState::new(setup));
 
         InputSystem {
             state: state.clone(),
@@ -175,6 +215,12 @@ impl InputSystem {
         self.state.keyboard.read().unwrap().is_key_repeat(key)
     }
 
+    /// Gets the modifier keys that are currently held down.
+    #[inline]
+    pub fn modifiers(&self) -> Modifiers {
+        self.state.keyboard.read().unwrap().modifiers()
+    }
+
     /// Gets captured text during the last frame.
     #[inline]
     pub fn text(&self) -> String {
@@ -182,6 +228,12 @@ impl InputSystem {
         String::from_iter(self.state.keyboard.read().unwrap().captured_chars())
     }
 
+    /// Gets the in-progress IME composition.
+    #[inline]
+    pub fn composition(&self) -> Option<Composition> {
+        self.state.keyboard.read().unwrap().composition().cloned()
+    }
+
     /// Returns true if a mouse is attached
     #[inline]
     pub fn has_mouse_attached(&self) -> bool {
@@ -223,7 +275,8 @@ impl InputSystem {
             .is_button_double_click(button)
     }
 
-    /// Gets the mouse position relative to the lower-left hand corner of the window.
+    /// Gets the mouse position relative to the corner of window specified by
+    /// `InputParams::origin`.
     #[inline]
     pub fn mouse_position(&self) -> Vector2<f32> {
         self.state.mouse.read().unwrap().position()
@@ -278,3 +331,45 @@ impl InputSystem {
         self.state.touchpad.read().unwrap().pan()
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn touch(position: Vector2<f32>) -> Event {
+        Event::InputDevice(InputEvent::Touch {
+            id: 0,
+            state: TouchState::Start,
+            position,
+        })
+    }
+
+    #[test]
+    fn origin() {
+        let moved = Event::InputDevice(InputEvent::MouseMoved {
+            position: (10.0, 20.0),
+        });
+
+        let mut state = Arc::new(InputState::new(InputParams::default()));
+        state.on(&moved).unwrap();
+        state.on(&touch(Vector2::new(10.0, 20.0))).unwrap();
+        assert_eq!(
+            state.mouse.read().unwrap().position(),
+            Vector2::new(10.0, 20.0)
+        );
+        assert_eq!(
+            state.touchpad.read().unwrap().position(0),
+            Some(Vector2::new(10.0, 20.0))
+        );
+
+        let position = Vector2::new(10.0, 20.0);
+        assert_eq!(
+            flip(position, Vector2::new(64, 48)),
+            Vector2::new(10.0, 28.0)
+        );
+        assert_eq!(
+            flip(position, Vector2::new(64, 100)),
+            Vector2::new(10.0, 80.0)
+        );
+    }
+}
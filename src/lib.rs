@@ -29,6 +29,8 @@
 extern crate gl;
 #[cfg(not(target_arch = "wasm32"))]
 extern crate glutin;
+#[cfg(not(target_arch = "wasm32"))]
+extern crate image;
 
 #[cfg(target_arch = "wasm32")]
 extern crate console_error_panic_hook;
@@ -49,6 +51,7 @@ extern crate cgmath;
 #[macro_use]
 extern crate serde;
 extern crate byteorder;
+extern crate seahash;
 extern crate serde_json;
 
 extern crate crossbeam_deque;
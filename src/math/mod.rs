@@ -3,16 +3,23 @@
 pub mod aabb;
 pub mod color;
 pub mod frustum;
+pub mod ndc;
 pub mod plane;
+pub mod ray;
+pub mod rotation;
 
 pub mod prelude {
     pub use super::aabb::{Aabb2, Aabb3};
     pub use super::color::Color;
     pub use super::frustum::{Frustum, FrustumPoints, Projection};
+    pub use super::ndc::{clip_to_ndc, ndc_to_screen, screen_to_ndc};
     pub use super::plane::{Plane, PlaneBound, PlaneRelation};
+    pub use super::ray::Ray;
+    pub use super::rotation::look_rotation;
 
     pub use cgmath::prelude::{EuclideanSpace, InnerSpace, MetricSpace, VectorSpace};
     pub use cgmath::prelude::{One, Zero};
     pub use cgmath::{Angle, Deg, Euler, Quaternion, Rad, Rotation};
     pub use cgmath::{Matrix, Matrix2, Matrix3, Matrix4, SquareMatrix, Vector2, Vector3, Vector4};
+    pub use cgmath::{Point2, Point3};
 }
@@ -0,0 +1,39 @@
+//! Conversions between clip space, NDC(normalized device coordinates) and screen space.
+//!
+//! The screen space is measured in pixels, with the origin at the lower left corner of
+//! window like `SurfaceViewport`. The viewport is the rectangle of screen that NDC maps
+//! to, so (-1, -1) is its lower left corner and (1, 1) is the upper right one.
+
+use cgmath::{BaseFloat, Vector2, Vector3, Vector4};
+
+use crate::math::prelude::Aabb2;
+
+/// Converts a point in clip space into NDC with the perspective division.
+#[inline]
+pub fn clip_to_ndc<S: BaseFloat>(v: Vector4<S>) -> Vector3<S> {
+    (v / v.w).truncate()
+}
+
+/// Converts a point in NDC into screen space. The depth is mapped from [-1, 1] to
+/// [0, 1], which is the default depth range.
+#[inline]
+pub fn ndc_to_screen<S: BaseFloat>(v: Vector3<S>, viewport: Aabb2<S>) -> Vector3<S> {
+    let half = S::one() / (S::one() + S::one());
+    let (min, dim) = (viewport.min(), viewport.dim());
+    Vector3::new(
+        min.x + (v.x + S::one()) * half * dim.x,
+        min.y + (v.y + S::one()) * half * dim.y,
+        (v.z + S::one()) * half,
+    )
+}
+
+/// Converts a point in screen space into NDC.
+#[inline]
+pub fn screen_to_ndc<S: BaseFloat>(v: Vector2<S>, viewport: Aabb2<S>) -> Vector2<S> {
+    let two = S::one() + S::one();
+    let (min, dim) = (viewport.min(), viewport.dim());
+    Vector2::new(
+        (v.x - min.x) / dim.x * two - S::one(),
+        (v.y - min.y) / dim.y * two - S::one(),
+    )
+}
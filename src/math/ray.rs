@@ -0,0 +1,60 @@
+//! Rays for picking and intersection tests.
+
+use cgmath::prelude::*;
+use cgmath::{BaseFloat, Vector3};
+
+use crate::math::prelude::Aabb3;
+
+/// A half-line that starts at `origin` and extends infinitely along `direction`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Ray<S> {
+    /// The origin of ray.
+    pub origin: Vector3<S>,
+    /// The normalized direction of ray.
+    pub direction: Vector3<S>,
+}
+
+impl<S: BaseFloat> Ray<S> {
+    /// Constructs a ray from `origin` along `direction`. The direction will be normalized.
+    pub fn new(origin: Vector3<S>, direction: Vector3<S>) -> Self {
+        Ray {
+            origin,
+            direction: direction.normalize(),
+        }
+    }
+
+    /// Gets the point at `distance` units along the ray.
+    #[inline]
+    pub fn point(&self, distance: S) -> Vector3<S> {
+        self.origin + self.direction * distance
+    }
+
+    /// Finds the distance from origin to the nearest intersection with `aabb`. If the
+    /// origin is inside the box, zero is returned.
+    pub fn intersect_aabb(&self, aabb: &Aabb3<S>) -> Option<S> {
+        let (min, max) = (aabb.min(), aabb.max());
+        let mut near = S::neg_infinity();
+        let mut far = S::infinity();
+
+        for i in 0..3 {
+            let (o, d) = (self.origin[i], self.direction[i]);
+            if d == S::zero() {
+                // The ray is parallel to this slab.
+                if o < min[i] || o > max[i] {
+                    return None;
+                }
+            } else {
+                let t1 = (min[i] - o) / d;
+                let t2 = (max[i] - o) / d;
+                near = near.max(t1.min(t2));
+                far = far.min(t1.max(t2));
+            }
+        }
+
+        if near > far || far < S::zero() {
+            None
+        } else {
+            Some(near.max(S::zero()))
+        }
+    }
+}
@@ -0,0 +1,14 @@
+//! Helpers to build rotations.
+
+use cgmath::{BaseFloat, InnerSpace, Matrix3, Quaternion, Vector3};
+
+/// Creates a rotation whose forward (the positive z-axis) looks along `forward`, and
+/// whose up is as close to `up` as possible.
+///
+/// Both vectors are not required to be normalized, but they should not be parallel.
+pub fn look_rotation<S: BaseFloat>(forward: Vector3<S>, up: Vector3<S>) -> Quaternion<S> {
+    let dir = forward.normalize();
+    let side = up.cross(dir).normalize();
+    let up = dir.cross(side).normalize();
+    Matrix3::from_cols(side, up, dir).into()
+}
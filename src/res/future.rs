@@ -0,0 +1,91 @@
+//! Adapts asynchronous loading requests to `std::future::Future`. Only available with
+//! the `futures` feature.
+//!
+//! ```rust,ignore
+//! let bytes = ResourceFuture::from(crayon::res::load_from("res:crate.bmp")?).await?;
+//! ```
+//!
+//! There is no executor provided, the future is woken from the thread that completes
+//! the request, so it works with any runtime.
+//!
+//! Resources that are created from files asynchronously could be awaited with the typed
+//! `HandleFuture`, which resolves to the handle once the resource is ready to use:
+//!
+//! ```rust,ignore
+//! let texture = video::texture_future(video::create_texture_from("res:crate.bmp")?).await?;
+//! ```
+
+use std::fmt::Debug;
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll, Waker};
+
+use super::request::{Request, Response};
+use super::utils::prelude::ResourceState;
+
+/// A future that resolves to the response of `Request`.
+pub struct ResourceFuture {
+    request: Option<Request>,
+}
+
+impl From<Request> for ResourceFuture {
+    fn from(request: Request) -> Self {
+        ResourceFuture {
+            request: Some(request),
+        }
+    }
+}
+
+impl Future for ResourceFuture {
+    type Output = Response;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<Self::Output> {
+        let mut request = self
+            .request
+            .take()
+            .expect("ResourceFuture polled after completion.");
+
+        let ready = match request {
+            Request::NotReady(ref latch) => latch.register(cx.waker()),
+            Request::Ok(_) => true,
+        };
+
+        if ready && request.poll() {
+            let rsp: Option<Response> = request.into();
+            Poll::Ready(rsp.unwrap())
+        } else {
+            self.request = Some(request);
+            Poll::Pending
+        }
+    }
+}
+
+/// A future that resolves to the handle once its resource is loaded, or an error if the
+/// resource failed to load or has been deleted.
+pub struct HandleFuture<H> {
+    handle: H,
+    poll: fn(H, &Waker) -> ResourceState,
+}
+
+impl<H> HandleFuture<H> {
+    /// Creates a future of `handle`. The `poll` function gets the state of resource, and
+    /// registers the waker to be woken once it changes, e.g. `ResourcePool::poll_state`.
+    pub fn new(handle: H, poll: fn(H, &Waker) -> ResourceState) -> Self {
+        HandleFuture { handle, poll }
+    }
+}
+
+impl<H: Debug + Copy + Unpin> Future for HandleFuture<H> {
+    type Output = Result<H, failure::Error>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Self::Output> {
+        match (self.poll)(self.handle, cx.waker()) {
+            ResourceState::Ok => Poll::Ready(Ok(self.handle)),
+            ResourceState::Err => Poll::Ready(Err(format_err!(
+                "{:?} failed to load or has been deleted.",
+                self.handle
+            ))),
+            ResourceState::NotReady => Poll::Pending,
+        }
+    }
+}
@@ -0,0 +1,96 @@
+//! Resource location in the `vfs:path` form.
+//!
+//! A `Location` is a path prefixed with the name of VFS shortcut, such like
+//! `res:textures/crate.png`, which is resolved into a full URL with `ShortcutResolver`.
+//!
+//! Only the first colon separates the VFS from path, so the colons in path are kept
+//! as they are and don't need to be escaped: `res:a:b.png` has `res` as its VFS and
+//! `a:b.png` as its path. The VFS name itself could not contain colons or slashes.
+
+use std::fmt;
+use std::str::FromStr;
+
+/// A parsed and validated location of resource.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Location {
+    location: String,
+    colon: usize,
+}
+
+impl Location {
+    /// Parses a location in the `vfs:path` form. It fails if the VFS or the path is
+    /// empty, or if the location is actually a URL like `file://path`.
+    pub fn parse<T: Into<String>>(location: T) -> Result<Self, failure::Error> {
+        let location = location.into();
+
+        let colon = location
+            .find(':')
+            .ok_or_else(|| format_err!("Location({}) must have a VFS!", location))?;
+
+        if colon == 0 {
+            bail!("The VFS of Location({}) could not be empty!", location);
+        }
+
+        if location[..colon].contains('/') {
+            bail!("The VFS of Location({}) could not contain '/'!", location);
+        }
+
+        let path = &location[colon + 1..];
+        if path.is_empty() {
+            bail!("The path of Location({}) could not be empty!", location);
+        }
+
+        if path.starts_with("//") {
+            bail!("Location({}) is a URL, use `Url` instead!", location);
+        }
+
+        Ok(Location { location, colon })
+    }
+
+    /// Gets the name of VFS without the trailing colon.
+    #[inline]
+    pub fn vfs(&self) -> &str {
+        &self.location[..self.colon]
+    }
+
+    /// Gets the VFS shortcut with the trailing colon, like `res:`.
+    #[inline]
+    pub fn shortcut(&self) -> &str {
+        &self.location[..=self.colon]
+    }
+
+    /// Gets the path of location.
+    #[inline]
+    pub fn path(&self) -> &str {
+        &self.location[self.colon + 1..]
+    }
+
+    /// Gets the last component of path.
+    #[inline]
+    pub fn filename(&self) -> &str {
+        let path = self.path();
+        path.rfind('/').map(|v| &path[v + 1..]).unwrap_or(path)
+    }
+}
+
+impl FromStr for Location {
+    type Err = failure::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Location::parse(s)
+    }
+}
+
+impl std::ops::Deref for Location {
+    type Target = str;
+
+    fn deref(&self) -> &Self::Target {
+        &self.location
+    }
+}
+
+impl fmt::Display for Location {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.location)
+    }
+}
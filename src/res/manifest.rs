@@ -1,16 +1,20 @@
 //! Manifest for all the AssetBundles in the build.
 
-use std::io::Read;
+use std::io::{Read, Write};
 
 use bincode;
 use inlinable_string::{InlinableString, StringExt};
 use uuid::Uuid;
 
 use crate::errors::*;
+use crate::utils::hash::content_hash;
 use crate::utils::prelude::{DataBuffer, DataBufferPtr, FastHashMap, HashValue};
 
 pub const NAME: &str = "MANIFEST";
-pub const MAGIC: [u8; 8] = [b'M', b'N', b'F', b'T', b' ', 0, 0, 1];
+pub const MAGIC: [u8; 8] = [b'M', b'N', b'F', b'T', b' ', 0, 0, 2];
+
+// The manifests built before tags and hash were introduced, which are still accepted.
+const MAGIC_V1: [u8; 8] = [b'M', b'N', b'F', b'T', b' ', 0, 0, 1];
 
 /// A manifest item in the build.
 #[derive(Serialize, Deserialize, Clone, Copy, Debug)]
@@ -18,6 +22,15 @@ pub struct ManifestItem {
     pub filename: DataBufferPtr<str>,
     pub dependencies: DataBufferPtr<[usize]>,
     pub uuid: Uuid,
+    /// The categories of this resource, like `ui` or `level1`.
+    pub tags: DataBufferPtr<[DataBufferPtr<str>]>,
+}
+
+#[derive(Deserialize)]
+struct ManifestItemV1 {
+    filename: DataBufferPtr<str>,
+    dependencies: DataBufferPtr<[usize]>,
+    uuid: Uuid,
 }
 
 /// Manifest for all the resources in the build.
@@ -25,6 +38,9 @@ pub struct ManifestItem {
 pub struct Manifest {
     pub items: Vec<ManifestItem>,
     pub buf: DataBuffer,
+    /// The digest of all the resources in the build, which changes whenever any of them
+    /// changes. It's `None` if the build does not provide one.
+    pub hash: Option<u64>,
 }
 
 impl Manifest {
@@ -37,11 +53,78 @@ impl Manifest {
         file.read_exact(&mut buf[0..8])?;
 
         // MAGIC: [u8; 8]
-        if buf[0..8] != MAGIC[..] {
+        if buf[0..8] == MAGIC[..] {
+            return Ok(bincode::deserialize_from(&mut file)?);
+        }
+
+        if buf[0..8] != MAGIC_V1[..] {
             bail!("[ManifestLoader] MAGIC number not match.");
         }
 
-        Ok(bincode::deserialize_from(&mut file)?)
+        // The legacy manifests were written without padding, so their slices might be
+        // unaligned. They are copied into a new buffer, which aligns them.
+        let mut manifest = Manifest::new();
+        let (items, buf): (Vec<ManifestItemV1>, DataBuffer) = bincode::deserialize_from(&mut file)?;
+
+        for v in items {
+            let dependencies = buf.to_vec(v.dependencies);
+            manifest.add::<_, &str>(buf.as_str(v.filename), v.uuid, &dependencies, &[]);
+        }
+
+        Ok(manifest)
+    }
+
+    /// Computes the digest of a build from the hashes of its resources, which could be
+    /// used as `hash` of manifest. The order of hashes does not matter, so the digest is
+    /// deterministic as long as the contents of resources are the same.
+    pub fn digest<T: IntoIterator<Item = u64>>(hashes: T) -> u64 {
+        let mut hashes: Vec<_> = hashes.into_iter().collect();
+        hashes.sort_unstable();
+
+        let mut bytes = Vec::with_capacity(hashes.len() * 8);
+        for v in hashes {
+            bytes.extend_from_slice(&v.to_le_bytes());
+        }
+
+        content_hash(&bytes)
+    }
+
+    pub fn write_to(&self, file: &mut dyn Write) -> Result<()> {
+        file.write_all(&MAGIC)?;
+        bincode::serialize_into(file, self)?;
+        Ok(())
+    }
+
+    /// Adds a resource, and returns its index in this manifest. The `dependencies` are
+    /// indices of items that added before.
+    pub fn add<T1, T2>(
+        &mut self,
+        filename: T1,
+        uuid: Uuid,
+        dependencies: &[usize],
+        tags: &[T2],
+    ) -> usize
+    where
+        T1: AsRef<str>,
+        T2: AsRef<str>,
+    {
+        let filename = self.buf.extend_from_str(filename.as_ref());
+        let dependencies = self.buf.extend_from_slice(dependencies);
+
+        let tags: Vec<_> = tags
+            .iter()
+            .map(|v| self.buf.extend_from_str(v.as_ref()))
+            .collect();
+        let tags = self.buf.extend_from_slice(&tags);
+
+        self.items.push(ManifestItem {
+            filename,
+            dependencies,
+            uuid,
+            tags,
+        });
+
+        self.items.len() - 1
     }
 }
 
@@ -51,6 +134,7 @@ pub struct ManfiestResolver {
     manifest_prefixs: Vec<InlinableString>,
     uuids: FastHashMap<Uuid, (usize, usize)>,
     filenames: FastHashMap<HashValue<str>, Uuid>,
+    tags: FastHashMap<HashValue<str>, Vec<Uuid>>,
 }
 
 impl ManfiestResolver {
@@ -60,6 +144,7 @@ impl ManfiestResolver {
             manifest_prefixs: Vec::new(),
             uuids: FastHashMap::default(),
             filenames: FastHashMap::default(),
+            tags: FastHashMap::default(),
         }
     }
 
@@ -78,6 +163,11 @@ impl ManfiestResolver {
 
             self.uuids.insert(v.uuid, (index, sub_index));
             self.filenames.insert(fullname.into(), v.uuid);
+
+            for &tag in manifest.buf.as_slice(v.tags) {
+                let tag = manifest.buf.as_str(tag).into();
+                self.tags.entry(tag).or_default().push(v.uuid);
+            }
         }
 
         self.manifests.push(manifest);
@@ -98,6 +188,23 @@ impl ManfiestResolver {
         self.filenames.get(&fullname).cloned()
     }
 
+    /// Return the UUIDs of all the resources that tagged with `tag`.
+    #[inline]
+    pub fn find_by_tag<T: AsRef<str>>(&self, tag: T) -> Vec<Uuid> {
+        let tag = tag.as_ref().into();
+        self.tags.get(&tag).cloned().unwrap_or_default()
+    }
+
+    /// Return the digest of all the manifests, or `None` if none of them provides one.
+    pub fn hash(&self) -> Option<u64> {
+        let hashes: Vec<_> = self.manifests.iter().filter_map(|v| v.hash).collect();
+        match hashes.len() {
+            0 => None,
+            1 => Some(hashes[0]),
+            _ => Some(Manifest::digest(hashes)),
+        }
+    }
+
     /// Resolve the UUID to full path of corresponding resource.
     #[inline]
     pub fn resolve(&self, uuid: Uuid) -> Option<String> {
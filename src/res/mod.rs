@@ -41,7 +41,14 @@
 //! from general UUID or readable identifier. The `Manifest` file is generated after the build
 //! process of `crayon-cli`.
 //!
+//! Resources could also be tagged with categories like `ui` or `level1` in manifest, which are
+//! queried with `find_by_tag`. And the build could provide a digest of all resources in
+//! manifest, which is returned by `manifest_hash`.
+//!
 
+#[cfg(feature = "futures")]
+pub mod future;
+pub mod location;
 pub mod manifest;
 pub mod request;
 pub mod shortcut;
@@ -90,7 +97,7 @@ impl Default for ResourceParams {
 /// Checks if the resource system is enabled.
 #[inline]
 pub fn valid() -> bool {
-    unsafe { !CTX.is_null() }
+    !CTX.is_null()
 }
 
 /// Resolve shortcuts in the provided string recursively and return None if not exists.
@@ -99,12 +106,38 @@ pub fn resolve<T: AsRef<str>>(url: T) -> Option<String> {
     ctx().resolve(url)
 }
 
+/// Return the registered schemas of virtual file systems, e.g. `file`.
+#[inline]
+pub fn schemas() -> Vec<String> {
+    ctx().schemas()
+}
+
+/// Return the registered shortcuts and their fullnames, e.g. `("res:", "file://res/")`.
+#[inline]
+pub fn shortcuts() -> Vec<(String, String)> {
+    ctx().shortcuts()
+}
+
 /// Return the UUID of resource located at provided path, and return None if not exists.
 #[inline]
 pub fn find<T: AsRef<str>>(filename: T) -> Option<Uuid> {
     ctx().find(filename)
 }
 
+/// Return the UUIDs of all the resources that tagged with `tag`, e.g. to prefetch all the
+/// resources of a level.
+#[inline]
+pub fn find_by_tag<T: AsRef<str>>(tag: T) -> Vec<Uuid> {
+    ctx().find_by_tag(tag)
+}
+
+/// Return the digest of manifests if the build provides one. It changes whenever any
+/// resource changes, e.g. it could be appended to urls as version to bust caches.
+#[inline]
+pub fn manifest_hash() -> Option<u64> {
+    ctx().manifest_hash()
+}
+
 /// Checks if the resource exists in this registry.
 #[inline]
 pub fn exists(uuid: Uuid) -> bool {
@@ -148,30 +181,25 @@ pub(crate) mod inside {
     use failure::ResultExt;
 
     use crate::sched::prelude::{CountLatch, Latch};
+    use crate::utils::global::Global;
 
     use super::system::ResourceSystem;
     use super::ResourceParams;
 
-    pub static mut CTX: *const ResourceSystem = std::ptr::null();
+    pub static CTX: Global<ResourceSystem> = Global::new();
 
     #[inline]
     pub fn ctx() -> &'static ResourceSystem {
-        unsafe {
-            debug_assert!(
-                !CTX.is_null(),
-                "resource system has not been initialized properly."
-            );
-
-            &*CTX
-        }
+        CTX.get()
+            .expect("resource system has not been initialized properly.")
     }
 
     /// Setup the resource system.
     pub unsafe fn setup(params: ResourceParams) -> Result<(), failure::Error> {
-        debug_assert!(CTX.is_null(), "duplicated setup of resource system.");
-
         let ctx = ResourceSystem::new(params)?;
-        CTX = Box::into_raw(Box::new(ctx));
+        if CTX.set(ctx).is_err() {
+            panic!("duplicated setup of resource system.");
+        }
         Ok(())
     }
 
@@ -201,11 +229,6 @@ pub(crate) mod inside {
 
     /// Discard the resource system.
     pub unsafe fn discard() {
-        if CTX.is_null() {
-            return;
-        }
-
-        drop(Box::from_raw(CTX as *mut ResourceSystem));
-        CTX = std::ptr::null();
+        CTX.discard();
     }
 }
@@ -49,6 +49,18 @@ impl ShortcutResolver {
         self.registry.contains_key(shortcut.as_ref())
     }
 
+    /// Gets the registered shortcuts and their fullnames, sorted by shortcuts.
+    pub fn shortcuts(&self) -> Vec<(String, String)> {
+        let mut shortcuts: Vec<_> = self
+            .registry
+            .iter()
+            .map(|(k, v)| (k.clone(), v.clone()))
+            .collect();
+
+        shortcuts.sort();
+        shortcuts
+    }
+
     /// Resolve shortcuts in the provided string recursively and return None if not exists.
     pub fn resolve<T: AsRef<str>>(&self, src: T) -> Option<String> {
         unsafe {
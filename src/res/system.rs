@@ -73,6 +73,18 @@ impl ResourceSystem {
         self.shortcut.resolve(url.as_ref())
     }
 
+    /// Return the registered schemas of virtual file systems.
+    #[inline]
+    pub fn schemas(&self) -> Vec<String> {
+        self.schemas.schemas()
+    }
+
+    /// Return the registered shortcuts and their fullnames.
+    #[inline]
+    pub fn shortcuts(&self) -> Vec<(String, String)> {
+        self.shortcut.shortcuts()
+    }
+
     /// Return the UUID of resource located at provided path, and return None if not exists.
     #[inline]
     pub fn find<T: AsRef<str>>(&self, filename: T) -> Option<Uuid> {
@@ -82,6 +94,18 @@ impl ResourceSystem {
             .and_then(|url| self.manifest.read().unwrap().find(&url))
     }
 
+    /// Return the UUIDs of all the resources that tagged with `tag`.
+    #[inline]
+    pub fn find_by_tag<T: AsRef<str>>(&self, tag: T) -> Vec<Uuid> {
+        self.manifest.read().unwrap().find_by_tag(tag)
+    }
+
+    /// Return the digest of manifests, which changes whenever any resource changes.
+    #[inline]
+    pub fn manifest_hash(&self) -> Option<u64> {
+        self.manifest.read().unwrap().hash()
+    }
+
     /// Checks if the resource exists in this registry.
     #[inline]
     pub fn exists(&self, uuid: Uuid) -> bool {
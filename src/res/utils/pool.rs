@@ -27,6 +27,8 @@
 
 use failure::Error;
 use std::sync::{Arc, Mutex};
+#[cfg(feature = "futures")]
+use std::task::Waker;
 use uuid::Uuid;
 
 use crate::utils::prelude::{FastHashMap, HandleLike, ObjectPool};
@@ -54,6 +56,8 @@ where
     requests: FastHashMap<H, Arc<Mutex<ResourceAsyncState<Loader::Intermediate>>>>,
     registry: FastHashMap<Uuid, H>,
     loader: Loader,
+    #[cfg(feature = "futures")]
+    wakers: FastHashMap<H, Vec<Waker>>,
 }
 
 impl<H, Loader> ResourcePool<H, Loader>
@@ -68,6 +72,8 @@ where
             registry: FastHashMap::default(),
             requests: FastHashMap::default(),
             loader,
+            #[cfg(feature = "futures")]
+            wakers: FastHashMap::default(),
         }
     }
 
@@ -108,6 +114,7 @@ where
             false
         });
 
+        self.wake();
         Ok(())
     }
 
@@ -204,6 +211,8 @@ where
             if let Some(resource) = e.resource {
                 self.loader.delete(handle, resource);
             }
+
+            self.wake();
         }
     }
 
@@ -224,6 +233,25 @@ where
             .unwrap_or(ResourceState::NotReady)
     }
 
+    /// Gets the resource state, and registers `waker` to be woken once the resource is
+    /// loaded or failed to load. Deleted resources are treated as failed.
+    #[cfg(feature = "futures")]
+    pub fn poll_state(&mut self, handle: H, waker: &Waker) -> ResourceState {
+        if !self.items.contains(handle) {
+            return ResourceState::Err;
+        }
+
+        let state = self.state(handle);
+        if state == ResourceState::NotReady {
+            let wakers = self.wakers.entry(handle).or_default();
+            if !wakers.iter().any(|v| v.will_wake(waker)) {
+                wakers.push(waker.clone());
+            }
+        }
+
+        state
+    }
+
     /// Checks if the handle is still avaiable in this pool.
     #[inline]
     pub fn contains(&self, handle: H) -> bool {
@@ -242,6 +270,46 @@ where
         self.items.get_mut(handle).and_then(|e| e.resource.as_mut())
     }
 
+    /// Applies `func` to the resource if it's ready, and returns `None` otherwise. It never
+    /// blocks, so it's fine to poll a resource that is still loading every frame.
+    #[inline]
+    pub fn try_and<F, R>(&self, handle: H, func: F) -> Option<R>
+    where
+        F: FnOnce(&Loader::Resource) -> R,
+    {
+        self.resource(handle).map(func)
+    }
+
+    /// Returns an iterator over all the resources that have been created.
+    #[inline]
+    pub fn resources<'a>(&'a self) -> impl Iterator<Item = &'a Loader::Resource> + 'a {
+        self.items.values().filter_map(|e| e.resource.as_ref())
+    }
+
+    // Wakes the futures of resources that are not loading anymore.
+    #[cfg(feature = "futures")]
+    fn wake(&mut self) {
+        let items = &self.items;
+        self.wakers.retain(|&handle, wakers| {
+            let loading = items
+                .get(handle)
+                .map(|e| e.resource.is_none() && e.error.is_none())
+                .unwrap_or(false);
+
+            if !loading {
+                for v in wakers.drain(..) {
+                    v.wake();
+                }
+            }
+
+            loading
+        });
+    }
+
+    #[cfg(not(feature = "futures"))]
+    #[inline]
+    fn wake(&mut self) {}
+
     #[inline]
     fn alloc(&mut self, uuid: Option<Uuid>) -> H {
         let entry = Item {
@@ -273,3 +341,109 @@ enum ResourceAsyncState<T> {
     Err(Error),
     NotReady,
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    impl_handle!(TestHandle);
+
+    #[derive(Clone)]
+    struct TestLoader {}
+
+    impl ResourceLoader for TestLoader {
+        type Handle = TestHandle;
+        type Intermediate = u32;
+        type Resource = u32;
+
+        fn load(&self, _: Self::Handle, bytes: &[u8]) -> Result<Self::Intermediate, Error> {
+            Ok(bytes.len() as u32)
+        }
+
+        fn create(&self, _: Self::Handle, v: Self::Intermediate) -> Result<Self::Resource, Error> {
+            Ok(v)
+        }
+
+        fn delete(&self, _: Self::Handle, _: Self::Resource) {}
+    }
+
+    #[test]
+    fn try_and() {
+        let mut pool = ResourcePool::new(TestLoader {});
+
+        let handle = pool.alloc(None);
+        let req = Arc::new(Mutex::new(ResourceAsyncState::NotReady));
+        pool.requests.insert(handle, req.clone());
+
+        pool.advance().unwrap();
+        assert_eq!(pool.try_and(handle, |v| *v + 1), None);
+
+        *req.lock().unwrap() = ResourceAsyncState::Ok(2);
+        pool.advance().unwrap();
+        assert_eq!(pool.try_and(handle, |v| *v + 1), Some(3));
+
+        pool.delete(handle);
+        assert_eq!(pool.try_and(handle, |v| *v + 1), None);
+    }
+
+    #[test]
+    #[cfg(feature = "futures")]
+    fn poll_state() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use std::task::Wake;
+
+        struct Counter(AtomicUsize);
+
+        impl Wake for Counter {
+            fn wake(self: Arc<Self>) {
+                self.0.fetch_add(1, Ordering::SeqCst);
+            }
+        }
+
+        let counter = Arc::new(Counter(AtomicUsize::new(0)));
+        let waker = Waker::from(counter.clone());
+        let mut pool = ResourcePool::new(TestLoader {});
+
+        let handle = pool.alloc(None);
+        let req = Arc::new(Mutex::new(ResourceAsyncState::NotReady));
+        pool.requests.insert(handle, req.clone());
+
+        // Registers the same waker only once.
+        assert_eq!(pool.poll_state(handle, &waker), ResourceState::NotReady);
+        assert_eq!(pool.poll_state(handle, &waker), ResourceState::NotReady);
+        pool.advance().unwrap();
+        assert_eq!(counter.0.load(Ordering::SeqCst), 0);
+
+        *req.lock().unwrap() = ResourceAsyncState::Ok(2);
+        pool.advance().unwrap();
+        assert_eq!(counter.0.load(Ordering::SeqCst), 1);
+        assert_eq!(pool.poll_state(handle, &waker), ResourceState::Ok);
+
+        // Futures of deleted resources are woken, and fail.
+        let other = pool.alloc(None);
+        assert_eq!(pool.poll_state(other, &waker), ResourceState::NotReady);
+        pool.delete(other);
+        assert_eq!(counter.0.load(Ordering::SeqCst), 2);
+        assert_eq!(pool.poll_state(other, &waker), ResourceState::Err);
+    }
+
+    #[test]
+    fn shared() {
+        let mut pool = ResourcePool::new(TestLoader {});
+
+        let uuid = Uuid::from_bytes([1; 16]);
+        let handle = pool.alloc(Some(uuid));
+        pool.items.get_mut(handle).unwrap().resource = Some(1);
+
+        let other = pool.create_from_uuid(uuid).unwrap();
+        assert_eq!(handle, other);
+
+        pool.delete(handle);
+        assert!(pool.contains(other));
+        assert_eq!(pool.state(other), ResourceState::Ok);
+
+        pool.delete(other);
+        assert!(!pool.contains(other));
+        assert!(pool.registry.is_empty());
+    }
+}
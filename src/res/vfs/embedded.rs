@@ -0,0 +1,111 @@
+//! A VFS that serves resources from bytes compiled into the executable.
+//!
+//! The bytes are a pack of files, which starts with `MAGIC`, followed by the serialized
+//! index of entries and then the contents of all the files. It could be produced with
+//! `Embedded::pack`, and mounted with a schema and shortcut like other VFSes:
+//!
+//! ```rust,ignore
+//! static PACK: &[u8] = include_bytes!("../resources.pack");
+//!
+//! let mut params = Params::default();
+//! params.res.schemas.add("embedded", Embedded::from_pack(PACK)?);
+//! params.res.shortcuts.add("res:", "embedded://resources/")?;
+//! params.res.dirs.push("res:".into());
+//! ```
+//!
+//! The pack should contain the `MANIFEST` of the build, so the resources could be found
+//! without touching any filesystem.
+
+use std::io::Cursor;
+use std::sync::Arc;
+
+use bincode;
+
+use crate::errors::*;
+use crate::sched::prelude::LockLatch;
+use crate::utils::hash::FastHashMap;
+
+use super::super::request::Response;
+use super::super::url::Url;
+use super::VFS;
+
+pub const MAGIC: [u8; 8] = [b'E', b'M', b'B', b'D', b' ', 0, 0, 1];
+
+#[derive(Debug, Default, Clone)]
+pub struct Embedded {
+    files: FastHashMap<String, &'static [u8]>,
+}
+
+impl Embedded {
+    pub fn new() -> Self {
+        Embedded {
+            files: FastHashMap::default(),
+        }
+    }
+
+    /// Creates a `Embedded` with all the files in pack.
+    pub fn from_pack(bytes: &'static [u8]) -> Result<Self> {
+        if bytes.len() < MAGIC.len() || bytes[0..MAGIC.len()] != MAGIC[..] {
+            bail!("[Embedded] MAGIC number not match.");
+        }
+
+        let mut cursor = Cursor::new(&bytes[MAGIC.len()..]);
+        let entries: Vec<(String, u64, u64)> = bincode::deserialize_from(&mut cursor)?;
+        let payload = &bytes[MAGIC.len() + cursor.position() as usize..];
+
+        let mut embedded = Embedded::new();
+        for (path, offset, len) in entries {
+            let end = match offset.checked_add(len) {
+                Some(end) if end <= payload.len() as u64 => end as usize,
+                _ => bail!("[Embedded] File {} is out of the bounds of pack.", path),
+            };
+
+            embedded.add(path, &payload[offset as usize..end]);
+        }
+
+        Ok(embedded)
+    }
+
+    /// Packs files into bytes that could be loaded with `from_pack`.
+    pub fn pack<T: AsRef<str>>(files: &[(T, &[u8])]) -> Result<Vec<u8>> {
+        let mut entries = Vec::with_capacity(files.len());
+        let mut offset = 0;
+        for (path, bytes) in files {
+            let path = path.as_ref().trim_start_matches('/').to_owned();
+            entries.push((path, offset as u64, bytes.len() as u64));
+            offset += bytes.len();
+        }
+
+        let mut buf = MAGIC.to_vec();
+        bincode::serialize_into(&mut buf, &entries)?;
+
+        for (_, bytes) in files {
+            buf.extend_from_slice(bytes);
+        }
+
+        Ok(buf)
+    }
+
+    /// Adds a file at `path`. Leading slashes of path are ignored.
+    pub fn add<T: AsRef<str>>(&mut self, path: T, bytes: &'static [u8]) {
+        let path = path.as_ref().trim_start_matches('/');
+        self.files.insert(path.to_owned(), bytes);
+    }
+
+    /// Gets the bytes of file at `path`.
+    pub fn get<T: AsRef<str>>(&self, path: T) -> Option<&'static [u8]> {
+        let path = path.as_ref().trim_start_matches('/');
+        self.files.get(path).cloned()
+    }
+}
+
+impl VFS for Embedded {
+    fn request(&self, url: &Url, state: Arc<LockLatch<Response>>) {
+        let response = match self.get(url.path()) {
+            Some(bytes) => Ok(bytes.to_vec().into_boxed_slice()),
+            None => Err(format_err!("[Embedded] File {} is not found.", url.path())),
+        };
+
+        state.set(response);
+    }
+}
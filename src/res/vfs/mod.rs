@@ -1,5 +1,6 @@
 #[cfg(not(target_arch = "wasm32"))]
 pub mod dir;
+pub mod embedded;
 #[cfg(target_arch = "wasm32")]
 pub mod http;
 
@@ -32,6 +33,13 @@ impl SchemaResolver {
         self.schemas.insert(schema.into(), Arc::new(vfs));
     }
 
+    /// Gets the registered schemas, sorted by names.
+    pub fn schemas(&self) -> Vec<String> {
+        let mut schemas: Vec<_> = self.schemas.keys().cloned().collect();
+        schemas.sort();
+        schemas
+    }
+
     #[inline]
     pub fn locate<T1: AsRef<str>>(&self, schema: T1) -> Result<Arc<VFS>, failure::Error> {
         let schema = schema.as_ref();
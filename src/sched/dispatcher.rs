@@ -0,0 +1,123 @@
+//! Runs systems concurrently based on the data they read and write.
+//!
+//! Every system declares its `Access`, the data types that it reads and writes. Two
+//! systems conflict if one of them writes a type that the other one reads or writes.
+//! A system always runs after the conflicting systems that were added before it, while
+//! the non-conflicting ones run concurrently in the global `Scheduler`:
+//!
+//! ```rust,ignore
+//! let mut dispatcher = Dispatcher::new();
+//! dispatcher.add(Access::new().read::<Velocity>().write::<Position>(), || { .. });
+//! dispatcher.add(Access::new().write::<Health>(), || { .. });
+//! dispatcher.add(Access::new().read::<Position>(), || { .. });
+//!
+//! // The first two systems run concurrently, and then the third one.
+//! dispatcher.run();
+//! ```
+
+use std::any::TypeId;
+
+/// The data types that a system reads and writes.
+#[derive(Debug, Default, Clone)]
+pub struct Access {
+    reads: Vec<TypeId>,
+    writes: Vec<TypeId>,
+}
+
+impl Access {
+    pub fn new() -> Self {
+        Access::default()
+    }
+
+    /// Declares that the system reads `T`.
+    pub fn read<T: 'static>(mut self) -> Self {
+        self.reads.push(TypeId::of::<T>());
+        self
+    }
+
+    /// Declares that the system writes `T`.
+    pub fn write<T: 'static>(mut self) -> Self {
+        self.writes.push(TypeId::of::<T>());
+        self
+    }
+
+    /// Checks if two systems could not run concurrently, which is the case when one of
+    /// them writes a type that the other one reads or writes.
+    pub fn conflicts(&self, rhs: &Access) -> bool {
+        self.writes
+            .iter()
+            .any(|v| rhs.reads.contains(v) || rhs.writes.contains(v))
+            || rhs.writes.iter().any(|v| self.reads.contains(v))
+    }
+}
+
+struct DispatcherSystem<'a> {
+    access: Access,
+    stage: usize,
+    func: Box<dyn FnMut() + Send + 'a>,
+}
+
+/// A set of systems that are scheduled with their dependencies.
+#[derive(Default)]
+pub struct Dispatcher<'a> {
+    systems: Vec<DispatcherSystem<'a>>,
+    stages: usize,
+}
+
+impl<'a> Dispatcher<'a> {
+    pub fn new() -> Self {
+        Dispatcher {
+            systems: Vec::new(),
+            stages: 0,
+        }
+    }
+
+    /// Adds a system, and returns its index. It runs after all the previously added
+    /// systems that it conflicts with.
+    pub fn add<F>(&mut self, access: Access, func: F) -> usize
+    where
+        F: FnMut() + Send + 'a,
+    {
+        let stage = self
+            .systems
+            .iter()
+            .filter(|v| v.access.conflicts(&access))
+            .map(|v| v.stage + 1)
+            .max()
+            .unwrap_or(0);
+
+        self.stages = self.stages.max(stage + 1);
+        self.systems.push(DispatcherSystem {
+            access,
+            stage,
+            func: Box::new(func),
+        });
+
+        self.systems.len() - 1
+    }
+
+    /// Gets the indices of systems grouped by stages. The systems in the same stage run
+    /// concurrently, and stages run one after another.
+    pub fn stages(&self) -> Vec<Vec<usize>> {
+        let mut stages = vec![Vec::new(); self.stages];
+        for (i, v) in self.systems.iter().enumerate() {
+            stages[v.stage].push(i);
+        }
+
+        stages
+    }
+
+    /// Runs all the systems, and blocks until they are finished.
+    pub fn run(&mut self) {
+        for stage in 0..self.stages {
+            let systems = self.systems.iter_mut().filter(|v| v.stage == stage);
+
+            super::scope(|s| {
+                for v in systems {
+                    let func = &mut v.func;
+                    s.spawn(move |_| func());
+                }
+            });
+        }
+    }
+}
@@ -1,5 +1,7 @@
 use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
 use std::sync::{Condvar, Mutex};
+#[cfg(feature = "futures")]
+use std::task::Waker;
 
 /// We define various kinds of latches, which are all a primitive signaling
 /// mechanism. A latch starts as false. Eventually someone calls `set()` and
@@ -73,6 +75,8 @@ impl LatchProbe for SpinLatch {
 pub struct LockLatch<T> {
     m: Mutex<Option<T>>,
     v: Condvar,
+    #[cfg(feature = "futures")]
+    wakers: Mutex<Vec<Waker>>,
 }
 
 impl<T> Default for LockLatch<T> {
@@ -80,6 +84,8 @@ impl<T> Default for LockLatch<T> {
         LockLatch {
             m: Mutex::new(None),
             v: Condvar::new(),
+            #[cfg(feature = "futures")]
+            wakers: Mutex::new(Vec::new()),
         }
     }
 }
@@ -92,11 +98,40 @@ impl<T> LockLatch<T> {
 
     #[inline]
     pub fn set(&self, v: T) {
-        let mut guard = self.m.lock().unwrap();
-        *guard = Some(v);
-        self.v.notify_all();
+        {
+            let mut guard = self.m.lock().unwrap();
+            *guard = Some(v);
+            self.v.notify_all();
+        }
+
+        self.wake();
+    }
+
+    /// Registers a `Waker` that will be woken once the latch is set. Returns true
+    /// instead if the latch has been set already.
+    #[cfg(feature = "futures")]
+    pub fn register(&self, waker: &Waker) -> bool {
+        {
+            let mut wakers = self.wakers.lock().unwrap();
+            if !wakers.iter().any(|v| v.will_wake(waker)) {
+                wakers.push(waker.clone());
+            }
+        }
+
+        self.m.lock().unwrap().is_some()
+    }
+
+    #[cfg(feature = "futures")]
+    fn wake(&self) {
+        for v in self.wakers.lock().unwrap().drain(..) {
+            v.wake();
+        }
     }
 
+    #[cfg(not(feature = "futures"))]
+    #[inline]
+    fn wake(&self) {}
+
     #[inline]
     pub fn take(&self) -> T {
         assert!(self.is_set());
@@ -109,9 +144,7 @@ impl<T> LockLatch<T> {
 impl Latch for LockLatch<()> {
     #[inline]
     fn set(&self) {
-        let mut guard = self.m.lock().unwrap();
-        *guard = Some(());
-        self.v.notify_all();
+        LockLatch::set(self, ());
     }
 }
 
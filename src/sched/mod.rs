@@ -1,12 +1,14 @@
+pub mod dispatcher;
 pub mod latch;
 pub mod scope;
-mod system;
+pub(crate) mod system;
 
 mod job;
 mod scheduler;
 mod unwind;
 
 pub mod prelude {
+    pub use super::dispatcher::{Access, Dispatcher};
     pub use super::latch::{CountLatch, Latch, LatchProbe, LockLatch, SpinLatch};
     pub use super::system::PanicHandler;
 }
@@ -17,7 +19,7 @@ use self::scope::Scope;
 /// Checks if the sched system is enabled.
 #[inline]
 pub fn valid() -> bool {
-    unsafe { !CTX.is_null() }
+    !CTX.is_null()
 }
 
 // /// Blocks current thread until latch is set. Try to keep busy by popping and stealing jobs
@@ -53,19 +55,15 @@ where
 }
 
 pub(crate) mod inside {
+    use crate::utils::global::Global;
+
     use super::system::{PanicHandler, SchedulerSystem};
 
-    pub static mut CTX: *const SchedulerSystem = std::ptr::null();
+    pub static CTX: Global<SchedulerSystem> = Global::new();
 
     pub fn ctx() -> &'static SchedulerSystem {
-        unsafe {
-            debug_assert!(
-                !CTX.is_null(),
-                "scheduler system has not been initialized properly."
-            );
-
-            &*CTX
-        }
+        CTX.get()
+            .expect("scheduler system has not been initialized properly.")
     }
 
     /// Setup the sched system.
@@ -74,27 +72,23 @@ pub(crate) mod inside {
         stack_size: Option<usize>,
         panic_handler: Option<Box<PanicHandler>>,
     ) {
-        debug_assert!(CTX.is_null(), "duplicated setup of sched system.");
-
-        CTX = Box::into_raw(Box::new(if num > 0 {
+        let ctx = if num > 0 {
             SchedulerSystem::new(num, stack_size, panic_handler)
         } else {
             SchedulerSystem::headless()
-        }));
+        };
+
+        if CTX.set(ctx).is_err() {
+            panic!("duplicated setup of sched system.");
+        }
     }
 
     /// Discard the sched system.
     pub unsafe fn discard() {
-        if CTX.is_null() {
-            return;
-        }
-
-        drop(Box::from_raw(CTX as *mut SchedulerSystem));
-        CTX = std::ptr::null();
+        CTX.discard();
     }
 
     pub unsafe fn terminate() {
         ctx().terminate();
     }
-
 }
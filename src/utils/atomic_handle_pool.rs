@@ -0,0 +1,162 @@
+use std::marker::PhantomData;
+use std::sync::atomic::{AtomicU32, AtomicU64, AtomicUsize, Ordering};
+
+use super::handle::{HandleIndex, HandleLike};
+
+const NIL: HandleIndex = HandleIndex::MAX;
+
+/// A lock-free version of `HandlePool`, which could create and free handles from
+/// multiple threads without contending a mutex, e.g. the loaders that run in the
+/// background.
+///
+/// The indices are bumped from 0 until the fixed `capacity`, and the freed indices are
+/// recycled with a lock-free stack. Like `HandlePool`, the version of a index is bumped
+/// every time it's created or freed, so handles that have been freed are never alive
+/// again.
+pub struct AtomicHandlePool<T: HandleLike> {
+    versions: Box<[AtomicU32]>,
+    // The next index of free stack, for every index that has been freed.
+    nexts: Box<[AtomicU32]>,
+    // The top index of free stack in low 32 bits, and a tag that changes every time
+    // the stack changes in high 32 bits, which prevents the ABA problem.
+    frees: AtomicU64,
+    bump: AtomicUsize,
+    len: AtomicUsize,
+    _marker: PhantomData<T>,
+}
+
+impl<T: HandleLike> AtomicHandlePool<T> {
+    /// Constructs a new, empty `AtomicHandlePool` which could hold at most `capacity`
+    /// alive handles.
+    pub fn new(capacity: usize) -> Self {
+        assert!(capacity < NIL as usize, "capacity is too large.");
+
+        AtomicHandlePool {
+            versions: (0..capacity).map(|_| AtomicU32::new(0)).collect(),
+            nexts: (0..capacity).map(|_| AtomicU32::new(NIL)).collect(),
+            frees: AtomicU64::new(u64::from(NIL)),
+            bump: AtomicUsize::new(0),
+            len: AtomicUsize::new(0),
+            _marker: PhantomData,
+        }
+    }
+
+    /// Creates a unused `Handle`, returns `None` if the pool is full.
+    pub fn create(&self) -> Option<T> {
+        let index = self.pop().or_else(|| self.bump())?;
+        let version = self.versions[index as usize].fetch_add(1, Ordering::AcqRel) + 1;
+        self.len.fetch_add(1, Ordering::Relaxed);
+        Some(T::new(index, version))
+    }
+
+    /// Returns true if this `Handle` was created by `AtomicHandlePool`, and has not been
+    /// freed yet.
+    pub fn contains(&self, handle: T) -> bool {
+        let version = handle.version();
+        (version & 0x1) == 1
+            && self
+                .versions
+                .get(handle.index() as usize)
+                .map(|v| v.load(Ordering::Acquire) == version)
+                .unwrap_or(false)
+    }
+
+    /// Recycles the `Handle` index, and mark its version as dead. Returns false if the
+    /// handle is not alive, e.g. it has been freed by another thread.
+    pub fn free(&self, handle: T) -> bool {
+        if !self.contains(handle) {
+            return false;
+        }
+
+        let index = handle.index();
+        let version = handle.version();
+        if self.versions[index as usize]
+            .compare_exchange(version, version + 1, Ordering::AcqRel, Ordering::Acquire)
+            .is_err()
+        {
+            return false;
+        }
+
+        self.len.fetch_sub(1, Ordering::Relaxed);
+        self.push(index);
+        true
+    }
+
+    /// Returns the total number of alive handle in this pool.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.len.load(Ordering::Relaxed)
+    }
+
+    /// Checks if the pool is empty.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the max number of alive handles in this pool.
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.versions.len()
+    }
+
+    fn bump(&self) -> Option<HandleIndex> {
+        let mut index = self.bump.load(Ordering::Relaxed);
+        loop {
+            if index >= self.versions.len() {
+                return None;
+            }
+
+            match self.bump.compare_exchange_weak(
+                index,
+                index + 1,
+                Ordering::Relaxed,
+                Ordering::Relaxed,
+            ) {
+                Ok(_) => return Some(index as HandleIndex),
+                Err(v) => index = v,
+            }
+        }
+    }
+
+    fn pop(&self) -> Option<HandleIndex> {
+        let mut top = self.frees.load(Ordering::Acquire);
+        loop {
+            let index = top as HandleIndex;
+            if index == NIL {
+                return None;
+            }
+
+            let next = self.nexts[index as usize].load(Ordering::Relaxed);
+            let v = Self::tagged(top, next);
+            match self
+                .frees
+                .compare_exchange_weak(top, v, Ordering::AcqRel, Ordering::Acquire)
+            {
+                Ok(_) => return Some(index),
+                Err(v) => top = v,
+            }
+        }
+    }
+
+    fn push(&self, index: HandleIndex) {
+        let mut top = self.frees.load(Ordering::Acquire);
+        loop {
+            self.nexts[index as usize].store(top as HandleIndex, Ordering::Relaxed);
+            let v = Self::tagged(top, index);
+            match self
+                .frees
+                .compare_exchange_weak(top, v, Ordering::AcqRel, Ordering::Acquire)
+            {
+                Ok(_) => return,
+                Err(v) => top = v,
+            }
+        }
+    }
+
+    #[inline]
+    fn tagged(top: u64, index: HandleIndex) -> u64 {
+        let tag = (top >> 32).wrapping_add(1) & 0xFFFF_FFFF;
+        (tag << 32) | u64::from(index)
+    }
+}
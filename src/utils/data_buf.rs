@@ -1,6 +1,6 @@
 use std::borrow::Borrow;
 use std::marker::PhantomData;
-use std::{mem, slice, str};
+use std::{mem, ptr, slice, str};
 
 /// Where we store all the intermediate bytes.
 #[derive(Serialize, Deserialize, Clone, Debug, Default)]
@@ -27,6 +27,7 @@ impl DataBuffer {
     where
         T: Copy,
     {
+        self.align::<T>();
         let data =
             unsafe { slice::from_raw_parts(value as *const T as *const u8, mem::size_of::<T>()) };
 
@@ -44,6 +45,7 @@ impl DataBuffer {
     where
         T: Copy,
     {
+        self.align::<T>();
         let len = mem::size_of::<T>().wrapping_mul(slice.len());
         let u8_slice = unsafe { slice::from_raw_parts(slice.as_ptr() as *const u8, len) };
         self.0.extend_from_slice(u8_slice);
@@ -67,6 +69,16 @@ impl DataBuffer {
         }
     }
 
+    // Pads the buffer, so the next object of `T` could be referenced in place. The
+    // alignment is relative to the start of buffer, since the bytes might be moved by
+    // reallocations. It holds in place as long as the allocation itself is aligned to
+    // `T`, which `as_ref` and `as_slice` assert.
+    fn align<T>(&mut self) {
+        let align = mem::align_of::<T>();
+        let len = self.0.len().div_ceil(align) * align;
+        self.0.resize(len, 0);
+    }
+
     /// Returns reference to object indicated by `DataBufferPtr`.
     #[inline]
     pub fn as_ref<T>(&self, ptr: DataBufferPtr<T>) -> &T
@@ -75,6 +87,7 @@ impl DataBuffer {
     {
         let slice = self.as_bytes(ptr);
         assert_eq!(slice.len(), mem::size_of::<T>());
+        assert_eq!(slice.as_ptr() as usize % mem::align_of::<T>(), 0);
         unsafe { &*(slice.as_ptr() as *const _) }
     }
 
@@ -87,9 +100,26 @@ impl DataBuffer {
         let slice = self.as_bytes(ptr);
         let len = slice.len() / mem::size_of::<T>();
         assert_eq!(slice.len(), mem::size_of::<T>().wrapping_mul(len));
+        assert_eq!(slice.as_ptr() as usize % mem::align_of::<T>(), 0);
         unsafe { slice::from_raw_parts(slice.as_ptr() as *const T, len) }
     }
 
+    /// Copies the objects indicated by `DataBufferPtr` into a vector. Unlike `as_slice`,
+    /// it does not require them to be aligned, e.g. in buffers written without padding.
+    pub fn to_vec<T>(&self, ptr: DataBufferPtr<[T]>) -> Vec<T>
+    where
+        T: Copy,
+    {
+        let slice = self.as_bytes(ptr);
+        let size = mem::size_of::<T>();
+        assert!(size > 0);
+        assert_eq!(slice.len() % size, 0);
+        slice
+            .chunks(size)
+            .map(|v| unsafe { ptr::read_unaligned(v.as_ptr() as *const T) })
+            .collect()
+    }
+
     /// Returns string slice indicated by `DataBufferPtr`.
     #[inline]
     pub fn as_str(&self, ptr: DataBufferPtr<str>) -> &str {
@@ -162,5 +192,8 @@ mod test {
         let text = "string serialization";
         let slice_text = buffer.extend_from_str(text);
         assert_eq!(text, buffer.as_str(slice_text));
+
+        let slice_u64 = buffer.extend_from_slice(&[1u64, 2]);
+        assert_eq!(buffer.as_slice(slice_u64), &[1, 2]);
     }
 }
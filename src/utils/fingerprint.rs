@@ -0,0 +1,54 @@
+//! Fingerprints of files, which could be used to detect changed assets.
+
+use std::fs;
+use std::path::Path;
+use std::time::UNIX_EPOCH;
+
+use crate::errors::*;
+
+use super::hash::content_hash;
+
+/// The digest of a file. Two files with the same `hash` and `len` are considered to
+/// have the same content.
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct FileFingerprint {
+    /// The `content_hash` of file.
+    pub hash: u64,
+    /// The length of file in bytes.
+    pub len: u64,
+    /// The last modification time in seconds since unix epoch, or 0 if not available.
+    pub mtime: u64,
+}
+
+impl FileFingerprint {
+    /// Creates a fingerprint from the content of file.
+    pub fn new(bytes: &[u8], mtime: u64) -> Self {
+        FileFingerprint {
+            hash: content_hash(bytes),
+            len: bytes.len() as u64,
+            mtime,
+        }
+    }
+
+    /// Reads the file at `path` and creates its fingerprint.
+    pub fn from_path<T: AsRef<Path>>(path: T) -> Result<Self> {
+        let path = path.as_ref();
+        let bytes = fs::read(path)?;
+
+        let mtime = fs::metadata(path)?
+            .modified()
+            .ok()
+            .and_then(|v| v.duration_since(UNIX_EPOCH).ok())
+            .map(|v| v.as_secs())
+            .unwrap_or(0);
+
+        Ok(FileFingerprint::new(&bytes, mtime))
+    }
+
+    /// Returns true if the contents of two files are different. The modification time
+    /// is ignored, since touching a file does not change it.
+    #[inline]
+    pub fn is_changed(&self, rhs: &FileFingerprint) -> bool {
+        self.hash != rhs.hash || self.len != rhs.len
+    }
+}
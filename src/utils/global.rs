@@ -0,0 +1,242 @@
+//! Sound storages of the global systems.
+//!
+//! Systems are set up once and then accessed through free functions everywhere,
+//! including the worker threads of scheduler. `Global` keeps the system behind an
+//! atomic pointer, and only accepts systems that could be shared between threads.
+//!
+//! A few systems like the window system wrap platform resources that must stay on the
+//! thread that created them. `LocalGlobal` keeps those systems, and panics if they are
+//! accessed from any other thread.
+
+use std::marker::PhantomData;
+use std::ptr;
+use std::sync::atomic::{AtomicPtr, AtomicUsize, Ordering};
+
+/// A global system that is shared between threads.
+pub struct Global<T> {
+    ptr: AtomicPtr<T>,
+    _marker: PhantomData<*const T>,
+}
+
+unsafe impl<T: Send + Sync> Send for Global<T> {}
+unsafe impl<T: Send + Sync> Sync for Global<T> {}
+
+impl<T> Default for Global<T> {
+    fn default() -> Self {
+        Global::new()
+    }
+}
+
+impl<T> Global<T> {
+    pub const fn new() -> Self {
+        Global {
+            ptr: AtomicPtr::new(ptr::null_mut()),
+            _marker: PhantomData,
+        }
+    }
+
+    /// Checks if the system has not been set up yet.
+    #[inline]
+    pub fn is_null(&self) -> bool {
+        self.ptr.load(Ordering::Acquire).is_null()
+    }
+
+    /// Sets up the system. The value is given back if there is a system already.
+    pub fn set(&self, value: T) -> Result<(), T> {
+        let ptr = Box::into_raw(Box::new(value));
+        match self
+            .ptr
+            .compare_exchange(ptr::null_mut(), ptr, Ordering::AcqRel, Ordering::Acquire)
+        {
+            Ok(_) => Ok(()),
+            Err(_) => Err(*unsafe { Box::from_raw(ptr) }),
+        }
+    }
+
+    /// Gets the system if it has been set up.
+    #[inline]
+    pub fn get(&self) -> Option<&T> {
+        unsafe { self.ptr.load(Ordering::Acquire).as_ref() }
+    }
+
+    /// Discards the system if it has been set up.
+    ///
+    /// # Safety
+    ///
+    /// There should be no references to the system that are still alive.
+    pub unsafe fn discard(&self) {
+        let ptr = self.ptr.swap(ptr::null_mut(), Ordering::AcqRel);
+        if !ptr.is_null() {
+            drop(Box::from_raw(ptr));
+        }
+    }
+}
+
+/// A global system that could only be used on the thread that set it up.
+pub struct LocalGlobal<T> {
+    ptr: AtomicPtr<T>,
+    // The token of owner thread, or 0 if the system has not been set up.
+    owner: AtomicUsize,
+    _marker: PhantomData<*const T>,
+}
+
+// The system itself is never handed out to other threads, `get` and `discard` check the
+// owner thread before touching it.
+unsafe impl<T> Send for LocalGlobal<T> {}
+unsafe impl<T> Sync for LocalGlobal<T> {}
+
+impl<T> Default for LocalGlobal<T> {
+    fn default() -> Self {
+        LocalGlobal::new()
+    }
+}
+
+impl<T> LocalGlobal<T> {
+    pub const fn new() -> Self {
+        LocalGlobal {
+            ptr: AtomicPtr::new(ptr::null_mut()),
+            owner: AtomicUsize::new(0),
+            _marker: PhantomData,
+        }
+    }
+
+    /// Checks if the system has not been set up yet.
+    #[inline]
+    pub fn is_null(&self) -> bool {
+        self.ptr.load(Ordering::Acquire).is_null()
+    }
+
+    /// Sets up the system, the current thread becomes the owner of it. The value is given
+    /// back if there is a system already.
+    pub fn set(&self, value: T) -> Result<(), T> {
+        let ptr = Box::into_raw(Box::new(value));
+        match self
+            .ptr
+            .compare_exchange(ptr::null_mut(), ptr, Ordering::AcqRel, Ordering::Acquire)
+        {
+            Ok(_) => {
+                self.owner.store(current_thread(), Ordering::Release);
+                Ok(())
+            }
+            Err(_) => Err(*unsafe { Box::from_raw(ptr) }),
+        }
+    }
+
+    /// Gets the system if it has been set up.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the current thread is not the one that set up the system.
+    #[inline]
+    pub fn get(&self) -> Option<&T> {
+        let ptr = self.ptr.load(Ordering::Acquire);
+        if ptr.is_null() {
+            return None;
+        }
+
+        self.check_owner();
+        unsafe { Some(&*ptr) }
+    }
+
+    /// Discards the system if it has been set up.
+    ///
+    /// # Safety
+    ///
+    /// There should be no references to the system that are still alive.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the current thread is not the one that set up the system.
+    pub unsafe fn discard(&self) {
+        if self.is_null() {
+            return;
+        }
+
+        self.check_owner();
+        self.owner.store(0, Ordering::Release);
+        let ptr = self.ptr.swap(ptr::null_mut(), Ordering::AcqRel);
+        if !ptr.is_null() {
+            drop(Box::from_raw(ptr));
+        }
+    }
+
+    #[inline]
+    fn check_owner(&self) {
+        assert!(
+            self.owner.load(Ordering::Acquire) == current_thread(),
+            "system could only be accessed from the thread that set it up."
+        );
+    }
+}
+
+// Identifies threads with non-zero tokens that are cached in thread locals, so checking
+// the owner thread does not lock anything.
+#[inline]
+fn current_thread() -> usize {
+    static NEXT: AtomicUsize = AtomicUsize::new(1);
+    thread_local!(static CURRENT: usize = NEXT.fetch_add(1, Ordering::Relaxed));
+    CURRENT.with(|v| *v)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::sync::atomic::AtomicBool;
+    use std::sync::Arc;
+    use std::thread;
+
+    struct Dropper(Arc<AtomicBool>);
+
+    impl Drop for Dropper {
+        fn drop(&mut self) {
+            self.0.store(true, Ordering::SeqCst);
+        }
+    }
+
+    #[test]
+    fn global() {
+        static GLOBAL: Global<u32> = Global::new();
+        assert!(GLOBAL.is_null());
+        assert!(GLOBAL.get().is_none());
+
+        assert_eq!(GLOBAL.set(1), Ok(()));
+        assert_eq!(GLOBAL.set(2), Err(2));
+        assert_eq!(GLOBAL.get(), Some(&1));
+
+        let v = thread::spawn(|| *GLOBAL.get().unwrap()).join().unwrap();
+        assert_eq!(v, 1);
+
+        unsafe { GLOBAL.discard() };
+        assert!(GLOBAL.is_null());
+        assert_eq!(GLOBAL.set(3), Ok(()));
+        assert_eq!(GLOBAL.get(), Some(&3));
+    }
+
+    #[test]
+    fn discard() {
+        let global = Global::new();
+        let dropped = Arc::new(AtomicBool::new(false));
+
+        assert!(global.set(Dropper(dropped.clone())).is_ok());
+        assert!(!dropped.load(Ordering::SeqCst));
+
+        unsafe { global.discard() };
+        assert!(dropped.load(Ordering::SeqCst));
+    }
+
+    #[test]
+    fn local_global() {
+        static GLOBAL: LocalGlobal<u32> = LocalGlobal::new();
+        assert!(GLOBAL.set(1).is_ok());
+        assert_eq!(GLOBAL.get(), Some(&1));
+
+        assert!(thread::spawn(|| GLOBAL.get().is_some()).join().is_err());
+        assert!(thread::spawn(|| unsafe { GLOBAL.discard() })
+            .join()
+            .is_err());
+
+        unsafe { GLOBAL.discard() };
+        assert!(GLOBAL.is_null());
+        assert!(thread::spawn(|| GLOBAL.get().is_none()).join().unwrap());
+    }
+}
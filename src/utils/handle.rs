@@ -59,6 +59,22 @@ impl Handle {
     pub fn version(self) -> HandleIndex {
         self.version
     }
+
+    /// Constructs a `Handle` from the raw parts returned by `into_raw`.
+    ///
+    /// Notes that this does NOT validate whether the handle is still alive, it might
+    /// refer to a freed or recycled slot.
+    #[inline]
+    pub fn from_raw(index: HandleIndex, version: HandleIndex) -> Self {
+        Handle { index, version }
+    }
+
+    /// Decomposes this `Handle` into its raw parts `(index, version)`, which could be
+    /// persisted and reconstructed with `from_raw`.
+    #[inline]
+    pub fn into_raw(self) -> (HandleIndex, HandleIndex) {
+        (self.index, self.version)
+    }
 }
 
 impl Deref for Handle {
@@ -141,6 +157,32 @@ macro_rules! impl_handle {
             }
         }
 
+        #[allow(dead_code)]
+        impl $name {
+            /// Constructs a handle from the raw parts returned by `into_raw`.
+            ///
+            /// Notes that this does NOT validate whether the handle is still alive, it
+            /// might refer to a freed or recycled object.
+            #[inline]
+            pub fn from_raw(
+                index: $crate::utils::handle::HandleIndex,
+                version: $crate::utils::handle::HandleIndex,
+            ) -> Self {
+                $name($crate::utils::handle::Handle::from_raw(index, version))
+            }
+
+            /// Decomposes this handle into its raw parts `(index, version)`.
+            #[inline]
+            pub fn into_raw(
+                self,
+            ) -> (
+                $crate::utils::handle::HandleIndex,
+                $crate::utils::handle::HandleIndex,
+            ) {
+                self.0.into_raw()
+            }
+        }
+
         impl $crate::utils::handle::HandleLike for $name {
             #[inline]
             fn new(
@@ -215,4 +257,17 @@ mod test {
         let h2 = TypeSafeHandle(Handle::default());
         assert_eq!(*h2, Handle::default());
     }
+
+    #[test]
+    fn raw() {
+        let h1 = TypeSafeHandle::from_raw(3, 7);
+        assert_eq!(h1.index(), 3);
+        assert_eq!(h1.version(), 7);
+        assert_eq!(h1.into_raw(), (3, 7));
+
+        let (index, version) = h1.into_raw();
+        assert_eq!(TypeSafeHandle::from_raw(index, version), h1);
+        assert_eq!(Handle::from_raw(index, version), *h1);
+        assert_eq!(Handle::from_raw(index, version).into_raw(), (3, 7));
+    }
 }
@@ -0,0 +1,117 @@
+use super::handle::HandleLike;
+use super::hash::FastHashMap;
+
+/// A map from handles to values, which keeps the values packed densely so they could be
+/// iterated as slices. Removing swaps the last value into the hole, so the order of
+/// values is NOT preserved.
+///
+/// Unlike `ObjectPool`, it does not allocate handles, the handles are created elsewhere,
+/// e.g. the entities of scene, and values are attached to them.
+pub struct HandleMap<H: HandleLike, T> {
+    remap: FastHashMap<H, usize>,
+    handles: Vec<H>,
+    values: Vec<T>,
+}
+
+impl<H: HandleLike, T> Default for HandleMap<H, T> {
+    fn default() -> Self {
+        HandleMap {
+            remap: FastHashMap::default(),
+            handles: Vec::new(),
+            values: Vec::new(),
+        }
+    }
+}
+
+impl<H: HandleLike, T> HandleMap<H, T> {
+    /// Constructs a new, empty `HandleMap`.
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Attaches `value` to `handle`. The value that attached to `handle` before is
+    /// returned if there is one.
+    pub fn add(&mut self, handle: H, mut value: T) -> Option<T> {
+        if let Some(&index) = self.remap.get(&handle) {
+            ::std::mem::swap(&mut self.values[index], &mut value);
+            Some(value)
+        } else {
+            self.remap.insert(handle, self.values.len());
+            self.handles.push(handle);
+            self.values.push(value);
+            None
+        }
+    }
+
+    /// Removes the value that attached to `handle`.
+    pub fn remove(&mut self, handle: H) -> Option<T> {
+        let index = self.remap.remove(&handle)?;
+        self.handles.swap_remove(index);
+
+        if index != self.handles.len() {
+            *self.remap.get_mut(&self.handles[index]).unwrap() = index;
+        }
+
+        Some(self.values.swap_remove(index))
+    }
+
+    /// Checks if there is a value attached to `handle`.
+    #[inline]
+    pub fn contains(&self, handle: H) -> bool {
+        self.remap.contains_key(&handle)
+    }
+
+    #[inline]
+    pub fn get(&self, handle: H) -> Option<&T> {
+        let values = &self.values;
+        self.remap.get(&handle).map(|&index| &values[index])
+    }
+
+    #[inline]
+    pub fn get_mut(&mut self, handle: H) -> Option<&mut T> {
+        let values = &mut self.values;
+        self.remap
+            .get(&handle)
+            .map(move |&index| &mut values[index])
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// Gets the handles in the dense order, which is the same as `values`.
+    #[inline]
+    pub fn keys(&self) -> &[H] {
+        &self.handles
+    }
+
+    /// Gets the values in the dense order.
+    #[inline]
+    pub fn values(&self) -> &[T] {
+        &self.values
+    }
+
+    /// Gets the mutable values in the dense order.
+    #[inline]
+    pub fn values_mut(&mut self) -> &mut [T] {
+        &mut self.values
+    }
+
+    /// Iterates the handles and their values in the dense order.
+    #[inline]
+    pub fn iter(&self) -> impl Iterator<Item = (H, &T)> {
+        self.handles.iter().cloned().zip(self.values.iter())
+    }
+
+    /// Iterates the handles and their mutable values in the dense order.
+    #[inline]
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (H, &mut T)> {
+        self.handles.iter().cloned().zip(self.values.iter_mut())
+    }
+}
@@ -39,6 +39,14 @@ pub fn hash<T: Hash + ?Sized>(v: &T) -> usize {
     state.finish() as usize
 }
 
+/// Hashes the content of bytes with seahash. Unlike the Fx based functions above, the
+/// result is stable across platforms and releases, so it could be persisted to detect
+/// changes of assets.
+#[inline]
+pub fn content_hash(bytes: &[u8]) -> u64 {
+    seahash::hash(bytes)
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
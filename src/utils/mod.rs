@@ -2,21 +2,34 @@
 
 #[macro_use]
 pub mod handle;
+pub mod atomic_handle_pool;
 pub mod data_buf;
 pub mod double_buf;
+pub mod fingerprint;
+pub mod global;
+pub mod handle_map;
 pub mod handle_pool;
 pub mod hash;
 pub mod hash_value;
 pub mod object_pool;
 pub mod time;
+pub mod timer;
+pub mod tween;
 
 pub mod prelude {
+    pub use super::atomic_handle_pool::AtomicHandlePool;
     pub use super::data_buf::{DataBuffer, DataBufferPtr};
     pub use super::double_buf::DoubleBuf;
+    pub use super::fingerprint::FileFingerprint;
     pub use super::handle::{Handle, HandleIndex, HandleLike};
+    pub use super::handle_map::HandleMap;
     pub use super::handle_pool::HandlePool;
-    pub use super::hash::{FastHashMap, FastHashSet};
+    pub use super::hash::{content_hash, FastHashMap, FastHashSet};
     pub use super::hash_value::HashValue;
     pub use super::object_pool::ObjectPool;
     pub use super::time::Timestamp;
+    pub use super::timer::{Timer, TimerMode};
+    pub use super::tween::{Easing, Lerp, Tween};
 }
+
+pub use self::hash::content_hash;
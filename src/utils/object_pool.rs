@@ -76,11 +76,9 @@ impl<H: HandleLike, T: Sized> ObjectPool<H, T> {
     #[inline]
     pub fn free(&mut self, handle: H) -> Option<T> {
         if self.handles.free(handle) {
-            unsafe {
-                let mut v = ::std::mem::uninitialized();
-                ::std::mem::swap(&mut v, &mut self.entries[handle.index() as usize]);
-                Some(v)
-            }
+            // The slot is left as moved-out, it's overwritten without dropping by the
+            // next `create` that reuses it.
+            unsafe { Some(::std::ptr::read(&self.entries[handle.index() as usize])) }
         } else {
             None
         }
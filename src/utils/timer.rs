@@ -0,0 +1,207 @@
+//! Fires after a duration of game time.
+//!
+//! A `Timer` is advanced with the scaled frame duration, so it respects the time scale
+//! and stops while the game is paused:
+//!
+//! ```rust
+//! use std::time::Duration;
+//! use crayon::utils::timer::Timer;
+//!
+//! let mut spawn = Timer::repeating(Duration::from_millis(500));
+//! // Advances with `application::frame_duration()` every frame.
+//! spawn.tick(Duration::from_millis(300));
+//! assert!(!spawn.is_elapsed());
+//! spawn.tick(Duration::from_millis(300));
+//! assert!(spawn.is_elapsed());
+//! ```
+
+use std::time::Duration;
+
+/// Whether a timer fires once or repeatedly.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimerMode {
+    /// Fires once when the duration is reached, and stays finished until reset.
+    Once,
+    /// Fires every duration, the time beyond the duration is carried to next interval.
+    Repeating,
+}
+
+/// Counts the game time, and fires once it reaches the duration.
+#[derive(Debug, Clone, Copy)]
+pub struct Timer {
+    duration: Duration,
+    mode: TimerMode,
+    elapsed: Duration,
+    times: u32,
+    finished: bool,
+}
+
+impl Timer {
+    pub fn new(duration: Duration, mode: TimerMode) -> Self {
+        Timer {
+            duration,
+            mode,
+            elapsed: Duration::from_millis(0),
+            times: 0,
+            finished: false,
+        }
+    }
+
+    /// Creates a timer that fires once after `duration`.
+    #[inline]
+    pub fn once(duration: Duration) -> Self {
+        Timer::new(duration, TimerMode::Once)
+    }
+
+    /// Creates a timer that fires every `duration`.
+    #[inline]
+    pub fn repeating(duration: Duration) -> Self {
+        Timer::new(duration, TimerMode::Repeating)
+    }
+
+    /// Advances the timer by `dt`, e.g. the `application::frame_duration()`, and returns
+    /// the number of times it fired during this tick.
+    pub fn tick(&mut self, dt: Duration) -> u32 {
+        self.times = 0;
+        if self.finished {
+            return 0;
+        }
+
+        self.elapsed += dt;
+        if self.elapsed < self.duration {
+            return 0;
+        }
+
+        match self.mode {
+            TimerMode::Once => {
+                self.elapsed = self.duration;
+                self.finished = true;
+                self.times = 1;
+            }
+            TimerMode::Repeating => {
+                if self.duration > Duration::from_millis(0) {
+                    let duration = self.duration.as_nanos();
+                    let elapsed = self.elapsed.as_nanos();
+                    self.times = (elapsed / duration) as u32;
+                    self.elapsed = Duration::from_nanos((elapsed % duration) as u64);
+                } else {
+                    self.times = 1;
+                    self.elapsed = Duration::from_millis(0);
+                }
+            }
+        }
+
+        self.times
+    }
+
+    /// Checks if the timer fired during the last tick.
+    #[inline]
+    pub fn is_elapsed(&self) -> bool {
+        self.times > 0
+    }
+
+    /// Gets the number of times the timer fired during the last tick. It could be more
+    /// than once for repeating timers if the tick is longer than the duration.
+    #[inline]
+    pub fn times_elapsed(&self) -> u32 {
+        self.times
+    }
+
+    /// Checks if a one-shot timer has fired. Repeating timers never finish.
+    #[inline]
+    pub fn is_finished(&self) -> bool {
+        self.finished
+    }
+
+    /// Gets the time elapsed since start, or since the last time a repeating timer fired.
+    #[inline]
+    pub fn elapsed(&self) -> Duration {
+        self.elapsed
+    }
+
+    /// Gets the time remaining until the timer fires next time.
+    #[inline]
+    pub fn remaining(&self) -> Duration {
+        self.duration
+            .checked_sub(self.elapsed)
+            .unwrap_or_else(|| Duration::from_millis(0))
+    }
+
+    #[inline]
+    pub fn duration(&self) -> Duration {
+        self.duration
+    }
+
+    #[inline]
+    pub fn mode(&self) -> TimerMode {
+        self.mode
+    }
+
+    /// Rewinds the timer to start.
+    #[inline]
+    pub fn reset(&mut self) {
+        self.elapsed = Duration::from_millis(0);
+        self.times = 0;
+        self.finished = false;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn ms(v: u64) -> Duration {
+        Duration::from_millis(v)
+    }
+
+    #[test]
+    fn once() {
+        let mut timer = Timer::once(ms(100));
+        assert_eq!(timer.tick(ms(60)), 0);
+        assert!(!timer.is_elapsed());
+        assert_eq!(timer.remaining(), ms(40));
+
+        assert_eq!(timer.tick(ms(40)), 1);
+        assert!(timer.is_elapsed());
+        assert!(timer.is_finished());
+
+        assert_eq!(timer.tick(ms(100)), 0);
+        assert_eq!(timer.tick(ms(1000)), 0);
+        assert!(!timer.is_elapsed());
+        assert!(timer.is_finished());
+
+        // Paused game time does not advance the timer.
+        timer.reset();
+        assert_eq!(timer.tick(ms(0)), 0);
+        assert!(!timer.is_finished());
+        assert_eq!(timer.tick(ms(150)), 1);
+        assert_eq!(timer.elapsed(), ms(100));
+    }
+
+    #[test]
+    fn repeating() {
+        let mut timer = Timer::repeating(ms(100));
+        assert_eq!(timer.tick(ms(70)), 0);
+        assert_eq!(timer.tick(ms(70)), 1);
+        assert_eq!(timer.elapsed(), ms(40));
+
+        assert_eq!(timer.tick(ms(70)), 1);
+        assert_eq!(timer.elapsed(), ms(10));
+        assert_eq!(timer.tick(ms(50)), 0);
+
+        assert_eq!(timer.tick(ms(250)), 3);
+        assert_eq!(timer.times_elapsed(), 3);
+        assert_eq!(timer.elapsed(), ms(10));
+        assert!(!timer.is_finished());
+
+        timer.reset();
+        assert_eq!(timer.elapsed(), ms(0));
+        assert!(!timer.is_elapsed());
+
+        // Zero-duration timers fire once every tick.
+        let mut timer = Timer::repeating(ms(0));
+        assert_eq!(timer.tick(ms(16)), 1);
+        assert_eq!(timer.tick(ms(16)), 1);
+        assert_eq!(timer.remaining(), ms(0));
+    }
+}
@@ -0,0 +1,292 @@
+//! Interpolates values over time with easing functions.
+//!
+//! A `Tween` animates a value from `from` to `to` during `duration`. The progress is
+//! shaped by an `Easing`, and the value itself is blended with `Lerp`, which is
+//! implemented for floats, vectors, colors and quaternions.
+//!
+//! ```rust
+//! use std::time::Duration;
+//! use crayon::utils::tween::{Easing, Tween};
+//!
+//! let mut fade = Tween::new(0.0f32, 1.0, Duration::from_millis(500), Easing::QuadOut);
+//! let alpha = fade.advance(Duration::from_millis(250));
+//! assert_eq!(alpha, 0.75);
+//! assert!(!fade.is_finished());
+//! ```
+
+use std::f32::consts::PI;
+use std::time::Duration;
+
+use crate::math::prelude::{Color, Quaternion, Vector2, Vector3, Vector4};
+
+/// Values that could be interpolated linearly.
+pub trait Lerp: Copy {
+    /// Interpolates between `self` and `rhs` by `t`, which is 0 at `self` and 1 at `rhs`.
+    fn lerp(self, rhs: Self, t: f32) -> Self;
+}
+
+impl Lerp for f32 {
+    #[inline]
+    fn lerp(self, rhs: Self, t: f32) -> Self {
+        self + (rhs - self) * t
+    }
+}
+
+impl Lerp for f64 {
+    #[inline]
+    fn lerp(self, rhs: Self, t: f32) -> Self {
+        self + (rhs - self) * f64::from(t)
+    }
+}
+
+impl Lerp for Vector2<f32> {
+    #[inline]
+    fn lerp(self, rhs: Self, t: f32) -> Self {
+        self + (rhs - self) * t
+    }
+}
+
+impl Lerp for Vector3<f32> {
+    #[inline]
+    fn lerp(self, rhs: Self, t: f32) -> Self {
+        self + (rhs - self) * t
+    }
+}
+
+impl Lerp for Vector4<f32> {
+    #[inline]
+    fn lerp(self, rhs: Self, t: f32) -> Self {
+        self + (rhs - self) * t
+    }
+}
+
+impl Lerp for Color<f32> {
+    #[inline]
+    fn lerp(self, rhs: Self, t: f32) -> Self {
+        Color::new(
+            self.r.lerp(rhs.r, t),
+            self.g.lerp(rhs.g, t),
+            self.b.lerp(rhs.b, t),
+            self.a.lerp(rhs.a, t),
+        )
+    }
+}
+
+/// Rotations are interpolated spherically, so the angular velocity is constant.
+impl Lerp for Quaternion<f32> {
+    #[inline]
+    fn lerp(self, rhs: Self, t: f32) -> Self {
+        self.slerp(rhs, t)
+    }
+}
+
+/// Shapes of the progress of tweens.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Easing {
+    Linear,
+    QuadIn,
+    QuadOut,
+    QuadInOut,
+    CubicIn,
+    CubicOut,
+    CubicInOut,
+    /// Oscillates around the start before moving to the end.
+    ElasticIn,
+    /// Overshoots the end and oscillates around it.
+    ElasticOut,
+    BounceIn,
+    /// Bounces at the end like a dropped ball.
+    BounceOut,
+}
+
+impl Easing {
+    /// Maps the linear progress `t` to the eased one. `t` is clamped to [0, 1], and the
+    /// result is always 0 at start and 1 at end.
+    pub fn apply(self, t: f32) -> f32 {
+        let t = t.max(0.0).min(1.0);
+        match self {
+            Easing::Linear => t,
+            Easing::QuadIn => t * t,
+            Easing::QuadOut => t * (2.0 - t),
+            Easing::QuadInOut => {
+                if t < 0.5 {
+                    2.0 * t * t
+                } else {
+                    -1.0 + (4.0 - 2.0 * t) * t
+                }
+            }
+            Easing::CubicIn => t * t * t,
+            Easing::CubicOut => {
+                let t = t - 1.0;
+                t * t * t + 1.0
+            }
+            Easing::CubicInOut => {
+                if t < 0.5 {
+                    4.0 * t * t * t
+                } else {
+                    let t = 2.0 * t - 2.0;
+                    0.5 * t * t * t + 1.0
+                }
+            }
+            Easing::ElasticIn => 1.0 - elastic_out(1.0 - t),
+            Easing::ElasticOut => elastic_out(t),
+            Easing::BounceIn => 1.0 - bounce_out(1.0 - t),
+            Easing::BounceOut => bounce_out(t),
+        }
+    }
+}
+
+fn elastic_out(t: f32) -> f32 {
+    if t == 0.0 || t == 1.0 {
+        return t;
+    }
+
+    2f32.powf(-10.0 * t) * ((t - 0.075) * (2.0 * PI) / 0.3).sin() + 1.0
+}
+
+fn bounce_out(t: f32) -> f32 {
+    const N: f32 = 7.5625;
+    const D: f32 = 2.75;
+
+    if t < 1.0 / D {
+        N * t * t
+    } else if t < 2.0 / D {
+        let t = t - 1.5 / D;
+        N * t * t + 0.75
+    } else if t < 2.5 / D {
+        let t = t - 2.25 / D;
+        N * t * t + 0.9375
+    } else {
+        let t = t - 2.625 / D;
+        N * t * t + 0.984_375
+    }
+}
+
+/// Animates a value from `from` to `to` during `duration`.
+#[derive(Debug, Clone, Copy)]
+pub struct Tween<T: Lerp> {
+    from: T,
+    to: T,
+    duration: Duration,
+    easing: Easing,
+    elapsed: Duration,
+}
+
+impl<T: Lerp> Tween<T> {
+    pub fn new(from: T, to: T, duration: Duration, easing: Easing) -> Self {
+        Tween {
+            from,
+            to,
+            duration,
+            easing,
+            elapsed: Duration::from_millis(0),
+        }
+    }
+
+    /// Advances the tween by `dt`, e.g. the `application::frame_duration()`, and returns
+    /// the current value.
+    pub fn advance(&mut self, dt: Duration) -> T {
+        self.elapsed = (self.elapsed + dt).min(self.duration);
+        self.value()
+    }
+
+    /// Gets the current value.
+    #[inline]
+    pub fn value(&self) -> T {
+        self.value_at(self.elapsed)
+    }
+
+    /// Gets the value at `time` since start. The value stays at `to` once `time` goes
+    /// beyond the duration.
+    pub fn value_at(&self, time: Duration) -> T {
+        let t = if self.duration > Duration::from_millis(0) {
+            time.as_secs_f32() / self.duration.as_secs_f32()
+        } else {
+            1.0
+        };
+
+        self.from.lerp(self.to, self.easing.apply(t))
+    }
+
+    /// Checks if the tween has reached its end.
+    #[inline]
+    pub fn is_finished(&self) -> bool {
+        self.elapsed >= self.duration
+    }
+
+    /// Rewinds the tween to start.
+    #[inline]
+    pub fn reset(&mut self) {
+        self.elapsed = Duration::from_millis(0);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use cgmath::{Deg, Rotation3};
+
+    const EASINGS: [(Easing, f32); 11] = [
+        (Easing::Linear, 0.5),
+        (Easing::QuadIn, 0.25),
+        (Easing::QuadOut, 0.75),
+        (Easing::QuadInOut, 0.5),
+        (Easing::CubicIn, 0.125),
+        (Easing::CubicOut, 0.875),
+        (Easing::CubicInOut, 0.5),
+        (Easing::ElasticIn, -0.015_625),
+        (Easing::ElasticOut, 1.015_625),
+        (Easing::BounceIn, 0.234_375),
+        (Easing::BounceOut, 0.765_625),
+    ];
+
+    #[test]
+    fn easing() {
+        for &(easing, midpoint) in &EASINGS {
+            assert_eq!(easing.apply(0.0), 0.0, "{:?}", easing);
+            assert_eq!(easing.apply(1.0), 1.0, "{:?}", easing);
+            assert_eq!(easing.apply(-1.0), 0.0, "{:?}", easing);
+            assert_eq!(easing.apply(2.0), 1.0, "{:?}", easing);
+            assert!((easing.apply(0.5) - midpoint).abs() < 1e-5, "{:?}", easing);
+        }
+    }
+
+    #[test]
+    fn tween() {
+        let duration = Duration::from_millis(400);
+        let mut tween = Tween::new(10.0f32, 20.0, duration, Easing::Linear);
+        assert_eq!(tween.value(), 10.0);
+        assert_eq!(tween.advance(Duration::from_millis(100)), 12.5);
+        assert_eq!(tween.value_at(Duration::from_millis(200)), 15.0);
+        assert!(!tween.is_finished());
+
+        assert_eq!(tween.advance(Duration::from_millis(500)), 20.0);
+        assert!(tween.is_finished());
+
+        tween.reset();
+        assert_eq!(tween.value(), 10.0);
+
+        let tween = Tween::new(1.0f32, 2.0, Duration::from_millis(0), Easing::QuadIn);
+        assert_eq!(tween.value(), 2.0);
+        assert!(tween.is_finished());
+    }
+
+    #[test]
+    fn lerp() {
+        let v = Lerp::lerp(
+            Vector3::new(0.0, 2.0, 4.0),
+            Vector3::new(2.0, 2.0, 0.0),
+            0.5,
+        );
+        assert_eq!(v, Vector3::new(1.0, 2.0, 2.0));
+
+        let c = Color::black().lerp(Color::white(), 0.25);
+        assert_eq!(c, Color::new(0.25, 0.25, 0.25, 1.0));
+
+        let from = Quaternion::from_angle_z(Deg(0.0f32));
+        let to = Quaternion::from_angle_z(Deg(90.0f32));
+        let q = Lerp::lerp(from, to, 0.5);
+        let expected = Quaternion::from_angle_z(Deg(45.0f32));
+        assert!((q.s - expected.s).abs() < 1e-5 && (q.v.z - expected.v.z).abs() < 1e-5);
+    }
+}
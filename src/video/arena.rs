@@ -0,0 +1,178 @@
+//! A per-frame arena of draw calls.
+//!
+//! Draw calls built with `DrawArena` keep their uniform variables in storage owned by
+//! the arena, which is reset rather than released at the start of every frame. After
+//! the first few frames, building draw calls does not touch the heap at all.
+//!
+//! ```rust,ignore
+//! let mut arena = DrawArena::new();
+//!
+//! loop {
+//!     let mut frame = arena.frame();
+//!     let dc = frame
+//!         .draw(shader, mesh)
+//!         .with_uniform_variable("u_Color", color)
+//!         .finish();
+//!
+//!     batch.draw_arena(0, &frame, dc);
+//!     batch.submit(surface)?;
+//! }
+//! ```
+//!
+//! The `ArenaDraw` handles borrow the arena they were allocated from, so it's a compile
+//! error to keep them after the arena has been reset.
+
+use std::marker::PhantomData;
+use std::mem;
+use std::ops::Range;
+
+use crate::utils::hash_value::HashValue;
+
+use super::assets::prelude::*;
+use super::MAX_UNIFORM_VARIABLES;
+
+pub(crate) struct ArenaDrawData {
+    pub shader: ShaderHandle,
+    pub mesh: MeshHandle,
+    pub mesh_index: MeshIndex,
+    uniforms: Range<usize>,
+}
+
+/// The reusable storage of draw calls.
+#[derive(Default)]
+pub struct DrawArena {
+    draws: Vec<ArenaDrawData>,
+    uniforms: Vec<(HashValue<str>, UniformVariable)>,
+}
+
+impl DrawArena {
+    /// Creates a new and empty `DrawArena`.
+    pub fn new() -> Self {
+        DrawArena::with_capacity(32, 256)
+    }
+
+    /// Creates a new `DrawArena` with pre-allocated space for draw calls and uniform
+    /// variables.
+    pub fn with_capacity(draws: usize, uniforms: usize) -> Self {
+        DrawArena {
+            draws: Vec::with_capacity(draws),
+            uniforms: Vec::with_capacity(uniforms),
+        }
+    }
+
+    /// Resets the arena and starts a new frame. All the draw calls allocated in previous
+    /// frame are discarded, while the allocated memory is kept for reuse.
+    pub fn frame(&mut self) -> DrawArenaFrame<'_> {
+        self.draws.clear();
+        self.uniforms.clear();
+        DrawArenaFrame { arena: self }
+    }
+
+    /// Gets the size of allocated backing storage in bytes.
+    pub fn capacity(&self) -> usize {
+        self.draws.capacity() * mem::size_of::<ArenaDrawData>()
+            + self.uniforms.capacity() * mem::size_of::<(HashValue<str>, UniformVariable)>()
+    }
+}
+
+/// A handle to the draw call allocated in `DrawArenaFrame`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct ArenaDraw<'a> {
+    index: usize,
+    _frame: PhantomData<&'a ()>,
+}
+
+/// A frame of `DrawArena`, draw calls are allocated from here.
+pub struct DrawArenaFrame<'a> {
+    arena: &'a mut DrawArena,
+}
+
+impl<'a> DrawArenaFrame<'a> {
+    /// Allocates a new draw call. Uniform variables are bound with the returned builder.
+    pub fn draw(&mut self, shader: ShaderHandle, mesh: MeshHandle) -> ArenaDrawBuilder<'_, 'a> {
+        let start = self.arena.uniforms.len();
+        self.arena.draws.push(ArenaDrawData {
+            shader,
+            mesh,
+            mesh_index: MeshIndex::All,
+            uniforms: start..start,
+        });
+
+        ArenaDrawBuilder {
+            arena: self.arena,
+            _frame: PhantomData,
+        }
+    }
+
+    /// Returns the number of draw calls allocated in this frame.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.arena.draws.len()
+    }
+
+    /// Returns true if there is no draw calls allocated in this frame.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.arena.draws.is_empty()
+    }
+
+    /// Gets a draw call and its uniform variables.
+    pub(crate) fn get(
+        &self,
+        draw: ArenaDraw<'a>,
+    ) -> (&ArenaDrawData, &[(HashValue<str>, UniformVariable)]) {
+        let v = &self.arena.draws[draw.index];
+        (v, &self.arena.uniforms[v.uniforms.clone()])
+    }
+}
+
+/// The builder of the draw call that was allocated last in `DrawArenaFrame`.
+pub struct ArenaDrawBuilder<'b, 'a: 'b> {
+    arena: &'b mut DrawArena,
+    _frame: PhantomData<&'a ()>,
+}
+
+impl<'b, 'a: 'b> ArenaDrawBuilder<'b, 'a> {
+    /// Sets the mesh index of draw call.
+    pub fn with_mesh_index(self, mesh_index: MeshIndex) -> Self {
+        self.arena.draws.last_mut().unwrap().mesh_index = mesh_index;
+        self
+    }
+
+    /// Binds the named field with `UniformVariable`. Binding a field twice overrides
+    /// the previous variable.
+    pub fn with_uniform_variable<F, V>(self, field: F, variable: V) -> Self
+    where
+        F: Into<HashValue<str>>,
+        V: Into<UniformVariable>,
+    {
+        let field = field.into();
+        let variable = variable.into();
+
+        {
+            let dc = self.arena.draws.last_mut().unwrap();
+            let uniforms = &mut self.arena.uniforms;
+
+            for v in &mut uniforms[dc.uniforms.clone()] {
+                if v.0 == field {
+                    v.1 = variable;
+                    return self;
+                }
+            }
+
+            assert!(dc.uniforms.len() < MAX_UNIFORM_VARIABLES);
+            uniforms.push((field, variable));
+            dc.uniforms.end += 1;
+        }
+
+        self
+    }
+
+    /// Finishes the draw call, and returns its handle.
+    pub fn finish(self) -> ArenaDraw<'a> {
+        ArenaDraw {
+            index: self.arena.draws.len() - 1,
+            _frame: PhantomData,
+        }
+    }
+}
@@ -1,7 +1,11 @@
 //! Immutable or dynamic vertex and index data.
 
+use std::io::Write;
+
+use byteorder::{ByteOrder, NativeEndian};
+
 use crate::math::prelude::Aabb3;
-use crate::video::assets::shader::Attribute;
+use crate::video::assets::shader::{Attribute, FrontFaceOrder};
 use crate::video::errors::{Error, Result};
 use crate::video::MAX_VERTEX_ATTRIBUTES;
 use smallvec::SmallVec;
@@ -27,6 +31,16 @@ pub struct MeshParams {
     pub sub_mesh_offsets: SmallVec<[usize; 8]>,
     /// Trivial bounding box of vertices.
     pub aabb: Aabb3<f32>,
+    /// The winding of front-facing triangles in data. Triangles are reversed into
+    /// `CounterClockwise`, which is the default of `RenderState`, when the mesh is
+    /// created, e.g. meshes imported from tools with other handedness. Creating a mesh
+    /// with other windings fails if its indices are not provided completely.
+    pub front_face: FrontFaceOrder,
+    /// Keeps a copy of vertices and indices in memory, which could be read back with
+    /// `video::mesh_cpu_data`. Notes that it doubles the memory usage of mesh, and the
+    /// copy is NOT updated with `update_vertex_buffer` or `update_index_buffer`.
+    #[serde(skip)]
+    pub keep_cpu_data: bool,
 }
 
 /// Continuous data of vertices and its indices.
@@ -49,6 +63,8 @@ impl Default for MeshParams {
             num_idxes: 0,
             aabb: Aabb3::zero(),
             sub_mesh_offsets: SmallVec::new(),
+            front_face: FrontFaceOrder::CounterClockwise,
+            keep_cpu_data: false,
         }
     }
 }
@@ -71,9 +87,60 @@ impl MeshParams {
             }
         }
 
+        if self.sub_mesh_offsets.windows(2).any(|v| v[0] > v[1]) {
+            return Err(Error::OutOfBounds);
+        }
+
+        if self.num_verts > self.index_format.max_vertex_count() {
+            return Err(Error::OutOfBounds);
+        }
+
         Ok(())
     }
 
+    /// Sets the number of vertices, and picks the smallest index format that could
+    /// address all of them.
+    #[inline]
+    pub fn set_num_verts(&mut self, num_verts: usize) {
+        self.num_verts = num_verts;
+        self.index_format = IndexFormat::for_vertex_count(num_verts);
+    }
+
+    /// Gets the `(first_index, index_count)` of indices that `index` refers to. It fails
+    /// if the range is out of the bounds of mesh or its sub-mesh, or the sub-mesh offsets
+    /// are not sorted.
+    pub fn index_range(&self, index: MeshIndex) -> Result<(usize, usize)> {
+        let (from, len, to) = match index {
+            MeshIndex::Ptr(from, len) => (from, len, self.num_idxes),
+            MeshIndex::SubMesh(sub) => {
+                let (from, to) = self.sub_mesh_range(sub)?;
+                let len = to.checked_sub(from).ok_or(Error::OutOfBounds)?;
+                (from, len, to)
+            }
+            MeshIndex::SubMeshPtr(sub, offset, len) => {
+                let (from, to) = self.sub_mesh_range(sub)?;
+                let from = from.checked_add(offset).ok_or(Error::OutOfBounds)?;
+                (from, len, to)
+            }
+            MeshIndex::All => (0, self.num_idxes, self.num_idxes),
+        };
+
+        match from.checked_add(len) {
+            Some(end) if end <= to => Ok((from, len)),
+            _ => Err(Error::OutOfBounds),
+        }
+    }
+
+    fn sub_mesh_range(&self, sub: usize) -> Result<(usize, usize)> {
+        let from = *self.sub_mesh_offsets.get(sub).ok_or(Error::OutOfBounds)?;
+        let to = self
+            .sub_mesh_offsets
+            .get(sub + 1)
+            .cloned()
+            .unwrap_or(self.num_idxes);
+        Ok((from, to))
+    }
+
     #[inline]
     pub fn vertex_buffer_len(&self) -> usize {
         self.num_verts * self.layout.stride() as usize
@@ -85,11 +152,17 @@ impl MeshParams {
     }
 }
 
-/// Mesh index.
+/// The range of indices that a draw call uses.
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
 pub enum MeshIndex {
+    /// All the indices of a sub-mesh.
     SubMesh(usize),
+    /// The indices of a sub-mesh in range `(first_index, index_count)`, where the first
+    /// index is relative to the start of sub-mesh.
+    SubMeshPtr(usize, usize, usize),
+    /// The indices in range `(first_index, index_count)`.
     Ptr(usize, usize),
+    /// All the indices.
     All,
 }
 
@@ -153,6 +226,25 @@ pub enum IndexFormat {
 }
 
 impl IndexFormat {
+    /// Gets the smallest index format that could address `num_verts` vertices.
+    #[inline]
+    pub fn for_vertex_count(num_verts: usize) -> Self {
+        if num_verts <= IndexFormat::U16.max_vertex_count() {
+            IndexFormat::U16
+        } else {
+            IndexFormat::U32
+        }
+    }
+
+    /// Gets the maximum number of vertices that could be addressed with this format.
+    #[inline]
+    pub fn max_vertex_count(self) -> usize {
+        match self {
+            IndexFormat::U16 => u16::max_value() as usize + 1,
+            IndexFormat::U32 => u32::max_value() as usize + 1,
+        }
+    }
+
     pub fn stride(self) -> usize {
         match self {
             IndexFormat::U16 => 2,
@@ -160,6 +252,37 @@ impl IndexFormat {
         }
     }
 
+    /// Encodes the indices into bytes of this format. It fails if any of the indices
+    /// could not be represented.
+    pub fn pack(self, values: &[u32]) -> Result<Vec<u8>> {
+        match self {
+            IndexFormat::U16 => {
+                let mut idxes = Vec::with_capacity(values.len());
+                for &v in values {
+                    if v > u32::from(u16::max_value()) {
+                        return Err(Error::OutOfBounds);
+                    }
+
+                    idxes.push(v as u16);
+                }
+
+                Ok(IndexFormat::encode(&idxes).to_vec())
+            }
+            IndexFormat::U32 => Ok(IndexFormat::encode(values).to_vec()),
+        }
+    }
+
+    /// Decodes the indices from bytes of this format.
+    pub fn unpack(self, bytes: &[u8]) -> Vec<u32> {
+        match self {
+            IndexFormat::U16 => bytes
+                .chunks_exact(2)
+                .map(|v| u32::from(NativeEndian::read_u16(v)))
+                .collect(),
+            IndexFormat::U32 => bytes.chunks_exact(4).map(NativeEndian::read_u32).collect(),
+        }
+    }
+
     pub fn encode<T>(values: &[T]) -> &[u8]
     where
         T: Copy,
@@ -326,6 +449,160 @@ fn size_of_vertex(format: VertexFormat) -> u8 {
     }
 }
 
+/// Writes the mesh as Wavefront OBJ. Positions, normals and texcoords are written if
+/// they are presented in the vertex layout, and every sub-mesh is written as a group
+/// named `submesh_{index}`.
+///
+/// The mesh is checked before anything is written, so nothing is written if it fails.
+pub fn export_obj<W: Write>(
+    params: &MeshParams,
+    data: &MeshData,
+    mut writer: W,
+) -> crate::errors::Result<()> {
+    params.validate(None)?;
+
+    if data.vptr.len() < params.vertex_buffer_len() {
+        return Err(Error::OutOfBounds.into());
+    }
+
+    if data.iptr.len() < params.index_buffer_len() {
+        return Err(Error::OutOfBounds.into());
+    }
+
+    match params.layout.element(Attribute::Position) {
+        Some(v) if v.size > 0 => {}
+        _ => return Err(Error::AttributeUndefined(format!("{:?}", Attribute::Position)).into()),
+    }
+
+    let idxes = params
+        .index_format
+        .unpack(&data.iptr[..params.index_buffer_len()]);
+
+    if idxes.iter().any(|&v| v as usize >= params.num_verts) {
+        return Err(Error::OutOfBounds.into());
+    }
+
+    let stride = params.layout.stride() as usize;
+    let vertices = (0..params.num_verts).map(|i| &data.vptr[i * stride..(i + 1) * stride]);
+
+    let attributes = [
+        (Attribute::Position, "v", 3),
+        (Attribute::Texcoord0, "vt", 2),
+        (Attribute::Normal, "vn", 3),
+    ];
+
+    let mut presented = [false; 3];
+    for (i, &(name, tag, size)) in attributes.iter().enumerate() {
+        if let Some(element) = params.layout.element(name) {
+            if element.size == 0 {
+                continue;
+            }
+
+            let offset = params.layout.offset(name).unwrap() as usize;
+            for vertex in vertices.clone() {
+                write!(writer, "{}", tag)?;
+                for c in 0..size {
+                    let v = if c < element.size {
+                        read_component(&vertex[offset..], element, c)
+                    } else {
+                        0.0
+                    };
+
+                    write!(writer, " {}", v)?;
+                }
+                writeln!(writer)?;
+            }
+
+            presented[i] = true;
+        }
+    }
+
+    let vertex = |v: u32| {
+        let v = v + 1;
+        match (presented[1], presented[2]) {
+            (false, false) => format!("{}", v),
+            (true, false) => format!("{}/{}", v, v),
+            (false, true) => format!("{}//{}", v, v),
+            (true, true) => format!("{}/{}/{}", v, v, v),
+        }
+    };
+
+    let mut offsets = params.sub_mesh_offsets.to_vec();
+    if offsets.is_empty() {
+        offsets.push(0);
+    }
+
+    for (i, &from) in offsets.iter().enumerate() {
+        let to = offsets.get(i + 1).cloned().unwrap_or(params.num_idxes);
+        let idxes = &idxes[from..to];
+
+        if !params.sub_mesh_offsets.is_empty() {
+            writeln!(writer, "g submesh_{}", i)?;
+        }
+
+        match params.primitive {
+            MeshPrimitive::Points => {
+                for &v in idxes {
+                    writeln!(writer, "p {}", vertex(v))?;
+                }
+            }
+            MeshPrimitive::Lines => {
+                for v in idxes.chunks_exact(2) {
+                    writeln!(writer, "l {} {}", vertex(v[0]), vertex(v[1]))?;
+                }
+            }
+            MeshPrimitive::LineStrip => {
+                for v in idxes.windows(2) {
+                    writeln!(writer, "l {} {}", vertex(v[0]), vertex(v[1]))?;
+                }
+            }
+            MeshPrimitive::Triangles => {
+                for v in idxes.chunks_exact(3) {
+                    let (a, b, c) = (vertex(v[0]), vertex(v[1]), vertex(v[2]));
+                    writeln!(writer, "f {} {} {}", a, b, c)?;
+                }
+            }
+            MeshPrimitive::TriangleStrip => {
+                // Every other triangle of strip is flipped to keep the winding order.
+                for (j, v) in idxes.windows(3).enumerate() {
+                    let (a, b, c) = if j % 2 == 0 {
+                        (vertex(v[0]), vertex(v[1]), vertex(v[2]))
+                    } else {
+                        (vertex(v[1]), vertex(v[0]), vertex(v[2]))
+                    };
+
+                    writeln!(writer, "f {} {} {}", a, b, c)?;
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn read_component(bytes: &[u8], element: VertexAttribute, index: u8) -> f32 {
+    let index = index as usize;
+    let (v, max) = match element.format {
+        VertexFormat::Byte => (f32::from(bytes[index] as i8), f32::from(i8::max_value())),
+        VertexFormat::UByte => (f32::from(bytes[index]), f32::from(u8::max_value())),
+        VertexFormat::Short => (
+            f32::from(NativeEndian::read_i16(&bytes[index * 2..])),
+            f32::from(i16::max_value()),
+        ),
+        VertexFormat::UShort => (
+            f32::from(NativeEndian::read_u16(&bytes[index * 2..])),
+            f32::from(u16::max_value()),
+        ),
+        VertexFormat::Float => (NativeEndian::read_f32(&bytes[index * 4..]), 1.0),
+    };
+
+    if element.normalized && element.format != VertexFormat::Float {
+        (v / max).max(-1.0)
+    } else {
+        v
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -368,6 +645,273 @@ mod test {
         assert_eq!(element.normalized, true);
         assert_eq!(layout.element(Attribute::Normal), None);
     }
+
+    #[test]
+    fn index_format() {
+        assert_eq!(IndexFormat::for_vertex_count(0), IndexFormat::U16);
+        assert_eq!(IndexFormat::for_vertex_count(65535), IndexFormat::U16);
+        assert_eq!(IndexFormat::for_vertex_count(65536), IndexFormat::U16);
+        assert_eq!(IndexFormat::for_vertex_count(65537), IndexFormat::U32);
+        assert_eq!(IndexFormat::for_vertex_count(1 << 20), IndexFormat::U32);
+
+        assert_eq!(IndexFormat::U16.pack(&[0, 65534, 65535]).unwrap().len(), 6);
+        assert!(IndexFormat::U16.pack(&[0, 65536]).is_err());
+        assert_eq!(
+            IndexFormat::U32.pack(&[0, 65536, 1 << 20]).unwrap().len(),
+            12
+        );
+
+        let mut params = MeshParams::default();
+        params.set_num_verts(65536);
+        assert_eq!(params.index_format, IndexFormat::U16);
+        assert!(params.validate(None).is_ok());
+
+        params.set_num_verts(65537);
+        assert_eq!(params.index_format, IndexFormat::U32);
+        assert!(params.validate(None).is_ok());
+
+        params.index_format = IndexFormat::U16;
+        assert!(params.validate(None).is_err());
+    }
+
+    #[test]
+    fn index_range() {
+        let mut params = MeshParams {
+            num_idxes: 12,
+            ..MeshParams::default()
+        };
+
+        params.sub_mesh_offsets.push(0);
+        params.sub_mesh_offsets.push(6);
+
+        assert_eq!(params.index_range(MeshIndex::All).unwrap(), (0, 12));
+        assert_eq!(params.index_range(MeshIndex::Ptr(0, 6)).unwrap(), (0, 6));
+        assert_eq!(params.index_range(MeshIndex::Ptr(6, 6)).unwrap(), (6, 6));
+        assert!(params.index_range(MeshIndex::Ptr(6, 7)).is_err());
+
+        assert_eq!(params.index_range(MeshIndex::SubMesh(0)).unwrap(), (0, 6));
+        assert_eq!(params.index_range(MeshIndex::SubMesh(1)).unwrap(), (6, 6));
+        assert!(params.index_range(MeshIndex::SubMesh(2)).is_err());
+
+        let index = MeshIndex::SubMeshPtr(1, 3, 3);
+        assert_eq!(params.index_range(index).unwrap(), (9, 3));
+        assert!(params.index_range(MeshIndex::SubMeshPtr(0, 3, 6)).is_err());
+        assert!(params.index_range(MeshIndex::SubMeshPtr(2, 0, 0)).is_err());
+
+        let index = MeshIndex::SubMeshPtr(1, usize::max_value(), 1);
+        assert!(params.index_range(index).is_err());
+        let index = MeshIndex::Ptr(1, usize::max_value());
+        assert!(params.index_range(index).is_err());
+
+        assert!(params.validate(None).is_ok());
+        params.sub_mesh_offsets[0] = 9;
+        assert!(params.validate(None).is_err());
+        assert!(params.index_range(MeshIndex::SubMesh(0)).is_err());
+    }
+
+    #[test]
+    fn export_obj() {
+        use super::super::mesh_loader::{MeshLoader, MAGIC};
+        use crate::res::utils::prelude::ResourceLoader;
+        use crate::utils::double_buf::DoubleBuf;
+        use std::sync::Arc;
+
+        let layout = VertexLayout::build()
+            .with(Attribute::Position, VertexFormat::Float, 3, false)
+            .with(Attribute::Normal, VertexFormat::Byte, 3, true)
+            .with(Attribute::Texcoord0, VertexFormat::Float, 2, false)
+            .finish();
+
+        // A quad and a triangle, which are saved as two sub-meshes.
+        let mut vptr = Vec::new();
+        for i in 0..7u8 {
+            let position = [f32::from(i), f32::from(i % 2), 0.0];
+            for v in &position {
+                vptr.extend_from_slice(&v.to_bits().to_ne_bytes());
+            }
+
+            vptr.extend_from_slice(&[0, 0, 127]);
+
+            for v in &[0.0f32, 1.0] {
+                vptr.extend_from_slice(&v.to_bits().to_ne_bytes());
+            }
+        }
+
+        let idxes = [0, 1, 2, 0, 2, 3, 4, 5, 6];
+        let mut params = MeshParams {
+            layout,
+            num_verts: 7,
+            num_idxes: idxes.len(),
+            ..MeshParams::default()
+        };
+
+        params.sub_mesh_offsets.push(0);
+        params.sub_mesh_offsets.push(6);
+
+        let count = |obj: &str, tag: &str| {
+            obj.lines()
+                .filter(|v| v.split_whitespace().next() == Some(tag))
+                .count()
+        };
+
+        for &format in &[IndexFormat::U16, IndexFormat::U32] {
+            params.index_format = format;
+            let data = MeshData {
+                vptr: vptr.clone().into_boxed_slice(),
+                iptr: format.pack(&idxes).unwrap().into_boxed_slice(),
+            };
+
+            let mut bytes = MAGIC.to_vec();
+            bincode::serialize_into(&mut bytes, &params).unwrap();
+            bincode::serialize_into(&mut bytes, &data).unwrap();
+
+            let mut obj = Vec::new();
+            super::export_obj(&params, &data, &mut obj).unwrap();
+            let obj = String::from_utf8(obj).unwrap();
+
+            // The loader might narrow the indices, which should not change the output.
+            let loader = MeshLoader::new(Arc::new(DoubleBuf::default()));
+            let (params, data) = loader.load(MeshHandle::default(), &bytes).unwrap();
+
+            let mut reloaded = Vec::new();
+            super::export_obj(&params, &data.unwrap(), &mut reloaded).unwrap();
+            assert_eq!(obj.as_bytes(), &reloaded[..]);
+
+            assert_eq!(count(&obj, "v"), params.num_verts);
+            assert_eq!(count(&obj, "vn"), params.num_verts);
+            assert_eq!(count(&obj, "vt"), params.num_verts);
+            assert_eq!(count(&obj, "f"), params.num_idxes / 3);
+            assert_eq!(count(&obj, "g"), 2);
+            assert!(obj.contains("vn 0 0 1\n"));
+            assert!(obj.contains("g submesh_1\nf 5/5/5 6/6/6 7/7/7\n"));
+        }
+
+        params.index_format = IndexFormat::U16;
+        let data = MeshData {
+            vptr: vptr.into_boxed_slice(),
+            iptr: IndexFormat::U16
+                .pack(&[0, 1, 7])
+                .unwrap()
+                .into_boxed_slice(),
+        };
+
+        params.num_idxes = 3;
+        params.sub_mesh_offsets.clear();
+        let mut obj = Vec::new();
+        assert!(super::export_obj(&params, &data, &mut obj).is_err());
+        assert!(obj.is_empty());
+
+        // The sub-meshes should be in the bounds of indices, and in ascending order.
+        let data = MeshData {
+            vptr: data.vptr,
+            iptr: IndexFormat::U16
+                .pack(&[0, 1, 2])
+                .unwrap()
+                .into_boxed_slice(),
+        };
+
+        assert!(super::export_obj(&params, &data, Vec::new()).is_ok());
+
+        params.sub_mesh_offsets = [0, 3][..].into();
+        assert!(super::export_obj(&params, &data, Vec::new()).is_err());
+
+        params.sub_mesh_offsets = [2, 1][..].into();
+        assert!(super::export_obj(&params, &data, Vec::new()).is_err());
+
+        // Nothing is written without positions.
+        params.sub_mesh_offsets.clear();
+        params.layout = VertexLayout::build()
+            .with(Attribute::Normal, VertexFormat::Byte, 3, true)
+            .finish();
+        let mut obj = Vec::new();
+        assert!(super::export_obj(&params, &data, &mut obj).is_err());
+        assert!(obj.is_empty());
+    }
+
+    #[test]
+    fn front_face() {
+        use super::super::mesh_loader::{MeshLoader, MAGIC};
+        use crate::res::utils::prelude::ResourceLoader;
+        use crate::utils::double_buf::DoubleBuf;
+        use std::sync::Arc;
+
+        let mut params = MeshParams {
+            layout: VertexLayout::build()
+                .with(Attribute::Position, VertexFormat::Float, 3, false)
+                .finish(),
+            num_verts: 4,
+            num_idxes: 6,
+            front_face: FrontFaceOrder::Clockwise,
+            ..MeshParams::default()
+        };
+
+        let idxes = [0, 1, 2, 0, 2, 3];
+        let loader = MeshLoader::new(Arc::new(DoubleBuf::default()));
+        for &format in &[IndexFormat::U16, IndexFormat::U32] {
+            params.index_format = format;
+            let data = MeshData {
+                vptr: vec![0; 48].into_boxed_slice(),
+                iptr: format.pack(&idxes).unwrap().into_boxed_slice(),
+            };
+
+            let mut bytes = MAGIC.to_vec();
+            bincode::serialize_into(&mut bytes, &params).unwrap();
+            bincode::serialize_into(&mut bytes, &data).unwrap();
+
+            let mut item = loader.load(MeshHandle::default(), &bytes).unwrap();
+            item.0.keep_cpu_data = true;
+
+            let (params, data) = loader.create(MeshHandle::default(), item).unwrap();
+            let data = data.unwrap();
+            assert_eq!(params.front_face, FrontFaceOrder::CounterClockwise);
+            assert_eq!(params.index_format.unpack(&data.iptr), [0, 2, 1, 0, 3, 2]);
+        }
+
+        // Keeps the winding as it is by default.
+        params.front_face = FrontFaceOrder::CounterClockwise;
+        params.keep_cpu_data = true;
+        let data = MeshData {
+            vptr: vec![0; 48].into_boxed_slice(),
+            iptr: params.index_format.pack(&idxes).unwrap().into_boxed_slice(),
+        };
+
+        let item = (params.clone(), Some(data));
+        let (params, data) = loader.create(MeshHandle::default(), item).unwrap();
+        assert_eq!(params.index_format.unpack(&data.unwrap().iptr), idxes);
+
+        // Partial or missing indices could not be reversed.
+        let params = MeshParams {
+            front_face: FrontFaceOrder::Clockwise,
+            ..params
+        };
+        let data = MeshData {
+            vptr: vec![0; 48].into_boxed_slice(),
+            iptr: params
+                .index_format
+                .pack(&idxes[..3])
+                .unwrap()
+                .into_boxed_slice(),
+        };
+
+        let item = (params.clone(), Some(data));
+        assert!(loader.create(MeshHandle::default(), item).is_err());
+
+        let item = (params.clone(), None);
+        assert!(loader.create(MeshHandle::default(), item).is_err());
+
+        let params = MeshParams {
+            primitive: MeshPrimitive::TriangleStrip,
+            front_face: FrontFaceOrder::Clockwise,
+            ..params
+        };
+        let data = MeshData {
+            vptr: vec![0; 48].into_boxed_slice(),
+            iptr: params.index_format.pack(&idxes).unwrap().into_boxed_slice(),
+        };
+
+        let item = (params, Some(data));
+        assert!(loader.create(MeshHandle::default(), item).is_err());
+    }
 }
 
 #[macro_use]
@@ -477,13 +1021,14 @@ pub mod macros {
         )
     }
 
+    // Measures the offset on a default value, since dereferencing a null pointer to do
+    // that is undefined behavior.
     #[doc(hidden)]
     #[macro_export]
     macro_rules! offset_of {
         ($ty:ty, $field:ident) => {{
-            use std;
-            let ptr: *const $ty = std::ptr::null();
-            unsafe { &(*ptr).$field as *const _ as usize }
+            let v: $ty = Default::default();
+            &v.$field as *const _ as usize - &v as *const $ty as usize
         }};
     }
 
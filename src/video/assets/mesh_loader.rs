@@ -1,15 +1,35 @@
 use bincode;
+use byteorder::{ByteOrder, NativeEndian};
 use std::io::Cursor;
 use std::sync::Arc;
 
+use smallvec::SmallVec;
+
 use crate::errors::*;
+use crate::math::prelude::Aabb3;
 use crate::res::utils::prelude::ResourceLoader;
 use crate::utils::double_buf::DoubleBuf;
 
 use super::super::backends::frame::{Command, Frame};
 use super::mesh::*;
+use super::shader::FrontFaceOrder;
+
+pub const MAGIC: [u8; 8] = [b'V', b'M', b'S', b'H', b' ', 0, 0, 2];
 
-pub const MAGIC: [u8; 8] = [b'V', b'M', b'S', b'H', b' ', 0, 0, 1];
+// The meshes built before winding was introduced, which are still accepted.
+const MAGIC_V1: [u8; 8] = [b'V', b'M', b'S', b'H', b' ', 0, 0, 1];
+
+#[derive(Deserialize)]
+struct MeshParamsV1 {
+    hint: MeshHint,
+    layout: VertexLayout,
+    index_format: IndexFormat,
+    primitive: MeshPrimitive,
+    num_verts: usize,
+    num_idxes: usize,
+    sub_mesh_offsets: SmallVec<[usize; 8]>,
+    aabb: Aabb3<f32>,
+}
 
 #[derive(Clone)]
 pub struct MeshLoader {
@@ -25,16 +45,47 @@ impl MeshLoader {
 impl ResourceLoader for MeshLoader {
     type Handle = MeshHandle;
     type Intermediate = (MeshParams, Option<MeshData>);
-    type Resource = MeshParams;
+    type Resource = (MeshParams, Option<Arc<MeshData>>);
 
     fn load(&self, handle: Self::Handle, bytes: &[u8]) -> Result<Self::Intermediate> {
-        if bytes[0..8] != MAGIC[..] {
+        let mut file = Cursor::new(&bytes[8..]);
+        let mut params: MeshParams = if bytes[0..8] == MAGIC[..] {
+            bincode::deserialize_from(&mut file)?
+        } else if bytes[0..8] == MAGIC_V1[..] {
+            let v: MeshParamsV1 = bincode::deserialize_from(&mut file)?;
+            MeshParams {
+                hint: v.hint,
+                layout: v.layout,
+                index_format: v.index_format,
+                primitive: v.primitive,
+                num_verts: v.num_verts,
+                num_idxes: v.num_idxes,
+                sub_mesh_offsets: v.sub_mesh_offsets,
+                aabb: v.aabb,
+                ..MeshParams::default()
+            }
+        } else {
             bail!("[MeshLoader] MAGIC number not match.");
-        }
+        };
 
-        let mut file = Cursor::new(&bytes[8..]);
-        let params: MeshParams = bincode::deserialize_from(&mut file)?;
-        let data = bincode::deserialize_from(&mut file)?;
+        let mut data: MeshData = bincode::deserialize_from(&mut file)?;
+
+        // Narrows the indices if all the vertices could be addressed with 16-bit.
+        let format = IndexFormat::for_vertex_count(params.num_verts);
+        if format != params.index_format {
+            if format == IndexFormat::U16 {
+                let mut idxes = vec![0; data.iptr.len() / 4];
+                NativeEndian::read_u32_into(&data.iptr[..idxes.len() * 4], &mut idxes);
+                data.iptr = format.pack(&idxes)?.into_boxed_slice();
+                params.index_format = format;
+            } else {
+                bail!(
+                    "[MeshLoader] {:?} could not address {} vertices.",
+                    params.index_format,
+                    params.num_verts
+                );
+            }
+        }
 
         info!(
             "[MeshLoader] load {:?}. (Verts: {}, Indxes: {})",
@@ -44,12 +95,34 @@ impl ResourceLoader for MeshLoader {
         Ok((params, Some(data)))
     }
 
-    fn create(&self, handle: Self::Handle, item: Self::Intermediate) -> Result<Self::Resource> {
+    fn create(&self, handle: Self::Handle, mut item: Self::Intermediate) -> Result<Self::Resource> {
         info!("[MeshLoader] create {:?}.", handle);
         item.0.validate(item.1.as_ref())?;
+
+        // Only the complete indices could be reversed, the indices that are uploaded
+        // later with `update_index_buffer` would have the original winding otherwise.
+        if item.0.front_face != FrontFaceOrder::CounterClockwise {
+            match item.1 {
+                Some(ref mut data) if data.iptr.len() == item.0.index_buffer_len() => {
+                    reverse_winding(&item.0, data)?;
+                    item.0.front_face = FrontFaceOrder::CounterClockwise;
+                }
+                _ => bail!(
+                    "[MeshLoader] Could not reverse the winding of {:?} without all of its indices.",
+                    handle
+                ),
+            }
+        }
+
+        let data = if item.0.keep_cpu_data {
+            item.1.clone().map(Arc::new)
+        } else {
+            None
+        };
+
         let cmd = Command::CreateMesh(Box::new((handle, item.0.clone(), item.1)));
         self.frames.write().cmds.push(cmd);
-        Ok(item.0)
+        Ok((item.0, data))
     }
 
     fn delete(&self, handle: Self::Handle, _: Self::Resource) {
@@ -58,3 +131,23 @@ impl ResourceLoader for MeshLoader {
         self.frames.write().cmds.push(cmd);
     }
 }
+
+// Swaps the last two indices of every triangle.
+fn reverse_winding(params: &MeshParams, data: &mut MeshData) -> Result<()> {
+    match params.primitive {
+        MeshPrimitive::Triangles => {
+            let size = params.index_format.stride();
+            for tri in data.iptr.chunks_exact_mut(size * 3) {
+                let (lhs, rhs) = tri[size..].split_at_mut(size);
+                lhs.swap_with_slice(rhs);
+            }
+
+            Ok(())
+        }
+        MeshPrimitive::TriangleStrip => bail!(
+            "[MeshLoader] Could not reverse the winding of {:?}.",
+            params.primitive
+        ),
+        _ => Ok(()),
+    }
+}
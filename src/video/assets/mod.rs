@@ -1,3 +1,4 @@
+pub mod query;
 pub mod shader;
 pub mod surface;
 pub mod texture;
@@ -7,12 +8,17 @@ pub mod mesh;
 pub mod mesh_loader;
 
 pub mod prelude {
-    pub use super::surface::{SurfaceHandle, SurfaceParams, SurfaceScissor, SurfaceViewport};
+    pub use super::query::{QueryHandle, QueryKind};
+
+    pub use super::surface::{
+        SurfaceClear, SurfaceHandle, SurfaceParams, SurfaceScissor, SurfaceViewport,
+    };
 
     pub use super::shader::{
-        Attribute, AttributeLayout, AttributeLayoutBuilder, BlendFactor, BlendValue, Comparison,
-        CullFace, Equation, FrontFaceOrder, RenderState, ShaderHandle, ShaderParams,
-        UniformVariable, UniformVariableLayout, UniformVariableLayoutBuilder, UniformVariableType,
+        Attribute, AttributeLayout, AttributeLayoutBuilder, BlendFactor, BlendMode, BlendValue,
+        Comparison, CullFace, Equation, FrontFaceOrder, RenderState, RenderStateDelta,
+        ShaderHandle, ShaderParams, ShaderStage, StencilOp, StencilState, UniformVariable,
+        UniformVariableLayout, UniformVariableLayoutBuilder, UniformVariableType,
     };
 
     pub use super::texture::{
@@ -0,0 +1,13 @@
+//! Queries that measure the draw calls submitted between `begin_query` and `end_query`
+//! on GPU, e.g. for profiling and occlusion culling.
+
+impl_handle!(QueryHandle);
+
+/// What a query measures.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum QueryKind {
+    /// The time in nanoseconds that GPU spent on the draw calls.
+    TimeElapsed,
+    /// The number of samples that passed the depth test.
+    SamplesPassed,
+}
@@ -8,7 +8,7 @@ use crate::utils::prelude::{FastHashMap, HashValue};
 use crate::video::assets::mesh::VertexLayout;
 use crate::video::assets::texture::{RenderTextureHandle, TextureHandle};
 use crate::video::errors::{Error, Result};
-use crate::video::{MAX_UNIFORM_VARIABLES, MAX_VERTEX_ATTRIBUTES};
+use crate::video::{MAX_UNIFORM_TEXTURE_SLOTS, MAX_UNIFORM_VARIABLES, MAX_VERTEX_ATTRIBUTES};
 
 impl_handle!(ShaderHandle);
 
@@ -19,6 +19,10 @@ pub struct ShaderParams {
     pub attributes: AttributeLayout,
     pub uniforms: UniformVariableLayout,
     pub state: RenderState,
+    /// Falls back to a built-in error shader, which draws everything in magenta, if the
+    /// sources fail to compile. The compile log is reported as a warning instead of
+    /// aborting the frame.
+    pub fallback: bool,
 }
 
 impl ShaderParams {
@@ -46,6 +50,13 @@ impl ShaderParams {
     }
 }
 
+/// The programmable stages of a shader.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum ShaderStage {
+    Vertex,
+    Fragment,
+}
+
 /// The possible pre-defined and named attributes in the vertex component, describing
 /// what the vertex component is used for.
 #[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, Copy)]
@@ -219,7 +230,7 @@ pub enum CullFace {
 }
 
 /// Define front- and back-facing polygons.
-#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, Copy)]
 pub enum FrontFaceOrder {
     Clockwise,
     CounterClockwise,
@@ -271,6 +282,119 @@ pub enum BlendFactor {
     OneMinusValue(BlendValue),
 }
 
+/// Presets of the commonly used color blending.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum BlendMode {
+    /// Replaces the destination with source, blending is disabled.
+    Opaque,
+    /// Blends source over destination with the alpha of source.
+    Alpha,
+    /// Blends source that has been multiplied by its alpha over destination.
+    PremultipliedAlpha,
+    /// Adds source weighted by its alpha to destination.
+    Additive,
+    /// Multiplies destination by source.
+    Multiply,
+}
+
+impl BlendMode {
+    /// Gets the equation and factors of source and destination, or `None` if blending
+    /// is disabled.
+    pub fn color_blend(self) -> Option<(Equation, BlendFactor, BlendFactor)> {
+        use self::BlendFactor::*;
+        use self::BlendValue::*;
+
+        match self {
+            BlendMode::Opaque => None,
+            BlendMode::Alpha => Some((
+                Equation::Add,
+                Value(SourceAlpha),
+                OneMinusValue(SourceAlpha),
+            )),
+            BlendMode::PremultipliedAlpha => Some((Equation::Add, One, OneMinusValue(SourceAlpha))),
+            BlendMode::Additive => Some((Equation::Add, Value(SourceAlpha), One)),
+            BlendMode::Multiply => Some((Equation::Add, Value(DestinationColor), Zero)),
+        }
+    }
+}
+
+/// Specifies the action to take on the stored stencil value.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum StencilOp {
+    /// Keeps the current value.
+    Keep,
+    /// Sets the value to 0.
+    Zero,
+    /// Sets the value to the reference value.
+    Replace,
+    /// Increments the value, and clamps to the maximum representable value.
+    Increment,
+    /// Increments the value, and wraps to zero when overflowed.
+    IncrementWrap,
+    /// Decrements the value, and clamps to 0.
+    Decrement,
+    /// Decrements the value, and wraps to the maximum representable value when underflowed.
+    DecrementWrap,
+    /// Bitwise inverts the value.
+    Invert,
+}
+
+/// The stencil test and the actions applied to stencil buffer.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct StencilState {
+    /// The comparison between the reference value and the stored value.
+    pub comparison: Comparison,
+    pub reference: u8,
+    /// The mask that is ANDed with both the reference value and the stored value
+    /// before comparing.
+    pub read_mask: u8,
+    /// The mask of bits that could be written into stencil buffer.
+    pub write_mask: u8,
+    /// The action when stencil test fails.
+    pub fail: StencilOp,
+    /// The action when stencil test passes, but depth test fails.
+    pub depth_fail: StencilOp,
+    /// The action when both stencil test and depth test pass.
+    pub pass: StencilOp,
+}
+
+impl Default for StencilState {
+    fn default() -> Self {
+        StencilState {
+            comparison: Comparison::Always,
+            reference: 0,
+            read_mask: 0xFF,
+            write_mask: 0xFF,
+            fail: StencilOp::Keep,
+            depth_fail: StencilOp::Keep,
+            pass: StencilOp::Keep,
+        }
+    }
+}
+
+impl StencilState {
+    /// Writes `reference` into stencil buffer wherever the fragments pass, e.g. to mark
+    /// the area of portals.
+    pub fn write(reference: u8) -> Self {
+        StencilState {
+            reference,
+            pass: StencilOp::Replace,
+            ..Default::default()
+        }
+    }
+
+    /// Discards the fragments unless the stored value compares with `reference`
+    /// successfully. The stencil buffer is left untouched.
+    pub fn test(comparison: Comparison, reference: u8) -> Self {
+        StencilState {
+            comparison,
+            reference,
+            write_mask: 0,
+            ..Default::default()
+        }
+    }
+}
+
 /// A struct that encapsulate all the necessary render states.
 #[derive(Debug, PartialEq, Clone, Copy)]
 pub struct RenderState {
@@ -281,6 +405,8 @@ pub struct RenderState {
     pub depth_write_offset: Option<(f32, f32)>,
     pub color_blend: Option<(Equation, BlendFactor, BlendFactor)>,
     pub color_write: (bool, bool, bool, bool),
+    /// The stencil test is disabled if it's `None`.
+    pub stencil: Option<StencilState>,
 }
 
 impl Default for RenderState {
@@ -293,17 +419,67 @@ impl Default for RenderState {
             depth_write_offset: None,
             color_blend: None,
             color_write: (true, true, true, true),
+            stencil: None,
+        }
+    }
+}
+
+impl RenderState {
+    /// Sets `color_blend` with the preset `BlendMode`.
+    #[inline]
+    pub fn set_blend_mode(&mut self, mode: BlendMode) {
+        self.color_blend = mode.color_blend();
+    }
+
+    /// Gets the sub-states that should be changed to switch from `self` to `rhs`.
+    pub fn diff(&self, rhs: &RenderState) -> RenderStateDelta {
+        RenderStateDelta {
+            cull: self.cull_face != rhs.cull_face || self.front_face_order != rhs.front_face_order,
+            depth: self.depth_test != rhs.depth_test
+                || self.depth_write != rhs.depth_write
+                || self.depth_write_offset != rhs.depth_write_offset,
+            blend: self.color_blend != rhs.color_blend,
+            color_write: self.color_write != rhs.color_write,
+            stencil: self.stencil != rhs.stencil,
         }
     }
 }
 
+/// The sub-states of `RenderState` that are different, which is the result of
+/// `RenderState::diff`.
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy)]
+pub struct RenderStateDelta {
+    /// `cull_face` or `front_face_order` has changed.
+    pub cull: bool,
+    /// `depth_test`, `depth_write` or `depth_write_offset` has changed.
+    pub depth: bool,
+    /// `color_blend` has changed.
+    pub blend: bool,
+    /// `color_write` has changed.
+    pub color_write: bool,
+    /// `stencil` has changed.
+    pub stencil: bool,
+}
+
+impl RenderStateDelta {
+    /// Returns true if nothing has changed.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        *self == RenderStateDelta::default()
+    }
+}
+
 /// Uniform variable type.
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub enum UniformVariableType {
     Texture,
+    TextureArray,
     RenderTexture,
     I32,
     F32,
+    Vector2i,
+    Vector3i,
+    Vector4i,
     Vector2f,
     Vector3f,
     Vector4f,
@@ -314,12 +490,19 @@ pub enum UniformVariableType {
 
 /// Uniform variable for video program object. Each matrix based `UniformVariable`
 /// is assumed to be supplied in row major order with a optional transpose.
+///
+/// A `TextureArray` binds the first `n` textures to a sampler array like
+/// `uniform sampler2D u_Textures[4]`, each of them occupies a texture slot.
 #[derive(Debug, Copy, Clone)]
 pub enum UniformVariable {
     Texture(TextureHandle),
+    TextureArray([TextureHandle; MAX_UNIFORM_TEXTURE_SLOTS], u8),
     RenderTexture(RenderTextureHandle),
     I32(i32),
     F32(f32),
+    Vector2i([i32; 2]),
+    Vector3i([i32; 3]),
+    Vector4i([i32; 4]),
     Vector2f([f32; 2]),
     Vector3f([f32; 3]),
     Vector4f([f32; 4]),
@@ -333,8 +516,12 @@ impl UniformVariable {
         match *self {
             UniformVariable::RenderTexture(_) => UniformVariableType::RenderTexture,
             UniformVariable::Texture(_) => UniformVariableType::Texture,
+            UniformVariable::TextureArray(_, _) => UniformVariableType::TextureArray,
             UniformVariable::I32(_) => UniformVariableType::I32,
             UniformVariable::F32(_) => UniformVariableType::F32,
+            UniformVariable::Vector2i(_) => UniformVariableType::Vector2i,
+            UniformVariable::Vector3i(_) => UniformVariableType::Vector3i,
+            UniformVariable::Vector4i(_) => UniformVariableType::Vector4i,
             UniformVariable::Vector2f(_) => UniformVariableType::Vector2f,
             UniformVariable::Vector3f(_) => UniformVariableType::Vector3f,
             UniformVariable::Vector4f(_) => UniformVariableType::Vector4f,
@@ -343,6 +530,40 @@ impl UniformVariable {
             UniformVariable::Matrix4f(_, _) => UniformVariableType::Matrix4f,
         }
     }
+
+    /// Checks if the variable could be consumed by video program object safely, which
+    /// means all the floats are finite and the texture array fits in texture slots.
+    pub fn validate(&self) -> Result<()> {
+        fn finite<'a, T: IntoIterator<Item = &'a f32>>(v: T) -> bool {
+            v.into_iter().all(|v| v.is_finite())
+        }
+
+        let valid = match *self {
+            UniformVariable::TextureArray(_, len) => {
+                if len as usize > MAX_UNIFORM_TEXTURE_SLOTS {
+                    let err = format!("texture array is too long, found: {}", len);
+                    return Err(Error::UniformVariableInvalid(self.variable_type(), err));
+                }
+
+                true
+            }
+            UniformVariable::F32(v) => v.is_finite(),
+            UniformVariable::Vector2f(ref v) => finite(v),
+            UniformVariable::Vector3f(ref v) => finite(v),
+            UniformVariable::Vector4f(ref v) => finite(v),
+            UniformVariable::Matrix2f(ref v, _) => finite(v.iter().flatten()),
+            UniformVariable::Matrix3f(ref v, _) => finite(v.iter().flatten()),
+            UniformVariable::Matrix4f(ref v, _) => finite(v.iter().flatten()),
+            _ => true,
+        };
+
+        if !valid {
+            let err = "found non-finite components".to_owned();
+            return Err(Error::UniformVariableInvalid(self.variable_type(), err));
+        }
+
+        Ok(())
+    }
 }
 
 impl Into<UniformVariable> for TextureHandle {
@@ -351,6 +572,20 @@ impl Into<UniformVariable> for TextureHandle {
     }
 }
 
+impl Into<UniformVariable> for &[TextureHandle] {
+    fn into(self) -> UniformVariable {
+        assert!(
+            self.len() <= MAX_UNIFORM_TEXTURE_SLOTS,
+            "Texture array is too long, found: {}",
+            self.len()
+        );
+
+        let mut textures = [TextureHandle::default(); MAX_UNIFORM_TEXTURE_SLOTS];
+        textures[..self.len()].copy_from_slice(self);
+        UniformVariable::TextureArray(textures, self.len() as u8)
+    }
+}
+
 impl Into<UniformVariable> for RenderTextureHandle {
     fn into(self) -> UniformVariable {
         UniformVariable::RenderTexture(self)
@@ -405,6 +640,42 @@ impl Into<UniformVariable> for [[f32; 4]; 4] {
     }
 }
 
+impl Into<UniformVariable> for Vector2<i32> {
+    fn into(self) -> UniformVariable {
+        UniformVariable::Vector2i(*self.as_ref())
+    }
+}
+
+impl Into<UniformVariable> for [i32; 2] {
+    fn into(self) -> UniformVariable {
+        UniformVariable::Vector2i(self)
+    }
+}
+
+impl Into<UniformVariable> for Vector3<i32> {
+    fn into(self) -> UniformVariable {
+        UniformVariable::Vector3i(*self.as_ref())
+    }
+}
+
+impl Into<UniformVariable> for [i32; 3] {
+    fn into(self) -> UniformVariable {
+        UniformVariable::Vector3i(self)
+    }
+}
+
+impl Into<UniformVariable> for Vector4<i32> {
+    fn into(self) -> UniformVariable {
+        UniformVariable::Vector4i(*self.as_ref())
+    }
+}
+
+impl Into<UniformVariable> for [i32; 4] {
+    fn into(self) -> UniformVariable {
+        UniformVariable::Vector4i(self)
+    }
+}
+
 impl Into<UniformVariable> for Vector2<f32> {
     fn into(self) -> UniformVariable {
         UniformVariable::Vector2f(*self.as_ref())
@@ -503,3 +774,123 @@ impl UniformVariableLayoutBuilder {
         self.0
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::utils::prelude::HandleLike;
+
+    #[test]
+    fn render_state_diff() {
+        let lhs = RenderState::default();
+        assert!(lhs.diff(&lhs).is_empty());
+
+        let rhs = RenderState {
+            cull_face: CullFace::Back,
+            ..lhs
+        };
+
+        assert_ne!(lhs, rhs);
+        let delta = lhs.diff(&rhs);
+        assert_eq!(
+            delta,
+            RenderStateDelta {
+                cull: true,
+                ..Default::default()
+            }
+        );
+
+        let mut rhs = rhs;
+        rhs.set_blend_mode(BlendMode::Alpha);
+        rhs.stencil = Some(StencilState::write(1));
+        let delta = lhs.diff(&rhs);
+        assert!(delta.cull && delta.blend && delta.stencil);
+        assert!(!delta.depth && !delta.color_write);
+    }
+
+    #[test]
+    fn integer_vectors() {
+        let v: UniformVariable = Vector3::new(1i32, 2, 3).into();
+        assert_eq!(v.variable_type(), UniformVariableType::Vector3i);
+
+        match v {
+            UniformVariable::Vector3i(v) => assert_eq!(v, [1, 2, 3]),
+            _ => unreachable!(),
+        }
+
+        let v: UniformVariable = [1i32, 2].into();
+        assert_eq!(v.variable_type(), UniformVariableType::Vector2i);
+        let v: UniformVariable = [1i32, 2, 3, 4].into();
+        assert_eq!(v.variable_type(), UniformVariableType::Vector4i);
+    }
+
+    #[test]
+    fn texture_array() {
+        let textures: Vec<_> = (1..5).map(|i| TextureHandle::new(i, 1)).collect();
+        let v: UniformVariable = textures[..].into();
+        assert_eq!(v.variable_type(), UniformVariableType::TextureArray);
+
+        match v {
+            UniformVariable::TextureArray(handles, len) => {
+                assert_eq!(len, 4);
+                assert_eq!(&handles[..4], &textures[..]);
+            }
+            _ => unreachable!(),
+        }
+
+        let layout = UniformVariableLayout::build()
+            .with("u_Textures", UniformVariableType::TextureArray)
+            .with("u_Tile", UniformVariableType::Vector3i)
+            .finish();
+
+        assert_eq!(
+            layout.variable_type("u_Textures"),
+            Some(UniformVariableType::TextureArray)
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn texture_array_overflow() {
+        let textures = [TextureHandle::default(); MAX_UNIFORM_TEXTURE_SLOTS + 1];
+        let _: UniformVariable = textures[..].into();
+    }
+
+    #[test]
+    fn blend_mode() {
+        use self::BlendFactor::*;
+        use self::BlendValue::*;
+
+        let mut state = RenderState::default();
+        state.set_blend_mode(BlendMode::Alpha);
+        assert_eq!(
+            state.color_blend,
+            Some((
+                Equation::Add,
+                Value(SourceAlpha),
+                OneMinusValue(SourceAlpha)
+            ))
+        );
+
+        state.set_blend_mode(BlendMode::PremultipliedAlpha);
+        assert_eq!(
+            state.color_blend,
+            Some((Equation::Add, One, OneMinusValue(SourceAlpha)))
+        );
+
+        state.set_blend_mode(BlendMode::Additive);
+        assert_eq!(
+            state.color_blend,
+            Some((Equation::Add, Value(SourceAlpha), One))
+        );
+
+        state.set_blend_mode(BlendMode::Multiply);
+        assert_eq!(
+            state.color_blend,
+            Some((Equation::Add, Value(DestinationColor), Zero))
+        );
+
+        state.set_blend_mode(BlendMode::Opaque);
+        assert_eq!(state.color_blend, None);
+    }
+}
@@ -18,6 +18,7 @@ pub struct SurfaceParams {
     pub(crate) clear_color: Option<Color<f32>>,
     pub(crate) clear_depth: Option<f32>,
     pub(crate) clear_stencil: Option<i32>,
+    pub(crate) sequence: bool,
 }
 
 impl Default for SurfaceParams {
@@ -28,12 +29,16 @@ impl Default for SurfaceParams {
             clear_color: Some(Color::black()),
             clear_depth: Some(1.0),
             clear_stencil: None,
+            sequence: false,
         }
     }
 }
 
 impl_handle!(SurfaceHandle);
 
+/// The clear flags of color, depth and stencil buffers.
+pub type SurfaceClear = (Option<Color<f32>>, Option<f32>, Option<i32>);
+
 impl SurfaceParams {
     /// Sets the attachments of internal frame-buffer. It consists of multiple color attachments
     /// and a optional `Depth/DepthStencil` buffer attachment.
@@ -64,7 +69,22 @@ impl SurfaceParams {
         Ok(())
     }
 
-    /// Sets the clear flags for this surface.A
+    /// Sets the clear flags for this surface.
+    ///
+    /// The depth buffer is cleared to exactly `depth`, which is clamped to [0, 1]. With a
+    /// reverse-z depth buffer, where the nearer fragments have the greater depth, clears it
+    /// to 0.0 and tests fragments with `Comparison::Greater`:
+    ///
+    /// ```rust
+    /// use crayon::prelude::*;
+    ///
+    /// let mut params = SurfaceParams::default();
+    /// params.set_clear(Color::black(), 0.0, None);
+    ///
+    /// let mut shader = ShaderParams::default();
+    /// shader.state.depth_test = Comparison::Greater;
+    /// shader.state.depth_write = true;
+    /// ```
     #[inline]
     pub fn set_clear<C, D, S>(&mut self, color: C, depth: D, stentil: S)
     where
@@ -76,6 +96,32 @@ impl SurfaceParams {
         self.clear_depth = depth.into();
         self.clear_stencil = stentil.into();
     }
+
+    /// Gets the clear flags of color, depth and stencil buffers.
+    #[inline]
+    pub fn clear(&self) -> SurfaceClear {
+        (self.clear_color, self.clear_depth, self.clear_stencil)
+    }
+
+    /// Sets whether this surface is sequenced.
+    ///
+    /// Draw calls submitted with `DrawCommandBuffer` are sorted by their keys (and states
+    /// if state sorting is enabled) before they are sent to video device, which is what
+    /// an ordered surface does. A sequenced surface bypasses the sorting, so its draw
+    /// calls are rendered in the exact order they were submitted regardless of their keys,
+    /// e.g. UI that relies on painter's algorithm.
+    ///
+    /// Draw calls submitted with `CommandBuffer` are never sorted.
+    #[inline]
+    pub fn set_sequence(&mut self, sequence: bool) {
+        self.sequence = sequence;
+    }
+
+    /// Checks if this surface is sequenced.
+    #[inline]
+    pub fn sequence(&self) -> bool {
+        self.sequence
+    }
 }
 
 /// Defines a rectangle, called the scissor box, in window coordinates. The test is
@@ -1,6 +1,7 @@
 //! Immutable or dynamic 2D texture. A texture is a container of one or more images. It
 //! can be the source of a texture access from a Shader.
 use crate::math::prelude::Vector2;
+use crate::video::assets::shader::BlendMode;
 use crate::video::errors::{Error, Result};
 
 impl_handle!(TextureHandle);
@@ -10,24 +11,40 @@ impl_handle!(TextureHandle);
 pub struct TextureParams {
     /// Hint abouts the intended update strategy of the data.
     pub hint: TextureHint,
-    /// Sets the wrap parameter for texture.
-    pub wrap: TextureWrap,
-    /// Specify how the texture is used whenever the pixel being sampled.
-    pub filter: TextureFilter,
+    /// Sets the wrap parameter for texture coordinate u(horizontal).
+    pub wrap_u: TextureWrap,
+    /// Sets the wrap parameter for texture coordinate v(vertical).
+    pub wrap_v: TextureWrap,
+    /// Specify how the texture is sampled when it's minified. The mipmaps are used
+    /// if there are more than one level.
+    pub min_filter: TextureFilter,
+    /// Specify how the texture is sampled when it's magnified, e.g. `Nearest` for the
+    /// crisp pixel-art.
+    pub mag_filter: TextureFilter,
+    /// Sets the max level of anisotropic filtering, which sharpens the textures viewed at
+    /// grazing angles, e.g. floors. It's clamped to the max level of the hardware, and
+    /// ignored if the hardware does not support it. `1` disables it.
+    pub anisotropy: u8,
     /// Sets the format of data.
     pub format: TextureFormat,
     /// Sets the dimensions of texture.
     pub dimensions: Vector2<u32>,
+    /// Whether the color components of data have already been multiplied by alpha.
+    pub premultiplied: bool,
 }
 
 impl Default for TextureParams {
     fn default() -> Self {
         TextureParams {
             format: TextureFormat::RGBA8,
-            wrap: TextureWrap::Clamp,
-            filter: TextureFilter::Linear,
+            wrap_u: TextureWrap::Clamp,
+            wrap_v: TextureWrap::Clamp,
+            min_filter: TextureFilter::Linear,
+            mag_filter: TextureFilter::Linear,
+            anisotropy: 1,
             hint: TextureHint::Immutable,
             dimensions: Vector2::new(0, 0),
+            premultiplied: false,
         }
     }
 }
@@ -43,6 +60,30 @@ impl TextureParams {
 
         Ok(())
     }
+
+    /// Sets the wrap parameter for both texture coordinates, e.g. `Repeat` for tiling.
+    #[inline]
+    pub fn set_wrap(&mut self, wrap: TextureWrap) {
+        self.wrap_u = wrap;
+        self.wrap_v = wrap;
+    }
+
+    /// Sets the filter for both minification and magnification.
+    #[inline]
+    pub fn set_filter(&mut self, filter: TextureFilter) {
+        self.min_filter = filter;
+        self.mag_filter = filter;
+    }
+
+    /// Gets the blend mode that composites this texture over destination correctly.
+    #[inline]
+    pub fn blend_mode(&self) -> BlendMode {
+        if self.premultiplied {
+            BlendMode::PremultipliedAlpha
+        } else {
+            BlendMode::Alpha
+        }
+    }
 }
 
 /// Continuous texture data of different mipmap levels.
@@ -53,10 +94,35 @@ pub struct TextureData {
     pub bytes: Vec<Box<[u8]>>,
 }
 
+impl TextureData {
+    /// Multiplies the color components of all mipmaps by alpha, which should be paired
+    /// with `TextureParams::premultiplied`. Returns false and leaves data untouched if
+    /// the format is not `RGBA8`.
+    pub fn premultiply(&mut self, format: TextureFormat) -> bool {
+        if format != TextureFormat::RGBA8 {
+            return false;
+        }
+
+        for mipmap in &mut self.bytes {
+            for pixel in mipmap.chunks_exact_mut(4) {
+                let alpha = u16::from(pixel[3]);
+                for v in &mut pixel[0..3] {
+                    *v = ((u16::from(*v) * alpha + 127) / 255) as u8;
+                }
+            }
+        }
+
+        true
+    }
+}
+
 /// A `RenderTexture` object is basicly texture object with special format. It can
 /// be used as a render target. If the `sampler` field is true, it can also be ther
 /// source of a texture access from a __shader__.
 ///
+/// If `samples` is greater than 1, the render texture is multisampled. Its contents
+/// are resolved into a single-sampled texture, which is described by `resolve`, when
+/// the surface it attached to has been drawn.
 #[derive(Debug, Copy, Clone)]
 pub struct RenderTextureParams {
     pub format: RenderTextureFormat,
@@ -64,6 +130,8 @@ pub struct RenderTextureParams {
     pub filter: TextureFilter,
     pub dimensions: Vector2<u32>,
     pub sampler: bool,
+    /// The number of samples per pixel, 0 or 1 means no multisampling.
+    pub samples: u16,
 }
 
 impl Default for RenderTextureParams {
@@ -74,6 +142,47 @@ impl Default for RenderTextureParams {
             filter: TextureFilter::Linear,
             dimensions: Vector2::new(0, 0),
             sampler: true,
+            samples: 0,
+        }
+    }
+}
+
+impl RenderTextureParams {
+    /// Checks the parameters against the maximum number of samples that the device
+    /// supports.
+    pub fn validate(&self, max_samples: u32) -> Result<()> {
+        if self.samples > 1 && !self.samples.is_power_of_two() {
+            return Err(Error::RenderTextureInvalid(format!(
+                "The number of samples must be a power of two, found: {}",
+                self.samples
+            )));
+        }
+
+        if u32::from(self.samples) > max_samples.max(1) {
+            return Err(Error::RenderTextureInvalid(format!(
+                "The number of samples exceeds the limit {}, found: {}",
+                max_samples, self.samples
+            )));
+        }
+
+        Ok(())
+    }
+
+    /// Returns true if this render texture is multisampled.
+    #[inline]
+    pub fn is_multisampled(&self) -> bool {
+        self.samples > 1
+    }
+
+    /// Gets the parameters of the single-sampled texture that a multisampled render
+    /// texture resolves into.
+    pub fn resolve(&self) -> Option<RenderTextureParams> {
+        if self.is_multisampled() {
+            let mut params = *self;
+            params.samples = 0;
+            Some(params)
+        } else {
+            None
         }
     }
 }
@@ -263,3 +372,142 @@ impl TextureFormat {
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn premultiply() {
+        let mut data = TextureData {
+            bytes: vec![
+                vec![255, 128, 0, 128, 255, 255, 255, 255, 10, 20, 30, 0].into_boxed_slice(),
+                vec![200, 100, 50, 51].into_boxed_slice(),
+            ],
+        };
+
+        assert!(data.premultiply(TextureFormat::RGBA8));
+        assert_eq!(
+            &*data.bytes[0],
+            &[128, 64, 0, 128, 255, 255, 255, 255, 0, 0, 0, 0]
+        );
+        assert_eq!(&*data.bytes[1], &[40, 20, 10, 51]);
+
+        // Opaque white, like the default texture, stays the same.
+        let mut white = TextureData {
+            bytes: vec![vec![255; 16].into_boxed_slice()],
+        };
+        assert!(white.premultiply(TextureFormat::RGBA8));
+        assert_eq!(&*white.bytes[0], &[255; 16][..]);
+
+        let mut rgb = TextureData {
+            bytes: vec![vec![255, 128, 0].into_boxed_slice()],
+        };
+        assert!(!rgb.premultiply(TextureFormat::RGB8));
+        assert_eq!(&*rgb.bytes[0], &[255, 128, 0]);
+
+        let mut params = TextureParams::default();
+        assert_eq!(params.blend_mode(), BlendMode::Alpha);
+        params.premultiplied = true;
+        assert_eq!(params.blend_mode(), BlendMode::PremultipliedAlpha);
+    }
+
+    #[test]
+    fn sampler_params() {
+        use crate::res::utils::prelude::ResourceLoader;
+        use crate::utils::double_buf::DoubleBuf;
+        use crate::video::assets::texture_loader::{TextureLoader, MAGIC};
+        use crate::video::backends::frame::Frame;
+        use std::sync::Arc;
+
+        let mut params = TextureParams {
+            wrap_u: TextureWrap::Repeat,
+            wrap_v: TextureWrap::Mirror,
+            mag_filter: TextureFilter::Nearest,
+            anisotropy: 8,
+            dimensions: Vector2::new(1, 1),
+            ..Default::default()
+        };
+
+        let mut bytes = MAGIC.to_vec();
+        bincode::serialize_into(&mut bytes, &params).unwrap();
+        let data = TextureData {
+            bytes: vec![vec![255; 4].into_boxed_slice()],
+        };
+        bincode::serialize_into(&mut bytes, &data).unwrap();
+
+        let frames = Arc::new(DoubleBuf::new(
+            Frame::with_capacity(0),
+            Frame::with_capacity(0),
+        ));
+
+        let loader = TextureLoader::new(frames);
+        let (v, _) = loader.load(TextureHandle::default(), &bytes).unwrap();
+        assert_eq!(
+            (v.wrap_u, v.wrap_v),
+            (TextureWrap::Repeat, TextureWrap::Mirror)
+        );
+        assert_eq!(v.min_filter, TextureFilter::Linear);
+        assert_eq!(v.mag_filter, TextureFilter::Nearest);
+        assert_eq!(v.anisotropy, 8);
+
+        params.set_wrap(TextureWrap::Clamp);
+        params.set_filter(TextureFilter::Nearest);
+        assert_eq!(
+            (params.wrap_u, params.wrap_v),
+            (TextureWrap::Clamp, TextureWrap::Clamp)
+        );
+        assert_eq!(params.min_filter, TextureFilter::Nearest);
+        assert_eq!(params.mag_filter, TextureFilter::Nearest);
+    }
+
+    #[test]
+    fn load_v1() {
+        use crate::res::utils::prelude::ResourceLoader;
+        use crate::utils::double_buf::DoubleBuf;
+        use crate::video::assets::texture_loader::TextureLoader;
+        use crate::video::backends::frame::Frame;
+        use std::sync::Arc;
+
+        // Textures built with 0.0.1 store a single wrap and filter mode, without the
+        // premultiplied flag.
+        let mut bytes = vec![b'V', b'T', b'E', b'X', b' ', 0, 0, 1];
+        let v1 = (
+            TextureHint::Immutable,
+            TextureWrap::Mirror,
+            TextureFilter::Nearest,
+            TextureFormat::RGBA8,
+            Vector2::new(2u32, 1),
+        );
+        bincode::serialize_into(&mut bytes, &v1).unwrap();
+        let data = TextureData {
+            bytes: vec![vec![255, 128, 0, 128, 10, 20, 30, 0].into_boxed_slice()],
+        };
+        bincode::serialize_into(&mut bytes, &data).unwrap();
+
+        let frames = Arc::new(DoubleBuf::new(
+            Frame::with_capacity(0),
+            Frame::with_capacity(0),
+        ));
+
+        let loader = TextureLoader::new(frames);
+        let (params, data) = loader.load(TextureHandle::default(), &bytes).unwrap();
+        assert_eq!(
+            (params.wrap_u, params.wrap_v),
+            (TextureWrap::Mirror, TextureWrap::Mirror)
+        );
+        assert_eq!(params.min_filter, TextureFilter::Nearest);
+        assert_eq!(params.mag_filter, TextureFilter::Nearest);
+        assert_eq!(params.anisotropy, 1);
+        assert_eq!(params.format, TextureFormat::RGBA8);
+        assert_eq!(params.dimensions, Vector2::new(2, 1));
+        assert!(!params.premultiplied);
+        assert_eq!(params.blend_mode(), BlendMode::Alpha);
+
+        // The pixels are loaded as stored, and can still be premultiplied.
+        let mut data = data.unwrap();
+        assert_eq!(&*data.bytes[0], &[255, 128, 0, 128, 10, 20, 30, 0]);
+        assert!(data.premultiply(params.format));
+        assert_eq!(&*data.bytes[0], &[128, 64, 0, 128, 0, 0, 0, 0]);
+    }
+}
@@ -3,13 +3,43 @@ use std::io::Cursor;
 use std::sync::Arc;
 
 use crate::errors::*;
+use crate::math::prelude::Vector2;
 use crate::res::utils::prelude::ResourceLoader;
 use crate::utils::double_buf::DoubleBuf;
 
 use super::super::backends::frame::{Command, Frame};
 use super::texture::*;
 
-pub const MAGIC: [u8; 8] = [b'V', b'T', b'E', b'X', b' ', 0, 0, 1];
+pub const MAGIC: [u8; 8] = [b'V', b'T', b'E', b'X', b' ', 0, 0, 2];
+
+// The textures built before the sampler params were set per axis, and premultiplied
+// alpha was introduced, which are still accepted.
+const MAGIC_V1: [u8; 8] = [b'V', b'T', b'E', b'X', b' ', 0, 0, 1];
+
+#[derive(Deserialize)]
+struct TextureParamsV1 {
+    hint: TextureHint,
+    wrap: TextureWrap,
+    filter: TextureFilter,
+    format: TextureFormat,
+    dimensions: Vector2<u32>,
+}
+
+impl From<TextureParamsV1> for TextureParams {
+    fn from(v: TextureParamsV1) -> Self {
+        TextureParams {
+            hint: v.hint,
+            wrap_u: v.wrap,
+            wrap_v: v.wrap,
+            min_filter: v.filter,
+            mag_filter: v.filter,
+            anisotropy: 1,
+            format: v.format,
+            dimensions: v.dimensions,
+            premultiplied: false,
+        }
+    }
+}
 
 #[derive(Clone)]
 pub struct TextureLoader {
@@ -28,12 +58,16 @@ impl ResourceLoader for TextureLoader {
     type Resource = TextureParams;
 
     fn load(&self, handle: Self::Handle, bytes: &[u8]) -> Result<Self::Intermediate> {
-        if bytes[0..8] != MAGIC[..] {
+        let mut file = Cursor::new(&bytes[8..]);
+        let params: TextureParams = if bytes[0..8] == MAGIC[..] {
+            bincode::deserialize_from(&mut file)?
+        } else if bytes[0..8] == MAGIC_V1[..] {
+            let v: TextureParamsV1 = bincode::deserialize_from(&mut file)?;
+            v.into()
+        } else {
             bail!("[TextureLoader] MAGIC number not match.");
-        }
+        };
 
-        let mut file = Cursor::new(&bytes[8..]);
-        let params: TextureParams = bincode::deserialize_from(&mut file)?;
         let data = bincode::deserialize_from(&mut file)?;
 
         info!(
@@ -1,8 +1,12 @@
+use std::path::PathBuf;
+
 use crate::errors::*;
 use crate::math::prelude::{Aabb2, Vector2};
 use crate::utils::prelude::{DataBuffer, DataBufferPtr, HashValue};
 
 use super::super::assets::prelude::*;
+use super::super::errors::Error as VideoError;
+use super::super::watch::ShaderSources;
 use super::Visitor;
 
 type VarsPtr = DataBufferPtr<[(HashValue<str>, UniformVariable)]>;
@@ -16,9 +20,11 @@ pub enum Command {
     UpdateViewport(SurfaceViewport),
 
     CreateSurface(Box<(SurfaceHandle, SurfaceParams)>),
+    UpdateSurfaceClear(SurfaceHandle, Box<SurfaceClear>),
     DeleteSurface(SurfaceHandle),
 
     CreateShader(Box<(ShaderHandle, ShaderParams, String, String)>),
+    ReloadShader(Box<(ShaderHandle, ShaderParams, String, String, ShaderSources)>),
     DeleteShader(ShaderHandle),
 
     CreateTexture(Box<(TextureHandle, TextureParams, Option<TextureData>)>),
@@ -32,12 +38,21 @@ pub enum Command {
     UpdateVertexBuffer(MeshHandle, usize, BytesPtr),
     UpdateIndexBuffer(MeshHandle, usize, BytesPtr),
     DeleteMesh(MeshHandle),
+
+    SaveScreenshot(Box<(Option<SurfaceHandle>, PathBuf)>),
+
+    BeginQuery(QueryHandle, QueryKind),
+    EndQuery(QueryHandle),
+    DeleteQuery(QueryHandle),
 }
 
 #[derive(Debug, Clone, Default)]
 pub struct Frame {
     pub cmds: Vec<Command>,
     pub bufs: DataBuffer,
+    /// The pixels read back for screenshots during the last dispatch, which should be
+    /// saved into files after it.
+    pub screenshots: Vec<(PathBuf, Vector2<u32>, Vec<u8>)>,
 }
 
 unsafe impl Send for Frame {}
@@ -49,6 +64,7 @@ impl Frame {
         Frame {
             cmds: Vec::with_capacity(16),
             bufs: DataBuffer::with_capacity(capacity),
+            screenshots: Vec::new(),
         }
     }
 
@@ -56,13 +72,17 @@ impl Frame {
     pub fn clear(&mut self) {
         self.cmds.clear();
         self.bufs.clear();
+        self.screenshots.clear();
     }
 
-    /// Dispatch frame tasks and draw calls to the backend context.
+    /// Dispatch frame tasks and draw calls to the backend context. The compile log of
+    /// the last created shader is written into `shader_log` along with its handle, or
+    /// `None` if it compiles.
     pub fn dispatch(
         &mut self,
         visitor: &mut Visitor,
         dimensions: Vector2<u32>,
+        shader_log: &mut Option<(ShaderHandle, String)>,
     ) -> Result<(u32, u32)> {
         unsafe {
             visitor.advance()?;
@@ -92,12 +112,57 @@ impl Frame {
                         visitor.create_surface(v.0, v.1)?;
                     }
 
+                    Command::UpdateSurfaceClear(handle, v) => {
+                        visitor.update_surface_clear(handle, v.0, v.1, v.2)?;
+                    }
+
                     Command::DeleteSurface(handle) => {
                         visitor.delete_surface(handle)?;
                     }
 
                     Command::CreateShader(v) => {
-                        visitor.create_shader(v.0, v.1, &v.2, &v.3)?;
+                        let (handle, params, vs, fs) = *v;
+                        match create_shader(visitor, handle, params, &vs, &fs) {
+                            Ok(None) => *shader_log = None,
+                            Ok(Some(err)) => {
+                                warn!(
+                                    "Failed to compile {:?}, falls back to the error shader.\n{}",
+                                    handle, err
+                                );
+
+                                *shader_log = Some((handle, compile_log(&err)));
+                            }
+                            Err(err) => {
+                                *shader_log = Some((handle, compile_log(&err)));
+                                return Err(err);
+                            }
+                        }
+                    }
+
+                    Command::ReloadShader(v) => {
+                        let (handle, params, vs, fs, sources) = *v;
+                        visitor.delete_shader(handle)?;
+
+                        match visitor.create_shader(handle, params.clone(), &vs, &fs) {
+                            Ok(_) => {
+                                // Keeps the log if it belongs to another shader.
+                                if shader_log.as_ref().map(|v| v.0) == Some(handle) {
+                                    *shader_log = None;
+                                }
+
+                                *sources.lock().unwrap() = (vs, fs);
+                            }
+                            Err(err) => {
+                                warn!(
+                                    "Failed to reload {:?}, keeps the last good sources.\n{}",
+                                    handle, err
+                                );
+
+                                *shader_log = Some((handle, compile_log(&err)));
+                                let sources = sources.lock().unwrap();
+                                create_shader(visitor, handle, params, &sources.0, &sources.1)?;
+                            }
+                        }
                     }
 
                     Command::DeleteShader(handle) => {
@@ -142,6 +207,24 @@ impl Frame {
                     Command::DeleteMesh(handle) => {
                         visitor.delete_mesh(handle)?;
                     }
+
+                    // Screenshots are best-effort, a failure should never abort the frame.
+                    Command::SaveScreenshot(v) => match visitor.read_pixels(v.0, dimensions) {
+                        Ok((size, pixels)) => self.screenshots.push((v.1, size, pixels)),
+                        Err(err) => warn!("Failed to read pixels for screenshot. {}", err),
+                    },
+
+                    Command::BeginQuery(handle, kind) => {
+                        visitor.begin_query(handle, kind)?;
+                    }
+
+                    Command::EndQuery(handle) => {
+                        visitor.end_query(handle)?;
+                    }
+
+                    Command::DeleteQuery(handle) => {
+                        visitor.delete_query(handle)?;
+                    }
                 }
             }
 
@@ -151,3 +234,677 @@ impl Frame {
         }
     }
 }
+
+const ERROR_VS: &str = "
+    #version 100
+    precision lowp float;
+
+    attribute vec4 Position;
+    uniform mat4 u_MVPMatrix;
+
+    void main() {
+        gl_Position = u_MVPMatrix * Position;
+    }
+";
+
+const ERROR_VS_WITHOUT_MATRIX: &str = "
+    #version 100
+    precision lowp float;
+
+    attribute vec4 Position;
+
+    void main() {
+        gl_Position = Position;
+    }
+";
+
+const ERROR_FS: &str = "
+    #version 100
+    precision lowp float;
+
+    void main() {
+        gl_FragColor = vec4(1.0, 0.0, 1.0, 1.0);
+    }
+";
+
+/// Creates the shader, or the error shader in its place if it fails to compile and
+/// `ShaderParams::fallback` is set. Returns the compile error if it falls back.
+unsafe fn create_shader(
+    visitor: &mut dyn Visitor,
+    handle: ShaderHandle,
+    params: ShaderParams,
+    vs: &str,
+    fs: &str,
+) -> Result<Option<Error>> {
+    if !params.fallback {
+        visitor.create_shader(handle, params, vs, fs)?;
+        return Ok(None);
+    }
+
+    let fallback = error_shader_params(&params);
+    if let Err(err) = visitor.create_shader(handle, params, vs, fs) {
+        let vs = if fallback.uniforms.is_empty() {
+            ERROR_VS_WITHOUT_MATRIX
+        } else {
+            ERROR_VS
+        };
+
+        visitor.create_shader(handle, fallback, vs, ERROR_FS)?;
+        return Ok(Some(err));
+    }
+
+    Ok(None)
+}
+
+/// Gets the info log of compiler or linker if available, or the whole error message.
+fn compile_log(err: &Error) -> String {
+    match err.downcast_ref::<VideoError>() {
+        Some(VideoError::ShaderCompileFailed(_, log)) | Some(VideoError::ShaderLinkFailed(log)) => {
+            log.clone()
+        }
+        _ => err.to_string(),
+    }
+}
+
+/// The error shader keeps the `Position` attribute and the `u_MVPMatrix` uniform of the
+/// original declaration, so it could be drawn with the same meshes and uniforms.
+fn error_shader_params(params: &ShaderParams) -> ShaderParams {
+    let size = params
+        .attributes
+        .iter()
+        .find(|v| v.0 == Attribute::Position)
+        .map_or(3, |v| v.1);
+
+    let mut uniforms = UniformVariableLayout::build();
+    if params.uniforms.variable_type("u_MVPMatrix") == Some(UniformVariableType::Matrix4f) {
+        uniforms = uniforms.with("u_MVPMatrix", UniformVariableType::Matrix4f);
+    }
+
+    ShaderParams {
+        attributes: AttributeLayout::build()
+            .with(Attribute::Position, size)
+            .finish(),
+        uniforms: uniforms.finish(),
+        state: params.state,
+        fallback: false,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::super::UniformVar;
+    use super::*;
+    use crate::math::prelude::Color;
+    use crate::utils::prelude::HandleLike;
+    use std::sync::{Arc, Mutex};
+
+    /// A visitor that fails to compile any sources other than the error shader, and
+    /// records the draw calls it receives.
+    #[derive(Default)]
+    struct StrictVisitor {
+        shaders: Vec<(ShaderHandle, ShaderParams, String)>,
+        draws: Vec<(ShaderHandle, MeshHandle, MeshIndex, Vec<UniformVar>)>,
+    }
+
+    impl Visitor for StrictVisitor {
+        unsafe fn create_surface(&mut self, _: SurfaceHandle, _: SurfaceParams) -> Result<()> {
+            Ok(())
+        }
+
+        unsafe fn update_surface_clear(
+            &mut self,
+            _: SurfaceHandle,
+            _: Option<Color<f32>>,
+            _: Option<f32>,
+            _: Option<i32>,
+        ) -> Result<()> {
+            Ok(())
+        }
+
+        unsafe fn delete_surface(&mut self, _: SurfaceHandle) -> Result<()> {
+            Ok(())
+        }
+
+        unsafe fn create_shader(
+            &mut self,
+            handle: ShaderHandle,
+            params: ShaderParams,
+            vs: &str,
+            fs: &str,
+        ) -> Result<()> {
+            if fs != ERROR_FS {
+                let log = format!("ERROR: 0:1: '{}' : syntax error", vs);
+                return Err(VideoError::ShaderCompileFailed(ShaderStage::Vertex, log).into());
+            }
+
+            self.shaders.push((handle, params, vs.to_owned()));
+            Ok(())
+        }
+
+        unsafe fn delete_shader(&mut self, _: ShaderHandle) -> Result<()> {
+            Ok(())
+        }
+
+        unsafe fn create_texture(
+            &mut self,
+            _: TextureHandle,
+            _: TextureParams,
+            _: Option<TextureData>,
+        ) -> Result<()> {
+            Ok(())
+        }
+
+        unsafe fn update_texture(
+            &mut self,
+            _: TextureHandle,
+            _: Aabb2<u32>,
+            _: &[u8],
+        ) -> Result<()> {
+            Ok(())
+        }
+
+        unsafe fn delete_texture(&mut self, _: TextureHandle) -> Result<()> {
+            Ok(())
+        }
+
+        unsafe fn create_render_texture(
+            &mut self,
+            _: RenderTextureHandle,
+            _: RenderTextureParams,
+        ) -> Result<()> {
+            Ok(())
+        }
+
+        unsafe fn delete_render_texture(&mut self, _: RenderTextureHandle) -> Result<()> {
+            Ok(())
+        }
+
+        fn max_samples(&self) -> u32 {
+            0
+        }
+
+        unsafe fn create_mesh(
+            &mut self,
+            _: MeshHandle,
+            _: MeshParams,
+            _: Option<MeshData>,
+        ) -> Result<()> {
+            Ok(())
+        }
+
+        unsafe fn update_vertex_buffer(&mut self, _: MeshHandle, _: usize, _: &[u8]) -> Result<()> {
+            Ok(())
+        }
+
+        unsafe fn update_index_buffer(&mut self, _: MeshHandle, _: usize, _: &[u8]) -> Result<()> {
+            Ok(())
+        }
+
+        unsafe fn delete_mesh(&mut self, _: MeshHandle) -> Result<()> {
+            Ok(())
+        }
+
+        unsafe fn bind(&mut self, _: SurfaceHandle, _: Vector2<u32>) -> Result<()> {
+            Ok(())
+        }
+
+        unsafe fn draw(
+            &mut self,
+            shader: ShaderHandle,
+            mesh: MeshHandle,
+            mesh_index: MeshIndex,
+            vars: &[UniformVar],
+        ) -> Result<u32> {
+            self.draws.push((shader, mesh, mesh_index, vars.to_vec()));
+            Ok(0)
+        }
+
+        unsafe fn update_surface_scissor(&mut self, _: SurfaceScissor) -> Result<()> {
+            Ok(())
+        }
+
+        unsafe fn update_surface_viewport(&mut self, _: SurfaceViewport) -> Result<()> {
+            Ok(())
+        }
+
+        unsafe fn read_pixels(
+            &mut self,
+            _: Option<SurfaceHandle>,
+            dimensions: Vector2<u32>,
+        ) -> Result<(Vector2<u32>, Vec<u8>)> {
+            Ok((dimensions, Vec::new()))
+        }
+
+        unsafe fn begin_query(&mut self, _: QueryHandle, _: QueryKind) -> Result<()> {
+            Ok(())
+        }
+
+        unsafe fn end_query(&mut self, _: QueryHandle) -> Result<()> {
+            Ok(())
+        }
+
+        unsafe fn query_result(&mut self, _: QueryHandle) -> Result<Option<u64>> {
+            Ok(None)
+        }
+
+        unsafe fn delete_query(&mut self, _: QueryHandle) -> Result<()> {
+            Ok(())
+        }
+
+        unsafe fn flush(&mut self) -> Result<()> {
+            Ok(())
+        }
+
+        unsafe fn advance(&mut self) -> Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn error_shader() {
+        let mut visitor = StrictVisitor::default();
+        let handle = ShaderHandle::new(1, 1);
+
+        let mut params = ShaderParams {
+            attributes: AttributeLayout::build()
+                .with(Attribute::Position, 2)
+                .with(Attribute::Texcoord0, 2)
+                .finish(),
+            uniforms: UniformVariableLayout::build()
+                .with("u_MVPMatrix", UniformVariableType::Matrix4f)
+                .with("u_Texture", UniformVariableType::Texture)
+                .finish(),
+            ..Default::default()
+        };
+
+        unsafe {
+            assert!(create_shader(&mut visitor, handle, params.clone(), "vs", "fs").is_err());
+            assert!(visitor.shaders.is_empty());
+
+            params.fallback = true;
+            let err = create_shader(&mut visitor, handle, params, "vs", "fs").unwrap();
+            let log = err.map(|v| compile_log(&v));
+            assert_eq!(log, Some("ERROR: 0:1: 'vs' : syntax error".to_owned()));
+        }
+
+        assert_eq!(visitor.shaders.len(), 1);
+
+        let (h, params, vs) = visitor.shaders.pop().unwrap();
+        assert_eq!(h, handle);
+        assert_eq!(vs, ERROR_VS);
+        assert!(!params.fallback);
+
+        let attributes: Vec<_> = params.attributes.iter().collect();
+        assert_eq!(attributes, [(Attribute::Position, 2, true)]);
+        assert_eq!(params.uniforms.len(), 1);
+        assert_eq!(
+            params.uniforms.variable_type("u_MVPMatrix"),
+            Some(UniformVariableType::Matrix4f)
+        );
+    }
+
+    #[test]
+    fn shader_log() {
+        let mut visitor = StrictVisitor::default();
+        let mut frame = Frame::with_capacity(64);
+        let mut log = None;
+
+        let (handle, params) = (ShaderHandle::new(1, 1), ShaderParams::default());
+        let vs = "void main() {".to_owned();
+        let v = (handle, params.clone(), vs, "fs".to_owned());
+        frame.cmds.push(Command::CreateShader(Box::new(v)));
+
+        let err = frame
+            .dispatch(&mut visitor, Vector2::new(0, 0), &mut log)
+            .unwrap_err();
+
+        let expected = "ERROR: 0:1: 'void main() {' : syntax error";
+        assert!(err.to_string().contains(expected));
+        assert!(err.to_string().contains("Vertex"));
+        assert_eq!(log, Some((handle, expected.to_owned())));
+
+        let v = (handle, params, "vs".to_owned(), ERROR_FS.to_owned());
+        frame.cmds.push(Command::CreateShader(Box::new(v)));
+        frame
+            .dispatch(&mut visitor, Vector2::new(0, 0), &mut log)
+            .unwrap();
+
+        assert_eq!(log, None);
+    }
+
+    #[test]
+    fn reload_shader() {
+        let mut visitor = StrictVisitor::default();
+        let mut frame = Frame::with_capacity(64);
+        let mut log = None;
+
+        let (handle, params) = (ShaderHandle::new(1, 1), ShaderParams::default());
+        let sources = Arc::new(Mutex::new(("vs".to_owned(), ERROR_FS.to_owned())));
+
+        // Keeps the last good sources if the new ones fail to compile.
+        let v = (
+            handle,
+            params.clone(),
+            "bad".to_owned(),
+            "fs".to_owned(),
+            sources.clone(),
+        );
+        frame.cmds.push(Command::ReloadShader(Box::new(v)));
+        frame
+            .dispatch(&mut visitor, Vector2::new(0, 0), &mut log)
+            .unwrap();
+
+        let expected = "ERROR: 0:1: 'bad' : syntax error".to_owned();
+        assert_eq!(log, Some((handle, expected.clone())));
+        assert_eq!(visitor.shaders.pop().unwrap().2, "vs");
+        assert_eq!(sources.lock().unwrap().0, "vs");
+
+        // Reloading other shaders keeps the log of this one.
+        let other = ShaderHandle::new(2, 1);
+        let v = (
+            other,
+            params.clone(),
+            "vs".to_owned(),
+            ERROR_FS.to_owned(),
+            sources.clone(),
+        );
+        frame.cmds.push(Command::ReloadShader(Box::new(v)));
+        frame
+            .dispatch(&mut visitor, Vector2::new(0, 0), &mut log)
+            .unwrap();
+
+        assert_eq!(log, Some((handle, expected)));
+        assert_eq!(visitor.shaders.pop().unwrap().0, other);
+
+        let v = (
+            handle,
+            params,
+            "good".to_owned(),
+            ERROR_FS.to_owned(),
+            sources.clone(),
+        );
+        frame.cmds.push(Command::ReloadShader(Box::new(v)));
+        frame
+            .dispatch(&mut visitor, Vector2::new(0, 0), &mut log)
+            .unwrap();
+
+        assert_eq!(log, None);
+        assert_eq!(visitor.shaders.pop().unwrap().2, "good");
+        assert_eq!(sources.lock().unwrap().0, "good");
+    }
+
+    #[test]
+    fn draw_uniforms() {
+        use crate::video::command::Draw;
+
+        let mut visitor = StrictVisitor::default();
+        let mut frame = Frame::with_capacity(64);
+        let mut log = None;
+
+        let (shader, mesh) = (ShaderHandle::new(1, 1), MeshHandle::new(1, 1));
+        let textures = [TextureHandle::new(1, 1), TextureHandle::new(2, 1)];
+
+        let mut dc = Draw::new(shader, mesh);
+        dc.mesh_index = MeshIndex::SubMesh(1);
+        dc.set_uniform_variable("u_V2", [1, 2]);
+        dc.set_uniform_variable("u_V3", [3, 4, 5]);
+        dc.set_uniform_variable("u_V4", [6, 7, 8, -9]);
+        dc.set_uniform_variable("u_Textures", &textures[..]);
+
+        // Pads the buffer, so the variables are not aligned by accident.
+        frame.bufs.extend_from_str("a");
+        let ptr = frame.bufs.extend_from_slice(&dc.uniforms);
+        let cmd = Command::Draw(dc.shader, dc.mesh, dc.mesh_index, ptr);
+        frame.cmds.push(cmd);
+        frame
+            .dispatch(&mut visitor, Vector2::new(0, 0), &mut log)
+            .unwrap();
+
+        assert_eq!(visitor.draws.len(), 1);
+        let (s, m, index, vars) = visitor.draws.pop().unwrap();
+        assert_eq!((s, m, index), (shader, mesh, MeshIndex::SubMesh(1)));
+
+        let fields: Vec<_> = vars.iter().map(|v| v.0).collect();
+        let expected: Vec<HashValue<str>> = vec![
+            "u_V2".into(),
+            "u_V3".into(),
+            "u_V4".into(),
+            "u_Textures".into(),
+        ];
+        assert_eq!(fields, expected);
+
+        match (vars[0].1, vars[1].1, vars[2].1, vars[3].1) {
+            (
+                UniformVariable::Vector2i(v2),
+                UniformVariable::Vector3i(v3),
+                UniformVariable::Vector4i(v4),
+                UniformVariable::TextureArray(handles, len),
+            ) => {
+                assert_eq!(v2, [1, 2]);
+                assert_eq!(v3, [3, 4, 5]);
+                assert_eq!(v4, [6, 7, 8, -9]);
+                assert_eq!(len, 2);
+                assert_eq!(&handles[..2], &textures[..]);
+            }
+            _ => panic!("uniform variables are not dispatched as they are."),
+        }
+    }
+
+    #[test]
+    fn draw_index_range() {
+        use super::super::headless::HeadlessVisitor;
+
+        let mut visitor = HeadlessVisitor::new();
+        let mut frame = Frame::with_capacity(64);
+        let mut log = None;
+
+        let mesh = MeshHandle::new(1, 1);
+        let params = MeshParams {
+            num_verts: 4,
+            num_idxes: 12,
+            ..MeshParams::default()
+        };
+
+        frame
+            .cmds
+            .push(Command::CreateMesh(Box::new((mesh, params, None))));
+
+        // Draws the first half of indices.
+        let shader = ShaderHandle::new(1, 1);
+        let vars = frame.bufs.extend_from_slice::<UniformVar>(&[]);
+        let cmd = Command::Draw(shader, mesh, MeshIndex::Ptr(0, 6), vars);
+        frame.cmds.push(cmd);
+
+        let (dc, tris) = frame
+            .dispatch(&mut visitor, Vector2::new(0, 0), &mut log)
+            .unwrap();
+        assert_eq!((dc, tris), (1, 2));
+
+        let cmd = Command::Draw(shader, mesh, MeshIndex::Ptr(6, 7), vars);
+        frame.cmds.push(cmd);
+        assert!(frame
+            .dispatch(&mut visitor, Vector2::new(0, 0), &mut log)
+            .is_err());
+    }
+
+    #[test]
+    fn query() {
+        use super::super::headless::HeadlessVisitor;
+
+        let mut visitor = HeadlessVisitor::new();
+        let mut frame = Frame::with_capacity(64);
+        let mut log = None;
+
+        let (q1, q2) = (QueryHandle::new(1, 1), QueryHandle::new(2, 1));
+        frame
+            .cmds
+            .push(Command::BeginQuery(q1, QueryKind::TimeElapsed));
+        frame.cmds.push(Command::EndQuery(q1));
+        frame
+            .dispatch(&mut visitor, Vector2::new(0, 0), &mut log)
+            .unwrap();
+
+        // The results are available in the next frame.
+        unsafe {
+            assert_eq!(visitor.query_result(q1).unwrap(), None);
+        }
+
+        frame
+            .dispatch(&mut visitor, Vector2::new(0, 0), &mut log)
+            .unwrap();
+
+        unsafe {
+            assert_eq!(visitor.query_result(q1).unwrap(), Some(0));
+            assert_eq!(visitor.query_result(q2).unwrap(), None);
+        }
+
+        // Only one query of each kind could be running.
+        frame
+            .cmds
+            .push(Command::BeginQuery(q2, QueryKind::SamplesPassed));
+        frame.cmds.push(Command::BeginQuery(
+            QueryHandle::new(3, 1),
+            QueryKind::SamplesPassed,
+        ));
+        assert!(frame
+            .dispatch(&mut visitor, Vector2::new(0, 0), &mut log)
+            .is_err());
+
+        frame.cmds.clear();
+        frame.cmds.push(Command::DeleteQuery(q1));
+        frame
+            .dispatch(&mut visitor, Vector2::new(0, 0), &mut log)
+            .unwrap();
+
+        unsafe {
+            assert_eq!(visitor.query_result(q1).unwrap(), None);
+        }
+    }
+
+    #[test]
+    fn screenshot() {
+        use super::super::headless::HeadlessVisitor;
+
+        let mut visitor = HeadlessVisitor::new();
+        let mut frame = Frame::with_capacity(64);
+        let mut log = None;
+
+        let path = PathBuf::from("screenshot.png");
+        frame
+            .cmds
+            .push(Command::SaveScreenshot(Box::new((None, path.clone()))));
+        frame
+            .dispatch(&mut visitor, Vector2::new(2, 2), &mut log)
+            .unwrap();
+
+        // The pixels are read back, but saved into files by the caller.
+        assert_eq!(frame.screenshots.len(), 1);
+        assert_eq!(frame.screenshots[0].0, path);
+        assert_eq!(frame.screenshots[0].1, Vector2::new(2, 2));
+        assert_eq!(frame.screenshots[0].2.len(), 16);
+    }
+
+    #[test]
+    #[cfg(feature = "software")]
+    fn stencil() {
+        use super::super::software::SoftwareVisitor;
+
+        let mut visitor = SoftwareVisitor::new();
+        let mut frame = Frame::with_capacity(64);
+        let mut log = None;
+
+        let surface = SurfaceHandle::new(1, 1);
+        let mut params = SurfaceParams::default();
+        params.set_clear(Color::black(), None, 0);
+        frame
+            .cmds
+            .push(Command::CreateSurface(Box::new((surface, params))));
+
+        // Marks the lower left half with the first shader, and then fills the window
+        // with the second one, which only passes on the marked pixels.
+        let (mark, fill) = (ShaderHandle::new(1, 1), ShaderHandle::new(2, 1));
+        for &(handle, stencil) in &[
+            (mark, StencilState::write(1)),
+            (fill, StencilState::test(Comparison::Equal, 1)),
+        ] {
+            let mut params = ShaderParams::default();
+            params.state.stencil = Some(stencil);
+            let v = (handle, params, "vs".to_owned(), "fs".to_owned());
+            frame.cmds.push(Command::CreateShader(Box::new(v)));
+        }
+
+        let mesh = MeshHandle::new(1, 1);
+        let mut params = MeshParams {
+            layout: VertexLayout::build()
+                .with(Attribute::Position, VertexFormat::Float, 2, false)
+                .finish(),
+            num_idxes: 9,
+            ..Default::default()
+        };
+        params.set_num_verts(4);
+
+        let verts: [f32; 8] = [-1.0, -1.0, 1.0, -1.0, -1.0, 1.0, 1.0, 1.0];
+        let data = MeshData {
+            vptr: IndexFormat::encode(&verts).to_vec().into_boxed_slice(),
+            iptr: IndexFormat::encode(&[0u16, 1, 2, 0, 1, 2, 1, 3, 2])
+                .to_vec()
+                .into_boxed_slice(),
+        };
+
+        frame
+            .cmds
+            .push(Command::CreateMesh(Box::new((mesh, params, Some(data)))));
+
+        let green = [("u_Color".into(), UniformVariable::Vector3f([0.0, 1.0, 0.0]))];
+        let red = [("u_Color".into(), UniformVariable::Vector3f([1.0, 0.0, 0.0]))];
+        let green = frame.bufs.extend_from_slice::<UniformVar>(&green);
+        let red = frame.bufs.extend_from_slice::<UniformVar>(&red);
+
+        frame.cmds.push(Command::Bind(surface));
+        frame
+            .cmds
+            .push(Command::Draw(mark, mesh, MeshIndex::Ptr(0, 3), green));
+        frame
+            .cmds
+            .push(Command::Draw(fill, mesh, MeshIndex::Ptr(3, 6), red));
+        frame
+            .cmds
+            .push(Command::SaveScreenshot(Box::new((None, "a.png".into()))));
+        frame
+            .dispatch(&mut visitor, Vector2::new(4, 4), &mut log)
+            .unwrap();
+
+        // The rows of pixels are bottom to top.
+        let pixels = &frame.screenshots[0].2;
+        assert_eq!(&pixels[0..4], &[255, 0, 0, 255]);
+        assert_eq!(&pixels[60..64], &[0, 0, 0, 255]);
+    }
+
+    #[test]
+    #[cfg(feature = "software")]
+    fn screenshot_failure() {
+        use super::super::software::SoftwareVisitor;
+
+        let mut visitor = SoftwareVisitor::new();
+        let mut frame = Frame::with_capacity(64);
+        let mut log = None;
+
+        // Failed screenshots are dropped without aborting the rest of frame.
+        let surface = Some(SurfaceHandle::new(1, 1));
+        let query = QueryHandle::new(1, 1);
+        frame
+            .cmds
+            .push(Command::SaveScreenshot(Box::new((surface, "a.png".into()))));
+        frame
+            .cmds
+            .push(Command::BeginQuery(query, QueryKind::TimeElapsed));
+        frame
+            .dispatch(&mut visitor, Vector2::new(2, 2), &mut log)
+            .unwrap();
+
+        assert!(frame.screenshots.is_empty());
+        unsafe {
+            assert!(visitor.end_query(query).is_ok());
+        }
+    }
+}
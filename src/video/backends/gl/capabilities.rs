@@ -5,6 +5,12 @@ use std::ffi;
 use std::mem;
 
 use crate::errors::*;
+use crate::video::assets::query::QueryKind;
+
+// The enums of `GL_EXT_texture_filter_anisotropic`, which are not generated in the core
+// bindings.
+pub const TEXTURE_MAX_ANISOTROPY_EXT: GLenum = 0x84FE;
+pub const MAX_TEXTURE_MAX_ANISOTROPY_EXT: GLenum = 0x84FF;
 
 /// Describes the OpenGL context profile.
 #[derive(Debug, Copy, Clone)]
@@ -171,6 +177,10 @@ extensions! {
     "GL_ARB_ES3_compatibility" => gl_arb_es3_compatibility,
     "GL_OES_compressed_ETC2_RGB8_texture" => gl_oes_compressed_etc2_rgb8_texture,
     "GL_OES_compressed_ETC2_RGBA8_texture" => gl_oes_compressed_etc2_rgba8_texture,
+    "GL_EXT_texture_filter_anisotropic" => gl_ext_texture_filter_anisotropic,
+    "GL_ARB_texture_filter_anisotropic" => gl_arb_texture_filter_anisotropic,
+    "GL_ARB_timer_query" => gl_arb_timer_query,
+    "GL_ARB_occlusion_query" => gl_arb_occlusion_query,
 }
 
 #[derive(Debug, Copy, Clone)]
@@ -225,6 +235,12 @@ pub struct Capabilities {
 
     /// Maximum number of color attachment bind points.
     pub max_color_attachments: u32,
+
+    /// Maximum number of samples of multisampled renderbuffers. `0` if not supported.
+    pub max_samples: u32,
+
+    /// Maximum level of anisotropic filtering. `0` if not supported.
+    pub max_anisotropy: f32,
 }
 
 impl Capabilities {
@@ -256,6 +272,8 @@ impl Capabilities {
             max_combined_texture_image_units: Capabilities::parse_texture_image_units(),
             max_indexed_uniform_buffer: Capabilities::parse_uniform_buffers(version, &extensions),
             max_color_attachments: Capabilities::parse_color_attachments(version, &extensions),
+            max_samples: Capabilities::parse_samples(version, &extensions),
+            max_anisotropy: Capabilities::parse_anisotropy(version, &extensions),
         })
     }
 
@@ -272,6 +290,17 @@ impl Capabilities {
         }
     }
 
+    pub fn has_query(&self, kind: QueryKind) -> bool {
+        match kind {
+            QueryKind::TimeElapsed => {
+                self.version >= Version::GL(3, 3) || self.extensions.gl_arb_timer_query
+            }
+            QueryKind::SamplesPassed => {
+                self.version >= Version::GL(1, 5) || self.extensions.gl_arb_occlusion_query
+            }
+        }
+    }
+
     #[inline]
     unsafe fn parse_str(id: GLenum) -> Result<String> {
         let s = gl::GetString(gl::RENDERER);
@@ -343,4 +372,32 @@ impl Capabilities {
             0
         }
     }
+
+    #[inline]
+    unsafe fn parse_samples(version: Version, exts: &Extensions) -> u32 {
+        if version >= Version::GL(3, 0)
+            || version >= Version::ES(3, 0)
+            || exts.gl_arb_framebuffer_object
+        {
+            let mut val = 0;
+            gl::GetIntegerv(gl::MAX_SAMPLES, &mut val);
+            val as u32
+        } else {
+            0
+        }
+    }
+
+    #[inline]
+    unsafe fn parse_anisotropy(version: Version, exts: &Extensions) -> f32 {
+        if version >= Version::GL(4, 6)
+            || exts.gl_ext_texture_filter_anisotropic
+            || exts.gl_arb_texture_filter_anisotropic
+        {
+            let mut val = 0.0;
+            gl::GetFloatv(MAX_TEXTURE_MAX_ANISOTROPY_EXT, &mut val);
+            val
+        } else {
+            0.0
+        }
+    }
 }
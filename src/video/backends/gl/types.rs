@@ -14,6 +14,15 @@ impl From<MeshHint> for GLenum {
     }
 }
 
+impl From<QueryKind> for GLenum {
+    fn from(kind: QueryKind) -> Self {
+        match kind {
+            QueryKind::TimeElapsed => gl::TIME_ELAPSED,
+            QueryKind::SamplesPassed => gl::SAMPLES_PASSED,
+        }
+    }
+}
+
 impl From<Comparison> for GLenum {
     fn from(cmp: Comparison) -> Self {
         match cmp {
@@ -29,6 +38,21 @@ impl From<Comparison> for GLenum {
     }
 }
 
+impl From<StencilOp> for GLenum {
+    fn from(op: StencilOp) -> Self {
+        match op {
+            StencilOp::Keep => gl::KEEP,
+            StencilOp::Zero => gl::ZERO,
+            StencilOp::Replace => gl::REPLACE,
+            StencilOp::Increment => gl::INCR,
+            StencilOp::IncrementWrap => gl::INCR_WRAP,
+            StencilOp::Decrement => gl::DECR,
+            StencilOp::DecrementWrap => gl::DECR_WRAP,
+            StencilOp::Invert => gl::INVERT,
+        }
+    }
+}
+
 impl From<Equation> for GLenum {
     fn from(eq: Equation) -> Self {
         match eq {
@@ -193,6 +217,33 @@ impl From<TextureWrap> for GLenum {
     }
 }
 
+/// Gets the `(min, mag)` filters of texture that has `levels` mipmaps.
+pub fn texture_filters(min: TextureFilter, mag: TextureFilter, levels: u32) -> (GLenum, GLenum) {
+    let min = match (min, levels > 1) {
+        (TextureFilter::Nearest, false) => gl::NEAREST,
+        (TextureFilter::Nearest, true) => gl::NEAREST_MIPMAP_NEAREST,
+        (TextureFilter::Linear, false) => gl::LINEAR,
+        (TextureFilter::Linear, true) => gl::LINEAR_MIPMAP_LINEAR,
+    };
+
+    let mag = match mag {
+        TextureFilter::Nearest => gl::NEAREST,
+        TextureFilter::Linear => gl::LINEAR,
+    };
+
+    (min, mag)
+}
+
+/// Gets the level of anisotropic filtering clamped to the `max` level of hardware, or
+/// `None` if it's disabled or not supported (`max` is 0).
+pub fn texture_anisotropy(level: u8, max: f32) -> Option<f32> {
+    if level <= 1 || max < 1.0 {
+        None
+    } else {
+        Some(f32::from(level).min(max))
+    }
+}
+
 impl From<RenderTextureFormat> for (GLenum, GLenum, GLenum) {
     fn from(format: RenderTextureFormat) -> Self {
         match format {
@@ -208,3 +259,59 @@ impl From<RenderTextureFormat> for (GLenum, GLenum, GLenum) {
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn stencil() {
+        let v = StencilState::write(1);
+        let ops: [GLenum; 3] = [v.fail.into(), v.depth_fail.into(), v.pass.into()];
+        assert_eq!(GLenum::from(v.comparison), gl::ALWAYS);
+        assert_eq!(ops, [gl::KEEP, gl::KEEP, gl::REPLACE]);
+        assert_eq!((v.reference, v.read_mask, v.write_mask), (1, 0xFF, 0xFF));
+
+        let v = StencilState::test(Comparison::Equal, 1);
+        let ops: [GLenum; 3] = [v.fail.into(), v.depth_fail.into(), v.pass.into()];
+        assert_eq!(GLenum::from(v.comparison), gl::EQUAL);
+        assert_eq!(ops, [gl::KEEP, gl::KEEP, gl::KEEP]);
+        assert_eq!((v.reference, v.read_mask, v.write_mask), (1, 0xFF, 0));
+    }
+
+    #[test]
+    fn filters() {
+        let mut params = TextureParams {
+            mag_filter: TextureFilter::Nearest,
+            ..Default::default()
+        };
+
+        let filters = texture_filters(params.min_filter, params.mag_filter, 1);
+        assert_eq!(filters, (gl::LINEAR, gl::NEAREST));
+
+        params.set_filter(TextureFilter::Nearest);
+        let filters = texture_filters(params.min_filter, params.mag_filter, 4);
+        assert_eq!(filters, (gl::NEAREST_MIPMAP_NEAREST, gl::NEAREST));
+
+        let filters = texture_filters(TextureFilter::Linear, TextureFilter::Linear, 4);
+        assert_eq!(filters, (gl::LINEAR_MIPMAP_LINEAR, gl::LINEAR));
+    }
+
+    #[test]
+    fn anisotropy() {
+        let params = TextureParams {
+            anisotropy: 16,
+            ..Default::default()
+        };
+
+        assert_eq!(texture_anisotropy(params.anisotropy, 16.0), Some(16.0));
+        assert_eq!(texture_anisotropy(params.anisotropy, 8.0), Some(8.0));
+        assert_eq!(texture_anisotropy(4, 8.0), Some(4.0));
+
+        // Disabled.
+        assert_eq!(texture_anisotropy(1, 16.0), None);
+        assert_eq!(texture_anisotropy(0, 16.0), None);
+        // Not supported by the hardware.
+        assert_eq!(texture_anisotropy(params.anisotropy, 0.0), None);
+    }
+}
@@ -10,15 +10,18 @@ use crate::utils::hash::{FastHashMap, FastHashSet};
 use crate::utils::hash_value::HashValue;
 
 use super::super::super::assets::prelude::*;
+use super::super::super::errors::Error as VideoError;
+use super::super::super::MAX_UNIFORM_TEXTURE_SLOTS;
 use super::super::utils::DataVec;
 use super::super::{UniformVar, Visitor};
-use super::capabilities::{Capabilities, Version};
+use super::capabilities::{Capabilities, Version, TEXTURE_MAX_ANISOTROPY_EXT};
 use super::types;
 
 #[derive(Debug, Clone)]
 struct GLSurfaceData {
     handle: SurfaceHandle,
     id: Option<GLuint>,
+    resolve: Option<GLuint>,
     dimensions: Option<Vector2<u32>>,
     params: SurfaceParams,
 }
@@ -90,9 +93,25 @@ struct GLTextureData {
 struct GLRenderTextureData {
     handle: RenderTextureHandle,
     id: GLuint,
+    msaa: Option<GLuint>,
     params: RenderTextureParams,
 }
 
+impl GLRenderTextureData {
+    /// Gets the object that should be attached to framebuffer. The multisampled
+    /// renderbuffer takes the place of texture unless `resolve` is true.
+    fn attachment(&self, resolve: bool) -> (GLuint, RenderTextureParams) {
+        match self.msaa {
+            Some(id) if !resolve => {
+                let mut params = self.params;
+                params.sampler = false;
+                (id, params)
+            }
+            _ => (self.id, self.params),
+        }
+    }
+}
+
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
 enum Sampler {
     RenderTexture(RenderTextureHandle),
@@ -120,6 +139,8 @@ pub struct GLVisitor {
     meshes: DataVec<GLMeshData>,
     textures: DataVec<GLTextureData>,
     render_textures: DataVec<GLRenderTextureData>,
+    // The queries and whether they have been ended.
+    queries: FastHashMap<QueryHandle, (GLuint, QueryKind, bool)>,
 }
 
 impl GLVisitor {
@@ -152,6 +173,7 @@ impl GLVisitor {
             meshes: DataVec::new(),
             textures: DataVec::new(),
             render_textures: DataVec::new(),
+            queries: FastHashMap::default(),
         };
 
         Self::reset_render_state(&mut visitor.state)?;
@@ -175,6 +197,7 @@ impl Visitor for GLVisitor {
             handle,
             params,
             id: None,
+            resolve: None,
             dimensions: None,
         };
 
@@ -187,6 +210,7 @@ impl Visitor for GLVisitor {
             self.state.binded_surface = None;
 
             let mut dimensions = None;
+            let mut samples = None;
             for (i, attachment) in params.colors.iter().enumerate() {
                 if let Some(v) = *attachment {
                     let rt = self
@@ -208,8 +232,18 @@ impl Visitor for GLVisitor {
                         );
                     }
 
+                    if samples.is_some() && samples != Some(rt.params.samples) {
+                        bail!(
+                            "Incompitable(mismatch samples) attachments of SurfaceObject {:?}",
+                            id
+                        );
+                    }
+
                     dimensions = Some(rt.params.dimensions);
-                    self.update_framebuffer_render_texture(rt.id, rt.params, i)?;
+                    samples = Some(rt.params.samples);
+
+                    let (rt_id, rt_params) = rt.attachment(false);
+                    self.update_framebuffer_render_texture(rt_id, rt_params, i)?;
                 }
             }
 
@@ -233,8 +267,18 @@ impl Visitor for GLVisitor {
                     );
                 }
 
+                if samples.is_some() && samples != Some(rt.params.samples) {
+                    bail!(
+                        "Incompitable(mismatch samples) attachments of SurfaceObject {:?}",
+                        id
+                    );
+                }
+
                 dimensions = Some(rt.params.dimensions);
-                self.update_framebuffer_render_texture(rt.id, rt.params, 0)?;
+                samples = Some(rt.params.samples);
+
+                let (rt_id, rt_params) = rt.attachment(false);
+                self.update_framebuffer_render_texture(rt_id, rt_params, 0)?;
             }
 
             let status = gl::CheckFramebufferStatus(gl::FRAMEBUFFER);
@@ -267,6 +311,12 @@ impl Visitor for GLVisitor {
                 }
             }
 
+            // Multisampled attachments are resolved into their textures by blitting
+            // into another framebuffer.
+            if samples.unwrap_or(0) > 1 {
+                data.resolve = Some(self.create_resolve_framebuffer(params)?);
+            }
+
             data.id = Some(id);
             data.dimensions = dimensions;
         };
@@ -276,6 +326,22 @@ impl Visitor for GLVisitor {
         Ok(())
     }
 
+    unsafe fn update_surface_clear(
+        &mut self,
+        handle: SurfaceHandle,
+        color: Option<Color<f32>>,
+        depth: Option<f32>,
+        stencil: Option<i32>,
+    ) -> Result<()> {
+        let surface = self
+            .surfaces
+            .get_mut(handle)
+            .ok_or_else(|| format_err!("{:?} is invalid.", handle))?;
+
+        surface.params.set_clear(color, depth, stencil);
+        Ok(())
+    }
+
     unsafe fn delete_surface(&mut self, handle: SurfaceHandle) -> Result<()> {
         let surface = self
             .surfaces
@@ -291,6 +357,11 @@ impl Visitor for GLVisitor {
             check()?;
         }
 
+        if let Some(id) = surface.resolve {
+            gl::DeleteFramebuffers(1, &id);
+            check()?;
+        }
+
         Ok(())
     }
 
@@ -301,8 +372,8 @@ impl Visitor for GLVisitor {
         vs: &str,
         fs: &str,
     ) -> Result<()> {
-        let vs = Self::compile(gl::VERTEX_SHADER, vs)?;
-        let fs = Self::compile(gl::FRAGMENT_SHADER, fs)?;
+        let vs = Self::compile(ShaderStage::Vertex, vs)?;
+        let fs = Self::compile(ShaderStage::Fragment, fs)?;
         let id = Self::link(&[vs, fs])?;
 
         gl::DetachShader(id, vs);
@@ -391,7 +462,7 @@ impl Visitor for GLVisitor {
             let len = data.bytes.len();
             if len > 0 {
                 Self::bind_texture(&mut self.state, Some(Sampler::Texture(handle)), 0, id)?;
-                Self::bind_texture_params(params.wrap, params.filter, len as u32)?;
+                Self::bind_texture_params(&self.capabilities, &params, len as u32)?;
 
                 let mut dims = (
                     params.dimensions.x as GLsizei,
@@ -489,7 +560,7 @@ impl Visitor for GLVisitor {
         )?;
 
         if !*texture.allocated.borrow() {
-            Self::bind_texture_params(texture.params.wrap, texture.params.filter, 1)?;
+            Self::bind_texture_params(&self.capabilities, &texture.params, 1)?;
 
             gl::TexImage2D(
                 gl::TEXTURE_2D,
@@ -542,13 +613,21 @@ impl Visitor for GLVisitor {
         handle: RenderTextureHandle,
         params: RenderTextureParams,
     ) -> Result<()> {
+        if u32::from(params.samples) > self.capabilities.max_samples.max(1) {
+            bail!(
+                "The number of samples of render texture exceeds the limit {}, found: {}",
+                self.capabilities.max_samples,
+                params.samples
+            );
+        }
+
         let id = if params.sampler {
             let mut id = 0;
             gl::GenTextures(1, &mut id);
             assert!(id != 0);
 
             Self::bind_texture(&mut self.state, Some(Sampler::RenderTexture(handle)), 0, id)?;
-            Self::bind_texture_params(params.wrap, params.filter, 1)?;
+            Self::bind_sampler_params(params.wrap, params.wrap, params.filter, params.filter, 1)?;
 
             let (internal_format, format, pixel_type) = params.format.into();
             gl::TexImage2D(
@@ -580,10 +659,35 @@ impl Visitor for GLVisitor {
             id
         };
 
+        let msaa = if params.is_multisampled() {
+            let mut id = 0;
+            gl::GenRenderbuffers(1, &mut id);
+            assert!(id != 0);
+            gl::BindRenderbuffer(gl::RENDERBUFFER, id);
+
+            let (internal_format, _, _) = params.format.into();
+            gl::RenderbufferStorageMultisample(
+                gl::RENDERBUFFER,
+                GLsizei::from(params.samples),
+                internal_format,
+                params.dimensions.x as GLint,
+                params.dimensions.y as GLint,
+            );
+            Some(id)
+        } else {
+            None
+        };
+
         check()?;
 
-        self.render_textures
-            .create(handle, GLRenderTextureData { handle, id, params });
+        let data = GLRenderTextureData {
+            handle,
+            id,
+            msaa,
+            params,
+        };
+
+        self.render_textures.create(handle, data);
 
         Ok(())
     }
@@ -606,9 +710,17 @@ impl Visitor for GLVisitor {
             gl::DeleteRenderbuffers(1, &rt.id);
         }
 
+        if let Some(id) = rt.msaa {
+            gl::DeleteRenderbuffers(1, &id);
+        }
+
         check()
     }
 
+    fn max_samples(&self) -> u32 {
+        self.capabilities.max_samples
+    }
+
     unsafe fn create_mesh(
         &mut self,
         handle: MeshHandle,
@@ -714,6 +826,8 @@ impl Visitor for GLVisitor {
             return Ok(());
         }
 
+        self.resolve_binded_surface()?;
+
         let surface = self
             .surfaces
             .get(handle)
@@ -740,6 +854,13 @@ impl Visitor for GLVisitor {
                 Self::set_depth_test(&mut self.state, true, Comparison::Always)?;
             }
 
+            // Disables stencil test to make sure that all the bits of stencil buffer are
+            // writable.
+            if surface.params.clear_stencil.is_some() {
+                self.state.binded_shader = None;
+                Self::set_stencil(&mut self.state, None)?;
+            }
+
             // Clears frame buffer.
             Self::clear(
                 surface.params.clear_color,
@@ -762,6 +883,114 @@ impl Visitor for GLVisitor {
         Self::set_viewport(&mut self.state, vp)
     }
 
+    unsafe fn read_pixels(
+        &mut self,
+        surface: Option<SurfaceHandle>,
+        dimensions: Vector2<u32>,
+    ) -> Result<(Vector2<u32>, Vec<u8>)> {
+        self.resolve_binded_surface()?;
+
+        let (id, dimensions) = if let Some(handle) = surface {
+            let surface = self
+                .surfaces
+                .get(handle)
+                .ok_or_else(|| format_err!("{:?} is invalid.", handle))?;
+
+            (
+                surface.resolve.or(surface.id).unwrap_or(0),
+                surface.dimensions.unwrap_or(dimensions),
+            )
+        } else {
+            (0, dimensions)
+        };
+
+        let mut pixels = vec![0u8; dimensions.x as usize * dimensions.y as usize * 4];
+
+        gl::BindFramebuffer(gl::FRAMEBUFFER, id);
+        gl::PixelStorei(gl::PACK_ALIGNMENT, 1);
+        gl::ReadPixels(
+            0,
+            0,
+            dimensions.x as GLsizei,
+            dimensions.y as GLsizei,
+            gl::RGBA,
+            gl::UNSIGNED_BYTE,
+            pixels.as_mut_ptr() as *mut _,
+        );
+
+        self.state.binded_surface = None;
+        check()?;
+        Ok((dimensions, pixels))
+    }
+
+    unsafe fn begin_query(&mut self, handle: QueryHandle, kind: QueryKind) -> Result<()> {
+        // The results of unsupported queries are never available.
+        if !self.capabilities.has_query(kind) {
+            return Ok(());
+        }
+
+        if self.queries.contains_key(&handle) {
+            bail!("{:?} has been begun already.", handle);
+        }
+
+        let mut id = 0;
+        gl::GenQueries(1, &mut id);
+        assert!(id != 0);
+
+        gl::BeginQuery(kind.into(), id);
+        self.queries.insert(handle, (id, kind, false));
+        check()
+    }
+
+    unsafe fn end_query(&mut self, handle: QueryHandle) -> Result<()> {
+        match self.queries.get_mut(&handle) {
+            Some((_, kind, ended)) if !*ended => {
+                gl::EndQuery((*kind).into());
+                *ended = true;
+            }
+            Some(_) => bail!("{:?} is not running.", handle),
+            None => return Ok(()),
+        }
+
+        check()
+    }
+
+    unsafe fn query_result(&mut self, handle: QueryHandle) -> Result<Option<u64>> {
+        let (id, kind) = match self.queries.get(&handle) {
+            Some(&(id, kind, true)) => (id, kind),
+            _ => return Ok(None),
+        };
+
+        let mut available = 0;
+        gl::GetQueryObjectiv(id, gl::QUERY_RESULT_AVAILABLE, &mut available);
+        if available == 0 {
+            check()?;
+            return Ok(None);
+        }
+
+        // The 64-bits results are only available with timer queries.
+        let v = if kind == QueryKind::TimeElapsed {
+            let mut v: GLuint64 = 0;
+            gl::GetQueryObjectui64v(id, gl::QUERY_RESULT, &mut v);
+            v
+        } else {
+            let mut v: GLuint = 0;
+            gl::GetQueryObjectuiv(id, gl::QUERY_RESULT, &mut v);
+            u64::from(v)
+        };
+
+        check()?;
+        Ok(Some(v))
+    }
+
+    unsafe fn delete_query(&mut self, handle: QueryHandle) -> Result<()> {
+        if let Some((id, _, _)) = self.queries.remove(&handle) {
+            gl::DeleteQueries(1, &id);
+        }
+
+        check()
+    }
+
     unsafe fn draw(
         &mut self,
         shader: ShaderHandle,
@@ -809,6 +1038,28 @@ impl Visitor for GLVisitor {
 
                         index += 1;
                     }
+                    UniformVariable::TextureArray(handles, len) => {
+                        let mut units = [0; MAX_UNIFORM_TEXTURE_SLOTS];
+                        for (i, &handle) in handles[..len as usize].iter().enumerate() {
+                            units[i] = index as i32;
+
+                            if let Some(texture) = self.textures.get(handle) {
+                                Self::bind_texture(
+                                    &mut self.state,
+                                    Some(Sampler::Texture(handle)),
+                                    index,
+                                    texture.id,
+                                )?;
+                            } else {
+                                Self::bind_texture(&mut self.state, None, index, 0)?;
+                            }
+
+                            index += 1;
+                        }
+
+                        gl::Uniform1iv(location, i32::from(len), units.as_ptr());
+                        check()?;
+                    }
                     UniformVariable::RenderTexture(handle) => {
                         let v = UniformVariable::I32(index as i32);
                         Self::bind_uniform_variable(location, &v)?;
@@ -843,32 +1094,11 @@ impl Visitor for GLVisitor {
             // Bind vertex buffer and vertex array object.
             Self::bind_mesh(&mut self.state, &shader, &mesh)?;
 
-            let (from, len) = match mesh_index {
-                MeshIndex::Ptr(from, len) => {
-                    if (from + len) > mesh.params.num_idxes {
-                        bail!("MeshIndex is out of bounds");
-                    }
-
-                    ((from * mesh.params.index_format.stride()), len)
-                }
-                MeshIndex::SubMesh(index) => {
-                    let num = mesh.params.sub_mesh_offsets.len();
-                    let from = mesh
-                        .params
-                        .sub_mesh_offsets
-                        .get(index)
-                        .ok_or_else(|| format_err!("MeshIndex is out of bounds"))?;
-
-                    let to = if index == (num - 1) {
-                        mesh.params.num_idxes
-                    } else {
-                        mesh.params.sub_mesh_offsets[index + 1]
-                    };
-
-                    ((from * mesh.params.index_format.stride()), (to - from))
-                }
-                MeshIndex::All => (0, mesh.params.num_idxes),
-            };
+            let (from, len) = mesh
+                .params
+                .index_range(mesh_index)
+                .map_err(|_| format_err!("{:?} is out of bounds.", mesh_index))?;
+            let from = from * mesh.params.index_format.stride();
 
             gl::DrawElements(
                 mesh.params.primitive.into(),
@@ -885,6 +1115,8 @@ impl Visitor for GLVisitor {
     }
 
     unsafe fn flush(&mut self) -> Result<()> {
+        self.resolve_binded_surface()?;
+
         if self.state.cleared_surfaces.is_empty() {
             Self::clear(Color::black(), None, None)?;
         }
@@ -904,12 +1136,29 @@ impl GLVisitor {
         check()?;
 
         let rs = shader.params.state;
-        Self::set_cull_face(state, rs.cull_face)?;
-        Self::set_front_face_order(state, rs.front_face_order)?;
-        Self::set_depth_test(state, rs.depth_write, rs.depth_test)?;
-        Self::set_depth_write_offset(state, rs.depth_write_offset)?;
-        Self::set_color_blend(state, rs.color_blend)?;
-        Self::set_color_write(state, rs.color_write)?;
+        let delta = state.render_state.diff(&rs);
+
+        if delta.cull {
+            Self::set_cull_face(state, rs.cull_face)?;
+            Self::set_front_face_order(state, rs.front_face_order)?;
+        }
+
+        if delta.depth {
+            Self::set_depth_test(state, rs.depth_write, rs.depth_test)?;
+            Self::set_depth_write_offset(state, rs.depth_write_offset)?;
+        }
+
+        if delta.blend {
+            Self::set_color_blend(state, rs.color_blend)?;
+        }
+
+        if delta.color_write {
+            Self::set_color_write(state, rs.color_write)?;
+        }
+
+        if delta.stencil {
+            Self::set_stencil(state, rs.stencil)?;
+        }
 
         state.binded_shader = Some(shader.handle);
         Ok(())
@@ -918,9 +1167,13 @@ impl GLVisitor {
     unsafe fn bind_uniform_variable(location: GLint, variable: &UniformVariable) -> Result<()> {
         match *variable {
             UniformVariable::Texture(_) => unreachable!(),
+            UniformVariable::TextureArray(_, _) => unreachable!(),
             UniformVariable::RenderTexture(_) => unreachable!(),
             UniformVariable::I32(v) => gl::Uniform1i(location, v),
             UniformVariable::F32(v) => gl::Uniform1f(location, v),
+            UniformVariable::Vector2i(v) => gl::Uniform2i(location, v[0], v[1]),
+            UniformVariable::Vector3i(v) => gl::Uniform3i(location, v[0], v[1], v[2]),
+            UniformVariable::Vector4i(v) => gl::Uniform4i(location, v[0], v[1], v[2], v[3]),
             UniformVariable::Vector2f(v) => gl::Uniform2f(location, v[0], v[1]),
             UniformVariable::Vector3f(v) => gl::Uniform3f(location, v[0], v[1], v[2]),
             UniformVariable::Vector4f(v) => gl::Uniform4f(location, v[0], v[1], v[2], v[3]),
@@ -1048,6 +1301,10 @@ impl GLVisitor {
         gl::ColorMask(1, 1, 1, 1);
         state.render_state.color_write = (true, true, true, true);
 
+        gl::Disable(gl::STENCIL_TEST);
+        gl::StencilMask(0xFF);
+        state.render_state.stencil = None;
+
         gl::Disable(gl::SCISSOR_TEST);
         state.scissor = SurfaceScissor::Disable;
 
@@ -1205,6 +1462,32 @@ impl GLVisitor {
         Ok(())
     }
 
+    /// Specifies the stencil test, and the actions applied to stencil buffer.
+    unsafe fn set_stencil(state: &mut GLMutableState, stencil: Option<StencilState>) -> Result<()> {
+        let rs = &mut state.render_state;
+
+        if rs.stencil != stencil {
+            if let Some(v) = stencil {
+                if rs.stencil.is_none() {
+                    gl::Enable(gl::STENCIL_TEST);
+                }
+
+                let (reference, read_mask) = (GLint::from(v.reference), GLuint::from(v.read_mask));
+                gl::StencilFunc(v.comparison.into(), reference, read_mask);
+                gl::StencilOp(v.fail.into(), v.depth_fail.into(), v.pass.into());
+                gl::StencilMask(GLuint::from(v.write_mask));
+            } else {
+                gl::Disable(gl::STENCIL_TEST);
+                gl::StencilMask(0xFF);
+            }
+
+            rs.stencil = stencil;
+            check()?;
+        }
+
+        Ok(())
+    }
+
     /// Set the scissor box relative to the top-lef corner of th window, in pixels.
     unsafe fn set_scissor(state: &mut GLMutableState, scissor: SurfaceScissor) -> Result<()> {
         match scissor {
@@ -1279,6 +1562,83 @@ impl GLVisitor {
 }
 
 impl GLVisitor {
+    unsafe fn create_resolve_framebuffer(&self, params: SurfaceParams) -> Result<GLuint> {
+        let mut id = 0;
+        gl::GenFramebuffers(1, &mut id);
+        assert!(id != 0);
+
+        gl::BindFramebuffer(gl::FRAMEBUFFER, id);
+
+        for (i, attachment) in params.colors.iter().enumerate() {
+            if let Some(rt) = attachment.and_then(|v| self.render_textures.get(v)) {
+                let (rt_id, rt_params) = rt.attachment(true);
+                self.update_framebuffer_render_texture(rt_id, rt_params, i)?;
+            }
+        }
+
+        if let Some(rt) = params
+            .depth_stencil
+            .and_then(|v| self.render_textures.get(v))
+        {
+            let (rt_id, rt_params) = rt.attachment(true);
+            self.update_framebuffer_render_texture(rt_id, rt_params, 0)?;
+        }
+
+        if gl::CheckFramebufferStatus(gl::FRAMEBUFFER) != gl::FRAMEBUFFER_COMPLETE {
+            gl::BindFramebuffer(gl::FRAMEBUFFER, 0);
+            gl::DeleteFramebuffers(1, &id);
+            bail!("[GL] Surface is incomplete. Failed to create the resolve framebuffer.");
+        }
+
+        Ok(id)
+    }
+
+    /// Resolves the multisampled attachments of the binded surface into their textures.
+    unsafe fn resolve_binded_surface(&mut self) -> Result<()> {
+        let surfaces = &self.surfaces;
+        let surface = match self.state.binded_surface.and_then(|v| surfaces.get(v)) {
+            Some(surface) => surface,
+            None => return Ok(()),
+        };
+
+        let (id, resolve, dimensions) = match (surface.id, surface.resolve, surface.dimensions) {
+            (Some(id), Some(resolve), Some(dimensions)) => (id, resolve, dimensions),
+            _ => return Ok(()),
+        };
+
+        // The scissor test affects blitting.
+        Self::set_scissor(&mut self.state, SurfaceScissor::Disable)?;
+
+        gl::BindFramebuffer(gl::READ_FRAMEBUFFER, id);
+        gl::BindFramebuffer(gl::DRAW_FRAMEBUFFER, resolve);
+
+        let (w, h) = (dimensions.x as GLint, dimensions.y as GLint);
+        for (i, attachment) in surface.params.colors.iter().enumerate() {
+            if attachment.is_some() {
+                let location = gl::COLOR_ATTACHMENT0 + i as u32;
+                gl::ReadBuffer(location);
+                gl::DrawBuffers(1, &location);
+                gl::BlitFramebuffer(0, 0, w, h, 0, 0, w, h, gl::COLOR_BUFFER_BIT, gl::NEAREST);
+            }
+        }
+
+        if let Some(v) = surface.params.depth_stencil {
+            let bits = match self.render_textures.get(v).map(|rt| rt.params.format) {
+                Some(RenderTextureFormat::Depth24Stencil8) => {
+                    gl::DEPTH_BUFFER_BIT | gl::STENCIL_BUFFER_BIT
+                }
+                _ => gl::DEPTH_BUFFER_BIT,
+            };
+
+            gl::BlitFramebuffer(0, 0, w, h, 0, 0, w, h, bits, gl::NEAREST);
+        }
+
+        gl::ReadBuffer(gl::COLOR_ATTACHMENT0);
+        gl::BindFramebuffer(gl::FRAMEBUFFER, 0);
+        self.state.binded_surface = None;
+        check()
+    }
+
     unsafe fn update_framebuffer_render_texture(
         &self,
         id: GLuint,
@@ -1338,8 +1698,12 @@ impl GLVisitor {
         check()
     }
 
-    unsafe fn compile(shader: GLenum, src: &str) -> Result<GLuint> {
-        let shader = gl::CreateShader(shader);
+    unsafe fn compile(stage: ShaderStage, src: &str) -> Result<GLuint> {
+        let shader = match stage {
+            ShaderStage::Vertex => gl::CreateShader(gl::VERTEX_SHADER),
+            ShaderStage::Fragment => gl::CreateShader(gl::FRAGMENT_SHADER),
+        };
+
         // Attempt to compile the shader
         let c_str = ::std::ffi::CString::new(src.as_bytes()).unwrap();
         gl::ShaderSource(shader, 1, &c_str.as_ptr(), ::std::ptr::null());
@@ -1353,8 +1717,7 @@ impl GLVisitor {
         if status != GLint::from(gl::TRUE) {
             let mut len = 0;
             gl::GetShaderiv(shader, gl::INFO_LOG_LENGTH, &mut len);
-            let mut buf = Vec::with_capacity(len as usize);
-            buf.set_len((len as usize) - 1); // subtract 1 to skip the trailing null character
+            let mut buf = vec![0u8; len.max(1) as usize];
             gl::GetShaderInfoLog(
                 shader,
                 len,
@@ -1362,7 +1725,9 @@ impl GLVisitor {
                 buf.as_mut_ptr() as *mut GLchar,
             );
 
-            bail!("{:?}\n{:?}", ::std::str::from_utf8(&buf).unwrap(), src);
+            gl::DeleteShader(shader);
+            let log = Self::info_log(buf);
+            Err(VideoError::ShaderCompileFailed(stage, log).into())
         } else {
             Ok(shader)
         }
@@ -1386,8 +1751,7 @@ impl GLVisitor {
         if status != GLint::from(gl::TRUE) {
             let mut len: GLint = 0;
             gl::GetProgramiv(program, gl::INFO_LOG_LENGTH, &mut len);
-            let mut buf = Vec::with_capacity(len as usize);
-            buf.set_len((len as usize) - 1); // subtract 1 to skip the trailing null character
+            let mut buf = vec![0u8; len.max(1) as usize];
             gl::GetProgramInfoLog(
                 program,
                 len,
@@ -1395,12 +1759,22 @@ impl GLVisitor {
                 buf.as_mut_ptr() as *mut GLchar,
             );
 
-            bail!("{:?}", ::std::str::from_utf8(&buf).unwrap());
+            gl::DeleteProgram(program);
+            Err(VideoError::ShaderLinkFailed(Self::info_log(buf)).into())
         } else {
             Ok(program)
         }
     }
 
+    /// Converts the null-terminated info log into string.
+    fn info_log(mut buf: Vec<u8>) -> String {
+        if let Some(len) = buf.iter().position(|&v| v == 0) {
+            buf.truncate(len);
+        }
+
+        String::from_utf8_lossy(&buf).trim_end().to_owned()
+    }
+
     unsafe fn create_buffer(
         &mut self,
         tp: GLuint,
@@ -1436,37 +1810,45 @@ impl GLVisitor {
     }
 
     unsafe fn bind_texture_params(
-        wrap: TextureWrap,
-        filter: TextureFilter,
+        caps: &Capabilities,
+        params: &TextureParams,
         levels: u32,
     ) -> Result<()> {
-        let wrap: GLenum = wrap.into();
-        gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_WRAP_S, wrap as GLint);
-        gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_WRAP_T, wrap as GLint);
-
-        match filter {
-            TextureFilter::Nearest => {
-                let min_filter = if levels > 1 {
-                    gl::NEAREST_MIPMAP_NEAREST
-                } else {
-                    gl::NEAREST
-                };
-
-                gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MIN_FILTER, min_filter as GLint);
-                gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MAG_FILTER, gl::NEAREST as GLint);
-            }
-            TextureFilter::Linear => {
-                let min_filter = if levels > 1 {
-                    gl::LINEAR_MIPMAP_LINEAR
-                } else {
-                    gl::LINEAR
-                };
+        Self::bind_sampler_params(
+            params.wrap_u,
+            params.wrap_v,
+            params.min_filter,
+            params.mag_filter,
+            levels,
+        )?;
 
-                gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MIN_FILTER, min_filter as GLint);
-                gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MAG_FILTER, gl::LINEAR as GLint);
-            }
+        if let Some(v) = types::texture_anisotropy(params.anisotropy, caps.max_anisotropy) {
+            gl::TexParameterf(gl::TEXTURE_2D, TEXTURE_MAX_ANISOTROPY_EXT, v);
+        } else if params.anisotropy > 1 {
+            warn!(
+                "The GL Context does not support anisotropic filtering, {}x is ignored.",
+                params.anisotropy
+            );
         }
 
+        Ok(())
+    }
+
+    unsafe fn bind_sampler_params(
+        wrap_u: TextureWrap,
+        wrap_v: TextureWrap,
+        min_filter: TextureFilter,
+        mag_filter: TextureFilter,
+        levels: u32,
+    ) -> Result<()> {
+        let (wrap_u, wrap_v): (GLenum, GLenum) = (wrap_u.into(), wrap_v.into());
+        gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_WRAP_S, wrap_u as GLint);
+        gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_WRAP_T, wrap_v as GLint);
+
+        let (min_filter, mag_filter) = types::texture_filters(min_filter, mag_filter, levels);
+        gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MIN_FILTER, min_filter as GLint);
+        gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MAG_FILTER, mag_filter as GLint);
+
         if levels > 1 {
             gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_BASE_LEVEL, 0);
             gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MAX_LEVEL, (levels - 1) as GLint);
@@ -2,13 +2,31 @@ use super::super::assets::prelude::*;
 use super::{UniformVar, Visitor};
 
 use crate::errors::*;
-use crate::math::prelude::{Aabb2, Vector2};
-
-pub struct HeadlessVisitor {}
+use crate::math::prelude::{Aabb2, Color, Vector2};
+use crate::utils::hash::{FastHashMap, FastHashSet};
+
+// Accepts multisampled render textures like common desktop GPUs.
+const MAX_SAMPLES: u32 = 16;
+
+/// A visitor that draws nothing. It still keeps the parameters of meshes, so draw calls
+/// are validated and counted like the real backends.
+///
+/// Queries always measure 0, and their results become available in the next frame after
+/// they are ended, like the results that lag behind on GPU.
+pub struct HeadlessVisitor {
+    meshes: FastHashMap<MeshHandle, MeshParams>,
+    // The queries and whether they have been ended.
+    queries: FastHashMap<QueryHandle, (QueryKind, bool)>,
+    availables: FastHashSet<QueryHandle>,
+}
 
 impl HeadlessVisitor {
     pub fn new() -> Self {
-        HeadlessVisitor {}
+        HeadlessVisitor {
+            meshes: FastHashMap::default(),
+            queries: FastHashMap::default(),
+            availables: FastHashSet::default(),
+        }
     }
 }
 
@@ -17,6 +35,16 @@ impl Visitor for HeadlessVisitor {
         Ok(())
     }
 
+    unsafe fn update_surface_clear(
+        &mut self,
+        _: SurfaceHandle,
+        _: Option<Color<f32>>,
+        _: Option<f32>,
+        _: Option<i32>,
+    ) -> Result<()> {
+        Ok(())
+    }
+
     unsafe fn delete_surface(&mut self, _: SurfaceHandle) -> Result<()> {
         Ok(())
     }
@@ -64,12 +92,17 @@ impl Visitor for HeadlessVisitor {
         Ok(())
     }
 
+    fn max_samples(&self) -> u32 {
+        MAX_SAMPLES
+    }
+
     unsafe fn create_mesh(
         &mut self,
-        _: MeshHandle,
-        _: MeshParams,
+        handle: MeshHandle,
+        params: MeshParams,
         _: Option<MeshData>,
     ) -> Result<()> {
+        self.meshes.insert(handle, params);
         Ok(())
     }
 
@@ -81,7 +114,8 @@ impl Visitor for HeadlessVisitor {
         Ok(())
     }
 
-    unsafe fn delete_mesh(&mut self, _: MeshHandle) -> Result<()> {
+    unsafe fn delete_mesh(&mut self, handle: MeshHandle) -> Result<()> {
+        self.meshes.remove(&handle);
         Ok(())
     }
 
@@ -92,11 +126,18 @@ impl Visitor for HeadlessVisitor {
     unsafe fn draw(
         &mut self,
         _: ShaderHandle,
-        _: MeshHandle,
-        _: MeshIndex,
+        mesh: MeshHandle,
+        mesh_index: MeshIndex,
         _: &[UniformVar],
     ) -> Result<u32> {
-        Ok(0)
+        if let Some(params) = self.meshes.get(&mesh) {
+            let (_, len) = params
+                .index_range(mesh_index)
+                .map_err(|_| format_err!("{:?} is out of bounds.", mesh_index))?;
+            Ok(params.primitive.assemble(len as u32))
+        } else {
+            Ok(0)
+        }
     }
 
     unsafe fn update_surface_scissor(&mut self, _: SurfaceScissor) -> Result<()> {
@@ -107,11 +148,61 @@ impl Visitor for HeadlessVisitor {
         Ok(())
     }
 
+    unsafe fn read_pixels(
+        &mut self,
+        _: Option<SurfaceHandle>,
+        dimensions: Vector2<u32>,
+    ) -> Result<(Vector2<u32>, Vec<u8>)> {
+        let len = dimensions.x as usize * dimensions.y as usize * 4;
+        Ok((dimensions, vec![0; len]))
+    }
+
+    unsafe fn begin_query(&mut self, handle: QueryHandle, kind: QueryKind) -> Result<()> {
+        if self.queries.values().any(|&(k, ended)| k == kind && !ended) {
+            bail!("Another query of {:?} is still running.", kind);
+        }
+
+        if self.queries.insert(handle, (kind, false)).is_some() {
+            bail!("{:?} has been begun already.", handle);
+        }
+
+        Ok(())
+    }
+
+    unsafe fn end_query(&mut self, handle: QueryHandle) -> Result<()> {
+        match self.queries.get_mut(&handle) {
+            Some((_, ended)) if !*ended => *ended = true,
+            _ => bail!("{:?} is not running.", handle),
+        }
+
+        Ok(())
+    }
+
+    unsafe fn query_result(&mut self, handle: QueryHandle) -> Result<Option<u64>> {
+        Ok(if self.availables.contains(&handle) {
+            Some(0)
+        } else {
+            None
+        })
+    }
+
+    unsafe fn delete_query(&mut self, handle: QueryHandle) -> Result<()> {
+        self.queries.remove(&handle);
+        self.availables.remove(&handle);
+        Ok(())
+    }
+
     unsafe fn flush(&mut self) -> Result<()> {
         Ok(())
     }
 
     unsafe fn advance(&mut self) -> Result<()> {
+        for (&handle, &(_, ended)) in &self.queries {
+            if ended {
+                self.availables.insert(handle);
+            }
+        }
+
         Ok(())
     }
 }
@@ -3,12 +3,14 @@
 
 pub mod frame;
 pub mod headless;
+#[cfg(feature = "software")]
+pub mod software;
 mod utils;
 
 use super::assets::prelude::*;
 
 use crate::errors::*;
-use crate::math::prelude::{Aabb2, Vector2};
+use crate::math::prelude::{Aabb2, Color, Vector2};
 use crate::utils::hash_value::HashValue;
 
 pub type UniformVar = (HashValue<str>, UniformVariable);
@@ -17,6 +19,14 @@ pub trait Visitor {
     unsafe fn create_surface(&mut self, handle: SurfaceHandle, params: SurfaceParams)
         -> Result<()>;
 
+    unsafe fn update_surface_clear(
+        &mut self,
+        handle: SurfaceHandle,
+        color: Option<Color<f32>>,
+        depth: Option<f32>,
+        stencil: Option<i32>,
+    ) -> Result<()>;
+
     unsafe fn delete_surface(&mut self, handle: SurfaceHandle) -> Result<()>;
 
     unsafe fn create_shader(
@@ -53,6 +63,10 @@ pub trait Visitor {
 
     unsafe fn delete_render_texture(&mut self, handle: RenderTextureHandle) -> Result<()>;
 
+    /// Gets the maximum number of samples of multisampled render textures, `0` if
+    /// multisampling is not supported.
+    fn max_samples(&self) -> u32;
+
     unsafe fn create_mesh(
         &mut self,
         handle: MeshHandle,
@@ -90,6 +104,26 @@ pub trait Visitor {
 
     unsafe fn update_surface_viewport(&mut self, vp: SurfaceViewport) -> Result<()>;
 
+    /// Reads the RGBA8 pixels of surface in bottom-to-top row order. The window framebuffer
+    /// with `dimensions` is used if `surface` is none.
+    unsafe fn read_pixels(
+        &mut self,
+        surface: Option<SurfaceHandle>,
+        dimensions: Vector2<u32>,
+    ) -> Result<(Vector2<u32>, Vec<u8>)>;
+
+    /// Starts a query which measures the draw calls until `end_query`. Only one query of
+    /// each kind could be active at the same time.
+    unsafe fn begin_query(&mut self, handle: QueryHandle, kind: QueryKind) -> Result<()>;
+
+    unsafe fn end_query(&mut self, handle: QueryHandle) -> Result<()>;
+
+    /// Polls the result of a ended query without blocking. Returns `None` if it's not
+    /// available yet.
+    unsafe fn query_result(&mut self, handle: QueryHandle) -> Result<Option<u64>>;
+
+    unsafe fn delete_query(&mut self, handle: QueryHandle) -> Result<()>;
+
     /// Blocks until all execution is complete. Such effects include all changes to render state, all
     /// changes to connection state, and all changes to the frame buffer contents.
     unsafe fn flush(&mut self) -> Result<()>;
@@ -119,3 +153,10 @@ pub fn new() -> Result<Box<Visitor>> {
 pub fn new_headless() -> Box<Visitor> {
     Box::new(self::headless::HeadlessVisitor::new())
 }
+
+/// Creates a backend that rasterizes into memory, so the rendering results could be
+/// checked without GPU.
+#[cfg(feature = "software")]
+pub fn new_software() -> Box<Visitor> {
+    Box::new(self::software::SoftwareVisitor::new())
+}
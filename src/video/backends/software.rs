@@ -0,0 +1,1095 @@
+//! A minimal software rasterizer, which makes it possible to check the rendering results
+//! with `read_pixels` on machines without any GPU, e.g. the CI servers.
+//!
+//! Shaders are NOT executed. Triangles are filled with the flat color of uniform
+//! `u_Color`(white by default), and the `Position` attribute of vertices, which should be
+//! floats, is treated as the position in clip space directly. The depth and stencil tests
+//! of `RenderState` are honored with the depth and stencil buffers that come along with
+//! each color buffer, and so are the color write masks, while blending and face culling
+//! are ignored.
+//!
+//! Multisampled render textures are rasterized with several samples per pixel, and
+//! resolved into their textures by averaging the samples when the surface is unbound or
+//! read, like the resolve step of GL backend.
+//!
+//! Queries are measured on CPU, e.g. the samples written by the rasterizer, and their
+//! results become available in the next frame after they are ended.
+
+use std::time::Instant;
+
+use byteorder::{ByteOrder, NativeEndian};
+
+use super::super::assets::prelude::*;
+use super::{UniformVar, Visitor};
+
+use crate::errors::*;
+use crate::math::prelude::{Aabb2, Color, Vector2, Vector3};
+use crate::utils::hash::{FastHashMap, FastHashSet};
+use crate::utils::hash_value::HashValue;
+
+const MAX_SAMPLES: u32 = 16;
+
+struct Framebuffer {
+    dimensions: Vector2<u32>,
+    samples: u32,
+    pixels: Vec<u8>,
+    depths: Vec<f32>,
+    stencils: Vec<u8>,
+}
+
+impl Framebuffer {
+    fn new(dimensions: Vector2<u32>) -> Self {
+        Framebuffer::with_samples(dimensions, 1)
+    }
+
+    fn with_samples(dimensions: Vector2<u32>, samples: u32) -> Self {
+        let len = dimensions.x as usize * dimensions.y as usize * samples as usize;
+        Framebuffer {
+            dimensions,
+            samples,
+            pixels: vec![0; len * 4],
+            depths: vec![1.0; len],
+            stencils: vec![0; len],
+        }
+    }
+
+    // Averages the samples of every pixel into `dst`, which is single-sampled.
+    fn resolve(&self, dst: &mut Framebuffer) {
+        let n = self.samples as usize;
+        for (i, v) in dst.pixels.chunks_mut(4).enumerate() {
+            for (c, v) in v.iter_mut().enumerate() {
+                let sum: usize = (0..n)
+                    .map(|s| self.pixels[(i * n + s) * 4 + c] as usize)
+                    .sum();
+                *v = ((sum + n / 2) / n) as u8;
+            }
+        }
+    }
+
+    fn clear(&mut self, color: Option<Color<f32>>, depth: Option<f32>, stencil: Option<i32>) {
+        if let Some(color) = color {
+            let rgba: [u8; 4] = color.into();
+            for v in self.pixels.chunks_mut(4) {
+                v.copy_from_slice(&rgba);
+            }
+        }
+
+        if let Some(depth) = depth {
+            let depth = depth.clamp(0.0, 1.0);
+            for v in &mut self.depths {
+                *v = depth;
+            }
+        }
+
+        if let Some(stencil) = stencil {
+            for v in &mut self.stencils {
+                *v = stencil as u8;
+            }
+        }
+    }
+}
+
+struct RenderTexture {
+    texture: Framebuffer,
+    // The multisampled buffer that draw calls go into, if `samples` is greater than 1.
+    msaa: Option<Framebuffer>,
+}
+
+struct Mesh {
+    params: MeshParams,
+    vptr: Vec<u8>,
+    iptr: Vec<u8>,
+}
+
+struct Query {
+    kind: QueryKind,
+    start: Instant,
+    value: u64,
+    ended: bool,
+    available: bool,
+}
+
+/// A visitor that rasterizes flat-shaded triangles into framebuffers in memory.
+pub struct SoftwareVisitor {
+    surfaces: FastHashMap<SurfaceHandle, SurfaceParams>,
+    shaders: FastHashMap<ShaderHandle, RenderState>,
+    render_textures: FastHashMap<RenderTextureHandle, RenderTexture>,
+    meshes: FastHashMap<MeshHandle, Mesh>,
+    window: Framebuffer,
+    dimensions: Vector2<u32>,
+    cleared_surfaces: FastHashSet<SurfaceHandle>,
+    binded_surface: Option<SurfaceHandle>,
+    viewport: SurfaceViewport,
+    scissor: SurfaceScissor,
+    queries: FastHashMap<QueryHandle, Query>,
+}
+
+impl SoftwareVisitor {
+    pub fn new() -> Self {
+        SoftwareVisitor {
+            surfaces: FastHashMap::default(),
+            shaders: FastHashMap::default(),
+            render_textures: FastHashMap::default(),
+            meshes: FastHashMap::default(),
+            window: Framebuffer::new(Vector2::new(0, 0)),
+            dimensions: Vector2::new(0, 0),
+            cleared_surfaces: FastHashSet::default(),
+            binded_surface: None,
+            viewport: SurfaceViewport {
+                position: Vector2::new(0, 0),
+                size: Vector2::new(0, 0),
+            },
+            scissor: SurfaceScissor::Disable,
+            queries: FastHashMap::default(),
+        }
+    }
+
+    // Gets the render texture of the first color attachment of surface.
+    fn attachment(&self, surface: Option<SurfaceHandle>) -> Result<Option<RenderTextureHandle>> {
+        if let Some(handle) = surface {
+            let params = self
+                .surfaces
+                .get(&handle)
+                .ok_or_else(|| format_err!("{:?} is invalid.", handle))?;
+            Ok(params.colors[0])
+        } else {
+            Ok(None)
+        }
+    }
+
+    // Gets the framebuffer that the first color attachment of surface refers to, or the
+    // window framebuffer which is resized to `dimensions` if there is no attachment. The
+    // multisampled buffer is returned instead of the texture if `msaa` is true.
+    fn framebuffer(
+        &mut self,
+        surface: Option<SurfaceHandle>,
+        dimensions: Vector2<u32>,
+        msaa: bool,
+    ) -> Result<&mut Framebuffer> {
+        if let Some(handle) = self.attachment(surface)? {
+            let rt = self
+                .render_textures
+                .get_mut(&handle)
+                .ok_or_else(|| format_err!("{:?} is invalid.", handle))?;
+
+            return Ok(match rt.msaa {
+                Some(ref mut fb) if msaa => fb,
+                _ => &mut rt.texture,
+            });
+        }
+
+        if self.window.dimensions != dimensions {
+            self.window = Framebuffer::new(dimensions);
+        }
+
+        Ok(&mut self.window)
+    }
+
+    // Resolves the multisampled buffer of the binded surface into its texture.
+    fn resolve_binded_surface(&mut self) -> Result<()> {
+        if let Some(handle) = self.attachment(self.binded_surface)? {
+            if let Some(rt) = self.render_textures.get_mut(&handle) {
+                if let Some(ref msaa) = rt.msaa {
+                    msaa.resolve(&mut rt.texture);
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl Default for SoftwareVisitor {
+    fn default() -> Self {
+        SoftwareVisitor::new()
+    }
+}
+
+impl Visitor for SoftwareVisitor {
+    unsafe fn create_surface(
+        &mut self,
+        handle: SurfaceHandle,
+        params: SurfaceParams,
+    ) -> Result<()> {
+        self.surfaces.insert(handle, params);
+        Ok(())
+    }
+
+    unsafe fn update_surface_clear(
+        &mut self,
+        handle: SurfaceHandle,
+        color: Option<Color<f32>>,
+        depth: Option<f32>,
+        stencil: Option<i32>,
+    ) -> Result<()> {
+        let params = self
+            .surfaces
+            .get_mut(&handle)
+            .ok_or_else(|| format_err!("{:?} is invalid.", handle))?;
+
+        params.clear_color = color;
+        params.clear_depth = depth;
+        params.clear_stencil = stencil;
+        Ok(())
+    }
+
+    unsafe fn delete_surface(&mut self, handle: SurfaceHandle) -> Result<()> {
+        self.surfaces.remove(&handle);
+        self.cleared_surfaces.remove(&handle);
+        if self.binded_surface == Some(handle) {
+            self.binded_surface = None;
+        }
+
+        Ok(())
+    }
+
+    unsafe fn create_shader(
+        &mut self,
+        handle: ShaderHandle,
+        params: ShaderParams,
+        _: &str,
+        _: &str,
+    ) -> Result<()> {
+        self.shaders.insert(handle, params.state);
+        Ok(())
+    }
+
+    unsafe fn delete_shader(&mut self, handle: ShaderHandle) -> Result<()> {
+        self.shaders.remove(&handle);
+        Ok(())
+    }
+
+    unsafe fn create_texture(
+        &mut self,
+        _: TextureHandle,
+        _: TextureParams,
+        _: Option<TextureData>,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    unsafe fn update_texture(&mut self, _: TextureHandle, _: Aabb2<u32>, _: &[u8]) -> Result<()> {
+        Ok(())
+    }
+
+    unsafe fn delete_texture(&mut self, _: TextureHandle) -> Result<()> {
+        Ok(())
+    }
+
+    unsafe fn create_render_texture(
+        &mut self,
+        handle: RenderTextureHandle,
+        params: RenderTextureParams,
+    ) -> Result<()> {
+        let msaa = if params.is_multisampled() {
+            let samples = u32::from(params.samples);
+            Some(Framebuffer::with_samples(params.dimensions, samples))
+        } else {
+            None
+        };
+
+        let rt = RenderTexture {
+            texture: Framebuffer::new(params.dimensions),
+            msaa,
+        };
+
+        self.render_textures.insert(handle, rt);
+        Ok(())
+    }
+
+    unsafe fn delete_render_texture(&mut self, handle: RenderTextureHandle) -> Result<()> {
+        self.render_textures.remove(&handle);
+        Ok(())
+    }
+
+    fn max_samples(&self) -> u32 {
+        MAX_SAMPLES
+    }
+
+    unsafe fn create_mesh(
+        &mut self,
+        handle: MeshHandle,
+        params: MeshParams,
+        data: Option<MeshData>,
+    ) -> Result<()> {
+        let mut vptr = vec![0; params.vertex_buffer_len()];
+        let mut iptr = vec![0; params.index_buffer_len()];
+
+        if let Some(data) = data {
+            vptr[..data.vptr.len()].copy_from_slice(&data.vptr);
+            iptr[..data.iptr.len()].copy_from_slice(&data.iptr);
+        }
+
+        self.meshes.insert(handle, Mesh { params, vptr, iptr });
+        Ok(())
+    }
+
+    unsafe fn update_vertex_buffer(
+        &mut self,
+        handle: MeshHandle,
+        o: usize,
+        bytes: &[u8],
+    ) -> Result<()> {
+        let mesh = self
+            .meshes
+            .get_mut(&handle)
+            .ok_or_else(|| format_err!("{:?} is invalid.", handle))?;
+
+        if o + bytes.len() > mesh.vptr.len() {
+            bail!("Out of bounds!");
+        }
+
+        mesh.vptr[o..o + bytes.len()].copy_from_slice(bytes);
+        Ok(())
+    }
+
+    unsafe fn update_index_buffer(
+        &mut self,
+        handle: MeshHandle,
+        o: usize,
+        bytes: &[u8],
+    ) -> Result<()> {
+        let mesh = self
+            .meshes
+            .get_mut(&handle)
+            .ok_or_else(|| format_err!("{:?} is invalid.", handle))?;
+
+        if o + bytes.len() > mesh.iptr.len() {
+            bail!("Out of bounds!");
+        }
+
+        mesh.iptr[o..o + bytes.len()].copy_from_slice(bytes);
+        Ok(())
+    }
+
+    unsafe fn delete_mesh(&mut self, handle: MeshHandle) -> Result<()> {
+        self.meshes.remove(&handle);
+        Ok(())
+    }
+
+    unsafe fn bind(&mut self, handle: SurfaceHandle, dimensions: Vector2<u32>) -> Result<()> {
+        if self.binded_surface == Some(handle) {
+            return Ok(());
+        }
+
+        self.resolve_binded_surface()?;
+        let (color, depth, stencil) = self
+            .surfaces
+            .get(&handle)
+            .ok_or_else(|| format_err!("{:?} is invalid.", handle))?
+            .clear();
+
+        self.dimensions = dimensions;
+        let cleared = !self.cleared_surfaces.insert(handle);
+        let fb = self.framebuffer(Some(handle), dimensions, true)?;
+        if !cleared {
+            fb.clear(color, depth, stencil);
+        }
+
+        self.viewport = SurfaceViewport {
+            position: Vector2::new(0, 0),
+            size: fb.dimensions,
+        };
+
+        self.scissor = SurfaceScissor::Disable;
+        self.binded_surface = Some(handle);
+        Ok(())
+    }
+
+    unsafe fn draw(
+        &mut self,
+        shader: ShaderHandle,
+        mesh: MeshHandle,
+        mesh_index: MeshIndex,
+        vars: &[UniformVar],
+    ) -> Result<u32> {
+        let surface = self
+            .binded_surface
+            .ok_or_else(|| format_err!("No surface is binded."))?;
+
+        let color_name: HashValue<str> = "u_Color".into();
+        let color = vars
+            .iter()
+            .find(|v| v.0 == color_name)
+            .map(|v| match v.1 {
+                UniformVariable::Vector3f(v) => Color::new(v[0], v[1], v[2], 1.0),
+                UniformVariable::Vector4f(v) => Color::new(v[0], v[1], v[2], v[3]),
+                _ => Color::white(),
+            })
+            .unwrap_or_else(Color::white);
+
+        let state = *self
+            .shaders
+            .get(&shader)
+            .ok_or_else(|| format_err!("{:?} is invalid.", shader))?;
+
+        let (viewport, scissor) = (self.viewport, self.scissor);
+        let (params, positions, idxes) = {
+            let mesh = self
+                .meshes
+                .get(&mesh)
+                .ok_or_else(|| format_err!("{:?} is invalid.", mesh))?;
+
+            let (from, len) = mesh
+                .params
+                .index_range(mesh_index)
+                .map_err(|_| format_err!("{:?} is out of bounds.", mesh_index))?;
+
+            let stride = mesh.params.index_format.stride();
+            let idxes = mesh
+                .params
+                .index_format
+                .unpack(&mesh.iptr[from * stride..(from + len) * stride]);
+
+            (mesh.params.clone(), positions(mesh)?, idxes)
+        };
+
+        let mut samples = 0;
+        let fb = self.framebuffer(Some(surface), self.dimensions, true)?;
+        if params.primitive == MeshPrimitive::Triangles {
+            let rgba: [u8; 4] = color.into();
+            for tri in idxes.chunks(3).filter(|v| v.len() == 3) {
+                let mut vertices = [Vector3::new(0.0, 0.0, 0.0); 3];
+                for (i, &index) in tri.iter().enumerate() {
+                    let v = positions
+                        .get(index as usize)
+                        .ok_or_else(|| format_err!("Index {} is out of bounds.", index))?;
+
+                    vertices[i] = to_screen(*v, viewport);
+                }
+
+                let rect = clip_rect(fb, viewport, scissor);
+                samples += rasterize(fb, vertices, rect, &state, rgba);
+            }
+        }
+
+        for v in self.queries.values_mut() {
+            if v.kind == QueryKind::SamplesPassed && !v.ended {
+                v.value += samples;
+            }
+        }
+
+        Ok(params.primitive.assemble(idxes.len() as u32))
+    }
+
+    unsafe fn update_surface_scissor(&mut self, scissor: SurfaceScissor) -> Result<()> {
+        self.scissor = scissor;
+        Ok(())
+    }
+
+    unsafe fn update_surface_viewport(&mut self, vp: SurfaceViewport) -> Result<()> {
+        self.viewport = vp;
+        Ok(())
+    }
+
+    unsafe fn read_pixels(
+        &mut self,
+        surface: Option<SurfaceHandle>,
+        dimensions: Vector2<u32>,
+    ) -> Result<(Vector2<u32>, Vec<u8>)> {
+        self.resolve_binded_surface()?;
+        let fb = self.framebuffer(surface, dimensions, false)?;
+        Ok((fb.dimensions, fb.pixels.clone()))
+    }
+
+    unsafe fn begin_query(&mut self, handle: QueryHandle, kind: QueryKind) -> Result<()> {
+        if self.queries.values().any(|v| v.kind == kind && !v.ended) {
+            bail!("Another query of {:?} is still running.", kind);
+        }
+
+        if self.queries.contains_key(&handle) {
+            bail!("{:?} has been begun already.", handle);
+        }
+
+        let query = Query {
+            kind,
+            start: Instant::now(),
+            value: 0,
+            ended: false,
+            available: false,
+        };
+
+        self.queries.insert(handle, query);
+        Ok(())
+    }
+
+    unsafe fn end_query(&mut self, handle: QueryHandle) -> Result<()> {
+        match self.queries.get_mut(&handle) {
+            Some(v) if !v.ended => {
+                if v.kind == QueryKind::TimeElapsed {
+                    v.value = v.start.elapsed().as_nanos() as u64;
+                }
+
+                v.ended = true;
+                Ok(())
+            }
+            _ => bail!("{:?} is not running.", handle),
+        }
+    }
+
+    unsafe fn query_result(&mut self, handle: QueryHandle) -> Result<Option<u64>> {
+        Ok(self
+            .queries
+            .get(&handle)
+            .filter(|v| v.available)
+            .map(|v| v.value))
+    }
+
+    unsafe fn delete_query(&mut self, handle: QueryHandle) -> Result<()> {
+        self.queries.remove(&handle);
+        Ok(())
+    }
+
+    unsafe fn flush(&mut self) -> Result<()> {
+        Ok(())
+    }
+
+    unsafe fn advance(&mut self) -> Result<()> {
+        self.resolve_binded_surface()?;
+        self.cleared_surfaces.clear();
+        self.binded_surface = None;
+
+        for v in self.queries.values_mut() {
+            v.available = v.ended;
+        }
+
+        Ok(())
+    }
+}
+
+// Reads the positions of vertices in clip space as `(x, y, z, w)`.
+fn positions(mesh: &Mesh) -> Result<Vec<[f32; 4]>> {
+    let layout = &mesh.params.layout;
+    let (element, offset) = match (
+        layout.element(Attribute::Position),
+        layout.offset(Attribute::Position),
+    ) {
+        (Some(element), Some(offset)) if element.size >= 2 => (element, offset as usize),
+        _ => bail!("Vertex attribute Position is required to rasterize meshes."),
+    };
+
+    if element.format != VertexFormat::Float {
+        bail!("Vertex attribute Position should be floats to rasterize meshes.");
+    }
+
+    let stride = layout.stride() as usize;
+    let mut positions = Vec::with_capacity(mesh.params.num_verts);
+    for i in 0..mesh.params.num_verts {
+        let bytes = &mesh.vptr[i * stride + offset..];
+        let x = NativeEndian::read_f32(&bytes[0..]);
+        let y = NativeEndian::read_f32(&bytes[4..]);
+        let z = if element.size >= 3 {
+            NativeEndian::read_f32(&bytes[8..])
+        } else {
+            0.0
+        };
+
+        let w = if element.size == 4 {
+            NativeEndian::read_f32(&bytes[12..])
+        } else {
+            1.0
+        };
+
+        positions.push([x, y, z, w]);
+    }
+
+    Ok(positions)
+}
+
+// Converts the position in clip space into window coordinates with depth in [0, 1].
+fn to_screen(v: [f32; 4], viewport: SurfaceViewport) -> Vector3<f32> {
+    let (x, y, z) = (v[0] / v[3], v[1] / v[3], v[2] / v[3]);
+    Vector3::new(
+        viewport.position.x as f32 + (x + 1.0) * 0.5 * viewport.size.x as f32,
+        viewport.position.y as f32 + (y + 1.0) * 0.5 * viewport.size.y as f32,
+        ((z + 1.0) * 0.5).clamp(0.0, 1.0),
+    )
+}
+
+fn depth_test(comparison: Comparison, src: f32, dst: f32) -> bool {
+    match comparison {
+        Comparison::Never => false,
+        Comparison::Less => src < dst,
+        Comparison::LessOrEqual => src <= dst,
+        Comparison::Greater => src > dst,
+        Comparison::GreaterOrEqual => src >= dst,
+        Comparison::Equal => (src - dst).abs() < f32::EPSILON,
+        Comparison::NotEqual => (src - dst).abs() >= f32::EPSILON,
+        Comparison::Always => true,
+    }
+}
+
+fn stencil_test(stencil: &StencilState, dst: u8) -> bool {
+    let src = stencil.reference & stencil.read_mask;
+    let dst = dst & stencil.read_mask;
+    match stencil.comparison {
+        Comparison::Never => false,
+        Comparison::Less => src < dst,
+        Comparison::LessOrEqual => src <= dst,
+        Comparison::Greater => src > dst,
+        Comparison::GreaterOrEqual => src >= dst,
+        Comparison::Equal => src == dst,
+        Comparison::NotEqual => src != dst,
+        Comparison::Always => true,
+    }
+}
+
+// Applies `op` on the stored stencil value, only the bits of `write_mask` are changed.
+fn stencil_op(stencil: &StencilState, op: StencilOp, dst: &mut u8) {
+    let v = match op {
+        StencilOp::Keep => *dst,
+        StencilOp::Zero => 0,
+        StencilOp::Replace => stencil.reference,
+        StencilOp::Increment => dst.saturating_add(1),
+        StencilOp::IncrementWrap => dst.wrapping_add(1),
+        StencilOp::Decrement => dst.saturating_sub(1),
+        StencilOp::DecrementWrap => dst.wrapping_sub(1),
+        StencilOp::Invert => !*dst,
+    };
+
+    *dst = (*dst & !stencil.write_mask) | (v & stencil.write_mask);
+}
+
+// Gets the `[min, max)` of pixels that could be written.
+fn clip_rect(
+    fb: &Framebuffer,
+    viewport: SurfaceViewport,
+    scissor: SurfaceScissor,
+) -> (Vector2<i32>, Vector2<i32>) {
+    let mut min = Vector2::new(viewport.position.x.max(0), viewport.position.y.max(0));
+    let mut max = Vector2::new(
+        (viewport.position.x + viewport.size.x as i32).min(fb.dimensions.x as i32),
+        (viewport.position.y + viewport.size.y as i32).min(fb.dimensions.y as i32),
+    );
+
+    if let SurfaceScissor::Enable { position, size } = scissor {
+        min.x = min.x.max(position.x);
+        min.y = min.y.max(position.y);
+        max.x = max.x.min(position.x + size.x as i32);
+        max.y = max.y.min(position.y + size.y as i32);
+    }
+
+    (min, max)
+}
+
+// Gets the position of `i`th sample in a pixel with `n` samples. The samples are spread
+// on distinct rows and columns of a `n * n` grid.
+fn sample_position(i: usize, n: usize) -> (f32, f32) {
+    if n <= 1 {
+        return (0.5, 0.5);
+    }
+
+    let x = (i as f32 + 0.5) / n as f32;
+    let y = (((i * 3) % n) as f32 + 0.5) / n as f32;
+    (x, y)
+}
+
+// Fills the samples that are inside of the triangle, or on its edges, and pass the stencil
+// and depth tests. Returns the number of filled samples.
+fn rasterize(
+    fb: &mut Framebuffer,
+    v: [Vector3<f32>; 3],
+    rect: (Vector2<i32>, Vector2<i32>),
+    state: &RenderState,
+    rgba: [u8; 4],
+) -> u64 {
+    let edge = |a: Vector3<f32>, b: Vector3<f32>, p: Vector3<f32>| {
+        (b.x - a.x) * (p.y - a.y) - (b.y - a.y) * (p.x - a.x)
+    };
+
+    let area = edge(v[0], v[1], v[2]);
+    if area == 0.0 || !area.is_finite() {
+        return 0;
+    }
+
+    let mut samples = 0;
+
+    let xmin = v.iter().fold(f32::MAX, |acc, p| acc.min(p.x)).floor() as i32;
+    let ymin = v.iter().fold(f32::MAX, |acc, p| acc.min(p.y)).floor() as i32;
+    let xmax = v.iter().fold(f32::MIN, |acc, p| acc.max(p.x)).ceil() as i32;
+    let ymax = v.iter().fold(f32::MIN, |acc, p| acc.max(p.y)).ceil() as i32;
+
+    let sign = area.signum();
+    let n = fb.samples as usize;
+    for y in ymin.max(rect.0.y)..ymax.min(rect.1.y) {
+        for x in xmin.max(rect.0.x)..xmax.min(rect.1.x) {
+            let pixel = y as usize * fb.dimensions.x as usize + x as usize;
+            for s in 0..n {
+                let (sx, sy) = sample_position(s, n);
+                let p = Vector3::new(x as f32 + sx, y as f32 + sy, 0.0);
+                let w = [
+                    edge(v[1], v[2], p),
+                    edge(v[2], v[0], p),
+                    edge(v[0], v[1], p),
+                ];
+                if w.iter().any(|&w| w * sign < 0.0) {
+                    continue;
+                }
+
+                let i = pixel * n + s;
+                if let Some(ref stencil) = state.stencil {
+                    if !stencil_test(stencil, fb.stencils[i]) {
+                        stencil_op(stencil, stencil.fail, &mut fb.stencils[i]);
+                        continue;
+                    }
+                }
+
+                let depth = (w[0] * v[0].z + w[1] * v[1].z + w[2] * v[2].z) / area;
+                let passed = depth_test(state.depth_test, depth, fb.depths[i]);
+                if let Some(ref stencil) = state.stencil {
+                    let op = if passed {
+                        stencil.pass
+                    } else {
+                        stencil.depth_fail
+                    };
+
+                    stencil_op(stencil, op, &mut fb.stencils[i]);
+                }
+
+                if !passed {
+                    continue;
+                }
+
+                if state.depth_write {
+                    fb.depths[i] = depth;
+                }
+
+                let (r, g, b, a) = state.color_write;
+                for (c, &write) in [r, g, b, a].iter().enumerate() {
+                    if write {
+                        fb.pixels[i * 4 + c] = rgba[c];
+                    }
+                }
+
+                samples += 1;
+            }
+        }
+    }
+
+    samples
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::utils::prelude::HandleLike;
+
+    #[test]
+    fn triangle() {
+        let mut visitor = SoftwareVisitor::new();
+        let rt = RenderTextureHandle::new(1, 1);
+        let surface = SurfaceHandle::new(1, 1);
+        let shader = ShaderHandle::new(1, 1);
+        let mesh = MeshHandle::new(1, 1);
+
+        let mut params = MeshParams {
+            layout: VertexLayout::build()
+                .with(Attribute::Position, VertexFormat::Float, 2, false)
+                .finish(),
+            num_idxes: 3,
+            ..Default::default()
+        };
+
+        params.set_num_verts(3);
+
+        let verts: [f32; 6] = [-1.0, -1.0, 1.0, -1.0, -1.0, 1.0];
+        let data = MeshData {
+            vptr: IndexFormat::encode(&verts).to_vec().into_boxed_slice(),
+            iptr: IndexFormat::encode(&[0u16, 1, 2])
+                .to_vec()
+                .into_boxed_slice(),
+        };
+
+        let red = [255, 0, 0, 255];
+        let vars = [(
+            "u_Color".into(),
+            UniformVariable::Vector4f([1.0, 0.0, 0.0, 1.0]),
+        )];
+
+        unsafe {
+            let rt_params = RenderTextureParams {
+                format: RenderTextureFormat::RGBA8,
+                dimensions: Vector2::new(4, 4),
+                ..Default::default()
+            };
+
+            let mut surface_params = SurfaceParams::default();
+            surface_params.colors[0] = Some(rt);
+
+            visitor.create_render_texture(rt, rt_params).unwrap();
+            visitor.create_surface(surface, surface_params).unwrap();
+            visitor.create_mesh(mesh, params, Some(data)).unwrap();
+            let shader_params = ShaderParams::default();
+            visitor
+                .create_shader(shader, shader_params, "", "")
+                .unwrap();
+
+            let query = QueryHandle::new(1, 1);
+            visitor.bind(surface, Vector2::new(8, 8)).unwrap();
+            visitor
+                .begin_query(query, QueryKind::SamplesPassed)
+                .unwrap();
+            let tris = visitor.draw(shader, mesh, MeshIndex::All, &vars).unwrap();
+            assert_eq!(tris, 1);
+            visitor.end_query(query).unwrap();
+            assert_eq!(visitor.query_result(query).unwrap(), None);
+
+            let (dimensions, pixels) = visitor
+                .read_pixels(Some(surface), Vector2::new(8, 8))
+                .unwrap();
+            assert_eq!(dimensions, Vector2::new(4, 4));
+
+            // The triangle covers the lower left half, rows are read from bottom to top.
+            for y in 0..4 {
+                for x in 0..4 {
+                    let i = (y * 4 + x) * 4;
+                    let expected = if x + y <= 3 { red } else { [0, 0, 0, 255] };
+                    assert_eq!(&pixels[i..i + 4], &expected, "({}, {})", x, y);
+                }
+            }
+
+            // The surface is cleared again in next frame.
+            visitor.advance().unwrap();
+            assert_eq!(visitor.query_result(query).unwrap(), Some(10));
+
+            visitor.bind(surface, Vector2::new(8, 8)).unwrap();
+            let (_, pixels) = visitor
+                .read_pixels(Some(surface), Vector2::new(8, 8))
+                .unwrap();
+            assert!(pixels.chunks(4).all(|v| v == [0, 0, 0, 255]));
+        }
+    }
+
+    #[test]
+    fn reverse_z() {
+        let mut visitor = SoftwareVisitor::new();
+        let rt = RenderTextureHandle::new(1, 1);
+        let surface = SurfaceHandle::new(1, 1);
+        let shader = ShaderHandle::new(1, 1);
+        let mesh = MeshHandle::new(1, 1);
+
+        let mut params = MeshParams {
+            layout: VertexLayout::build()
+                .with(Attribute::Position, VertexFormat::Float, 3, false)
+                .finish(),
+            num_idxes: 6,
+            ..Default::default()
+        };
+
+        params.set_num_verts(6);
+
+        // The nearer triangle has the greater depth with reverse-z.
+        let verts: [f32; 18] = [
+            -1.0, -1.0, 0.5, 1.0, -1.0, 0.5, -1.0, 1.0, 0.5, // near
+            -1.0, -1.0, -0.5, 1.0, -1.0, -0.5, -1.0, 1.0, -0.5, // far
+        ];
+
+        let data = MeshData {
+            vptr: IndexFormat::encode(&verts).to_vec().into_boxed_slice(),
+            iptr: IndexFormat::encode(&[0u16, 1, 2, 3, 4, 5])
+                .to_vec()
+                .into_boxed_slice(),
+        };
+
+        let (near, far) = (MeshIndex::Ptr(0, 3), MeshIndex::Ptr(3, 3));
+        let green = [("u_Color".into(), UniformVariable::Vector3f([0.0, 1.0, 0.0]))];
+        let red = [("u_Color".into(), UniformVariable::Vector3f([1.0, 0.0, 0.0]))];
+
+        unsafe {
+            let rt_params = RenderTextureParams {
+                format: RenderTextureFormat::RGBA8,
+                dimensions: Vector2::new(4, 4),
+                ..Default::default()
+            };
+
+            let mut surface_params = SurfaceParams::default();
+            surface_params.colors[0] = Some(rt);
+            surface_params.set_clear(Color::black(), 0.0, None);
+
+            let shader_params = ShaderParams {
+                state: RenderState {
+                    depth_test: Comparison::Greater,
+                    depth_write: true,
+                    ..Default::default()
+                },
+                ..Default::default()
+            };
+
+            visitor.create_render_texture(rt, rt_params).unwrap();
+            visitor.create_surface(surface, surface_params).unwrap();
+            visitor.create_mesh(mesh, params, Some(data)).unwrap();
+            visitor
+                .create_shader(shader, shader_params, "", "")
+                .unwrap();
+
+            for &(first, second) in &[(near, far), (far, near)] {
+                let (first_color, second_color) = if first == near {
+                    (&green, &red)
+                } else {
+                    (&red, &green)
+                };
+
+                visitor.bind(surface, Vector2::new(4, 4)).unwrap();
+                visitor.draw(shader, mesh, first, first_color).unwrap();
+                visitor.draw(shader, mesh, second, second_color).unwrap();
+
+                let (_, pixels) = visitor
+                    .read_pixels(Some(surface), Vector2::new(4, 4))
+                    .unwrap();
+
+                assert_eq!(&pixels[0..4], &[0, 255, 0, 255]);
+                assert_eq!(&pixels[60..64], &[0, 0, 0, 255]);
+                visitor.advance().unwrap();
+            }
+        }
+    }
+
+    #[test]
+    fn window_viewport() {
+        let mut visitor = SoftwareVisitor::new();
+        let surface = SurfaceHandle::new(1, 1);
+        let shader = ShaderHandle::new(1, 1);
+        let mesh = MeshHandle::new(1, 1);
+
+        let mut params = MeshParams {
+            layout: VertexLayout::build()
+                .with(Attribute::Position, VertexFormat::Float, 2, false)
+                .finish(),
+            num_idxes: 6,
+            ..Default::default()
+        };
+
+        params.set_num_verts(6);
+
+        // The second triangle covers the whole viewport, and beyond.
+        let verts: [f32; 12] = [
+            -1.0, -1.0, 1.0, -1.0, -1.0, 1.0, // lower left half
+            -1.0, -1.0, 3.0, -1.0, -1.0, 3.0, // all
+        ];
+
+        let data = MeshData {
+            vptr: IndexFormat::encode(&verts).to_vec().into_boxed_slice(),
+            iptr: IndexFormat::encode(&[0u16, 1, 2, 3, 4, 5])
+                .to_vec()
+                .into_boxed_slice(),
+        };
+
+        let red = [("u_Color".into(), UniformVariable::Vector3f([1.0, 0.0, 0.0]))];
+        let green = [("u_Color".into(), UniformVariable::Vector3f([0.0, 1.0, 0.0]))];
+        let dimensions = Vector2::new(8, 8);
+
+        unsafe {
+            visitor
+                .create_surface(surface, SurfaceParams::default())
+                .unwrap();
+            visitor.create_mesh(mesh, params, Some(data)).unwrap();
+            visitor
+                .create_shader(shader, ShaderParams::default(), "", "")
+                .unwrap();
+
+            visitor.bind(surface, dimensions).unwrap();
+            visitor
+                .draw(shader, mesh, MeshIndex::Ptr(0, 3), &red)
+                .unwrap();
+
+            // A smaller viewport neither resizes nor wipes the window framebuffer.
+            let vp = SurfaceViewport {
+                position: Vector2::new(0, 0),
+                size: Vector2::new(4, 4),
+            };
+
+            visitor.update_surface_viewport(vp).unwrap();
+            visitor
+                .draw(shader, mesh, MeshIndex::Ptr(3, 3), &green)
+                .unwrap();
+
+            let (size, pixels) = visitor.read_pixels(None, dimensions).unwrap();
+            assert_eq!(size, dimensions);
+
+            let pixel = |x: usize, y: usize| &pixels[(y * 8 + x) * 4..(y * 8 + x) * 4 + 4];
+            assert_eq!(pixel(0, 0), &[0, 255, 0, 255]);
+            assert_eq!(pixel(3, 3), &[0, 255, 0, 255]);
+            assert_eq!(pixel(4, 0), &[255, 0, 0, 255]);
+            assert_eq!(pixel(0, 7), &[255, 0, 0, 255]);
+            assert_eq!(pixel(7, 7), &[0, 0, 0, 255]);
+        }
+    }
+
+    #[test]
+    fn multisample() {
+        let mut visitor = SoftwareVisitor::new();
+        let rt = RenderTextureHandle::new(1, 1);
+        let surface = SurfaceHandle::new(1, 1);
+        let shader = ShaderHandle::new(1, 1);
+        let mesh = MeshHandle::new(1, 1);
+
+        let mut params = MeshParams {
+            layout: VertexLayout::build()
+                .with(Attribute::Position, VertexFormat::Float, 2, false)
+                .finish(),
+            num_idxes: 3,
+            ..Default::default()
+        };
+
+        params.set_num_verts(3);
+
+        let verts: [f32; 6] = [-1.0, -1.0, 1.0, -1.0, -1.0, 1.0];
+        let data = MeshData {
+            vptr: IndexFormat::encode(&verts).to_vec().into_boxed_slice(),
+            iptr: IndexFormat::encode(&[0u16, 1, 2])
+                .to_vec()
+                .into_boxed_slice(),
+        };
+
+        let red = [("u_Color".into(), UniformVariable::Vector3f([1.0, 0.0, 0.0]))];
+
+        unsafe {
+            let rt_params = RenderTextureParams {
+                format: RenderTextureFormat::RGBA8,
+                dimensions: Vector2::new(4, 4),
+                samples: 4,
+                ..Default::default()
+            };
+
+            let mut surface_params = SurfaceParams::default();
+            surface_params.colors[0] = Some(rt);
+
+            visitor.create_render_texture(rt, rt_params).unwrap();
+            visitor.create_surface(surface, surface_params).unwrap();
+            visitor.create_mesh(mesh, params, Some(data)).unwrap();
+            visitor
+                .create_shader(shader, ShaderParams::default(), "", "")
+                .unwrap();
+
+            let query = QueryHandle::new(1, 1);
+            visitor.bind(surface, Vector2::new(4, 4)).unwrap();
+            visitor
+                .begin_query(query, QueryKind::SamplesPassed)
+                .unwrap();
+            visitor.draw(shader, mesh, MeshIndex::All, &red).unwrap();
+            visitor.end_query(query).unwrap();
+
+            // The samples are resolved into the texture when it's read.
+            let (_, pixels) = visitor
+                .read_pixels(Some(surface), Vector2::new(4, 4))
+                .unwrap();
+
+            // The pixels across the edge of triangle are covered partially.
+            for y in 0..4 {
+                for x in 0..4 {
+                    let i = (y * 4 + x) * 4;
+                    let expected = match x + y {
+                        0..=2 => [255, 0, 0, 255],
+                        3 => [64, 0, 0, 255],
+                        _ => [0, 0, 0, 255],
+                    };
+
+                    assert_eq!(&pixels[i..i + 4], &expected, "({}, {})", x, y);
+                }
+            }
+
+            visitor.advance().unwrap();
+            assert_eq!(visitor.query_result(query).unwrap(), Some(6 * 4 + 4));
+        }
+    }
+}
@@ -2,6 +2,10 @@ use web_sys::WebGl2RenderingContext as WebGL;
 
 use crate::video::assets::texture::TextureFormat;
 
+// The enums of `EXT_texture_filter_anisotropic`.
+pub const TEXTURE_MAX_ANISOTROPY_EXT: u32 = 0x84FE;
+pub const MAX_TEXTURE_MAX_ANISOTROPY_EXT: u32 = 0x84FF;
+
 /// Represents the capabilities of the context.
 ///
 /// Contrary to the state, these values never change.
@@ -9,12 +13,27 @@ use crate::video::assets::texture::TextureFormat;
 pub struct Capabilities {
     /// The list of OpenGL extensions support by this implementation.
     pub extensions: Extensions,
+
+    /// Maximum level of anisotropic filtering. `0` if not supported.
+    pub max_anisotropy: f32,
 }
 
 impl Capabilities {
     pub unsafe fn new(ctx: &WebGL) -> Result<Capabilities, failure::Error> {
+        let extensions = Extensions::parse(ctx)?;
+
+        let max_anisotropy = if extensions.ext_texture_filter_anisotropic {
+            ctx.get_parameter(MAX_TEXTURE_MAX_ANISOTROPY_EXT)
+                .ok()
+                .and_then(|v| v.as_f64())
+                .unwrap_or(0.0) as f32
+        } else {
+            0.0
+        };
+
         Ok(Capabilities {
-            extensions: Extensions::parse(ctx)?,
+            extensions,
+            max_anisotropy,
         })
     }
 
@@ -72,4 +91,5 @@ extensions! {
     "WEBGL_compressed_texture_s3tc" => webgl_compressed_texture_s3tc,
     "WEBGL_compressed_texture_pvrtc" => webgl_compressed_texture_pvrtc,
     "WEBGL_compressed_texture_etc" => webgl_compressed_texture_etc,
+    "EXT_texture_filter_anisotropic" => ext_texture_filter_anisotropic,
 }
@@ -60,6 +60,21 @@ impl From<Comparison> for u32 {
     }
 }
 
+impl From<StencilOp> for u32 {
+    fn from(op: StencilOp) -> Self {
+        match op {
+            StencilOp::Keep => WebGL::KEEP,
+            StencilOp::Zero => WebGL::ZERO,
+            StencilOp::Replace => WebGL::REPLACE,
+            StencilOp::Increment => WebGL::INCR,
+            StencilOp::IncrementWrap => WebGL::INCR_WRAP,
+            StencilOp::Decrement => WebGL::DECR,
+            StencilOp::DecrementWrap => WebGL::DECR_WRAP,
+            StencilOp::Invert => WebGL::INVERT,
+        }
+    }
+}
+
 impl From<Equation> for u32 {
     fn from(eq: Equation) -> Self {
         match eq {
@@ -99,6 +114,33 @@ impl From<TextureWrap> for u32 {
     }
 }
 
+/// Gets the `(min, mag)` filters of texture that has `levels` mipmaps.
+pub fn texture_filters(min: TextureFilter, mag: TextureFilter, levels: u32) -> (u32, u32) {
+    let min = match (min, levels > 1) {
+        (TextureFilter::Nearest, false) => WebGL::NEAREST,
+        (TextureFilter::Nearest, true) => WebGL::NEAREST_MIPMAP_NEAREST,
+        (TextureFilter::Linear, false) => WebGL::LINEAR,
+        (TextureFilter::Linear, true) => WebGL::LINEAR_MIPMAP_LINEAR,
+    };
+
+    let mag = match mag {
+        TextureFilter::Nearest => WebGL::NEAREST,
+        TextureFilter::Linear => WebGL::LINEAR,
+    };
+
+    (min, mag)
+}
+
+/// Gets the level of anisotropic filtering clamped to the `max` level of hardware, or
+/// `None` if it's disabled or not supported (`max` is 0).
+pub fn texture_anisotropy(level: u8, max: f32) -> Option<f32> {
+    if level <= 1 || max < 1.0 {
+        None
+    } else {
+        Some(f32::from(level).min(max))
+    }
+}
+
 impl From<TextureFormat> for (u32, u32, u32) {
     fn from(format: TextureFormat) -> Self {
         // FIXME
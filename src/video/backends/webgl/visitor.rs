@@ -14,10 +14,13 @@ use crate::math::prelude::*;
 use crate::utils::hash::{FastHashMap, FastHashSet};
 use crate::utils::hash_value::HashValue;
 use crate::video::assets::prelude::*;
+use crate::video::errors::Error as VideoError;
+use crate::video::MAX_UNIFORM_TEXTURE_SLOTS;
 
 use super::super::utils::DataVec;
 use super::super::{UniformVar, Visitor};
-use super::capabilities::Capabilities;
+use super::capabilities::{Capabilities, TEXTURE_MAX_ANISOTROPY_EXT};
+use super::types;
 
 #[derive(Debug, Clone)]
 struct GLSurfaceData {
@@ -289,6 +292,22 @@ impl Visitor for WebGLVisitor {
         Ok(())
     }
 
+    unsafe fn update_surface_clear(
+        &mut self,
+        handle: SurfaceHandle,
+        color: Option<Color<f32>>,
+        depth: Option<f32>,
+        stencil: Option<i32>,
+    ) -> Result<()> {
+        let surface = self
+            .surfaces
+            .get_mut(handle)
+            .ok_or_else(|| format_err!("{:?} is invalid.", handle))?;
+
+        surface.params.set_clear(color, depth, stencil);
+        Ok(())
+    }
+
     unsafe fn delete_surface(&mut self, handle: SurfaceHandle) -> Result<()> {
         let surface = self
             .surfaces
@@ -314,8 +333,8 @@ impl Visitor for WebGLVisitor {
         vs: &str,
         fs: &str,
     ) -> Result<()> {
-        let vs = Self::compile(&self.ctx, WebGL::VERTEX_SHADER, vs)?;
-        let fs = Self::compile(&self.ctx, WebGL::FRAGMENT_SHADER, fs)?;
+        let vs = Self::compile(&self.ctx, ShaderStage::Vertex, vs)?;
+        let fs = Self::compile(&self.ctx, ShaderStage::Fragment, fs)?;
         let id = Self::link(&self.ctx, &[vs, fs])?;
 
         let shader = GLShaderData {
@@ -399,7 +418,7 @@ impl Visitor for WebGLVisitor {
                     Some(&id),
                 )?;
 
-                Self::bind_texture_params(&self.ctx, params.wrap, params.filter, len as u32)?;
+                Self::bind_texture_params(&self.ctx, &self.capabilities, &params, len as u32)?;
 
                 let (internal_format, format, pixel_type) = params.format.into();
                 let mut dims = (params.dimensions.x as i32, params.dimensions.y as i32);
@@ -497,7 +516,7 @@ impl Visitor for WebGLVisitor {
         )?;
 
         if !*texture.allocated.borrow() {
-            Self::bind_texture_params(&self.ctx, texture.params.wrap, texture.params.filter, 1)?;
+            Self::bind_texture_params(&self.ctx, &self.capabilities, &texture.params, 1)?;
 
             self.ctx
                 .tex_image_2d_with_i32_and_i32_and_i32_and_format_and_type_and_opt_u8_array(
@@ -553,6 +572,10 @@ impl Visitor for WebGLVisitor {
         handle: RenderTextureHandle,
         params: RenderTextureParams,
     ) -> Result<()> {
+        if params.is_multisampled() {
+            bail!("[WebGL] Multisampled render texture is not supported yet.");
+        }
+
         let id = if params.sampler {
             let id = self.ctx.create_texture().unwrap();
 
@@ -563,7 +586,14 @@ impl Visitor for WebGLVisitor {
                 0,
                 Some(&id),
             )?;
-            Self::bind_texture_params(&self.ctx, params.wrap, params.filter, 1)?;
+            Self::bind_sampler_params(
+                &self.ctx,
+                params.wrap,
+                params.wrap,
+                params.filter,
+                params.filter,
+                1,
+            )?;
 
             let (internal_format, format, pixel_type) = params.format.into();
             self.ctx
@@ -633,6 +663,10 @@ impl Visitor for WebGLVisitor {
         check(&self.ctx)
     }
 
+    fn max_samples(&self) -> u32 {
+        0
+    }
+
     unsafe fn create_mesh(
         &mut self,
         handle: MeshHandle,
@@ -765,6 +799,13 @@ impl Visitor for WebGLVisitor {
                 Self::set_depth_test(&self.ctx, &mut self.state, true, Comparison::Always)?;
             }
 
+            // Disables stencil test to make sure that all the bits of stencil buffer are
+            // writable.
+            if surface.params.clear_stencil.is_some() {
+                self.state.binded_shader = None;
+                Self::set_stencil(&self.ctx, &mut self.state, None)?;
+            }
+
             // Clears frame buffer.
             Self::clear(
                 &self.ctx,
@@ -828,6 +869,30 @@ impl Visitor for WebGLVisitor {
 
                         index += 1;
                     }
+                    UniformVariable::TextureArray(handles, len) => {
+                        let mut units = [0; MAX_UNIFORM_TEXTURE_SLOTS];
+                        for (i, &handle) in handles[..len as usize].iter().enumerate() {
+                            units[i] = index as i32;
+
+                            if let Some(texture) = self.textures.get(handle) {
+                                Self::bind_texture(
+                                    &self.ctx,
+                                    &mut self.state,
+                                    Some(Sampler::Texture(handle)),
+                                    index,
+                                    Some(&texture.id),
+                                )?;
+                            } else {
+                                Self::bind_texture(&self.ctx, &mut self.state, None, index, None)?;
+                            }
+
+                            index += 1;
+                        }
+
+                        self.ctx
+                            .uniform1iv_with_i32_array(Some(&location), &mut units[..len as usize]);
+                        check(&self.ctx)?;
+                    }
                     UniformVariable::RenderTexture(handle) => {
                         let v = UniformVariable::I32(index as i32);
                         Self::bind_uniform_variable(&self.ctx, &location, &v)?;
@@ -866,32 +931,11 @@ impl Visitor for WebGLVisitor {
             // Bind vertex buffer and vertex array object.
             Self::bind_mesh(&self.ctx, &mut self.state, &shader, &mesh)?;
 
-            let (from, len) = match mesh_index {
-                MeshIndex::Ptr(from, len) => {
-                    if (from + len) > mesh.params.num_idxes {
-                        bail!("MeshIndex is out of bounds");
-                    }
-
-                    ((from * mesh.params.index_format.stride()), len)
-                }
-                MeshIndex::SubMesh(index) => {
-                    let num = mesh.params.sub_mesh_offsets.len();
-                    let from = mesh
-                        .params
-                        .sub_mesh_offsets
-                        .get(index)
-                        .ok_or_else(|| format_err!("MeshIndex is out of bounds"))?;
-
-                    let to = if index == (num - 1) {
-                        mesh.params.num_idxes
-                    } else {
-                        mesh.params.sub_mesh_offsets[index + 1]
-                    };
-
-                    ((from * mesh.params.index_format.stride()), (to - from))
-                }
-                MeshIndex::All => (0, mesh.params.num_idxes),
-            };
+            let (from, len) = mesh
+                .params
+                .index_range(mesh_index)
+                .map_err(|_| format_err!("{:?} is out of bounds.", mesh_index))?;
+            let from = from * mesh.params.index_format.stride();
 
             self.ctx.draw_elements_with_i32(
                 mesh.params.primitive.into(),
@@ -915,6 +959,31 @@ impl Visitor for WebGLVisitor {
         Self::set_viewport(&self.ctx, &mut self.state, vp)
     }
 
+    unsafe fn read_pixels(
+        &mut self,
+        _: Option<SurfaceHandle>,
+        _: Vector2<u32>,
+    ) -> Result<(Vector2<u32>, Vec<u8>)> {
+        bail!("[WebGL] Reading pixels is not supported yet.");
+    }
+
+    // Queries are not supported yet, their results are never available.
+    unsafe fn begin_query(&mut self, _: QueryHandle, _: QueryKind) -> Result<()> {
+        Ok(())
+    }
+
+    unsafe fn end_query(&mut self, _: QueryHandle) -> Result<()> {
+        Ok(())
+    }
+
+    unsafe fn query_result(&mut self, _: QueryHandle) -> Result<Option<u64>> {
+        Ok(None)
+    }
+
+    unsafe fn delete_query(&mut self, _: QueryHandle) -> Result<()> {
+        Ok(())
+    }
+
     unsafe fn flush(&mut self) -> Result<()> {
         self.ctx.finish();
         Ok(())
@@ -958,7 +1027,12 @@ impl WebGLVisitor {
 }
 
 impl WebGLVisitor {
-    unsafe fn compile(ctx: &WebGL, tp: u32, src: &str) -> Result<WebGlShader> {
+    unsafe fn compile(ctx: &WebGL, stage: ShaderStage, src: &str) -> Result<WebGlShader> {
+        let tp = match stage {
+            ShaderStage::Vertex => WebGL::VERTEX_SHADER,
+            ShaderStage::Fragment => WebGL::FRAGMENT_SHADER,
+        };
+
         let shader = ctx
             .create_shader(tp)
             .ok_or_else(|| "Unable to create shader object".into())
@@ -974,11 +1048,12 @@ impl WebGLVisitor {
         {
             Ok(shader)
         } else {
-            let err = ctx
+            let log = ctx
                 .get_shader_info_log(&shader)
                 .unwrap_or_else(|| "Unknown error creating shader".into());
 
-            bail!(err);
+            ctx.delete_shader(Some(&shader));
+            Err(VideoError::ShaderCompileFailed(stage, log).into())
         }
     }
 
@@ -1003,11 +1078,12 @@ impl WebGLVisitor {
         {
             Ok(program)
         } else {
-            let err = ctx
+            let log = ctx
                 .get_program_info_log(&program)
                 .unwrap_or_else(|| "Unknown error creating program object".into());
 
-            bail!(err);
+            ctx.delete_program(Some(&program));
+            Err(VideoError::ShaderLinkFailed(log).into())
         }
     }
 
@@ -1024,12 +1100,29 @@ impl WebGLVisitor {
         check(ctx)?;
 
         let rs = &shader.params.state;
-        Self::set_cull_face(ctx, state, rs.cull_face)?;
-        Self::set_front_face_order(ctx, state, rs.front_face_order)?;
-        Self::set_depth_test(ctx, state, rs.depth_write, rs.depth_test)?;
-        Self::set_depth_write_offset(ctx, state, rs.depth_write_offset)?;
-        Self::set_color_blend(ctx, state, rs.color_blend)?;
-        Self::set_color_write(ctx, state, rs.color_write)?;
+        let delta = state.render_state.diff(rs);
+
+        if delta.cull {
+            Self::set_cull_face(ctx, state, rs.cull_face)?;
+            Self::set_front_face_order(ctx, state, rs.front_face_order)?;
+        }
+
+        if delta.depth {
+            Self::set_depth_test(ctx, state, rs.depth_write, rs.depth_test)?;
+            Self::set_depth_write_offset(ctx, state, rs.depth_write_offset)?;
+        }
+
+        if delta.blend {
+            Self::set_color_blend(ctx, state, rs.color_blend)?;
+        }
+
+        if delta.color_write {
+            Self::set_color_write(ctx, state, rs.color_write)?;
+        }
+
+        if delta.stencil {
+            Self::set_stencil(ctx, state, rs.stencil)?;
+        }
 
         state.binded_shader = Some(shader.handle);
         Ok(())
@@ -1105,9 +1198,13 @@ impl WebGLVisitor {
     ) -> Result<()> {
         match *variable {
             UniformVariable::Texture(_) => unreachable!(),
+            UniformVariable::TextureArray(_, _) => unreachable!(),
             UniformVariable::RenderTexture(_) => unreachable!(),
             UniformVariable::I32(v) => ctx.uniform1i(Some(&location), v),
             UniformVariable::F32(v) => ctx.uniform1f(Some(&location), v),
+            UniformVariable::Vector2i(v) => ctx.uniform2i(Some(&location), v[0], v[1]),
+            UniformVariable::Vector3i(v) => ctx.uniform3i(Some(&location), v[0], v[1], v[2]),
+            UniformVariable::Vector4i(v) => ctx.uniform4i(Some(&location), v[0], v[1], v[2], v[3]),
             UniformVariable::Vector2f(v) => ctx.uniform2f(Some(&location), v[0], v[1]),
             UniformVariable::Vector3f(v) => ctx.uniform3f(Some(&location), v[0], v[1], v[2]),
             UniformVariable::Vector4f(v) => ctx.uniform4f(Some(&location), v[0], v[1], v[2], v[3]),
@@ -1151,6 +1248,10 @@ impl WebGLVisitor {
         ctx.color_mask(true, true, true, true);
         rs.color_write = (true, true, true, true);
 
+        ctx.disable(WebGL::STENCIL_TEST);
+        ctx.stencil_mask(0xFF);
+        rs.stencil = None;
+
         ctx.disable(WebGL::SCISSOR_TEST);
         state.scissor = SurfaceScissor::Disable;
 
@@ -1309,6 +1410,36 @@ impl WebGLVisitor {
         Ok(())
     }
 
+    /// Specifies the stencil test, and the actions applied to stencil buffer.
+    unsafe fn set_stencil(
+        ctx: &WebGL,
+        state: &mut WebGLState,
+        stencil: Option<StencilState>,
+    ) -> Result<()> {
+        let state = &mut state.render_state;
+
+        if state.stencil != stencil {
+            if let Some(v) = stencil {
+                if state.stencil.is_none() {
+                    ctx.enable(WebGL::STENCIL_TEST);
+                }
+
+                let (reference, read_mask) = (i32::from(v.reference), u32::from(v.read_mask));
+                ctx.stencil_func(v.comparison.into(), reference, read_mask);
+                ctx.stencil_op(v.fail.into(), v.depth_fail.into(), v.pass.into());
+                ctx.stencil_mask(u32::from(v.write_mask));
+            } else {
+                ctx.disable(WebGL::STENCIL_TEST);
+                ctx.stencil_mask(0xFF);
+            }
+
+            state.stencil = stencil;
+            check(&ctx)?;
+        }
+
+        Ok(())
+    }
+
     /// Set the scissor box relative to the top-lef corner of th window, in pixels.
     unsafe fn set_scissor(
         ctx: &WebGL,
@@ -1416,47 +1547,54 @@ impl WebGLVisitor {
 
     unsafe fn bind_texture_params(
         ctx: &WebGL,
-        wrap: TextureWrap,
-        filter: TextureFilter,
+        caps: &Capabilities,
+        params: &TextureParams,
         levels: u32,
     ) -> Result<()> {
-        let wrap: u32 = wrap.into();
-        let wrap = wrap as i32;
-
-        ctx.tex_parameteri(WebGL::TEXTURE_2D, WebGL::TEXTURE_WRAP_S, wrap);
-        ctx.tex_parameteri(WebGL::TEXTURE_2D, WebGL::TEXTURE_WRAP_T, wrap);
-
-        match filter {
-            TextureFilter::Nearest => {
-                let min_filter = if levels > 1 {
-                    WebGL::NEAREST_MIPMAP_NEAREST
-                } else {
-                    WebGL::NEAREST
-                } as i32;
+        Self::bind_sampler_params(
+            ctx,
+            params.wrap_u,
+            params.wrap_v,
+            params.min_filter,
+            params.mag_filter,
+            levels,
+        )?;
 
-                ctx.tex_parameteri(WebGL::TEXTURE_2D, WebGL::TEXTURE_MIN_FILTER, min_filter);
+        if let Some(v) = types::texture_anisotropy(params.anisotropy, caps.max_anisotropy) {
+            ctx.tex_parameterf(WebGL::TEXTURE_2D, TEXTURE_MAX_ANISOTROPY_EXT, v);
+        } else if params.anisotropy > 1 {
+            warn!(
+                "The WebGL Context does not support anisotropic filtering, {}x is ignored.",
+                params.anisotropy
+            );
+        }
 
-                ctx.tex_parameteri(
-                    WebGL::TEXTURE_2D,
-                    WebGL::TEXTURE_MAG_FILTER,
-                    WebGL::NEAREST as i32,
-                );
-            }
-            TextureFilter::Linear => {
-                let min_filter = if levels > 1 {
-                    WebGL::LINEAR_MIPMAP_LINEAR
-                } else {
-                    WebGL::LINEAR
-                } as i32;
+        check(ctx)
+    }
 
-                ctx.tex_parameteri(WebGL::TEXTURE_2D, WebGL::TEXTURE_MIN_FILTER, min_filter);
-                ctx.tex_parameteri(
-                    WebGL::TEXTURE_2D,
-                    WebGL::TEXTURE_MAG_FILTER,
-                    WebGL::LINEAR as i32,
-                );
-            }
-        }
+    unsafe fn bind_sampler_params(
+        ctx: &WebGL,
+        wrap_u: TextureWrap,
+        wrap_v: TextureWrap,
+        min_filter: TextureFilter,
+        mag_filter: TextureFilter,
+        levels: u32,
+    ) -> Result<()> {
+        let (wrap_u, wrap_v): (u32, u32) = (wrap_u.into(), wrap_v.into());
+        ctx.tex_parameteri(WebGL::TEXTURE_2D, WebGL::TEXTURE_WRAP_S, wrap_u as i32);
+        ctx.tex_parameteri(WebGL::TEXTURE_2D, WebGL::TEXTURE_WRAP_T, wrap_v as i32);
+
+        let (min_filter, mag_filter) = types::texture_filters(min_filter, mag_filter, levels);
+        ctx.tex_parameteri(
+            WebGL::TEXTURE_2D,
+            WebGL::TEXTURE_MIN_FILTER,
+            min_filter as i32,
+        );
+        ctx.tex_parameteri(
+            WebGL::TEXTURE_2D,
+            WebGL::TEXTURE_MAG_FILTER,
+            mag_filter as i32,
+        );
 
         if levels > 1 {
             ctx.tex_parameteri(WebGL::TEXTURE_2D, WebGL::TEXTURE_BASE_LEVEL, 0);
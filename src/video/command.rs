@@ -1,6 +1,11 @@
+use std::convert::TryInto;
+
+use smallvec::SmallVec;
+
 use crate::math::prelude::Aabb2;
-use crate::utils::prelude::{DataBuffer, HashValue};
+use crate::utils::prelude::{DataBuffer, DoubleBuf, HashValue};
 
+use super::arena::{ArenaDraw, DrawArenaFrame};
 use super::assets::prelude::*;
 use super::backends::frame::Command;
 use super::errors::*;
@@ -26,8 +31,7 @@ impl CommandBuffer {
     /// Draws ur mesh.
     #[inline]
     pub fn draw(&mut self, dc: Draw) {
-        let len = dc.uniforms_len;
-        let ptr = self.bufs.extend_from_slice(&dc.uniforms[0..len]);
+        let ptr = self.bufs.extend_from_slice(&dc.uniforms);
         let cmd = Command::Draw(dc.shader, dc.mesh, dc.mesh_index, ptr);
         self.cmds.push(cmd);
     }
@@ -118,17 +122,144 @@ impl CommandBuffer {
     }
 }
 
+/// A double-buffered `CommandBuffer`, which moves the construction of draw calls off the
+/// main thread.
+///
+/// Worker threads record commands into the back buffer concurrently, e.g. in the jobs of
+/// `sched::scope`. Once the scope returns, which means all the recording jobs have set
+/// their latches, the render thread swaps the buffers and submits the front one, while the
+/// workers could go on recording the next frame.
+///
+/// ```rust,ignore
+/// crayon::sched::scope(|s| {
+///     for chunk in objects.chunks(64) {
+///         s.spawn(|_| {
+///             for v in chunk {
+///                 cmds.draw(v.draw_call());
+///             }
+///         });
+///     }
+/// });
+///
+/// cmds.swap();
+/// cmds.submit(surface)?;
+/// ```
+#[derive(Default)]
+pub struct DoubleCommandBuffer {
+    bufs: DoubleBuf<CommandBuffer>,
+}
+
+impl DoubleCommandBuffer {
+    /// Creates a new and empty `DoubleCommandBuffer`.
+    pub fn new() -> Self {
+        DoubleCommandBuffer {
+            bufs: DoubleBuf::new(CommandBuffer::new(), CommandBuffer::new()),
+        }
+    }
+
+    /// Records a draw call into the back buffer. It could be called from any threads.
+    #[inline]
+    pub fn draw(&self, dc: Draw) {
+        self.bufs.write().draw(dc);
+    }
+
+    /// Records commands into the back buffer with `func`, the buffer is locked during the
+    /// call, so the commands are kept in order.
+    #[inline]
+    pub fn record<F>(&self, func: F)
+    where
+        F: FnOnce(&mut CommandBuffer),
+    {
+        func(&mut self.bufs.write());
+    }
+
+    /// Swaps the back buffer with the front one. It blocks until the threads that are
+    /// recording have finished.
+    #[inline]
+    pub fn swap(&self) {
+        self.bufs.swap();
+    }
+
+    /// Submits the commands of front buffer into video device, and clears it.
+    #[inline]
+    pub fn submit(&self, surface: SurfaceHandle) -> Result<()> {
+        self.bufs.write_back_buf().submit(surface)
+    }
+}
+
 /// The draw call buffer of video system, which provides simple sort functionality for convenience.
+///
+/// Draw calls are sorted by the user provided key `T` before submission. Any `Ord + Copy`
+/// type could be used as key, e.g. tuples like `(u8, u32, u16)`, so there is no need to
+/// pack everything into a single integer.
+///
+/// With `set_state_sorting` enabled, draw calls with equal keys are further grouped by
+/// shader and then textures, so consecutive draw calls could reuse the bound states.
 pub struct DrawCommandBuffer<T: Ord + Copy> {
-    cmds: Vec<(T, Command)>,
+    cmds: Vec<(T, DrawState, Command)>,
     bufs: DataBuffer,
+    state_sorting: bool,
+    stats: DrawStats,
+}
+
+/// The statistics of state changes of the last submitted `DrawCommandBuffer`.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub struct DrawStats {
+    pub drawcalls: u32,
+    /// The number of shader binds.
+    pub shader_binds: u32,
+    /// The number of texture binds.
+    pub texture_binds: u32,
+    /// The number of binds avoided, compared with binding the shader and all the textures
+    /// for every draw call.
+    pub avoided_binds: u32,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
+enum Sampler {
+    Texture(TextureHandle),
+    RenderTexture(RenderTextureHandle),
+}
+
+/// The states that should be bound before a draw call.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+struct DrawState {
+    shader: ShaderHandle,
+    samplers: SmallVec<[Sampler; 4]>,
 }
 
+impl DrawState {
+    fn new(shader: ShaderHandle, uniforms: &[(HashValue<str>, UniformVariable)]) -> Self {
+        let mut samplers = SmallVec::new();
+        for v in uniforms {
+            match v.1 {
+                UniformVariable::Texture(handle) => samplers.push(Sampler::Texture(handle)),
+                UniformVariable::RenderTexture(handle) => {
+                    samplers.push(Sampler::RenderTexture(handle))
+                }
+                UniformVariable::TextureArray(handles, len) => {
+                    for &handle in &handles[..len as usize] {
+                        samplers.push(Sampler::Texture(handle));
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        DrawState { shader, samplers }
+    }
+}
+
+/// The `DrawCommandBuffer` sorted by packed `u64` keys, which is the common case.
+pub type PackedDrawCommandBuffer = DrawCommandBuffer<u64>;
+
 impl<T: Ord + Copy> Default for DrawCommandBuffer<T> {
     fn default() -> Self {
         DrawCommandBuffer {
             cmds: Vec::with_capacity(32),
             bufs: DataBuffer::with_capacity(512),
+            state_sorting: false,
+            stats: DrawStats::default(),
         }
     }
 }
@@ -139,27 +270,57 @@ impl<T: Ord + Copy> DrawCommandBuffer<T> {
         Default::default()
     }
 
+    /// Sets whether draw calls with equal keys should be grouped by shader and textures.
+    /// It's disabled by default, since the submission order of draw calls matters in
+    /// some cases, e.g. blending.
+    #[inline]
+    pub fn set_state_sorting(&mut self, enable: bool) {
+        self.state_sorting = enable;
+    }
+
+    /// Gets the statistics of state changes of the last submission.
+    #[inline]
+    pub fn stats(&self) -> DrawStats {
+        self.stats
+    }
+
     /// Draws ur mesh.
     #[inline]
     pub fn draw(&mut self, order: T, dc: Draw) {
-        let len = dc.uniforms_len;
-        let ptr = self.bufs.extend_from_slice(&dc.uniforms[0..len]);
+        let state = DrawState::new(dc.shader, &dc.uniforms);
+        let ptr = self.bufs.extend_from_slice(&dc.uniforms);
+        let cmd = Command::Draw(dc.shader, dc.mesh, dc.mesh_index, ptr);
+        self.cmds.push((order, state, cmd));
+    }
+
+    /// Draws the draw call that was allocated from `DrawArenaFrame`.
+    #[inline]
+    pub fn draw_arena<'a>(&mut self, order: T, frame: &DrawArenaFrame<'a>, dc: ArenaDraw<'a>) {
+        let (dc, uniforms) = frame.get(dc);
+        let state = DrawState::new(dc.shader, uniforms);
+        let ptr = self.bufs.extend_from_slice(uniforms);
         let cmd = Command::Draw(dc.shader, dc.mesh, dc.mesh_index, ptr);
-        self.cmds.push((order, cmd));
+        self.cmds.push((order, state, cmd));
     }
 
     /// Clears the batch, and submits all the sorted commands into video device. Its guaranteed that
     /// all the commands in this batch will be executed one by one in order.
     ///
+    /// The commands are not sorted if the surface is sequenced, see `SurfaceParams::set_sequence`.
+    ///
     /// Notes that this method has no effect on the allocated capacity of the underlying storage.
     pub fn submit(&mut self, surface: SurfaceHandle) -> Result<()> {
+        let sequence = super::surface(surface)
+            .map(|v| v.sequence())
+            .unwrap_or(false);
+        self.prepare(sequence);
+
         let doubele_frame = unsafe { super::inside::frames() };
         let mut frame = doubele_frame.write();
         frame.cmds.push(Command::Bind(surface));
 
-        self.cmds.as_mut_slice().sort_by_key(|v| v.0);
         for v in self.cmds.drain(..) {
-            if let (_, Command::Draw(shader, mesh, mesh_index, ptr)) = v {
+            if let (_, _, Command::Draw(shader, mesh, mesh_index, ptr)) = v {
                 let vars = self.bufs.as_slice(ptr);
                 let ptr = frame.bufs.extend_from_slice(vars);
                 let cmd = Command::Draw(shader, mesh, mesh_index, ptr);
@@ -170,13 +331,63 @@ impl<T: Ord + Copy> DrawCommandBuffer<T> {
         self.bufs.clear();
         Ok(())
     }
+
+    /// Sorts the draw calls unless they are submitted to a sequenced surface, and updates
+    /// the statistics.
+    fn prepare(&mut self, sequence: bool) {
+        if !sequence {
+            self.sort();
+        }
+
+        self.stats = self.count_binds();
+    }
+
+    /// Sorts the draw calls by their keys. The sort is stable, draw calls with equal keys
+    /// keep the order they were submitted unless state sorting is enabled.
+    #[inline]
+    fn sort(&mut self) {
+        if self.state_sorting {
+            self.cmds
+                .sort_by(|lhs, rhs| (lhs.0, &lhs.1).cmp(&(rhs.0, &rhs.1)));
+        } else {
+            self.cmds.sort_by_key(|v| v.0);
+        }
+    }
+
+    fn count_binds(&self) -> DrawStats {
+        let mut stats = DrawStats::default();
+        let mut last: Option<&DrawState> = None;
+
+        for v in &self.cmds {
+            let state = &v.1;
+            stats.drawcalls += 1;
+            stats.avoided_binds += 1 + state.samplers.len() as u32;
+
+            if last.map(|w| w.shader) != Some(state.shader) {
+                stats.shader_binds += 1;
+            }
+
+            for (i, sampler) in state.samplers.iter().enumerate() {
+                if last.and_then(|w| w.samplers.get(i)) != Some(sampler) {
+                    stats.texture_binds += 1;
+                }
+            }
+
+            last = Some(state);
+        }
+
+        stats.avoided_binds -= stats.shader_binds + stats.texture_binds;
+        stats
+    }
 }
 
 /// A draw call.
-#[derive(Debug, Copy, Clone)]
+///
+/// The uniform variables are stored inline as long as there are no more than 8 of them,
+/// so building a typical draw call does not touch the heap.
+#[derive(Debug, Clone)]
 pub struct Draw {
-    pub(crate) uniforms: [(HashValue<str>, UniformVariable); MAX_UNIFORM_VARIABLES],
-    pub(crate) uniforms_len: usize,
+    pub(crate) uniforms: SmallVec<[(HashValue<str>, UniformVariable); 8]>,
 
     pub shader: ShaderHandle,
     pub mesh: MeshHandle,
@@ -186,35 +397,263 @@ pub struct Draw {
 impl Draw {
     /// Creates a new and empty draw call.
     pub fn new(shader: ShaderHandle, mesh: MeshHandle) -> Self {
-        let nil = (HashValue::zero(), UniformVariable::I32(0));
         Draw {
             shader,
             mesh,
-            uniforms: [nil; MAX_UNIFORM_VARIABLES],
-            uniforms_len: 0,
+            uniforms: SmallVec::new(),
             mesh_index: MeshIndex::All,
         }
     }
 
-    /// Binds the named field with `UniformVariable`.
+    /// Binds the named field with `UniformVariable`. Binding a field twice overrides
+    /// the previous variable.
     pub fn set_uniform_variable<F, V>(&mut self, field: F, variable: V)
     where
         F: Into<HashValue<str>>,
         V: Into<UniformVariable>,
     {
-        assert!(self.uniforms_len < MAX_UNIFORM_VARIABLES);
-
         let field = field.into();
         let variable = variable.into();
 
-        for i in 0..self.uniforms_len {
-            if self.uniforms[i].0 == field {
-                self.uniforms[i] = (field, variable);
+        for v in &mut self.uniforms {
+            if v.0 == field {
+                v.1 = variable;
                 return;
             }
         }
 
-        self.uniforms[self.uniforms_len] = (field, variable);
-        self.uniforms_len += 1;
+        assert!(self.uniforms.len() < MAX_UNIFORM_VARIABLES);
+        self.uniforms.push((field, variable));
+    }
+
+    /// Binds the named field with `UniformVariable` like `set_uniform_variable`, but
+    /// returns an error instead if the variable could not be converted, it's rejected
+    /// by `UniformVariable::validate`, or there are `MAX_UNIFORM_VARIABLES` fields bound
+    /// already. The draw call is left untouched in that case.
+    pub fn try_set_uniform_variable<F, V>(&mut self, field: F, variable: V) -> Result<()>
+    where
+        F: Into<HashValue<str>>,
+        V: TryInto<UniformVariable>,
+        Error: From<V::Error>,
+    {
+        let field = field.into();
+        let variable = variable.try_into()?;
+        variable.validate()?;
+
+        if self.uniforms.len() >= MAX_UNIFORM_VARIABLES
+            && self.uniforms.iter().all(|v| v.0 != field)
+        {
+            return Err(Error::OutOfBounds);
+        }
+
+        self.set_uniform_variable(field, variable);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::utils::handle::HandleLike;
+
+    #[test]
+    fn sort_by_tuple_key() {
+        let shader = ShaderHandle::new(1, 1);
+        let mesh = MeshHandle::new(1, 1);
+
+        let mut batch = DrawCommandBuffer::<(u8, u32, u16)>::new();
+        batch.draw((1, 0, 0), Draw::new(shader, mesh));
+        batch.draw((0, 2, 7), Draw::new(shader, mesh));
+        batch.draw((0, 2, 3), Draw::new(shader, mesh));
+        batch.draw((0, 1, 9), Draw::new(shader, mesh));
+        batch.draw((0, 2, 3), Draw::new(shader, mesh));
+        batch.sort();
+
+        let keys: Vec<_> = batch.cmds.iter().map(|v| v.0).collect();
+        assert_eq!(
+            keys,
+            [(0, 1, 9), (0, 2, 3), (0, 2, 3), (0, 2, 7), (1, 0, 0)]
+        );
+    }
+
+    #[test]
+    fn state_sorting() {
+        let shaders = [ShaderHandle::new(1, 1), ShaderHandle::new(2, 1)];
+        let textures = [TextureHandle::new(1, 1), TextureHandle::new(2, 1)];
+        let mesh = MeshHandle::new(1, 1);
+
+        let mut batch = DrawCommandBuffer::<u8>::new();
+        let draw = |batch: &mut DrawCommandBuffer<u8>| {
+            for i in 0..8 {
+                let mut dc = Draw::new(shaders[i % 2], mesh);
+                dc.set_uniform_variable("u_Texture", textures[i % 2]);
+                batch.draw(0, dc);
+            }
+        };
+
+        draw(&mut batch);
+        batch.sort();
+        let stats = batch.count_binds();
+        assert_eq!(stats.drawcalls, 8);
+        assert_eq!(stats.shader_binds, 8);
+        assert_eq!(stats.texture_binds, 8);
+        assert_eq!(stats.avoided_binds, 0);
+
+        batch.cmds.clear();
+        batch.set_state_sorting(true);
+        draw(&mut batch);
+        batch.sort();
+        let stats = batch.count_binds();
+        assert_eq!(stats.drawcalls, 8);
+        assert_eq!(stats.shader_binds, 2);
+        assert_eq!(stats.texture_binds, 2);
+        assert_eq!(stats.avoided_binds, 12);
+
+        let order: Vec<_> = batch.cmds.iter().map(|v| v.1.shader).collect();
+        assert_eq!(&order[..4], &[shaders[0]; 4]);
+        assert_eq!(&order[4..], &[shaders[1]; 4]);
+    }
+
+    #[test]
+    fn sequence() {
+        let shaders = [ShaderHandle::new(1, 1), ShaderHandle::new(2, 1)];
+        let mesh = MeshHandle::new(1, 1);
+
+        let mut batch = DrawCommandBuffer::<u8>::new();
+        batch.set_state_sorting(true);
+        let draw = |batch: &mut DrawCommandBuffer<u8>| {
+            for (i, &order) in [3, 1, 2, 0].iter().enumerate() {
+                batch.draw(order, Draw::new(shaders[i % 2], mesh));
+            }
+        };
+
+        draw(&mut batch);
+        batch.prepare(true);
+        let keys: Vec<_> = batch.cmds.iter().map(|v| v.0).collect();
+        assert_eq!(keys, [3, 1, 2, 0]);
+        assert_eq!(batch.stats().shader_binds, 4);
+
+        batch.cmds.clear();
+        draw(&mut batch);
+        batch.prepare(false);
+        let keys: Vec<_> = batch.cmds.iter().map(|v| v.0).collect();
+        assert_eq!(keys, [0, 1, 2, 3]);
+    }
+
+    #[test]
+    fn uniforms() {
+        let shader = ShaderHandle::new(1, 1);
+        let mesh = MeshHandle::new(1, 1);
+
+        let mut dc = Draw::new(shader, mesh);
+        for i in 0..8 {
+            dc.set_uniform_variable(format!("u_{}", i).as_str(), i as f32);
+        }
+
+        dc.set_uniform_variable("u_3", 8.0);
+        assert_eq!(dc.uniforms.len(), 8);
+        assert!(!dc.uniforms.spilled());
+
+        let fields: Vec<_> = (0..8)
+            .map(|i| HashValue::from(format!("u_{}", i).as_str()))
+            .collect();
+        for (i, v) in dc.uniforms.iter().enumerate() {
+            assert_eq!(v.0, fields[i]);
+        }
+
+        match dc.uniforms[3].1 {
+            UniformVariable::F32(v) => assert_eq!(v, 8.0),
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn try_uniforms() {
+        use crate::math::prelude::{Matrix4, SquareMatrix};
+
+        let shader = ShaderHandle::new(1, 1);
+        let mesh = MeshHandle::new(1, 1);
+
+        let mut dc = Draw::new(shader, mesh);
+        assert!(dc
+            .try_set_uniform_variable("u_MVP", Matrix4::identity())
+            .is_ok());
+        assert!(dc.try_set_uniform_variable("u_Time", 1.0).is_ok());
+
+        let mut m = Matrix4::<f32>::identity();
+        m[2][1] = ::std::f32::NAN;
+        assert!(dc.try_set_uniform_variable("u_MVP", m).is_err());
+        assert!(dc
+            .try_set_uniform_variable("u_Time", ::std::f32::INFINITY)
+            .is_err());
+        assert!(dc
+            .try_set_uniform_variable("u_Color", [0.0, ::std::f32::NAN, 0.0])
+            .is_err());
+        assert_eq!(dc.uniforms.len(), 2);
+
+        match dc.uniforms[0].1 {
+            UniformVariable::Matrix4f(v, _) => assert_eq!(v[2][1], 0.0),
+            _ => unreachable!(),
+        }
+
+        for i in dc.uniforms.len()..MAX_UNIFORM_VARIABLES {
+            let field = format!("u_Field{}", i);
+            assert!(dc.try_set_uniform_variable(field.as_str(), 1.0).is_ok());
+        }
+
+        assert!(dc.try_set_uniform_variable("u_Color", 1.0).is_err());
+        assert!(dc.try_set_uniform_variable("u_Time", 2.0).is_ok());
+        assert_eq!(dc.uniforms.len(), MAX_UNIFORM_VARIABLES);
+    }
+
+    #[test]
+    fn double_buffered() {
+        use crate::sched::latch::{LatchWaitProbe, LockLatch};
+        use crate::sched::system::SchedulerSystem;
+        use std::sync::Arc;
+
+        let sched = SchedulerSystem::new(2, None, None);
+        let cmds = Arc::new(DoubleCommandBuffer::new());
+        let latches: Vec<_> = (0..2u32)
+            .map(|i| {
+                let cmds = cmds.clone();
+                let latch = Arc::new(LockLatch::new());
+                let done = latch.clone();
+                sched.spawn(move || {
+                    for j in 0..100 {
+                        let dc = Draw::new(ShaderHandle::new(i, 1), MeshHandle::new(j, 1));
+                        cmds.draw(dc);
+                    }
+
+                    done.set(());
+                });
+
+                latch
+            })
+            .collect();
+
+        for v in latches {
+            v.wait();
+        }
+
+        sched.terminate();
+
+        cmds.swap();
+        cmds.draw(Draw::new(ShaderHandle::new(2, 1), MeshHandle::new(0, 1)));
+
+        let front = cmds.bufs.read_back_buf();
+        let mut recorded: Vec<_> = front
+            .cmds
+            .iter()
+            .map(|v| match *v {
+                Command::Draw(shader, mesh, _, _) => (shader.index(), mesh.index()),
+                _ => unreachable!(),
+            })
+            .collect();
+
+        recorded.sort();
+        let expected: Vec<_> = (0..2).flat_map(|i| (0..100).map(move |j| (i, j))).collect();
+        assert_eq!(recorded, expected);
+        assert_eq!(cmds.bufs.write().cmds.len(), 1);
     }
 }
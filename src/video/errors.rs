@@ -1,7 +1,14 @@
+use super::assets::query::QueryKind;
+use super::assets::shader::{ShaderStage, UniformVariableType};
+
 #[derive(Debug, Fail)]
 pub enum Error {
     #[fail(display = "Failed to create shader, errors: \n{}.", _0)]
     ShaderInvalid(String),
+    #[fail(display = "Failed to compile {:?} shader, errors:\n{}", _0, _1)]
+    ShaderCompileFailed(ShaderStage, String),
+    #[fail(display = "Failed to link shader program, errors:\n{}", _0)]
+    ShaderLinkFailed(String),
     #[fail(display = "{} is invalid.", _0)]
     HandleInvalid(String),
     #[fail(display = "Out of bounds.")]
@@ -10,10 +17,35 @@ pub enum Error {
     UpdateImmutableBuffer,
     #[fail(display = "Can NOT sample render buffer.")]
     SampleRenderBuffer,
+    #[fail(display = "Failed to create render texture, errors: {}.", _0)]
+    RenderTextureInvalid(String),
     #[fail(display = "Failed to create surface, errors:\n{}\n", _0)]
     SurfaceInvalid(String),
     #[fail(display = "Attribute({}) is undefined.", _0)]
     AttributeUndefined(String),
+    #[fail(display = "Uniform variable({}) is undefined.", _0)]
+    UniformVariableUndefined(String),
+    #[fail(
+        display = "Uniform variable({}) is declared as {:?}, but found {:?}.",
+        _0, _1, _2
+    )]
+    UniformVariableMismatch(String, UniformVariableType, UniformVariableType),
+    #[fail(display = "Uniform variable of {:?} is invalid, errors: {}.", _0, _1)]
+    UniformVariableInvalid(UniformVariableType, String),
+    #[fail(display = "Another query of {:?} is still running.", _0)]
+    QueryRunning(QueryKind),
+    #[fail(display = "{} is not running.", _0)]
+    QueryNotRunning(String),
+    #[fail(display = "{} is not supported.", _0)]
+    NotSupported(String),
+}
+
+// Allows infallible conversions into `UniformVariable` to be used where fallible
+// ones are expected.
+impl From<::std::convert::Infallible> for Error {
+    fn from(v: ::std::convert::Infallible) -> Self {
+        match v {}
+    }
 }
 
 pub type Result<T> = ::std::result::Result<T, Error>;
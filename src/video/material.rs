@@ -0,0 +1,220 @@
+//! Uniform variables that are type-checked against the declaration of shader.
+//!
+//! Binding a uniform variable to `Draw` directly is not checked at all, a typo in the
+//! field name or a mismatched type only shows up as a glitch when rendering. `Material`
+//! keeps the `UniformVariableLayout` of its shader, and rejects those bindings with
+//! descriptive errors instead:
+//!
+//! ```rust,ignore
+//! let mut mtl = Material::new(shader)?;
+//! mtl.bind("u_Ambient", [1.0, 1.0, 1.0])?;
+//!
+//! batch.draw(mtl.draw(mesh));
+//! ```
+//!
+//! Materials created with `MaterialBuilder` have every declared uniform variable bound,
+//! the ones that are not specified explicitly are filled with defaults:
+//!
+//! ```rust,ignore
+//! let mtl = Material::build(shader)?
+//!     .with("u_Ambient", [1.0, 1.0, 1.0])?
+//!     .with_default_texture(checker)
+//!     .finish();
+//! ```
+
+use smallvec::SmallVec;
+
+use crate::utils::hash_value::HashValue;
+
+use super::assets::prelude::*;
+use super::command::Draw;
+use super::errors::*;
+use super::MAX_UNIFORM_TEXTURE_SLOTS;
+
+/// A shader with its type-checked uniform variables.
+#[derive(Debug, Clone)]
+pub struct Material {
+    shader: ShaderHandle,
+    layout: UniformVariableLayout,
+    uniforms: SmallVec<[(HashValue<str>, UniformVariable); 8]>,
+}
+
+impl Material {
+    /// Creates a new `Material` with the uniform variable layout of `shader`.
+    pub fn new(shader: ShaderHandle) -> Result<Self> {
+        let params =
+            super::shader(shader).ok_or_else(|| Error::HandleInvalid(format!("{:?}", shader)))?;
+
+        Ok(Material::with_layout(shader, params.uniforms))
+    }
+
+    /// Creates a new `Material` with specified uniform variable layout.
+    pub fn with_layout(shader: ShaderHandle, layout: UniformVariableLayout) -> Self {
+        Material {
+            shader,
+            layout,
+            uniforms: SmallVec::new(),
+        }
+    }
+
+    /// Creates a `MaterialBuilder` with the uniform variable layout of `shader`.
+    #[inline]
+    pub fn build(shader: ShaderHandle) -> Result<MaterialBuilder> {
+        Ok(MaterialBuilder::new(Material::new(shader)?))
+    }
+
+    /// Gets the shader of this material.
+    #[inline]
+    pub fn shader(&self) -> ShaderHandle {
+        self.shader
+    }
+
+    /// Gets the variable bound to the named field.
+    pub fn variable<F>(&self, field: F) -> Option<UniformVariable>
+    where
+        F: Into<HashValue<str>>,
+    {
+        let field = field.into();
+        self.uniforms.iter().find(|v| v.0 == field).map(|v| v.1)
+    }
+
+    /// Binds the named field with `UniformVariable`. It fails if the field is not declared
+    /// by shader, or if the type of variable does not match the declaration.
+    pub fn bind<F, V>(&mut self, field: F, variable: V) -> Result<()>
+    where
+        F: AsRef<str>,
+        V: Into<UniformVariable>,
+    {
+        let name = field.as_ref();
+        let variable = variable.into();
+
+        let expected = self
+            .layout
+            .variable_type(name)
+            .ok_or_else(|| Error::UniformVariableUndefined(name.to_owned()))?;
+
+        let found = variable.variable_type();
+        if expected != found {
+            return Err(Error::UniformVariableMismatch(
+                name.to_owned(),
+                expected,
+                found,
+            ));
+        }
+
+        let field = HashValue::from(name);
+        for v in &mut self.uniforms {
+            if v.0 == field {
+                v.1 = variable;
+                return Ok(());
+            }
+        }
+
+        self.uniforms.push((field, variable));
+        Ok(())
+    }
+
+    /// Creates a draw call of `mesh` with all the bound uniform variables.
+    pub fn draw(&self, mesh: MeshHandle) -> Draw {
+        let mut dc = Draw::new(self.shader, mesh);
+        dc.uniforms = self.uniforms.clone();
+        dc
+    }
+}
+
+/// The builder of `Material`, which makes sure that all the declared uniform variables
+/// are bound.
+pub struct MaterialBuilder {
+    mtl: Material,
+    default_texture: Option<TextureHandle>,
+}
+
+impl MaterialBuilder {
+    /// Creates a new `MaterialBuilder` that starts with the bindings of `mtl`.
+    pub fn new(mtl: Material) -> Self {
+        MaterialBuilder {
+            mtl,
+            default_texture: None,
+        }
+    }
+
+    /// Binds the named field with `UniformVariable`.
+    pub fn with<F, V>(mut self, field: F, variable: V) -> Result<Self>
+    where
+        F: AsRef<str>,
+        V: Into<UniformVariable>,
+    {
+        self.mtl.bind(field, variable)?;
+        Ok(self)
+    }
+
+    /// Sets the texture that unbound texture fields default to. Without it, those fields
+    /// sample from the shared white texture of `video::white_texture`.
+    pub fn with_default_texture(mut self, texture: TextureHandle) -> Self {
+        self.default_texture = Some(texture);
+        self
+    }
+
+    /// Binds defaults to the fields that are still unbound, and returns the material.
+    /// Numbers and vectors default to zeros, matrices default to identity, and textures
+    /// default to white.
+    pub fn finish(mut self) -> Material {
+        let unbound: Vec<_> = self
+            .mtl
+            .layout
+            .iter()
+            .filter(|v| self.mtl.variable(v.0.as_str()).is_none())
+            .map(|v| (v.0.clone(), v.1))
+            .collect();
+
+        for (field, tp) in unbound {
+            let variable = self.default_variable(tp);
+            self.mtl.uniforms.push((HashValue::from(&field), variable));
+        }
+
+        self.mtl
+    }
+
+    fn default_texture(&mut self) -> TextureHandle {
+        *self
+            .default_texture
+            .get_or_insert_with(super::white_texture)
+    }
+
+    fn default_variable(&mut self, tp: UniformVariableType) -> UniformVariable {
+        match tp {
+            UniformVariableType::Texture => UniformVariable::Texture(self.default_texture()),
+            UniformVariableType::TextureArray => {
+                let texture = self.default_texture();
+                UniformVariable::TextureArray([texture; MAX_UNIFORM_TEXTURE_SLOTS], 0)
+            }
+            UniformVariableType::RenderTexture => {
+                UniformVariable::RenderTexture(RenderTextureHandle::default())
+            }
+            UniformVariableType::I32 => UniformVariable::I32(0),
+            UniformVariableType::F32 => UniformVariable::F32(0.0),
+            UniformVariableType::Vector2i => UniformVariable::Vector2i([0; 2]),
+            UniformVariableType::Vector3i => UniformVariable::Vector3i([0; 3]),
+            UniformVariableType::Vector4i => UniformVariable::Vector4i([0; 4]),
+            UniformVariableType::Vector2f => UniformVariable::Vector2f([0.0; 2]),
+            UniformVariableType::Vector3f => UniformVariable::Vector3f([0.0; 3]),
+            UniformVariableType::Vector4f => UniformVariable::Vector4f([0.0; 4]),
+            UniformVariableType::Matrix2f => {
+                UniformVariable::Matrix2f([[1.0, 0.0], [0.0, 1.0]], false)
+            }
+            UniformVariableType::Matrix3f => UniformVariable::Matrix3f(
+                [[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]],
+                false,
+            ),
+            UniformVariableType::Matrix4f => UniformVariable::Matrix4f(
+                [
+                    [1.0, 0.0, 0.0, 0.0],
+                    [0.0, 1.0, 0.0, 0.0],
+                    [0.0, 0.0, 1.0, 0.0],
+                    [0.0, 0.0, 0.0, 1.0],
+                ],
+                false,
+            ),
+        }
+    }
+}
@@ -194,29 +194,47 @@ pub const MAX_UNIFORM_VARIABLES: usize = 32;
 /// Maximum number of textures in shader.
 pub const MAX_UNIFORM_TEXTURE_SLOTS: usize = 8;
 
+pub mod arena;
 #[macro_use]
 pub mod assets;
 pub mod command;
 pub mod errors;
+pub mod material;
+pub mod postprocess;
+pub mod screenshot;
 
 mod system;
+mod watch;
 
 mod backends;
 
 pub mod prelude {
+    pub use super::arena::{ArenaDraw, DrawArena, DrawArenaFrame};
     pub use super::assets::prelude::*;
-    pub use super::command::{CommandBuffer, Draw, DrawCommandBuffer};
+    pub use super::command::{
+        CommandBuffer, DoubleCommandBuffer, Draw, DrawCommandBuffer, DrawStats,
+        PackedDrawCommandBuffer,
+    };
+    pub use super::material::{Material, MaterialBuilder};
+    pub use super::postprocess::PostProcessChain;
+    pub use super::system::MemoryStats;
 }
 
+use std::path::PathBuf;
+use std::sync::Arc;
+
 use uuid::Uuid;
 
-use crate::math::prelude::Aabb2;
+use crate::math::prelude::{Aabb2, Aabb3, Color};
 use crate::prelude::CrResult;
+#[cfg(feature = "futures")]
+use crate::res::future::HandleFuture;
 use crate::res::utils::prelude::ResourceState;
 
 use self::assets::prelude::*;
 use self::errors::*;
 use self::inside::ctx;
+use self::system::MemoryStats;
 
 /// Creates an surface with `SurfaceParams`.
 #[inline]
@@ -236,6 +254,23 @@ pub fn surface_state(handle: SurfaceHandle) -> ResourceState {
     ctx().surface_state(handle)
 }
 
+/// Updates the clear flags of surface. Notes that a surface is cleared only once per
+/// frame, when it's bound for the first time.
+#[inline]
+pub fn update_surface_clear<C, D, S>(
+    handle: SurfaceHandle,
+    color: C,
+    depth: D,
+    stencil: S,
+) -> Result<()>
+where
+    C: Into<Option<Color<f32>>>,
+    D: Into<Option<f32>>,
+    S: Into<Option<i32>>,
+{
+    ctx().update_surface_clear(handle, color, depth, stencil)
+}
+
 /// Deletes surface object.
 #[inline]
 pub fn delete_surface(handle: SurfaceHandle) {
@@ -249,12 +284,46 @@ pub fn create_shader(params: ShaderParams, vs: String, fs: String) -> Result<Sha
     ctx().create_shader(params, vs, fs)
 }
 
+/// Creates a shader with the embedded sources, and then relinks it in place with the
+/// sources at `vs_path` and `fs_path`, which is useful to iterate on shaders without
+/// recompiling.
+///
+/// In debug builds, the files are polled every 500 milliseconds and the shader is
+/// relinked behind the same handle whenever they change. Release builds never poll
+/// them, call `reload_watched_shaders` instead. If the files could not be compiled, the
+/// error is logged and the shader keeps the last sources that compiled successfully.
+#[inline]
+pub fn create_shader_watched<T: Into<PathBuf>>(
+    params: ShaderParams,
+    vs: String,
+    fs: String,
+    vs_path: T,
+    fs_path: T,
+) -> Result<ShaderHandle> {
+    ctx().create_shader_watched(params, vs, fs, vs_path, fs_path)
+}
+
+/// Polls the files of watched shaders immediately, and relinks the shaders whose files
+/// have changed. Returns the handles of relinked shaders.
+#[inline]
+pub fn reload_watched_shaders() -> Vec<ShaderHandle> {
+    ctx().reload_watched_shaders()
+}
+
 /// Gets the `ShaderParams` if available.
 #[inline]
 pub fn shader(handle: ShaderHandle) -> Option<ShaderParams> {
     ctx().shader(handle)
 }
 
+/// Gets the compile log of the most recently created shader. Returns `None` if it
+/// compiles without errors. Shaders are compiled when the frame is dispatched, so the
+/// log is available in the next frame.
+#[inline]
+pub fn last_shader_log() -> Option<String> {
+    ctx().last_shader_log()
+}
+
 /// Get the resource state of specified shader.
 #[inline]
 pub fn shader_state(handle: ShaderHandle) -> ResourceState {
@@ -276,6 +345,18 @@ where
     ctx().create_mesh(params, data)
 }
 
+/// Create a new mesh object from bytes that are already laid out as `params` describes,
+/// e.g. the vertices of procedurally generated meshes. The bytes are moved into the mesh
+/// without copying.
+#[inline]
+pub fn create_mesh_raw(
+    params: MeshParams,
+    vertex_bytes: Box<[u8]>,
+    index_bytes: Option<Box<[u8]>>,
+) -> CrResult<MeshHandle> {
+    ctx().create_mesh_raw(params, vertex_bytes, index_bytes)
+}
+
 /// Creates a mesh object from file asynchronously.
 #[inline]
 pub fn create_mesh_from<T: AsRef<str>>(url: T) -> CrResult<MeshHandle> {
@@ -294,12 +375,34 @@ pub fn mesh(handle: MeshHandle) -> Option<MeshParams> {
     ctx().mesh(handle)
 }
 
+/// Gets the bounding box of mesh if available. It's cheaper than `mesh` when only the
+/// bounds are needed, e.g. culling every frame.
+#[inline]
+pub fn mesh_aabb(handle: MeshHandle) -> Option<Aabb3<f32>> {
+    ctx().mesh_aabb(handle)
+}
+
+/// Gets the copy of vertices and indices in memory, which is only available if the
+/// mesh is created with `MeshParams::keep_cpu_data`.
+#[inline]
+pub fn mesh_cpu_data(handle: MeshHandle) -> Option<Arc<MeshData>> {
+    ctx().mesh_cpu_data(handle)
+}
+
 /// Get the resource state of specified mesh.
 #[inline]
 pub fn mesh_state(handle: MeshHandle) -> ResourceState {
     ctx().mesh_state(handle)
 }
 
+/// Returns a future that resolves once the mesh is loaded. Only available with the
+/// `futures` feature.
+#[cfg(feature = "futures")]
+#[inline]
+pub fn mesh_future(handle: MeshHandle) -> HandleFuture<MeshHandle> {
+    HandleFuture::new(handle, |handle, waker| ctx().poll_mesh_state(handle, waker))
+}
+
 /// Update a subset of dynamic vertex buffer. Use `offset` specifies the offset
 /// into the buffer object's data store where data replacement will begin, measured
 /// in bytes.
@@ -350,6 +453,22 @@ pub fn texture_state(handle: TextureHandle) -> ResourceState {
     ctx().texture_state(handle)
 }
 
+/// Gets the shared 1x1 white texture, which is created on first use.
+#[inline]
+pub fn white_texture() -> TextureHandle {
+    ctx().white_texture()
+}
+
+/// Returns a future that resolves once the texture is loaded. Only available with the
+/// `futures` feature.
+#[cfg(feature = "futures")]
+#[inline]
+pub fn texture_future(handle: TextureHandle) -> HandleFuture<TextureHandle> {
+    HandleFuture::new(handle, |handle, waker| {
+        ctx().poll_texture_state(handle, waker)
+    })
+}
+
 /// Update a contiguous subregion of an existing two-dimensional texture object.
 #[inline]
 pub fn update_texture(handle: TextureHandle, area: Aabb2<u32>, data: &[u8]) -> CrResult<()> {
@@ -386,54 +505,116 @@ pub fn delete_render_texture(handle: RenderTextureHandle) {
     ctx().delete_render_texture(handle)
 }
 
+/// Gets the estimated video memory consumed by all the living textures, meshes and
+/// render textures.
+#[inline]
+pub fn memory_stats() -> MemoryStats {
+    ctx().memory_stats()
+}
+
+/// Saves the window framebuffer into a PNG file. The pixels are read back when the
+/// current frame is dispatched, after all the draw calls submitted before.
+///
+/// The file is written after the dispatch, and failures are only logged.
+///
+/// Returns `Error::NotSupported` on web browsers(wasm32), which could not write files.
+#[inline]
+pub fn save_screenshot<T: Into<PathBuf>>(path: T) -> Result<()> {
+    ctx().save_screenshot(None, path)
+}
+
+/// Saves the first color attachment of surface into a PNG file. The pixels are read
+/// back when the current frame is dispatched, after all the draw calls submitted before.
+///
+/// Returns `Error::NotSupported` on web browsers(wasm32) like `save_screenshot`.
+#[inline]
+pub fn save_surface_screenshot<T: Into<PathBuf>>(surface: SurfaceHandle, path: T) -> Result<()> {
+    ctx().save_screenshot(Some(surface), path)
+}
+
+/// Starts a query of `kind`, which measures the draw calls submitted until `end_query`,
+/// e.g. the time GPU spent on them. Only one query of each kind could be running at the
+/// same time.
+///
+/// The result is never available if the kind of query is not supported by the device.
+#[inline]
+pub fn begin_query(kind: QueryKind) -> Result<QueryHandle> {
+    ctx().begin_query(kind)
+}
+
+/// Ends the query.
+#[inline]
+pub fn end_query(handle: QueryHandle) -> Result<()> {
+    ctx().end_query(handle)
+}
+
+/// Gets the result of query without blocking, or `None` if it's not available yet.
+///
+/// The commands are dispatched to GPU at the end of next frame, and then GPU works
+/// asynchronously, so the results usually lag a frame or two behind. Polls it every
+/// frame until it's available.
+#[inline]
+pub fn query_result(handle: QueryHandle) -> Option<u64> {
+    ctx().query_result(handle)
+}
+
+/// Deletes the query object.
+#[inline]
+pub fn delete_query(handle: QueryHandle) {
+    ctx().delete_query(handle)
+}
+
 pub(crate) mod inside {
     use std::sync::Arc;
 
     use crate::errors::*;
     use crate::utils::double_buf::DoubleBuf;
+    use crate::utils::global::Global;
 
     use super::backends::frame::Frame;
     use super::system::VideoSystem;
 
-    pub static mut CTX: *const VideoSystem = std::ptr::null();
+    pub static CTX: Global<VideoSystem> = Global::new();
 
     #[inline]
     pub fn ctx() -> &'static VideoSystem {
-        unsafe {
-            debug_assert!(
-                !CTX.is_null(),
-                "video system has not been initialized properly."
-            );
-
-            &*CTX
-        }
+        CTX.get()
+            .expect("video system has not been initialized properly.")
     }
 
     /// Setup the video system.
     pub unsafe fn setup() -> Result<()> {
-        debug_assert!(CTX.is_null(), "duplicated setup of video system.");
-
         let ctx = VideoSystem::new()?;
-        CTX = Box::into_raw(Box::new(ctx));
+        if CTX.set(ctx).is_err() {
+            panic!("duplicated setup of video system.");
+        }
         Ok(())
     }
 
-    /// Setup the video system.
-    pub unsafe fn headless() {
-        debug_assert!(CTX.is_null(), "duplicated setup of video system.");
-
-        let ctx = VideoSystem::headless();
-        CTX = Box::into_raw(Box::new(ctx));
+    /// Setup the headless video system, which renders with the software rasterizer if
+    /// `software` is true.
+    pub unsafe fn headless(software: bool) {
+        #[cfg(feature = "software")]
+        let ctx = if software {
+            VideoSystem::software()
+        } else {
+            VideoSystem::headless()
+        };
+
+        #[cfg(not(feature = "software"))]
+        let ctx = {
+            assert!(!software, "`software` feature is required.");
+            VideoSystem::headless()
+        };
+
+        if CTX.set(ctx).is_err() {
+            panic!("duplicated setup of video system.");
+        }
     }
 
     /// Discard the video system.
     pub unsafe fn discard() {
-        if CTX.is_null() {
-            return;
-        }
-
-        drop(Box::from_raw(CTX as *mut VideoSystem));
-        CTX = std::ptr::null();
+        CTX.discard();
     }
 
     pub unsafe fn frames() -> Arc<DoubleBuf<Frame>> {
@@ -0,0 +1,156 @@
+//! A chain of full-screen post-processing passes.
+//!
+//! The scene is rendered into `PostProcessChain::input` first. Every pass then draws a
+//! full-screen quad with its shader, reading the output of previous pass as a render
+//! texture and writing into the other one. The chain ping-pongs between two render
+//! textures, so it only allocates twice the memory of a single target regardless of
+//! the number of passes.
+//!
+//! The quad consists of a `Position` attribute with 2 floats in normalized device
+//! coordinates, which should be declared in the attribute layout of pass shaders.
+
+use crate::errors::*;
+use crate::utils::hash_value::HashValue;
+
+use super::assets::prelude::*;
+use super::command::{CommandBuffer, Draw};
+
+struct PostProcessPass {
+    dc: Draw,
+    source: HashValue<str>,
+}
+
+/// A helper that manages the intermediate render targets of post-processing passes.
+pub struct PostProcessChain {
+    passes: Vec<PostProcessPass>,
+    textures: [RenderTextureHandle; 2],
+    surfaces: [SurfaceHandle; 2],
+    mesh: MeshHandle,
+    batch: CommandBuffer,
+}
+
+impl Drop for PostProcessChain {
+    fn drop(&mut self) {
+        for i in 0..2 {
+            super::delete_surface(self.surfaces[i]);
+            super::delete_render_texture(self.textures[i]);
+        }
+
+        super::delete_mesh(self.mesh);
+    }
+}
+
+impl PostProcessChain {
+    /// Creates a new `PostProcessChain` with two render textures of `params`.
+    pub fn new(mut params: RenderTextureParams) -> Result<Self> {
+        params.sampler = true;
+
+        let textures = [
+            super::create_render_texture(params)?,
+            super::create_render_texture(params)?,
+        ];
+
+        let mut surfaces = [SurfaceHandle::default(); 2];
+        for i in 0..2 {
+            let mut params = SurfaceParams::default();
+            params.set_attachments(&[textures[i]], None)?;
+            surfaces[i] = super::create_surface(params)?;
+        }
+
+        let verts: [[f32; 2]; 4] = [[-1.0, -1.0], [1.0, -1.0], [1.0, 1.0], [-1.0, 1.0]];
+        let idxes: [u16; 6] = [0, 1, 2, 0, 2, 3];
+
+        let mut params = MeshParams::default();
+        params.num_verts = 4;
+        params.num_idxes = 6;
+        params.layout = VertexLayout::build()
+            .with(Attribute::Position, VertexFormat::Float, 2, false)
+            .finish();
+
+        let vptr = unsafe {
+            let len = verts.len() * ::std::mem::size_of::<[f32; 2]>();
+            ::std::slice::from_raw_parts(verts.as_ptr() as *const u8, len)
+        };
+
+        let data = MeshData {
+            vptr: vptr.into(),
+            iptr: IndexFormat::encode(&idxes).into(),
+        };
+
+        let mesh = super::create_mesh(params, Some(data))?;
+
+        Ok(PostProcessChain {
+            passes: Vec::new(),
+            textures,
+            surfaces,
+            mesh,
+            batch: CommandBuffer::new(),
+        })
+    }
+
+    /// Gets the surface that the scene should be rendered into.
+    #[inline]
+    pub fn input(&self) -> SurfaceHandle {
+        self.surfaces[0]
+    }
+
+    /// Appends a pass drawn with `shader`. The output of previous pass is bound to the
+    /// uniform variable named `source`. Returns the index of the pass.
+    pub fn add_pass<T>(&mut self, shader: ShaderHandle, source: T) -> usize
+    where
+        T: Into<HashValue<str>>,
+    {
+        self.passes.push(PostProcessPass {
+            dc: Draw::new(shader, self.mesh),
+            source: source.into(),
+        });
+
+        self.passes.len() - 1
+    }
+
+    /// Binds the named field of a pass with `UniformVariable`.
+    pub fn set_uniform_variable<F, V>(&mut self, pass: usize, field: F, variable: V)
+    where
+        F: Into<HashValue<str>>,
+        V: Into<UniformVariable>,
+    {
+        self.passes[pass].dc.set_uniform_variable(field, variable);
+    }
+
+    /// Returns the number of passes.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.passes.len()
+    }
+
+    /// Returns true if there is no passes.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.passes.is_empty()
+    }
+
+    /// Gets the render textures that a pass reads from and writes into.
+    #[inline]
+    pub fn targets(&self, pass: usize) -> (RenderTextureHandle, RenderTextureHandle) {
+        (self.textures[pass % 2], self.textures[(pass + 1) % 2])
+    }
+
+    /// Gets the render texture that holds the result of the last pass.
+    #[inline]
+    pub fn texture(&self) -> RenderTextureHandle {
+        self.textures[self.passes.len() % 2]
+    }
+
+    /// Submits all the passes in order, and returns the final texture.
+    pub fn submit(&mut self) -> Result<RenderTextureHandle> {
+        for i in 0..self.passes.len() {
+            let mut dc = self.passes[i].dc.clone();
+            dc.set_uniform_variable(self.passes[i].source, self.textures[i % 2]);
+
+            self.batch.draw(dc);
+            self.batch.submit(self.surfaces[(i + 1) % 2])?;
+        }
+
+        Ok(self.texture())
+    }
+}
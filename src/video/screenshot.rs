@@ -0,0 +1,50 @@
+//! Encodes the pixels read back from framebuffers into image files.
+
+use std::path::Path;
+
+use crate::errors::*;
+use crate::math::prelude::Vector2;
+
+/// Flips the rows of RGBA8 pixels vertically. It converts between the bottom-to-top
+/// row order of framebuffers and the top-to-bottom row order of images.
+pub fn flip_vertically(dimensions: Vector2<u32>, pixels: &[u8]) -> Vec<u8> {
+    let stride = dimensions.x as usize * 4;
+    if stride == 0 {
+        return Vec::new();
+    }
+
+    let mut flipped = Vec::with_capacity(pixels.len());
+    for row in pixels.chunks(stride).rev() {
+        flipped.extend_from_slice(row);
+    }
+
+    flipped
+}
+
+/// Saves RGBA8 pixels in bottom-to-top row order into a PNG file.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn save<P: AsRef<Path>>(path: P, dimensions: Vector2<u32>, pixels: &[u8]) -> Result<()> {
+    let len = dimensions.x as usize * dimensions.y as usize * 4;
+    if pixels.len() != len {
+        bail!(
+            "The length of pixels {} does not match the dimensions {:?}.",
+            pixels.len(),
+            dimensions
+        );
+    }
+
+    let path = path.as_ref();
+    let flipped = flip_vertically(dimensions, pixels);
+    let color = image::ColorType::RGBA(8);
+
+    image::save_buffer(path, &flipped, dimensions.x, dimensions.y, color)
+        .map_err(|err| format_err!("Failed to save screenshot into {:?}. {}", path, err))?;
+
+    Ok(())
+}
+
+/// Saves RGBA8 pixels in bottom-to-top row order into a PNG file.
+#[cfg(target_arch = "wasm32")]
+pub fn save<P: AsRef<Path>>(_: P, _: Vector2<u32>, _: &[u8]) -> Result<()> {
+    bail!("Saving screenshots into files is not supported on web browsers.");
+}
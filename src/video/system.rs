@@ -1,11 +1,15 @@
-use std::sync::{Arc, RwLock};
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex, RwLock};
+#[cfg(feature = "futures")]
+use std::task::Waker;
+use std::time::Duration;
 use uuid::Uuid;
 
 use crate::application::prelude::{LifecycleListener, LifecycleListenerHandle};
-use crate::math::prelude::{Aabb2, Vector2};
+use crate::math::prelude::{Aabb2, Aabb3, Color, Vector2};
 use crate::prelude::CrResult;
 use crate::res::utils::prelude::{ResourcePool, ResourceState};
-use crate::utils::prelude::{DoubleBuf, ObjectPool};
+use crate::utils::prelude::{DoubleBuf, FastHashMap, ObjectPool, Timestamp};
 
 use super::assets::mesh_loader::MeshLoader;
 use super::assets::prelude::*;
@@ -13,6 +17,21 @@ use super::assets::texture_loader::TextureLoader;
 use super::backends::frame::*;
 use super::backends::{self, Visitor};
 use super::errors::*;
+use super::watch::ShaderWatch;
+
+/// The interval between two polls of watched shader files.
+const SHADER_WATCH_INTERVAL: Duration = Duration::from_millis(500);
+
+/// The estimated video memory consumed by resources, in bytes. It's computed from the
+/// formats and dimensions of resources, rather than the actual allocations of drivers.
+/// Mipmaps are not taken into account.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct MemoryStats {
+    pub texture_bytes: usize,
+    pub mesh_bytes: usize,
+    /// Multisampled render textures are counted with their resolve targets.
+    pub render_texture_bytes: usize,
+}
 
 /// The centralized management of video sub-system.
 pub struct VideoSystem {
@@ -27,10 +46,21 @@ struct VideoState {
     meshes: RwLock<ResourcePool<MeshHandle, MeshLoader>>,
     textures: RwLock<ResourcePool<TextureHandle, TextureLoader>>,
     render_textures: RwLock<ObjectPool<RenderTextureHandle, RenderTextureParams>>,
+    queries: RwLock<ObjectPool<QueryHandle, QueryState>>,
+    max_samples: u32,
+    shader_log: RwLock<Option<(ShaderHandle, String)>>,
+    shader_watches: Mutex<FastHashMap<ShaderHandle, ShaderWatch>>,
+    white: Mutex<Option<TextureHandle>>,
+}
+
+struct QueryState {
+    kind: QueryKind,
+    running: bool,
+    result: Option<u64>,
 }
 
 impl VideoState {
-    fn new() -> Self {
+    fn new(max_samples: u32) -> Self {
         let frames = Arc::new(DoubleBuf::new(
             Frame::with_capacity(64 * 1024),
             Frame::with_capacity(64 * 1024),
@@ -42,13 +72,38 @@ impl VideoState {
             meshes: RwLock::new(ResourcePool::new(MeshLoader::new(frames.clone()))),
             textures: RwLock::new(ResourcePool::new(TextureLoader::new(frames.clone()))),
             render_textures: RwLock::new(ObjectPool::new()),
+            queries: RwLock::new(ObjectPool::new()),
+            max_samples,
+            shader_log: RwLock::new(None),
+            shader_watches: Mutex::new(FastHashMap::default()),
+            white: Mutex::new(None),
             frames,
         }
     }
+
+    fn reload_watched_shaders(&self) -> Vec<ShaderHandle> {
+        let mut reloaded = Vec::new();
+        for (&handle, watch) in self.shader_watches.lock().unwrap().iter_mut() {
+            if let Some((vs, fs)) = watch.poll() {
+                if let Err(err) = watch.params.validate(&vs, &fs) {
+                    warn!("Failed to reload {:?}.\n{}", handle, err);
+                    continue;
+                }
+
+                let v = (handle, watch.params.clone(), vs, fs, watch.sources.clone());
+                let cmd = Command::ReloadShader(Box::new(v));
+                self.frames.write().cmds.push(cmd);
+                reloaded.push(handle);
+            }
+        }
+
+        reloaded
+    }
 }
 
 struct Lifecycle {
     last_dimensions: Vector2<u32>,
+    last_shader_poll: Timestamp,
     visitor: Box<dyn Visitor>,
     state: Arc<VideoState>,
 }
@@ -60,6 +115,12 @@ impl LifecycleListener for Lifecycle {
         self.state.frames.write().clear();
         self.state.meshes.write().unwrap().advance()?;
         self.state.textures.write().unwrap().advance()?;
+
+        if cfg!(debug_assertions) && self.last_shader_poll.elapsed() >= SHADER_WATCH_INTERVAL {
+            self.last_shader_poll = Timestamp::now();
+            self.state.reload_watched_shaders();
+        }
+
         Ok(())
     }
 
@@ -72,10 +133,31 @@ impl LifecycleListener for Lifecycle {
             crate::window::inside::resize(dimensions);
         }
 
-        self.state
-            .frames
-            .write_back_buf()
-            .dispatch(self.visitor.as_mut(), self.last_dimensions)?;
+        // Dispatches into a local copy of the shader log, so `last_shader_log` is not
+        // blocked until the whole frame is submitted.
+        let mut shader_log = self.state.shader_log.read().unwrap().clone();
+        let screenshots = {
+            let mut frame = self.state.frames.write_back_buf();
+            frame.dispatch(self.visitor.as_mut(), self.last_dimensions, &mut shader_log)?;
+            frame.screenshots.drain(..).collect::<Vec<_>>()
+        };
+
+        *self.state.shader_log.write().unwrap() = shader_log;
+
+        // Encodes and writes screenshots after the dispatch, failures are only logged
+        // since `save_screenshot` has returned already.
+        for (path, size, pixels) in screenshots {
+            if let Err(err) = super::screenshot::save(&path, size, &pixels) {
+                warn!("{}", err);
+            }
+        }
+
+        // Polls the results of queries, which usually lag a frame or two on GPU.
+        for (handle, v) in self.state.queries.write().unwrap().iter_mut() {
+            if !v.running && v.result.is_none() {
+                v.result = unsafe { self.visitor.query_result(handle)? };
+            }
+        }
 
         Ok(())
     }
@@ -90,8 +172,8 @@ impl Drop for VideoSystem {
 impl VideoSystem {
     /// Create a new `VideoSystem`.
     pub fn new() -> CrResult<Self> {
-        let state = Arc::new(VideoState::new());
         let visitor = backends::new()?;
+        let state = Arc::new(VideoState::new(visitor.max_samples()));
 
         Ok(VideoSystem {
             state: state.clone(),
@@ -99,14 +181,15 @@ impl VideoSystem {
                 state,
                 visitor,
                 last_dimensions: dimensions_pixels(),
+                last_shader_poll: Timestamp::now(),
             }),
         })
     }
 
     /// Create a headless `VideoSystem`.
     pub fn headless() -> Self {
-        let state = Arc::new(VideoState::new());
         let visitor = backends::new_headless();
+        let state = Arc::new(VideoState::new(visitor.max_samples()));
 
         VideoSystem {
             state: state.clone(),
@@ -114,6 +197,25 @@ impl VideoSystem {
                 state,
                 visitor,
                 last_dimensions: Vector2::new(0, 0),
+                last_shader_poll: Timestamp::now(),
+            }),
+        }
+    }
+
+    /// Create a `VideoSystem` with the software rasterizer, which renders into memory
+    /// without any window or GPU.
+    #[cfg(feature = "software")]
+    pub fn software() -> Self {
+        let visitor = backends::new_software();
+        let state = Arc::new(VideoState::new(visitor.max_samples()));
+
+        VideoSystem {
+            state: state.clone(),
+            lis: crate::application::attach(Lifecycle {
+                state,
+                visitor,
+                last_dimensions: Vector2::new(0, 0),
+                last_shader_poll: Timestamp::now(),
             }),
         }
     }
@@ -151,6 +253,36 @@ impl VideoSystem {
         }
     }
 
+    /// Updates the clear flags of surface. Notes that a surface is cleared only once per
+    /// frame, when it's bound for the first time.
+    pub fn update_surface_clear<C, D, S>(
+        &self,
+        handle: SurfaceHandle,
+        color: C,
+        depth: D,
+        stencil: S,
+    ) -> Result<()>
+    where
+        C: Into<Option<Color<f32>>>,
+        D: Into<Option<f32>>,
+        S: Into<Option<i32>>,
+    {
+        let (color, depth, stencil) = (color.into(), depth.into(), stencil.into());
+
+        {
+            let mut surfaces = self.state.surfaces.write().unwrap();
+            let params = surfaces
+                .get_mut(handle)
+                .ok_or_else(|| Error::HandleInvalid(format!("{:?}", handle)))?;
+
+            params.set_clear(color, depth, stencil);
+        }
+
+        let cmd = Command::UpdateSurfaceClear(handle, Box::new((color, depth, stencil)));
+        self.state.frames.write().cmds.push(cmd);
+        Ok(())
+    }
+
     /// Deletes surface object.
     pub fn delete_surface(&self, handle: SurfaceHandle) {
         if self.state.surfaces.write().unwrap().free(handle).is_some() {
@@ -158,6 +290,82 @@ impl VideoSystem {
             self.state.frames.write().cmds.push(cmd);
         }
     }
+
+    /// Saves the contents of surface, or the window framebuffer if `surface` is none,
+    /// into a PNG file when the current frame is dispatched.
+    pub fn save_screenshot<T: Into<PathBuf>>(
+        &self,
+        surface: Option<SurfaceHandle>,
+        path: T,
+    ) -> Result<()> {
+        if cfg!(target_arch = "wasm32") {
+            return Err(Error::NotSupported("Screenshot on web browsers".into()));
+        }
+
+        if let Some(handle) = surface {
+            if !self.state.surfaces.read().unwrap().contains(handle) {
+                return Err(Error::HandleInvalid(format!("{:?}", handle)));
+            }
+        }
+
+        let cmd = Command::SaveScreenshot(Box::new((surface, path.into())));
+        self.state.frames.write().cmds.push(cmd);
+        Ok(())
+    }
+}
+
+impl VideoSystem {
+    /// Starts a query of `kind`, which measures the draw calls submitted until `end_query`.
+    pub fn begin_query(&self, kind: QueryKind) -> Result<QueryHandle> {
+        let handle = {
+            let mut queries = self.state.queries.write().unwrap();
+            if queries.values().any(|v| v.kind == kind && v.running) {
+                return Err(Error::QueryRunning(kind));
+            }
+
+            queries.create(QueryState {
+                kind,
+                running: true,
+                result: None,
+            })
+        };
+
+        let cmd = Command::BeginQuery(handle, kind);
+        self.state.frames.write().cmds.push(cmd);
+        Ok(handle)
+    }
+
+    /// Ends the query.
+    pub fn end_query(&self, handle: QueryHandle) -> Result<()> {
+        match self.state.queries.write().unwrap().get_mut(handle) {
+            Some(v) if v.running => v.running = false,
+            Some(_) => return Err(Error::QueryNotRunning(format!("{:?}", handle))),
+            None => return Err(Error::HandleInvalid(format!("{:?}", handle))),
+        }
+
+        let cmd = Command::EndQuery(handle);
+        self.state.frames.write().cmds.push(cmd);
+        Ok(())
+    }
+
+    /// Gets the result of query without blocking. Returns `None` if it's not available yet.
+    #[inline]
+    pub fn query_result(&self, handle: QueryHandle) -> Option<u64> {
+        self.state
+            .queries
+            .read()
+            .unwrap()
+            .get(handle)
+            .and_then(|v| v.result)
+    }
+
+    /// Deletes the query object.
+    pub fn delete_query(&self, handle: QueryHandle) {
+        if self.state.queries.write().unwrap().free(handle).is_some() {
+            let cmd = Command::DeleteQuery(handle);
+            self.state.frames.write().cmds.push(cmd);
+        }
+    }
 }
 
 impl VideoSystem {
@@ -181,12 +389,51 @@ impl VideoSystem {
         Ok(handle)
     }
 
+    /// Creates a shader with the embedded sources, and then relinks it in place with the
+    /// sources at `vs_path` and `fs_path`. The files are polled every
+    /// `SHADER_WATCH_INTERVAL` in debug builds and the shader is relinked whenever they
+    /// change, the last good sources are kept if the files could not be compiled.
+    pub fn create_shader_watched<T: Into<PathBuf>>(
+        &self,
+        params: ShaderParams,
+        vs: String,
+        fs: String,
+        vs_path: T,
+        fs_path: T,
+    ) -> Result<ShaderHandle> {
+        let handle = self.create_shader(params.clone(), vs.clone(), fs.clone())?;
+        let paths = [vs_path.into(), fs_path.into()];
+        let watch = ShaderWatch::new(params, vs, fs, paths);
+        self.state
+            .shader_watches
+            .lock()
+            .unwrap()
+            .insert(handle, watch);
+        self.state.reload_watched_shaders();
+        Ok(handle)
+    }
+
+    /// Polls the files of watched shaders, and relinks the shaders whose files have
+    /// changed. Returns the handles of relinked shaders.
+    #[inline]
+    pub fn reload_watched_shaders(&self) -> Vec<ShaderHandle> {
+        self.state.reload_watched_shaders()
+    }
+
     /// Gets the `ShaderParams` if available.
     #[inline]
     pub fn shader(&self, handle: ShaderHandle) -> Option<ShaderParams> {
         self.state.shaders.read().unwrap().get(handle).cloned()
     }
 
+    /// Gets the compile log of the most recently created shader. Returns `None` if it
+    /// compiles without errors.
+    #[inline]
+    pub fn last_shader_log(&self) -> Option<String> {
+        let shader_log = self.state.shader_log.read().unwrap();
+        shader_log.as_ref().map(|v| v.1.clone())
+    }
+
     /// Get the resource state of specified shader.
     #[inline]
     pub fn shader_state(&self, handle: ShaderHandle) -> ResourceState {
@@ -201,6 +448,7 @@ impl VideoSystem {
     #[inline]
     pub fn delete_shader(&self, handle: ShaderHandle) {
         if self.state.shaders.write().unwrap().free(handle).is_some() {
+            self.state.shader_watches.lock().unwrap().remove(&handle);
             let cmd = Command::DeleteShader(handle);
             self.state.frames.write().cmds.push(cmd);
         }
@@ -218,6 +466,41 @@ impl VideoSystem {
         meshes.create((params, data.into()))
     }
 
+    /// Create a new mesh object from bytes that are already laid out as `params`
+    /// describes. The lengths of bytes must match the buffers of mesh exactly, and they
+    /// are moved into the `MeshData` that is uploaded like `create_mesh`.
+    pub fn create_mesh_raw(
+        &self,
+        params: MeshParams,
+        vertex_bytes: Box<[u8]>,
+        index_bytes: Option<Box<[u8]>>,
+    ) -> CrResult<MeshHandle> {
+        if vertex_bytes.len() != params.vertex_buffer_len() {
+            bail!(
+                "The length of vertex bytes should be {}, found: {}.",
+                params.vertex_buffer_len(),
+                vertex_bytes.len()
+            );
+        }
+
+        if let Some(ref v) = index_bytes {
+            if v.len() != params.index_buffer_len() {
+                bail!(
+                    "The length of index bytes should be {}, found: {}.",
+                    params.index_buffer_len(),
+                    v.len()
+                );
+            }
+        }
+
+        let data = MeshData {
+            vptr: vertex_bytes,
+            iptr: index_bytes.unwrap_or_default(),
+        };
+
+        self.create_mesh(params, data)
+    }
+
     /// Creates a mesh object from file asynchronously.
     #[inline]
     pub fn create_mesh_from<T: AsRef<str>>(&self, url: T) -> CrResult<MeshHandle> {
@@ -235,7 +518,35 @@ impl VideoSystem {
     /// Gets the `MeshParams` if available.
     #[inline]
     pub fn mesh(&self, handle: MeshHandle) -> Option<MeshParams> {
-        self.state.meshes.read().unwrap().resource(handle).cloned()
+        self.state
+            .meshes
+            .read()
+            .unwrap()
+            .resource(handle)
+            .map(|v| v.0.clone())
+    }
+
+    /// Gets the bounding box of mesh if available, without cloning its `MeshParams`.
+    #[inline]
+    pub fn mesh_aabb(&self, handle: MeshHandle) -> Option<Aabb3<f32>> {
+        self.state
+            .meshes
+            .read()
+            .unwrap()
+            .resource(handle)
+            .map(|v| v.0.aabb)
+    }
+
+    /// Gets the copy of vertices and indices in memory, which is only available if the
+    /// mesh is created with `MeshParams::keep_cpu_data`.
+    #[inline]
+    pub fn mesh_cpu_data(&self, handle: MeshHandle) -> Option<Arc<MeshData>> {
+        self.state
+            .meshes
+            .read()
+            .unwrap()
+            .resource(handle)
+            .and_then(|v| v.1.clone())
     }
 
     /// Get the resource state of specified mesh.
@@ -244,6 +555,14 @@ impl VideoSystem {
         self.state.meshes.read().unwrap().state(handle)
     }
 
+    /// Gets the resource state of mesh, and registers `waker` to be woken once it's
+    /// loaded or failed.
+    #[cfg(feature = "futures")]
+    #[inline]
+    pub fn poll_mesh_state(&self, handle: MeshHandle, waker: &Waker) -> ResourceState {
+        self.state.meshes.write().unwrap().poll_state(handle, waker)
+    }
+
     /// Update a subset of dynamic vertex buffer. Use `offset` specifies the offset
     /// into the buffer object's data store where data replacement will begin, measured
     /// in bytes.
@@ -322,6 +641,43 @@ impl VideoSystem {
         self.state.textures.read().unwrap().state(handle)
     }
 
+    /// Gets the shared 1x1 white texture, which is created on first use and recreated
+    /// if it has been deleted.
+    pub fn white_texture(&self) -> TextureHandle {
+        let mut white = self.state.white.lock().unwrap();
+        let mut textures = self.state.textures.write().unwrap();
+        if let Some(handle) = *white {
+            if textures.contains(handle) {
+                return handle;
+            }
+        }
+
+        let params = TextureParams {
+            dimensions: Vector2::new(1, 1),
+            ..Default::default()
+        };
+
+        let data = TextureData {
+            bytes: vec![vec![255; 4].into_boxed_slice()],
+        };
+
+        let handle = textures.create((params, Some(data))).unwrap();
+        *white = Some(handle);
+        handle
+    }
+
+    /// Gets the resource state of texture, and registers `waker` to be woken once it's
+    /// loaded or failed.
+    #[cfg(feature = "futures")]
+    #[inline]
+    pub fn poll_texture_state(&self, handle: TextureHandle, waker: &Waker) -> ResourceState {
+        self.state
+            .textures
+            .write()
+            .unwrap()
+            .poll_state(handle, waker)
+    }
+
     /// Update a contiguous subregion of an existing two-dimensional texture object.
     pub fn update_texture(
         &self,
@@ -353,6 +709,8 @@ impl VideoSystem {
         &self,
         params: RenderTextureParams,
     ) -> Result<RenderTextureHandle> {
+        params.validate(self.state.max_samples)?;
+
         let handle = self.state.render_textures.write().unwrap().create(params);
 
         {
@@ -399,6 +757,51 @@ impl VideoSystem {
     }
 }
 
+impl VideoSystem {
+    /// Gets the estimated video memory consumed by all the living resources.
+    pub fn memory_stats(&self) -> MemoryStats {
+        let texture_bytes = self
+            .state
+            .textures
+            .read()
+            .unwrap()
+            .resources()
+            .map(|v| v.format.size(v.dimensions) as usize)
+            .sum();
+
+        let mesh_bytes = self
+            .state
+            .meshes
+            .read()
+            .unwrap()
+            .resources()
+            .map(|v| v.0.vertex_buffer_len() + v.0.index_buffer_len())
+            .sum();
+
+        let render_texture_bytes = self
+            .state
+            .render_textures
+            .read()
+            .unwrap()
+            .values()
+            .map(|v| {
+                let size = v.format.size(v.dimensions) as usize;
+                if v.is_multisampled() {
+                    size * (usize::from(v.samples) + 1)
+                } else {
+                    size
+                }
+            })
+            .sum();
+
+        MemoryStats {
+            texture_bytes,
+            mesh_bytes,
+            render_texture_bytes,
+        }
+    }
+}
+
 fn dimensions_pixels() -> Vector2<u32> {
     let dimensions = crate::window::dimensions();
     let dpr = crate::window::device_pixel_ratio();
@@ -0,0 +1,53 @@
+//! Reloads the sources of shaders from files when they change.
+
+use std::fs;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+
+use crate::utils::fingerprint::FileFingerprint;
+
+use super::assets::prelude::ShaderParams;
+
+/// The vertex and fragment sources that compiled successfully most recently. It's
+/// shared with the backend, which relinks the shader with these sources if the reloaded
+/// ones fail to compile.
+pub type ShaderSources = Arc<Mutex<(String, String)>>;
+
+pub struct ShaderWatch {
+    pub params: ShaderParams,
+    pub sources: ShaderSources,
+    paths: [PathBuf; 2],
+    fingerprints: Option<[FileFingerprint; 2]>,
+}
+
+impl ShaderWatch {
+    pub fn new(params: ShaderParams, vs: String, fs: String, paths: [PathBuf; 2]) -> Self {
+        ShaderWatch {
+            params,
+            sources: Arc::new(Mutex::new((vs, fs))),
+            paths,
+            fingerprints: None,
+        }
+    }
+
+    /// Reads the sources from files, and returns them if any of the files has changed
+    /// since last poll. Files that could not be read are skipped until they come back.
+    pub fn poll(&mut self) -> Option<(String, String)> {
+        let vs = fs::read_to_string(&self.paths[0]).ok()?;
+        let fs = fs::read_to_string(&self.paths[1]).ok()?;
+
+        let fingerprints = [
+            FileFingerprint::new(vs.as_bytes(), 0),
+            FileFingerprint::new(fs.as_bytes(), 0),
+        ];
+
+        if let Some(ref v) = self.fingerprints {
+            if !v[0].is_changed(&fingerprints[0]) && !v[1].is_changed(&fingerprints[1]) {
+                return None;
+            }
+        }
+
+        self.fingerprints = Some(fingerprints);
+        Some((vs, fs))
+    }
+}
@@ -39,6 +39,12 @@ fn from_window_event(source: &glutin::WindowEvent, dimensions: Vector2<u32>) ->
             Event::Window(WindowEvent::Resized(width as u32, height as u32)),
         ),
 
+        glutin::WindowEvent::HiDpiFactorChanged(ratio) => {
+            Some(Event::Window(WindowEvent::ScaleFactorChanged {
+                ratio: ratio as f32,
+            }))
+        }
+
         glutin::WindowEvent::CursorMoved { position, .. } => {
             Some(Event::InputDevice(InputEvent::MouseMoved {
                 position: (position.x as f32, dimensions.y as f32 - position.y as f32),
@@ -105,7 +111,11 @@ fn from_window_event(source: &glutin::WindowEvent, dimensions: Vector2<u32>) ->
         glutin::WindowEvent::Touch(touch) => Some(Event::InputDevice(InputEvent::Touch {
             id: touch.id as u8,
             state: from_touch_state(touch.phase),
-            position: (touch.location.x as f32, touch.location.y as f32).into(),
+            position: (
+                touch.location.x as f32,
+                dimensions.y as f32 - touch.location.y as f32,
+            )
+                .into(),
         })),
 
         _ => None,
@@ -3,7 +3,8 @@ use std::sync::{Arc, Mutex};
 use wasm_bindgen::prelude::*;
 use wasm_bindgen::JsCast;
 use web_sys::{
-    self, Document, Element, HtmlCanvasElement, KeyboardEvent, MouseEvent, Node, UiEvent, Window,
+    self, CompositionEvent, Document, Element, HtmlCanvasElement, KeyboardEvent, MouseEvent, Node,
+    UiEvent, Window,
 };
 
 use crate::input::prelude::{InputEvent, MouseButton};
@@ -20,11 +21,15 @@ pub struct WebVisitor {
     document: Document,
     canvas: HtmlCanvasElement,
     events: Arc<Mutex<Vec<Event>>>,
+    device_pixel_ratio: f32,
     on_mouse_move: Closure<FnMut(MouseEvent)>,
     on_mouse_down: Closure<FnMut(MouseEvent)>,
     on_mouse_up: Closure<FnMut(MouseEvent)>,
     on_key_down: Closure<FnMut(KeyboardEvent)>,
     on_key_up: Closure<FnMut(KeyboardEvent)>,
+    on_composition_start: Closure<FnMut(CompositionEvent)>,
+    on_composition_update: Closure<FnMut(CompositionEvent)>,
+    on_composition_end: Closure<FnMut(CompositionEvent)>,
     on_resize: Closure<FnMut(UiEvent)>,
     on_focus: Closure<FnMut(UiEvent)>,
     on_lost_focus: Closure<FnMut(UiEvent)>,
@@ -156,6 +161,53 @@ impl WebVisitor {
             .add_event_listener_with_callback("keyup", on_key_up.as_ref().unchecked_ref())
             .unwrap();
 
+        let on_composition_start = {
+            let clone = events.clone();
+            Closure::wrap(Box::new(move |_: CompositionEvent| {
+                let evt = Event::InputDevice(InputEvent::CompositionStart);
+                clone.lock().unwrap().push(evt);
+            }) as Box<FnMut(_)>)
+        };
+
+        window
+            .add_event_listener_with_callback(
+                "compositionstart",
+                on_composition_start.as_ref().unchecked_ref(),
+            ).unwrap();
+
+        let on_composition_update = {
+            let clone = events.clone();
+            Closure::wrap(Box::new(move |v: CompositionEvent| {
+                // Browsers do not report the caret inside preedit string, so we assume
+                // it's always at the end.
+                let text = v.data().unwrap_or_default();
+                let cursor = text.chars().count();
+                let evt = Event::InputDevice(InputEvent::CompositionUpdate { text, cursor });
+                clone.lock().unwrap().push(evt);
+            }) as Box<FnMut(_)>)
+        };
+
+        window
+            .add_event_listener_with_callback(
+                "compositionupdate",
+                on_composition_update.as_ref().unchecked_ref(),
+            ).unwrap();
+
+        let on_composition_end = {
+            let clone = events.clone();
+            Closure::wrap(Box::new(move |v: CompositionEvent| {
+                let text = v.data().unwrap_or_default();
+                let evt = Event::InputDevice(InputEvent::CompositionCommit { text });
+                clone.lock().unwrap().push(evt);
+            }) as Box<FnMut(_)>)
+        };
+
+        window
+            .add_event_listener_with_callback(
+                "compositionend",
+                on_composition_end.as_ref().unchecked_ref(),
+            ).unwrap();
+
         let on_focus = {
             let clone = events.clone();
             Closure::wrap(Box::new(move |_: UiEvent| {
@@ -198,11 +250,15 @@ impl WebVisitor {
             document: document,
             canvas: canvas,
             events: events,
+            device_pixel_ratio: window.device_pixel_ratio() as f32,
             on_mouse_down: on_mouse_down,
             on_mouse_up: on_mouse_up,
             on_mouse_move: on_mouse_move,
             on_key_down: on_key_down,
             on_key_up: on_key_up,
+            on_composition_start: on_composition_start,
+            on_composition_update: on_composition_update,
+            on_composition_end: on_composition_end,
             on_focus: on_focus,
             on_lost_focus: on_lost_focus,
             on_resize: on_resize,
@@ -271,6 +327,13 @@ impl Visitor for WebVisitor {
     fn poll_events(&mut self, v: &mut Vec<Event>) {
         let mut events = self.events.lock().unwrap();
         v.extend(events.drain(..));
+
+        // Browsers do not notify the changes of device pixel ratio directly.
+        let ratio = self.device_pixel_ratio();
+        if ratio != self.device_pixel_ratio {
+            self.device_pixel_ratio = ratio;
+            v.push(Event::Window(WindowEvent::ScaleFactorChanged { ratio }));
+        }
     }
 
     #[inline]
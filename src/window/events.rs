@@ -19,10 +19,13 @@ pub enum WindowEvent {
     Resized(u32, u32),
     /// The position of window has changed.
     Moved(u32, u32),
+    /// The device pixel ratio of window has changed, e.g. the window has been moved
+    /// to a monitor with different DPI.
+    ScaleFactorChanged { ratio: f32 },
 }
 
 /// The enumerations of all events that come from various kinds of user input.
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone)]
 pub enum Event {
     Window(WindowEvent),
     InputDevice(InputEvent),
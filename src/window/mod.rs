@@ -1,4 +1,7 @@
 //! Represents an OpenGL context and the window or environment around it.
+//!
+//! The window system could only be used on the thread that sets up the engine, calling
+//! its functions from other threads (e.g. the jobs of scheduler) panics.
 pub mod events;
 
 pub mod prelude {
@@ -114,38 +117,33 @@ pub fn device_pixel_ratio() -> f32 {
 pub(crate) mod inside {
     use crate::errors::*;
     use crate::math::prelude::Vector2;
+    use crate::utils::global::LocalGlobal;
 
     use super::system::WindowSystem;
     use super::WindowParams;
 
-    pub static mut CTX: *const WindowSystem = std::ptr::null();
+    pub static CTX: LocalGlobal<WindowSystem> = LocalGlobal::new();
 
     #[inline]
     pub fn ctx() -> &'static WindowSystem {
-        unsafe {
-            debug_assert!(
-                !CTX.is_null(),
-                "window system has not been initialized properly."
-            );
-
-            &*CTX
-        }
+        CTX.get()
+            .expect("window system has not been initialized properly.")
     }
 
     /// Setup the window system.
     pub unsafe fn setup(params: WindowParams) -> Result<()> {
-        debug_assert!(CTX.is_null(), "duplicated setup of window system.");
-
         let ctx = WindowSystem::from(params)?;
-        CTX = Box::into_raw(Box::new(ctx));
+        if CTX.set(ctx).is_err() {
+            panic!("duplicated setup of window system.");
+        }
         Ok(())
     }
 
     pub unsafe fn headless() {
-        debug_assert!(CTX.is_null(), "duplicated setup of window system.");
-
         let ctx = WindowSystem::headless();
-        CTX = Box::into_raw(Box::new(ctx));
+        if CTX.set(ctx).is_err() {
+            panic!("duplicated setup of window system.");
+        }
     }
 
     /// Resize the GL context.
@@ -156,11 +154,6 @@ pub(crate) mod inside {
 
     /// Discard the window system.
     pub unsafe fn discard() {
-        if CTX.is_null() {
-            return;
-        }
-
-        drop(Box::from_raw(CTX as *mut WindowSystem));
-        CTX = std::ptr::null();
+        CTX.discard();
     }
 }
@@ -6,7 +6,7 @@ use crate::math::prelude::Vector2;
 use crate::utils::object_pool::ObjectPool;
 
 use super::backends::{self, Visitor};
-use super::events::Event;
+use super::events::{Event, WindowEvent};
 use super::WindowParams;
 
 impl_handle!(EventListenerHandle);
@@ -23,6 +23,7 @@ pub struct WindowSystem {
 
 struct WindowState {
     visitor: RwLock<Box<dyn Visitor>>,
+    device_pixel_ratio: RwLock<f32>,
     events: Mutex<Vec<Event>>,
     last_frame_listeners: Mutex<Vec<Arc<Mutex<dyn EventListener>>>>,
     listeners: Mutex<ObjectPool<EventListenerHandle, Arc<Mutex<dyn EventListener>>>>,
@@ -37,6 +38,13 @@ impl LifecycleListener for Arc<WindowState> {
         let mut visitor = self.visitor.write().unwrap();
         visitor.poll_events(&mut events);
 
+        // Updates the ratio before notifying listeners, so they could query it.
+        for v in events.iter() {
+            if let Event::Window(WindowEvent::ScaleFactorChanged { ratio }) = *v {
+                *self.device_pixel_ratio.write().unwrap() = ratio;
+            }
+        }
+
         let mut last_frame_listeners = self.last_frame_listeners.lock().unwrap();
 
         {
@@ -66,6 +74,18 @@ impl LifecycleListener for Arc<WindowState> {
     }
 }
 
+impl WindowState {
+    fn new(visitor: Box<dyn Visitor>) -> Self {
+        WindowState {
+            last_frame_listeners: Mutex::new(Vec::new()),
+            listeners: Mutex::new(ObjectPool::new()),
+            events: Mutex::new(Vec::new()),
+            device_pixel_ratio: RwLock::new(visitor.device_pixel_ratio()),
+            visitor: RwLock::new(visitor),
+        }
+    }
+}
+
 impl Drop for WindowSystem {
     fn drop(&mut self) {
         crate::application::detach(self.lis);
@@ -75,12 +95,7 @@ impl Drop for WindowSystem {
 impl WindowSystem {
     /// Creates a new `WindowSystem` and initalize OpenGL context.
     pub fn from(params: WindowParams) -> Result<Self> {
-        let state = Arc::new(WindowState {
-            last_frame_listeners: Mutex::new(Vec::new()),
-            listeners: Mutex::new(ObjectPool::new()),
-            events: Mutex::new(Vec::new()),
-            visitor: RwLock::new(backends::new(params)?),
-        });
+        let state = Arc::new(WindowState::new(backends::new(params)?));
 
         let window = WindowSystem {
             state: state.clone(),
@@ -92,12 +107,7 @@ impl WindowSystem {
 
     /// Creates a new `Window` with headless context.
     pub fn headless() -> Self {
-        let state = Arc::new(WindowState {
-            last_frame_listeners: Mutex::new(Vec::new()),
-            listeners: Mutex::new(ObjectPool::new()),
-            events: Mutex::new(Vec::new()),
-            visitor: RwLock::new(backends::new_headless()),
-        });
+        let state = Arc::new(WindowState::new(backends::new_headless()));
 
         WindowSystem {
             state: state.clone(),
@@ -172,9 +182,11 @@ impl WindowSystem {
 
     /// Returns the ratio between the backing framebuffer resolution and the window size in
     /// screen pixels. This is typically one for a normal display and two for a retina display.
+    ///
+    /// It's updated with `WindowEvent::ScaleFactorChanged` before listeners are notified.
     #[inline]
     pub fn device_pixel_ratio(&self) -> f32 {
-        self.state.visitor.read().unwrap().device_pixel_ratio()
+        *self.state.device_pixel_ratio.read().unwrap()
     }
 
     /// Resize the GL context.
@@ -183,3 +195,82 @@ impl WindowSystem {
         self.state.visitor.read().unwrap().resize(dimensions);
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::sync::Weak;
+
+    struct ScaleVisitor(Vec<Event>);
+
+    impl Visitor for ScaleVisitor {
+        fn show(&self) {}
+        fn hide(&self) {}
+
+        fn position(&self) -> Vector2<i32> {
+            (0, 0).into()
+        }
+
+        fn dimensions(&self) -> Vector2<u32> {
+            (0, 0).into()
+        }
+
+        fn device_pixel_ratio(&self) -> f32 {
+            1.0
+        }
+
+        fn resize(&self, _: Vector2<u32>) {}
+
+        fn poll_events(&mut self, events: &mut Vec<Event>) {
+            events.append(&mut self.0);
+        }
+
+        fn is_current(&self) -> bool {
+            true
+        }
+
+        fn make_current(&self) -> Result<()> {
+            Ok(())
+        }
+
+        fn swap_buffers(&self) -> Result<()> {
+            Ok(())
+        }
+    }
+
+    struct Recorder(Weak<WindowState>, Arc<Mutex<Vec<(f32, f32)>>>);
+
+    impl EventListener for Recorder {
+        fn on(&mut self, v: &Event) -> Result<()> {
+            if let Event::Window(WindowEvent::ScaleFactorChanged { ratio }) = *v {
+                let state = self.0.upgrade().unwrap();
+                let current = *state.device_pixel_ratio.read().unwrap();
+                self.1.lock().unwrap().push((ratio, current));
+            }
+
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn scale_factor_changed() {
+        let event = Event::Window(WindowEvent::ScaleFactorChanged { ratio: 2.0 });
+        let mut state = Arc::new(WindowState::new(Box::new(ScaleVisitor(vec![event]))));
+        assert_eq!(*state.device_pixel_ratio.read().unwrap(), 1.0);
+
+        let records = Arc::new(Mutex::new(Vec::new()));
+        let lis = Recorder(Arc::downgrade(&state), records.clone());
+        state
+            .listeners
+            .lock()
+            .unwrap()
+            .create(Arc::new(Mutex::new(lis)));
+
+        state.on_pre_update().unwrap();
+        assert_eq!(*records.lock().unwrap(), [(2.0, 2.0)]);
+        assert_eq!(*state.device_pixel_ratio.read().unwrap(), 2.0);
+
+        state.on_pre_update().unwrap();
+        assert_eq!(records.lock().unwrap().len(), 1);
+    }
+}
@@ -0,0 +1,74 @@
+extern crate crayon;
+
+use std::collections::HashSet;
+use std::sync::Arc;
+use std::thread;
+
+use crayon::utils::prelude::*;
+
+#[test]
+fn basic() {
+    let pool = AtomicHandlePool::<Handle>::new(2);
+    assert!(pool.is_empty());
+
+    let e1 = pool.create().unwrap();
+    let e2 = pool.create().unwrap();
+    assert_eq!((e1.index(), e1.version()), (0, 1));
+    assert_eq!((e2.index(), e2.version()), (1, 1));
+    assert!(pool.create().is_none());
+    assert_eq!(pool.len(), 2);
+
+    assert!(pool.free(e1));
+    assert!(!pool.free(e1));
+    assert!(!pool.contains(e1));
+    assert!(pool.contains(e2));
+
+    let e3 = pool.create().unwrap();
+    assert_eq!((e3.index(), e3.version()), (0, 3));
+    assert!(!pool.contains(e1));
+    assert!(pool.contains(e3));
+    assert_eq!(pool.len(), 2);
+}
+
+#[test]
+fn stress() {
+    let pool = Arc::new(AtomicHandlePool::<Handle>::new(1024));
+
+    let mut threads = Vec::new();
+    for i in 0..8 {
+        let pool = pool.clone();
+        threads.push(thread::spawn(move || {
+            let mut handles = Vec::new();
+            for j in 0..10_000 {
+                if (i + j) % 3 != 0 {
+                    let handle = pool.create().unwrap();
+                    assert!(pool.contains(handle));
+                    handles.push(handle);
+                } else if let Some(handle) = handles.pop() {
+                    assert!(pool.free(handle));
+                    assert!(!pool.contains(handle));
+                }
+
+                if handles.len() >= 64 {
+                    for v in handles.drain(..) {
+                        assert!(pool.free(v));
+                    }
+                }
+            }
+
+            handles
+        }));
+    }
+
+    let mut alives = HashSet::new();
+    let mut indices = HashSet::new();
+    for v in threads {
+        for handle in v.join().unwrap() {
+            assert!(pool.contains(handle));
+            assert!(alives.insert(handle));
+            assert!(indices.insert(handle.index()));
+        }
+    }
+
+    assert_eq!(pool.len(), alives.len());
+}
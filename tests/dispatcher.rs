@@ -0,0 +1,78 @@
+extern crate crayon;
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use crayon::sched::prelude::*;
+
+struct Position;
+struct Velocity;
+struct Health;
+
+/// Waits until `n` systems have arrived, and returns false if it times out.
+fn rendezvous(arrived: &AtomicUsize, n: usize) -> bool {
+    arrived.fetch_add(1, Ordering::SeqCst);
+
+    let ts = Instant::now();
+    while arrived.load(Ordering::SeqCst) < n {
+        if ts.elapsed() > Duration::from_secs(5) {
+            return false;
+        }
+
+        std::thread::yield_now();
+    }
+
+    true
+}
+
+#[test]
+fn dispatch() {
+    crayon::application::oneshot().unwrap();
+
+    let a = Access::new().read::<Velocity>().write::<Position>();
+    let b = Access::new().write::<Health>();
+    let c = Access::new().read::<Position>();
+    assert!(!a.conflicts(&b));
+    assert!(a.conflicts(&c) && c.conflicts(&a));
+    assert!(!b.conflicts(&c));
+    assert!(!c.conflicts(&c.clone()));
+    assert!(b.conflicts(&b.clone()));
+
+    let arrived = AtomicUsize::new(0);
+    let concurrent = AtomicUsize::new(0);
+    let order = Mutex::new(Vec::new());
+
+    {
+        let mut dispatcher = Dispatcher::new();
+
+        dispatcher.add(a, || {
+            if rendezvous(&arrived, 2) {
+                concurrent.fetch_add(1, Ordering::SeqCst);
+            }
+
+            order.lock().unwrap().push("a");
+        });
+
+        dispatcher.add(b, || {
+            if rendezvous(&arrived, 2) {
+                concurrent.fetch_add(1, Ordering::SeqCst);
+            }
+
+            order.lock().unwrap().push("b");
+        });
+
+        dispatcher.add(c, || {
+            order.lock().unwrap().push("c");
+        });
+
+        assert_eq!(dispatcher.stages(), [vec![0, 1], vec![2]]);
+        dispatcher.run();
+    }
+
+    assert_eq!(concurrent.load(Ordering::SeqCst), 2);
+
+    let order = order.into_inner().unwrap();
+    assert_eq!(order.len(), 3);
+    assert_eq!(order[2], "c");
+}
@@ -0,0 +1,47 @@
+extern crate crayon;
+
+use crayon::prelude::*;
+use crayon::utils::handle::HandleLike;
+
+#[test]
+fn reuse() {
+    crayon::application::oneshot().unwrap();
+
+    let shader = ShaderHandle::new(1, 1);
+    let mesh = MeshHandle::new(1, 1);
+    let surface = SurfaceHandle::default();
+
+    let mut arena = DrawArena::with_capacity(0, 0);
+    let mut batch = DrawCommandBuffer::<usize>::new();
+    let mut capacity = 0;
+
+    for n in 0..2 {
+        let mut frame = arena.frame();
+        assert!(frame.is_empty());
+
+        let mut draws = Vec::new();
+        for i in 0..64 {
+            let mut builder = frame.draw(shader, mesh);
+            // More uniform variables than a `Draw` could hold inline.
+            for j in 0..12 {
+                builder = builder.with_uniform_variable(format!("u_{}", j).as_str(), j as f32);
+            }
+
+            draws.push((i, builder.with_uniform_variable("u_0", -1.0).finish()));
+        }
+
+        assert_eq!(frame.len(), 64);
+        for (i, dc) in draws {
+            batch.draw_arena(i, &frame, dc);
+        }
+
+        batch.submit(surface).unwrap();
+
+        if n == 0 {
+            capacity = arena.capacity();
+            assert!(capacity > 0);
+        } else {
+            assert_eq!(arena.capacity(), capacity);
+        }
+    }
+}
@@ -0,0 +1,50 @@
+extern crate crayon;
+
+use crayon::res::request::Request;
+use crayon::res::url::Url;
+use crayon::res::vfs::embedded::Embedded;
+use crayon::res::vfs::VFS;
+
+fn load(vfs: &Embedded, url: &str) -> Option<Vec<u8>> {
+    let latch = Request::latch();
+    vfs.request(&Url::new(url).unwrap(), latch.clone());
+    latch.take().ok().map(|v| v.to_vec())
+}
+
+#[test]
+fn pack() {
+    let files: &[(&str, &[u8])] = &[("a.bin", &[1, 2, 3]), ("/dir/b.bin", &[4, 5])];
+    let bytes: &'static [u8] = Box::leak(Embedded::pack(files).unwrap().into_boxed_slice());
+    let vfs = Embedded::from_pack(bytes).unwrap();
+
+    assert_eq!(load(&vfs, "embedded://pack/a.bin"), Some(vec![1, 2, 3]));
+    assert_eq!(load(&vfs, "embedded://pack/dir/b.bin"), Some(vec![4, 5]));
+    assert_eq!(load(&vfs, "embedded://pack/c.bin"), None);
+}
+
+#[test]
+fn invalid_pack() {
+    assert!(Embedded::from_pack(b"NOT A PACK").is_err());
+
+    let files: &[(&str, &[u8])] = &[("a.bin", &[1, 2, 3])];
+    let mut bytes = Embedded::pack(files).unwrap();
+    bytes.pop();
+    let bytes: &'static [u8] = Box::leak(bytes.into_boxed_slice());
+    assert!(Embedded::from_pack(bytes).is_err());
+
+    // The offset of file overflows with its length.
+    let mut bytes = Embedded::pack(files).unwrap();
+    let pos = bytes.windows(5).position(|v| v == b"a.bin").unwrap() + 5;
+    bytes[pos..pos + 8].copy_from_slice(&[0xFF; 8]);
+    let bytes: &'static [u8] = Box::leak(bytes.into_boxed_slice());
+    assert!(Embedded::from_pack(bytes).is_err());
+}
+
+#[test]
+fn add() {
+    let mut vfs = Embedded::new();
+    vfs.add("/a.bin", b"hello");
+
+    assert_eq!(vfs.get("a.bin"), Some(&b"hello"[..]));
+    assert_eq!(load(&vfs, "embedded://pack/a.bin"), Some(b"hello".to_vec()));
+}
@@ -0,0 +1,39 @@
+extern crate crayon;
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use crayon::errors::Result;
+use crayon::prelude::*;
+
+static UPDATES: AtomicUsize = AtomicUsize::new(0);
+static RENDERS: AtomicUsize = AtomicUsize::new(0);
+
+struct Window {}
+
+impl LifecycleListener for Window {
+    fn on_update(&mut self) -> Result<()> {
+        UPDATES.fetch_add(1, Ordering::SeqCst);
+        Ok(())
+    }
+
+    fn on_render(&mut self) -> Result<()> {
+        RENDERS.fetch_add(1, Ordering::SeqCst);
+        Ok(())
+    }
+}
+
+#[test]
+fn run_headless() {
+    let params = Params::default();
+    Engine::new_with(&params)
+        .with_headless(true)
+        .run(|| {
+            assert!(crayon::application::headless());
+            Ok(Window {})
+        })
+        .unwrap();
+
+    assert_eq!(UPDATES.load(Ordering::SeqCst), 1);
+    assert_eq!(RENDERS.load(Ordering::SeqCst), 1);
+    assert!(!crayon::application::valid());
+}
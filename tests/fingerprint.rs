@@ -0,0 +1,39 @@
+extern crate crayon;
+
+use std::fs;
+
+use crayon::utils::prelude::*;
+
+#[test]
+fn content_hash_stable() {
+    let bytes = vec![0u8, 1, 2, 3, 4, 5, 6, 7, 8];
+    assert_eq!(content_hash(&bytes), content_hash(&bytes.clone()));
+
+    let mut changed = bytes.clone();
+    changed[4] = 255;
+    assert_ne!(content_hash(&bytes), content_hash(&changed));
+
+    // Pins the value, since persisted hashes would be invalidated if it changes.
+    assert_eq!(content_hash(b"to be or not to be"), 1988685042348123509);
+}
+
+#[test]
+fn fingerprint() {
+    let path = std::env::temp_dir().join("crayon-fingerprint-test.bin");
+
+    fs::write(&path, b"hello world").unwrap();
+    let a = FileFingerprint::from_path(&path).unwrap();
+    assert_eq!(a, FileFingerprint::new(b"hello world", a.mtime));
+    assert_eq!(a.len, 11);
+
+    fs::write(&path, b"hello world").unwrap();
+    let b = FileFingerprint::from_path(&path).unwrap();
+    assert!(!a.is_changed(&b));
+
+    fs::write(&path, b"hello World").unwrap();
+    let c = FileFingerprint::from_path(&path).unwrap();
+    assert!(a.is_changed(&c));
+
+    fs::remove_file(&path).unwrap();
+    assert!(FileFingerprint::from_path(&path).is_err());
+}
@@ -0,0 +1,119 @@
+#![cfg(feature = "futures")]
+
+extern crate crayon;
+
+use std::collections::HashMap;
+use std::future::Future;
+use std::io;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll, Wake, Waker};
+use std::thread;
+use std::time::Duration;
+
+use crayon::res::future::ResourceFuture;
+use crayon::res::request::{Request, Response};
+use crayon::res::url::Url;
+use crayon::res::vfs::VFS;
+use crayon::sched::prelude::LockLatch;
+use crayon::video;
+use crayon::video::prelude::TextureParams;
+
+/// A VFS that serves files from memory after a delay.
+#[derive(Debug, Default)]
+struct MemoryFS {
+    files: HashMap<String, Vec<u8>>,
+}
+
+impl VFS for MemoryFS {
+    fn request(&self, url: &Url, state: Arc<LockLatch<Response>>) {
+        let rsp = match self.files.get(url.path()) {
+            Some(bytes) => Ok(bytes.clone().into_boxed_slice()),
+            None => Err(io::Error::new(io::ErrorKind::NotFound, url.path()).into()),
+        };
+
+        thread::spawn(move || {
+            thread::sleep(Duration::from_millis(20));
+            state.set(rsp);
+        });
+    }
+}
+
+struct ThreadWaker(thread::Thread, Mutex<usize>);
+
+impl Wake for ThreadWaker {
+    fn wake(self: Arc<Self>) {
+        *self.1.lock().unwrap() += 1;
+        self.0.unpark();
+    }
+}
+
+/// Polls the future on current thread, and parks in between.
+fn block_on<F: Future>(future: F) -> (F::Output, usize) {
+    let mut future = Box::pin(future);
+    let inner = Arc::new(ThreadWaker(thread::current(), Mutex::new(0)));
+    let waker = Waker::from(inner.clone());
+    let mut cx = Context::from_waker(&waker);
+
+    loop {
+        if let Poll::Ready(v) = future.as_mut().poll(&mut cx) {
+            let wakes = *inner.1.lock().unwrap();
+            return (v, wakes);
+        }
+
+        thread::park();
+    }
+}
+
+fn load(fs: &MemoryFS, url: &str) -> ResourceFuture {
+    let latch = Request::latch();
+    fs.request(&Url::new(url).unwrap(), latch.clone());
+    Request::new(latch).into()
+}
+
+#[test]
+fn await_memory_fs() {
+    let mut fs = MemoryFS::default();
+    fs.files.insert("/crate.bin".into(), vec![1, 2, 3]);
+
+    let (rsp, wakes) = block_on(async {
+        let bytes = load(&fs, "mem://memory/crate.bin").await.unwrap();
+        let err = load(&fs, "mem://memory/blah.bin").await;
+        (bytes, err.is_err())
+    });
+
+    assert_eq!(&rsp.0[..], &[1, 2, 3]);
+    assert!(rsp.1);
+    assert!(wakes >= 2);
+
+    let (rsp, wakes) = block_on(ResourceFuture::from(Request::ok(vec![4])));
+    assert_eq!(&rsp.unwrap()[..], &[4]);
+    assert_eq!(wakes, 0);
+}
+
+#[test]
+fn register_once() {
+    let latch = LockLatch::<()>::new();
+    let inner = Arc::new(ThreadWaker(thread::current(), Mutex::new(0)));
+    let waker = Waker::from(inner.clone());
+
+    for _ in 0..8 {
+        assert!(!latch.register(&waker));
+    }
+
+    latch.set(());
+    assert_eq!(*inner.1.lock().unwrap(), 1);
+    assert!(latch.register(&waker));
+}
+
+#[test]
+fn await_texture() {
+    crayon::application::oneshot().unwrap();
+
+    let texture = video::create_texture(TextureParams::default(), None).unwrap();
+    let (rsp, _) = block_on(video::texture_future(texture));
+    assert_eq!(rsp.unwrap(), texture);
+
+    video::delete_texture(texture);
+    let (rsp, _) = block_on(video::texture_future(texture));
+    assert!(rsp.is_err());
+}
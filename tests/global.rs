@@ -0,0 +1,38 @@
+extern crate crayon;
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use crayon::input::prelude::*;
+use crayon::video::prelude::*;
+
+#[test]
+fn concurrent_access() {
+    crayon::application::oneshot().unwrap();
+
+    let finished = AtomicUsize::new(0);
+    crayon::sched::scope(|s| {
+        for _ in 0..16 {
+            s.spawn(|_| {
+                assert!(crayon::res::valid());
+                assert!(crayon::input::valid());
+
+                let params = SurfaceParams::default();
+                let surface = crayon::video::create_surface(params).unwrap();
+                assert!(crayon::video::surface(surface).is_some());
+                crayon::video::delete_surface(surface);
+
+                assert!(crayon::res::find("not_exists").is_none());
+                assert!(!crayon::input::is_key_down(Key::A));
+                crayon::application::fps();
+
+                finished.fetch_add(1, Ordering::SeqCst);
+            });
+        }
+    });
+
+    assert_eq!(finished.load(Ordering::SeqCst), 16);
+
+    // The window system could only be used on the thread that set it up.
+    crayon::window::dimensions();
+    assert!(std::thread::spawn(crayon::window::dimensions).join().is_err());
+}
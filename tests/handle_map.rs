@@ -0,0 +1,94 @@
+#[macro_use]
+extern crate crayon;
+
+use crayon::utils::prelude::*;
+
+impl_handle!(MeshHandle);
+
+#[test]
+fn basic() {
+    let mut map = HandleMap::<Handle, i32>::new();
+    let (h1, h2) = (Handle::new(1, 1), Handle::new(2, 1));
+
+    assert_eq!(map.add(h1, 1), None);
+    assert_eq!(map.add(h2, 2), None);
+    assert_eq!(map.add(h1, 3), Some(1));
+    assert_eq!(map.len(), 2);
+    assert!(map.contains(h1));
+    assert_eq!(map.get(h1), Some(&3));
+
+    *map.get_mut(h2).unwrap() += 1;
+    assert_eq!(map.get(h2), Some(&3));
+
+    // Handles with the same index but another version are different ones.
+    assert!(!map.contains(Handle::new(1, 2)));
+    assert_eq!(map.remove(Handle::new(1, 2)), None);
+}
+
+#[test]
+fn remove_then_iter() {
+    let mut map = HandleMap::<Handle, u32>::new();
+    for i in 0..10 {
+        map.add(Handle::new(i, 1), i * 10);
+    }
+
+    assert_eq!(map.remove(Handle::new(0, 1)), Some(0));
+    assert_eq!(map.remove(Handle::new(5, 1)), Some(50));
+    assert_eq!(map.remove(Handle::new(9, 1)), Some(90));
+    assert_eq!(map.remove(Handle::new(9, 1)), None);
+    assert_eq!(map.len(), 7);
+
+    for (handle, &v) in map.iter() {
+        assert_eq!(v, handle.index() * 10);
+        assert_eq!(map.get(handle), Some(&v));
+    }
+
+    for (handle, v) in map.keys().iter().zip(map.values()) {
+        assert_eq!(*v, handle.index() * 10);
+    }
+
+    let mut keys: Vec<_> = map.keys().iter().map(|v| v.index()).collect();
+    keys.sort();
+    assert_eq!(keys, [1, 2, 3, 4, 6, 7, 8]);
+
+    for v in map.values_mut() {
+        *v += 1;
+    }
+
+    for (handle, v) in map.iter_mut() {
+        assert_eq!(*v, handle.index() * 10 + 1);
+    }
+
+    while let Some(&handle) = map.keys().first() {
+        map.remove(handle);
+    }
+
+    assert!(map.is_empty());
+    assert_eq!(map.iter().count(), 0);
+}
+
+#[test]
+fn typed_handles() {
+    // Attaches data to handles that are allocated by another pool, like the renderers
+    // that keep materials of meshes.
+    let mut pool = HandlePool::<MeshHandle>::new();
+    let mut materials = HandleMap::<MeshHandle, u32>::new();
+
+    let m1 = pool.create();
+    let m2 = pool.create();
+    materials.add(m1, 1);
+    materials.add(m2, 2);
+
+    pool.free(m1);
+    materials.remove(m1);
+
+    // The recycled handle does not see the data of the freed one.
+    let m3 = pool.create();
+    assert_eq!(m3.index(), m1.index());
+    assert_eq!(materials.get(m3), None);
+    assert_eq!(materials.get(m2), Some(&2));
+
+    materials.add(m3, 3);
+    assert_eq!(materials.keys(), &[m2, m3]);
+    assert_eq!(materials.values(), &[2, 3]);
+}
@@ -0,0 +1,33 @@
+extern crate crayon;
+
+use crayon::res::location::Location;
+
+#[test]
+fn parse() {
+    let location = Location::parse("res:textures/crate.png").unwrap();
+    assert_eq!(location.vfs(), "res");
+    assert_eq!(location.shortcut(), "res:");
+    assert_eq!(location.path(), "textures/crate.png");
+    assert_eq!(location.filename(), "crate.png");
+    assert_eq!(&*location, "res:textures/crate.png");
+
+    let location: Location = "std:/cornell_box.obj".parse().unwrap();
+    assert_eq!(location.vfs(), "std");
+    assert_eq!(location.path(), "/cornell_box.obj");
+    assert_eq!(location.filename(), "cornell_box.obj");
+
+    let location = Location::parse("res:a:b.png").unwrap();
+    assert_eq!(location.vfs(), "res");
+    assert_eq!(location.path(), "a:b.png");
+    assert_eq!(location.filename(), "a:b.png");
+}
+
+#[test]
+fn err() {
+    assert!(Location::parse("").is_err());
+    assert!(Location::parse("textures/crate.png").is_err());
+    assert!(Location::parse(":textures/crate.png").is_err());
+    assert!(Location::parse("res:").is_err());
+    assert!(Location::parse("a/res:crate.png").is_err());
+    assert!(Location::parse("file://textures/crate.png").is_err());
+}
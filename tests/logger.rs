@@ -0,0 +1,42 @@
+#[macro_use]
+extern crate crayon;
+
+use std::sync::{Arc, Mutex};
+
+use crayon::application::logger::{Level, LevelFilter, Logger};
+
+#[test]
+fn callback() {
+    let records = Arc::new(Mutex::new(Vec::new()));
+    let clone = records.clone();
+
+    let logger = Logger::new(LevelFilter::Info)
+        .with_module("noisy", LevelFilter::Error)
+        .with_module("noisy::verbose", LevelFilter::Warn)
+        .with_callback(move |v| clone.lock().unwrap().push(v.clone()))
+        .with_stderr(false);
+
+    assert_eq!(logger.level("noisy"), LevelFilter::Error);
+    assert_eq!(logger.level("noisy::verbose::inner"), LevelFilter::Warn);
+    assert_eq!(logger.level("noisyness"), LevelFilter::Info);
+
+    logger.install().unwrap();
+    assert!(Logger::new(LevelFilter::Info).install().is_err());
+
+    info!("hello {}", 1);
+    warn!(target: "noisy", "dropped");
+    error!(target: "noisy", "kept");
+    warn!(target: "noisy::verbose", "verbose");
+
+    let records = records.lock().unwrap();
+    assert_eq!(records.len(), 3);
+
+    assert_eq!(records[0].level, Level::Info);
+    assert_eq!(records[0].target, "logger");
+    assert_eq!(records[0].message, "hello 1");
+    assert!(records[0].file.is_some());
+
+    assert_eq!(records[1].level, Level::Error);
+    assert_eq!(records[1].message, "kept");
+    assert_eq!(records[2].target, "noisy::verbose");
+}
@@ -0,0 +1,101 @@
+extern crate crayon;
+
+use std::io::Cursor;
+
+use crayon::bincode;
+use crayon::res::manifest::{ManfiestResolver, Manifest};
+use crayon::utils::hash::content_hash;
+use crayon::uuid::Uuid;
+
+#[test]
+fn tags() {
+    let (a, b, c) = (
+        Uuid::from_bytes([1; 16]),
+        Uuid::from_bytes([2; 16]),
+        Uuid::from_bytes([3; 16]),
+    );
+
+    let mut manifest = Manifest::new();
+    manifest.add("a.png", a, &[], &["ui", "level1"]);
+    manifest.add("b.wav", b, &[], &["audio"]);
+    let i = manifest.add("c.mesh", c, &[0], &["level1"]);
+    assert_eq!(i, 2);
+
+    let mut bytes = Vec::new();
+    manifest.write_to(&mut bytes).unwrap();
+
+    let mut resolver = ManfiestResolver::new();
+    resolver.add("res:", &mut Cursor::new(bytes)).unwrap();
+
+    let mut level1 = resolver.find_by_tag("level1");
+    level1.sort();
+    assert_eq!(level1, [a, c]);
+
+    assert_eq!(resolver.find_by_tag("audio"), vec![b]);
+    assert!(resolver.find_by_tag("level2").is_empty());
+
+    assert_eq!(resolver.find("res:/c.mesh"), Some(c));
+    assert_eq!(resolver.dependencies(c).unwrap().collect::<Vec<_>>(), [a]);
+}
+
+#[test]
+fn untagged() {
+    // Manifests that were built before tags were introduced. They were written without
+    // padding, so the dependencies of `b.mesh` are at an unaligned offset.
+    let (a, b) = (Uuid::from_bytes([1; 16]), Uuid::from_bytes([2; 16]));
+    let mut buf = b"a.pngb.mesh".to_vec();
+    buf.extend_from_slice(&0usize.to_ne_bytes());
+
+    let size = std::mem::size_of::<usize>() as u32;
+    let items = vec![
+        ((0u32, 5u32), (11u32, 0u32), a),
+        ((5u32, 6u32), (11u32, size), b),
+    ];
+
+    let mut bytes = vec![b'M', b'N', b'F', b'T', b' ', 0, 0, 1];
+    bincode::serialize_into(&mut bytes, &(items, buf)).unwrap();
+
+    let manifest = Manifest::load_from(&mut Cursor::new(&bytes)).unwrap();
+    assert_eq!(manifest.items.len(), 2);
+    assert_eq!(manifest.buf.as_str(manifest.items[1].filename), "b.mesh");
+    assert_eq!(manifest.buf.as_slice(manifest.items[1].dependencies), [0]);
+    assert!(manifest.buf.as_slice(manifest.items[0].tags).is_empty());
+
+    let mut resolver = ManfiestResolver::new();
+    resolver.add("res:", &mut Cursor::new(bytes)).unwrap();
+    assert_eq!(resolver.find("res:/b.mesh"), Some(b));
+    assert_eq!(resolver.dependencies(b).unwrap().collect::<Vec<_>>(), [a]);
+    assert!(resolver.find_by_tag("ui").is_empty());
+}
+
+#[test]
+fn hash() {
+    let (a, b) = (Uuid::from_bytes([1; 16]), Uuid::from_bytes([2; 16]));
+    let (ha, hb) = (content_hash(b"a.png"), content_hash(b"b.wav"));
+
+    let digest = Manifest::digest(vec![ha, hb]);
+    assert_eq!(digest, Manifest::digest(vec![hb, ha]));
+    assert_ne!(
+        digest,
+        Manifest::digest(vec![ha, content_hash(b"b.wav changed")])
+    );
+
+    let mut manifest = Manifest::new();
+    manifest.add("a.png", a, &[], &["ui"]);
+    manifest.add("b.wav", b, &[], &["audio"]);
+
+    let mut bytes = Vec::new();
+    manifest.write_to(&mut bytes).unwrap();
+
+    let mut resolver = ManfiestResolver::new();
+    resolver.add("res:", &mut Cursor::new(bytes)).unwrap();
+    assert_eq!(resolver.hash(), None);
+
+    manifest.hash = Some(digest);
+    let mut bytes = Vec::new();
+    manifest.write_to(&mut bytes).unwrap();
+
+    let mut resolver = ManfiestResolver::new();
+    resolver.add("res:", &mut Cursor::new(bytes)).unwrap();
+    assert_eq!(resolver.hash(), Some(digest));
+}
@@ -0,0 +1,125 @@
+extern crate crayon;
+
+use crayon::prelude::*;
+use crayon::video::errors::Error;
+
+fn shader() -> ShaderHandle {
+    let attributes = AttributeLayout::build()
+        .with(Attribute::Position, 3)
+        .finish();
+
+    let uniforms = UniformVariableLayout::build()
+        .with("u_Ambient", UniformVariableType::Vector3f)
+        .with("u_Texture", UniformVariableType::Texture)
+        .finish();
+
+    let mut params = ShaderParams::default();
+    params.attributes = attributes;
+    params.uniforms = uniforms;
+    video::create_shader(params, "vs".into(), "fs".into()).unwrap()
+}
+
+#[test]
+fn bind() {
+    crayon::application::oneshot().unwrap();
+
+    let mut mtl = Material::new(shader()).unwrap();
+    mtl.bind("u_Ambient", [1.0, 1.0, 1.0]).unwrap();
+    mtl.bind("u_Ambient", [0.5, 0.5, 0.5]).unwrap();
+    mtl.bind("u_Texture", TextureHandle::default()).unwrap();
+
+    let dc = mtl.draw(MeshHandle::default());
+    assert_eq!(dc.shader, mtl.shader());
+
+    match mtl.bind("u_Ambient", [1.0, 1.0, 1.0, 1.0]) {
+        Err(Error::UniformVariableMismatch(field, expected, found)) => {
+            assert_eq!(field, "u_Ambient");
+            assert_eq!(expected, UniformVariableType::Vector3f);
+            assert_eq!(found, UniformVariableType::Vector4f);
+        }
+        _ => unreachable!(),
+    }
+
+    match mtl.bind("u_Texture", 1.0) {
+        Err(err) => assert_eq!(
+            format!("{}", err),
+            "Uniform variable(u_Texture) is declared as Texture, but found F32."
+        ),
+        _ => unreachable!(),
+    }
+
+    match mtl.bind("u_Diffuse", [1.0, 1.0, 1.0]) {
+        Err(Error::UniformVariableUndefined(field)) => assert_eq!(field, "u_Diffuse"),
+        _ => unreachable!(),
+    }
+
+    match Material::new(ShaderHandle::default()) {
+        Err(Error::HandleInvalid(_)) => {}
+        _ => unreachable!(),
+    }
+
+    let white = video::white_texture();
+    assert_eq!(video::texture_state(white), ResourceState::Ok);
+    assert_eq!(video::white_texture(), white);
+
+    let mtl = Material::build(shader()).unwrap().finish();
+    match mtl.variable("u_Texture") {
+        Some(UniformVariable::Texture(v)) => assert_eq!(v, white),
+        _ => unreachable!(),
+    }
+
+    video::delete_texture(white);
+    let white = video::white_texture();
+    assert_eq!(video::texture_state(white), ResourceState::Ok);
+}
+
+#[test]
+fn defaults() {
+    use crayon::utils::handle::HandleLike;
+
+    let uniforms = UniformVariableLayout::build()
+        .with("u_Shininess", UniformVariableType::F32)
+        .with("u_Ambient", UniformVariableType::Vector3f)
+        .with("u_ModelMatrix", UniformVariableType::Matrix4f)
+        .with("u_Texture", UniformVariableType::Texture)
+        .finish();
+
+    let white = TextureHandle::new(1, 1);
+    let mtl = Material::with_layout(ShaderHandle::default(), uniforms.clone());
+    let mtl = MaterialBuilder::new(mtl)
+        .with("u_Ambient", [1.0, 0.5, 0.25])
+        .unwrap()
+        .with_default_texture(white)
+        .finish();
+
+    for v in uniforms.iter() {
+        let variable = mtl.variable(v.0.as_str()).unwrap();
+        assert_eq!(variable.variable_type(), v.1);
+    }
+
+    match mtl.variable("u_Shininess") {
+        Some(UniformVariable::F32(v)) => assert_eq!(v, 0.0),
+        _ => unreachable!(),
+    }
+
+    match mtl.variable("u_Ambient") {
+        Some(UniformVariable::Vector3f(v)) => assert_eq!(v, [1.0, 0.5, 0.25]),
+        _ => unreachable!(),
+    }
+
+    match mtl.variable("u_ModelMatrix") {
+        Some(UniformVariable::Matrix4f(v, _)) => {
+            let identity: [[f32; 4]; 4] = Matrix4::identity().into();
+            assert_eq!(v, identity);
+        }
+        _ => unreachable!(),
+    }
+
+    match mtl.variable("u_Texture") {
+        Some(UniformVariable::Texture(v)) => assert_eq!(v, white),
+        _ => unreachable!(),
+    }
+
+    let mtl = Material::with_layout(ShaderHandle::default(), uniforms);
+    assert!(MaterialBuilder::new(mtl).with("u_Shininess", 1).is_err());
+}
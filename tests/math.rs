@@ -0,0 +1,79 @@
+#[macro_use]
+extern crate crayon;
+extern crate serde;
+extern crate serde_json;
+
+use std::fmt::Debug;
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use crayon::bincode;
+use crayon::math::prelude::*;
+
+fn round_trip<T>(v: T)
+where
+    T: Serialize + DeserializeOwned + PartialEq + Debug,
+{
+    let bytes = bincode::serialize(&v).unwrap();
+    assert_eq!(bincode::deserialize::<T>(&bytes).unwrap(), v);
+
+    let json = serde_json::to_string(&v).unwrap();
+    assert_eq!(serde_json::from_str::<T>(&json).unwrap(), v);
+}
+
+#[test]
+fn serde() {
+    round_trip(Vector2::new(1.0f32, 2.0));
+    round_trip(Vector3::new(1.0f32, 2.0, 3.0));
+    round_trip(Vector4::new(1.0f32, 2.0, 3.0, 4.0));
+    round_trip(Quaternion::new(1.0f32, 2.0, 3.0, 4.0));
+    round_trip(Matrix2::new(1.0f32, 2.0, 3.0, 4.0));
+    round_trip(Matrix3::from_value(2.0f32));
+    round_trip(Matrix4::from_translation(Vector3::new(1.0f32, 2.0, 3.0)));
+    round_trip(Color::new(0.1f32, 0.2, 0.3, 0.4));
+    round_trip(Color::<f32>::white());
+}
+
+#[test]
+fn ndc() {
+    let viewport = Aabb2::new(Point2::new(10.0f32, 20.0), Point2::new(110.0, 70.0));
+
+    let corners = [
+        (Vector2::new(-1.0, -1.0), Vector2::new(10.0, 20.0)),
+        (Vector2::new(1.0, -1.0), Vector2::new(110.0, 20.0)),
+        (Vector2::new(-1.0, 1.0), Vector2::new(10.0, 70.0)),
+        (Vector2::new(1.0, 1.0), Vector2::new(110.0, 70.0)),
+        (Vector2::new(0.0, 0.0), Vector2::new(60.0, 45.0)),
+    ];
+
+    for &(ndc, screen) in &corners {
+        let v = ndc_to_screen(ndc.extend(-1.0), viewport);
+        assert_eq!(v, screen.extend(0.0));
+        assert_eq!(ndc_to_screen(ndc.extend(1.0), viewport).z, 1.0);
+        assert_eq!(screen_to_ndc(screen, viewport), ndc);
+    }
+
+    let projection = Projection::perspective_matrix(Deg(90.0).into(), 1.0, 0.1, 100.0);
+    let v = clip_to_ndc(projection * Vector4::new(0.0, 0.0, 100.0, 1.0));
+    assert_ulps_eq!(v, Vector3::new(0.0, 0.0, 1.0), epsilon = 1e-5);
+
+    let v = clip_to_ndc(projection * Vector4::new(-0.1, 0.1, 0.1, 1.0));
+    assert_ulps_eq!(v, Vector3::new(-1.0, 1.0, -1.0), epsilon = 1e-5);
+}
+
+#[test]
+fn rotation() {
+    let up = Vector3::new(0.0, 1.0, 0.0);
+
+    let rotation = look_rotation(Vector3::new(0.0, 0.0, 2.0), up);
+    assert_ulps_eq!(rotation, Quaternion::one());
+
+    let rotation = look_rotation(Vector3::new(1.0, 0.0, 0.0), up);
+    assert_ulps_eq!(rotation, Euler::new(Deg(0.0), Deg(90.0), Deg(0.0)).into());
+    assert_ulps_eq!(
+        rotation * Vector3::new(0.0, 0.0, 1.0),
+        Vector3::new(1.0, 0.0, 0.0)
+    );
+    assert_ulps_eq!(rotation * up, up);
+}
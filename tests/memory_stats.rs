@@ -0,0 +1,44 @@
+extern crate crayon;
+
+use crayon::prelude::*;
+
+#[test]
+fn memory_stats() {
+    crayon::application::oneshot().unwrap();
+
+    let stats = video::memory_stats();
+    assert_eq!(stats, MemoryStats::default());
+
+    let mut params = TextureParams::default();
+    params.format = TextureFormat::RGBA8;
+    params.dimensions = Vector2::new(64, 32);
+    let texture = video::create_texture(params, None).unwrap();
+
+    let mut params = MeshParams::default();
+    params.layout = VertexLayout::build()
+        .with(Attribute::Position, VertexFormat::Float, 3, false)
+        .finish();
+    params.num_verts = 4;
+    params.num_idxes = 6;
+    let mesh = video::create_mesh(params, None).unwrap();
+
+    let mut params = RenderTextureParams::default();
+    params.format = RenderTextureFormat::RGBA8;
+    params.dimensions = Vector2::new(16, 16);
+    let rt = video::create_render_texture(params).unwrap();
+
+    params.samples = 4;
+    let msaa = video::create_render_texture(params).unwrap();
+
+    let stats = video::memory_stats();
+    assert_eq!(stats.texture_bytes, 64 * 32 * 4);
+    assert_eq!(stats.mesh_bytes, 4 * 12 + 6 * 2);
+    assert_eq!(stats.render_texture_bytes, 16 * 16 * 4 + 16 * 16 * 4 * 5);
+
+    video::delete_texture(texture);
+    video::delete_mesh(mesh);
+    video::delete_render_texture(rt);
+    video::delete_render_texture(msaa);
+
+    assert_eq!(video::memory_stats(), MemoryStats::default());
+}
@@ -0,0 +1,48 @@
+extern crate crayon;
+
+use crayon::prelude::*;
+
+#[test]
+fn mesh_cpu_data() {
+    crayon::application::oneshot().unwrap();
+
+    let verts: [[f32; 3]; 3] = [[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [0.0, 1.0, 0.0]];
+    let idxes: [u16; 3] = [0, 1, 2];
+
+    let mut vptr = Vec::new();
+    for v in verts.iter().flat_map(|v| v.iter()) {
+        vptr.extend_from_slice(&v.to_bits().to_ne_bytes());
+    }
+
+    let mut iptr = Vec::new();
+    for v in &idxes {
+        iptr.extend_from_slice(&v.to_ne_bytes());
+    }
+
+    let data = MeshData {
+        vptr: vptr.clone().into_boxed_slice(),
+        iptr: iptr.clone().into_boxed_slice(),
+    };
+
+    let mut params = MeshParams::default();
+    params.layout = VertexLayout::build()
+        .with(Attribute::Position, VertexFormat::Float, 3, false)
+        .finish();
+    params.num_verts = 3;
+    params.num_idxes = 3;
+
+    let mesh = video::create_mesh(params.clone(), data.clone()).unwrap();
+    assert!(video::mesh_cpu_data(mesh).is_none());
+    video::delete_mesh(mesh);
+
+    params.keep_cpu_data = true;
+    let mesh = video::create_mesh(params, data).unwrap();
+    let cpu = video::mesh_cpu_data(mesh).unwrap();
+    assert_eq!(&cpu.vptr[..], &vptr[..]);
+    assert_eq!(&cpu.iptr[..], &iptr[..]);
+    assert_eq!(video::mesh_aabb(mesh), video::mesh(mesh).map(|v| v.aabb));
+
+    video::delete_mesh(mesh);
+    assert!(video::mesh_cpu_data(mesh).is_none());
+    assert!(video::mesh_aabb(mesh).is_none());
+}
@@ -0,0 +1,62 @@
+extern crate crayon;
+
+use crayon::prelude::*;
+
+#[test]
+fn mesh_raw() {
+    crayon::application::oneshot().unwrap();
+
+    let verts: [[f32; 2]; 3] = [[0.0, 0.0], [1.0, 0.0], [0.0, 1.0]];
+    let idxes: [u16; 3] = [0, 1, 2];
+
+    let mut vptr = Vec::new();
+    for v in verts.iter().flat_map(|v| v.iter()) {
+        vptr.extend_from_slice(&v.to_bits().to_ne_bytes());
+    }
+
+    let mut iptr = Vec::new();
+    for v in &idxes {
+        iptr.extend_from_slice(&v.to_ne_bytes());
+    }
+
+    let params = MeshParams {
+        layout: VertexLayout::build()
+            .with(Attribute::Position, VertexFormat::Float, 2, false)
+            .finish(),
+        num_verts: 3,
+        num_idxes: 3,
+        keep_cpu_data: true,
+        ..Default::default()
+    };
+
+    let data = MeshData {
+        vptr: vptr.into_boxed_slice(),
+        iptr: iptr.into_boxed_slice(),
+    };
+
+    let encoded = video::create_mesh(params.clone(), data).unwrap();
+    let raw = video::create_mesh_raw(
+        params.clone(),
+        IndexFormat::encode(&verts).into(),
+        Some(IndexFormat::encode(&idxes).into()),
+    )
+    .unwrap();
+
+    let (lhs, rhs) = (
+        video::mesh_cpu_data(encoded).unwrap(),
+        video::mesh_cpu_data(raw).unwrap(),
+    );
+    assert_eq!(lhs.vptr, rhs.vptr);
+    assert_eq!(lhs.iptr, rhs.iptr);
+
+    // The lengths of bytes must match the mesh exactly.
+    let vptr: Box<[u8]> = IndexFormat::encode(&verts[..2]).into();
+    assert!(video::create_mesh_raw(params.clone(), vptr, None).is_err());
+
+    let vptr: Box<[u8]> = IndexFormat::encode(&verts).into();
+    let iptr = IndexFormat::encode(&idxes[..2]).into();
+    assert!(video::create_mesh_raw(params.clone(), vptr.clone(), Some(iptr)).is_err());
+
+    let raw = video::create_mesh_raw(params, vptr, None).unwrap();
+    assert!(video::mesh_cpu_data(raw).unwrap().iptr.is_empty());
+}
@@ -0,0 +1,47 @@
+extern crate crayon;
+
+use crayon::prelude::*;
+
+#[test]
+fn ping_pong() {
+    crayon::application::oneshot().unwrap();
+
+    let mut params = RenderTextureParams::default();
+    params.dimensions = (64, 64).into();
+    let mut chain = PostProcessChain::new(params).unwrap();
+
+    let attributes = AttributeLayout::build()
+        .with(Attribute::Position, 2)
+        .finish();
+
+    let uniforms = UniformVariableLayout::build()
+        .with("u_Source", UniformVariableType::RenderTexture)
+        .with("u_Strength", UniformVariableType::F32)
+        .finish();
+
+    let mut params = ShaderParams::default();
+    params.attributes = attributes;
+    params.uniforms = uniforms;
+    let shader = video::create_shader(params, "vs".into(), "fs".into()).unwrap();
+
+    assert!(chain.is_empty());
+    assert_eq!(chain.texture(), chain.targets(0).0);
+
+    for i in 0..3 {
+        assert_eq!(chain.add_pass(shader, "u_Source"), i);
+        chain.set_uniform_variable(i, "u_Strength", i as f32);
+    }
+
+    assert_eq!(chain.len(), 3);
+
+    let (a, b) = chain.targets(0);
+    assert_ne!(a, b);
+    assert_eq!(chain.targets(1), (b, a));
+    assert_eq!(chain.targets(2), (a, b));
+
+    // The last pass writes into `b`.
+    assert_eq!(chain.texture(), b);
+    assert_eq!(chain.submit().unwrap(), b);
+
+    crayon::application::discard();
+}
@@ -0,0 +1,36 @@
+extern crate crayon;
+
+use crayon::errors::Result;
+use crayon::prelude::*;
+
+struct Window {}
+
+impl LifecycleListener for Window {}
+
+#[test]
+fn validate() {
+    let params = Params::default();
+    Engine::new_with(&params)
+        .with_headless(true)
+        .run(|| -> Result<Window> {
+            let q1 = video::begin_query(QueryKind::TimeElapsed).unwrap();
+            assert!(video::begin_query(QueryKind::TimeElapsed).is_err());
+
+            let q2 = video::begin_query(QueryKind::SamplesPassed).unwrap();
+            video::end_query(q1).unwrap();
+            assert!(video::end_query(q1).is_err());
+            video::end_query(q2).unwrap();
+
+            let q3 = video::begin_query(QueryKind::TimeElapsed).unwrap();
+            video::end_query(q3).unwrap();
+
+            video::delete_query(q1);
+            assert!(video::end_query(q1).is_err());
+            assert_eq!(video::query_result(q1), None);
+
+            // The rejected queries never reach the backend, so the frame is dispatched
+            // without errors.
+            Ok(Window {})
+        })
+        .unwrap();
+}
@@ -0,0 +1,35 @@
+extern crate crayon;
+
+use crayon::prelude::*;
+
+// The resolved pixels of multisampled render textures are checked with the software
+// rasterizer, see the `multisample` test in tests/software.rs.
+#[test]
+fn multisample() {
+    crayon::application::oneshot().unwrap();
+
+    let mut params = RenderTextureParams::default();
+    params.format = RenderTextureFormat::RGBA8;
+    params.dimensions = (256, 128).into();
+    params.samples = 4;
+
+    let rt = video::create_render_texture(params).unwrap();
+    let mut params = video::render_texture(rt).unwrap();
+    assert!(params.is_multisampled());
+
+    let resolve = params.resolve().unwrap();
+    assert!(!resolve.is_multisampled());
+    assert!(resolve.sampler);
+    assert_eq!(resolve.format, RenderTextureFormat::RGBA8);
+    assert_eq!(resolve.dimensions, Vector2::new(256, 128));
+    assert!(resolve.resolve().is_none());
+
+    params.samples = 3;
+    assert!(video::create_render_texture(params).is_err());
+
+    // The number of samples is checked against the limit of device up front.
+    params.samples = 1024;
+    assert!(video::create_render_texture(params).is_err());
+
+    crayon::application::discard();
+}
@@ -0,0 +1,36 @@
+extern crate crayon;
+
+use crayon::errors::Result;
+use crayon::prelude::*;
+use crayon::res::vfs::embedded::Embedded;
+
+struct Window {}
+
+impl LifecycleListener for Window {}
+
+#[test]
+fn listings() {
+    let mut params = Params::default();
+    params.res.schemas.add("embedded", Embedded::new());
+    params
+        .res
+        .shortcuts
+        .add("res:", "embedded://pack/")
+        .unwrap();
+
+    Engine::new_with(&params)
+        .with_headless(true)
+        .run(|| -> Result<Window> {
+            let schemas = crayon::res::schemas();
+            assert!(schemas.contains(&"embedded".to_owned()));
+
+            let shortcuts = crayon::res::shortcuts();
+            assert_eq!(
+                shortcuts,
+                vec![("res:".to_owned(), "embedded://pack/".to_owned())]
+            );
+
+            Ok(Window {})
+        })
+        .unwrap();
+}
@@ -0,0 +1,34 @@
+extern crate crayon;
+extern crate image;
+
+use crayon::video::screenshot;
+
+#[test]
+fn save() {
+    let red = [255, 0, 0, 255];
+    let green = [0, 255, 0, 255];
+
+    // Framebuffers are read from bottom to top, the first row is the bottom one.
+    let mut pixels = Vec::new();
+    for _ in 0..3 {
+        pixels.extend_from_slice(&red);
+    }
+
+    for _ in 0..3 {
+        pixels.extend_from_slice(&green);
+    }
+
+    let path = std::env::temp_dir().join("crayon-screenshot-test.png");
+    screenshot::save(&path, (3, 2).into(), &pixels).unwrap();
+
+    let image = image::open(&path).unwrap().to_rgba();
+    std::fs::remove_file(&path).unwrap();
+
+    assert_eq!(image.dimensions(), (3, 2));
+    for x in 0..3 {
+        assert_eq!(image.get_pixel(x, 0).data, green);
+        assert_eq!(image.get_pixel(x, 1).data, red);
+    }
+
+    assert!(screenshot::save(&path, (4, 2).into(), &pixels).is_err());
+}
@@ -0,0 +1,8 @@
+extern crate crayon;
+
+#[test]
+fn duplicated_setup() {
+    crayon::application::oneshot().unwrap();
+    assert!(crayon::application::oneshot().is_err());
+    assert!(crayon::application::valid());
+}
@@ -0,0 +1,35 @@
+extern crate crayon;
+
+use std::fs;
+
+use crayon::prelude::*;
+
+#[test]
+fn reload() {
+    crayon::application::oneshot().unwrap();
+
+    let vs = std::env::temp_dir().join("crayon-shader-watch-test.vs");
+    let fs = std::env::temp_dir().join("crayon-shader-watch-test.fs");
+    fs::write(&vs, "void main() {}").unwrap();
+    fs::write(&fs, "void main() {}").unwrap();
+
+    let params = ShaderParams::default();
+    let shader = video::create_shader_watched(params, "vs".into(), "fs".into(), &vs, &fs).unwrap();
+
+    // The sources have been loaded from files already.
+    assert!(video::reload_watched_shaders().is_empty());
+
+    fs::write(&fs, "void main() { gl_FragColor = vec4(1.0); }").unwrap();
+    assert_eq!(video::reload_watched_shaders(), [shader]);
+    assert!(video::reload_watched_shaders().is_empty());
+
+    // Files that could not be read are skipped.
+    fs::remove_file(&vs).unwrap();
+    assert!(video::reload_watched_shaders().is_empty());
+
+    fs::write(&vs, "void main() { gl_Position = vec4(1.0); }").unwrap();
+    assert_eq!(video::reload_watched_shaders(), [shader]);
+
+    fs::remove_file(&vs).unwrap();
+    fs::remove_file(&fs).unwrap();
+}
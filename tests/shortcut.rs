@@ -35,3 +35,18 @@ fn err() {
     // Fullname must end in a '/' (dir) or ':' (other shortcut).
     assert!(resolver.add("home:", "file://docs").is_err());
 }
+
+#[test]
+fn shortcuts() {
+    let mut resolver = ShortcutResolver::new();
+    resolver.add("b:", "a:crayon/").unwrap();
+    resolver.add("a:", "file://docs/").unwrap();
+
+    assert_eq!(
+        resolver.shortcuts(),
+        vec![
+            ("a:".to_owned(), "file://docs/".to_owned()),
+            ("b:".to_owned(), "a:crayon/".to_owned()),
+        ]
+    );
+}
@@ -0,0 +1,114 @@
+#![cfg(feature = "software")]
+
+#[macro_use]
+extern crate crayon;
+extern crate image;
+
+use crayon::errors::Result;
+use crayon::prelude::*;
+
+// The shaders are not executed by the software rasterizer.
+const VS: &str = "
+    #version 100
+    attribute vec2 Position;
+
+    void main() {
+        gl_Position = vec4(Position, 0.0, 1.0);
+    }
+";
+
+const FS: &str = "
+    #version 100
+    precision lowp float;
+    uniform vec4 u_Color;
+
+    void main() {
+        gl_FragColor = u_Color;
+    }
+";
+
+impl_vertex!{
+    Vertex {
+        position => [Position; Float; 2; false],
+    }
+}
+
+struct Window {}
+
+impl LifecycleListener for Window {}
+
+fn draw(surface: SurfaceHandle) -> Result<()> {
+    let params = MeshParams {
+        layout: Vertex::layout(),
+        num_verts: 3,
+        num_idxes: 3,
+        ..Default::default()
+    };
+
+    let verts = [
+        Vertex::new([-1.0, -1.0]),
+        Vertex::new([1.0, -1.0]),
+        Vertex::new([-1.0, 1.0]),
+    ];
+
+    let data = MeshData {
+        vptr: Vertex::encode(&verts).into(),
+        iptr: IndexFormat::encode(&[0u16, 1, 2]).into(),
+    };
+
+    let mesh = video::create_mesh(params, Some(data))?;
+
+    let mut params = ShaderParams::default();
+    params.attributes = AttributeLayout::build()
+        .with(Attribute::Position, 2)
+        .finish();
+    params.uniforms = UniformVariableLayout::build()
+        .with("u_Color", UniformVariableType::Vector4f)
+        .finish();
+
+    let shader = video::create_shader(params, VS.to_owned(), FS.to_owned())?;
+
+    let mut dc = Draw::new(shader, mesh);
+    dc.set_uniform_variable("u_Color", Color::<f32>::red().rgba());
+
+    let mut batch = CommandBuffer::new();
+    batch.draw(dc);
+    batch.submit(surface)?;
+    Ok(())
+}
+
+#[test]
+fn multisample() {
+    let path = std::env::temp_dir().join("crayon-software-test.png");
+    let output = path.clone();
+
+    Engine::new_with(&Params::default())
+        .with_headless(true)
+        .with_software(true)
+        .run(move || -> Result<Window> {
+            let mut params = RenderTextureParams::default();
+            params.format = RenderTextureFormat::RGBA8;
+            params.dimensions = (4, 4).into();
+            params.samples = 4;
+            let rt = video::create_render_texture(params)?;
+
+            let mut params = SurfaceParams::default();
+            params.set_attachments(&[rt], None)?;
+            params.set_clear(Color::black(), None, None);
+            let surface = video::create_surface(params)?;
+
+            // The screenshot is taken from the resolved texture after the draw.
+            draw(surface)?;
+            video::save_surface_screenshot(surface, output)?;
+            Ok(Window {})
+        })
+        .unwrap();
+
+    let image = image::open(&path).unwrap().to_rgba();
+    std::fs::remove_file(&path).unwrap();
+
+    // The rows of image are top to bottom, while the triangle covers the lower left half.
+    assert_eq!(image.get_pixel(0, 3).data, [255, 0, 0, 255]);
+    assert_eq!(image.get_pixel(3, 3).data, [64, 0, 0, 255]);
+    assert_eq!(image.get_pixel(3, 0).data, [0, 0, 0, 255]);
+}